@@ -0,0 +1,72 @@
+// Замер выбора формы рёбер при отрисовке на полном ориентированном графе из 300 вершин:
+// прежний поиск противоположной дуги в графе (до трёх раз на ребро: для формы ребра,
+// стрелки и подписи) и поиск во множестве противоположных дуг, вычисляемом один раз
+// после изменения графа
+// cargo bench --bench antiparallel_edges
+
+use std::collections::BTreeSet;
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+use graph_visualizer::{
+    graph::{Edge, EdgeWeights, Graph, VertexId},
+    graph_generators::gen_complete,
+    graph_geometry::antiparallel_edges,
+};
+
+// Количество вершин графа
+const VERTEX_CNT: usize = 300;
+
+// Количество рёбер, рисуемых кривыми, с поиском противоположной дуги в графе
+fn count_curves_lookup(g: &Graph<VertexId, EdgeWeights>) -> usize {
+    let mut cnt = 0;
+    for i in g.get_vertices().keys() {
+        for Edge { to, .. } in g.get_edge_list(i).unwrap() {
+            // Форма ребра, стрелка и подпись
+            for _ in 0..3 {
+                if i != to && g.get_is_directed() && g.get_edge(to, i).is_ok() {
+                    cnt += 1;
+                }
+            }
+        }
+    }
+    cnt
+}
+
+// Количество рёбер, рисуемых кривыми, с поиском во множестве противоположных дуг
+fn count_curves_set(
+    g: &Graph<VertexId, EdgeWeights>,
+    antiparallel: &BTreeSet<(VertexId, VertexId)>,
+) -> usize {
+    let mut cnt = 0;
+    for i in g.get_vertices().keys() {
+        for Edge { to, .. } in g.get_edge_list(i).unwrap() {
+            if antiparallel.contains(&(i.clone(), to.clone())) {
+                cnt += 3;
+            }
+        }
+    }
+    cnt
+}
+
+fn bench_antiparallel_edges(c: &mut Criterion) {
+    let g: Graph<VertexId, EdgeWeights> = gen_complete(VERTEX_CNT, true, None, 1).unwrap();
+    let antiparallel = antiparallel_edges(&g);
+    assert_eq!(count_curves_lookup(&g), count_curves_set(&g, &antiparallel));
+
+    let mut group = c.benchmark_group("antiparallel_edges");
+    group.sample_size(10);
+    // Один кадр при поиске в графе и при поиске во множестве
+    group.bench_function("lookup", |b| b.iter(|| count_curves_lookup(black_box(&g))));
+    group.bench_function("set", |b| {
+        b.iter(|| count_curves_set(black_box(&g), &antiparallel))
+    });
+    // Вычисление множества (один раз после изменения графа)
+    group.bench_function("precompute", |b| {
+        b.iter(|| antiparallel_edges(black_box(&g)))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_antiparallel_edges);
+criterion_main!(benches);
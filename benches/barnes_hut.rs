@@ -0,0 +1,37 @@
+// Сравнение вычисления сил отталкивания деревом квадрантов без приближения (θ = 0)
+// и с приближением Барнса-Хата (θ = 0.7) для 5000 случайных точек
+// cargo bench --bench barnes_hut
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use graph_visualizer::quad_tree::QuadTree;
+
+// Количество точек
+const POINT_CNT: usize = 5000;
+
+fn bench_theta(c: &mut Criterion) {
+    let mut rng = StdRng::seed_from_u64(1);
+    let points: Vec<(f32, f32)> = (0..POINT_CNT)
+        .map(|_| (rng.gen_range(-1.0..1.0), rng.gen_range(-1.0..1.0)))
+        .collect();
+    let mut tree = QuadTree::new();
+    tree.build(points.iter().copied(), -1.0, 1.0, -1.0, 1.0);
+
+    let mut group = c.benchmark_group("barnes_hut");
+    group.sample_size(10);
+    for theta in [0.0, 0.7] {
+        group.bench_with_input(BenchmarkId::from_parameter(theta), &theta, |b, &theta| {
+            b.iter(|| {
+                points
+                    .iter()
+                    .map(|&p| black_box(tree.get_force(p, theta)))
+                    .fold((0.0, 0.0), |acc, f| (acc.0 + f.0, acc.1 + f.1))
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_theta);
+criterion_main!(benches);
@@ -0,0 +1,46 @@
+// Замер времени вычисления сил, действующих на вершины (шаг симуляции расположения),
+// на случайном графе из 10000 вершин при параллельном и последовательном вычислении
+// cargo bench --bench layout_update
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use graph_visualizer::{
+    graph::{EdgeWeights, Graph, VertexId},
+    graph_generators::gen_random_gnp,
+    graph_layout::{edge_indices, ForceLayout},
+};
+
+// Количество вершин графа
+const VERTEX_CNT: usize = 10000;
+// Среднее количество рёбер у вершины
+const AVG_DEGREE: f64 = 3.0;
+
+fn bench_layout_update(c: &mut Criterion) {
+    let g: Graph<VertexId, EdgeWeights> =
+        gen_random_gnp(VERTEX_CNT, AVG_DEGREE / VERTEX_CNT as f64, false, None, 1).unwrap();
+    let edges = edge_indices(&g);
+
+    // Одинаковые начальные координаты при каждом запуске
+    let mut rng = StdRng::seed_from_u64(1);
+    let coords: Vec<(f32, f32)> = (0..VERTEX_CNT)
+        .map(|_| (rng.gen_range(-0.5..0.5), rng.gen_range(-0.5..0.5)))
+        .collect();
+
+    let mut layout = ForceLayout::new();
+    let mut group = c.benchmark_group("layout_update");
+    group.sample_size(10);
+    for (name, parallel) in [("parallel", true), ("serial", false)] {
+        layout.set_parallel(parallel);
+        group.bench_function(name, |b| {
+            b.iter(|| {
+                layout.compute_forces(black_box(&coords), &edges);
+                black_box(layout.get_forces());
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_layout_update);
+criterion_main!(benches);
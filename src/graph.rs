@@ -1,40 +1,166 @@
 use std::{
-    collections::{BTreeMap, BTreeSet},
+    cmp::Ordering,
+    collections::{BTreeMap, BTreeSet, VecDeque},
     fmt::Display,
     io::{BufRead, Write},
-    ops::{Add, Sub},
+    ops::{Add, Range, Sub},
     str::FromStr,
+    sync::atomic::{self, AtomicU64},
 };
 
 use ordered_float::OrderedFloat;
 
 use crate::{
-    graph_errors::{GraphError, GraphInterfaceError, GraphOperationError},
-    graph_parser::{add_edge, add_vertex, new_graph},
+    graph_errors::{GraphAlgorithmError, GraphError, GraphInterfaceError, GraphOperationError},
+    graph_parser::{
+        add_edge, add_matrix_row, add_matrix_vertices, add_position, add_vertex, new_graph,
+    },
+    strings::Lang,
 };
 // Идентификатор вершины
 pub trait VertexKey: Ord + Display + FromStr + Clone {}
 // Вес ребра
-pub trait EdgeWeight: Add<Output = Self> + Sub<Output = Self> + Ord + Display + Clone {}
+pub trait EdgeWeight: Add<Output = Self> + Sub<Output = Self> + Ord + Display + Clone {
+    // Значение веса в виде дробного числа (для отрисовки)
+    fn as_f32(&self) -> f32;
+    // Запись веса для сохранения в файл: кратчайшее представление, из которого читается
+    // то же значение (дробные числа всегда записываются с точкой или порядком)
+    fn to_file_string(&self) -> String;
+    // Сложение весов (None при переполнении целых весов)
+    fn checked_add(self, rhs: Self) -> Option<Self>;
+}
 
 impl VertexKey for i32 {}
-impl EdgeWeight for i32 {}
-impl EdgeWeight for OrderedFloat<f32> {}
+impl VertexKey for VertexId {}
+impl EdgeWeight for i32 {
+    fn as_f32(&self) -> f32 {
+        *self as f32
+    }
+    fn to_file_string(&self) -> String {
+        self.to_string()
+    }
+    fn checked_add(self, rhs: Self) -> Option<Self> {
+        i32::checked_add(self, rhs)
+    }
+}
+impl EdgeWeight for OrderedFloat<f32> {
+    fn as_f32(&self) -> f32 {
+        self.0
+    }
+    fn to_file_string(&self) -> String {
+        format!("{:?}", self.0)
+    }
+    fn checked_add(self, rhs: Self) -> Option<Self> {
+        Some(self + rhs)
+    }
+}
+
+// Строковый идентификатор вершины
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VertexId {
+    name: String,     // Текст идентификатора
+    num: Option<i64>, // Числовое значение, если идентификатор является числом
+}
 
+impl VertexId {
+    pub fn as_str(&self) -> &str {
+        &self.name
+    }
+}
+
+// Числовые идентификаторы сравниваются как числа и идут перед строковыми
+impl Ord for VertexId {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.num, other.num) {
+            (Some(x), Some(y)) => x.cmp(&y).then_with(|| self.name.cmp(&other.name)),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => self.name.cmp(&other.name),
+        }
+    }
+}
+impl PartialOrd for VertexId {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl Display for VertexId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.name.fmt(f)
+    }
+}
+// Идентификатор - непустая строка без пробельных символов
+impl FromStr for VertexId {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() || s.chars().any(char::is_whitespace) {
+            return Err(());
+        }
+        Ok(Self {
+            name: String::from(s),
+            num: s.parse().ok(),
+        })
+    }
+}
+
+// Вес ребра: целый или дробный. Все веса одного графа имеют один тип, но если целый вес
+// всё же встречается с дробным, то при сложении, вычитании и сравнении целый вес
+// приводится к дробному (и результат арифметических операций дробный)
 #[derive(Debug, Clone)]
 pub enum EdgeWeights {
     I32(i32),
     F32(OrderedFloat<f32>),
 }
 
+impl EdgeWeights {
+    // Дробный ли вес
+    pub fn is_float(&self) -> bool {
+        matches!(self, Self::F32(_))
+    }
+
+    // Вес в виде дробного числа (для операций с весами разных типов)
+    fn to_ordered_float(&self) -> OrderedFloat<f32> {
+        match self {
+            Self::I32(x) => OrderedFloat(*x as f32),
+            Self::F32(x) => *x,
+        }
+    }
+
+    // Сложение с ошибкой вместо приведения, если типы весов различаются,
+    // и вместо переполнения целых весов
+    pub fn checked_add(self, rhs: Self) -> Result<Self, GraphAlgorithmError> {
+        match (self, rhs) {
+            (Self::I32(x), Self::I32(y)) => x
+                .checked_add(y)
+                .map(Self::I32)
+                .ok_or(GraphAlgorithmError::WeightOverflow),
+            (Self::F32(x), Self::F32(y)) => Ok(Self::F32(x + y)),
+            _ => Err(GraphAlgorithmError::MixedWeightTypes),
+        }
+    }
+
+    // Вычитание с ошибкой вместо приведения, если типы весов различаются,
+    // и вместо переполнения целых весов
+    pub fn checked_sub(self, rhs: Self) -> Result<Self, GraphAlgorithmError> {
+        match (self, rhs) {
+            (Self::I32(x), Self::I32(y)) => x
+                .checked_sub(y)
+                .map(Self::I32)
+                .ok_or(GraphAlgorithmError::WeightOverflow),
+            (Self::F32(x), Self::F32(y)) => Ok(Self::F32(x - y)),
+            _ => Err(GraphAlgorithmError::MixedWeightTypes),
+        }
+    }
+}
+
 impl Add for EdgeWeights {
     type Output = Self;
 
     fn add(self, rhs: Self) -> Self::Output {
         match (self, rhs) {
             (Self::I32(x), Self::I32(y)) => Self::I32(x + y),
-            (Self::F32(x), Self::F32(y)) => Self::F32(x + y),
-            _ => unreachable!(),
+            (x, y) => Self::F32(x.to_ordered_float() + y.to_ordered_float()),
         }
     }
 }
@@ -44,8 +170,7 @@ impl Sub for EdgeWeights {
     fn sub(self, rhs: Self) -> Self::Output {
         match (self, rhs) {
             (Self::I32(x), Self::I32(y)) => Self::I32(x - y),
-            (Self::F32(x), Self::F32(y)) => Self::F32(x - y),
-            _ => unreachable!(),
+            (x, y) => Self::F32(x.to_ordered_float() - y.to_ordered_float()),
         }
     }
 }
@@ -53,27 +178,21 @@ impl PartialEq for EdgeWeights {
     fn eq(&self, other: &Self) -> bool {
         match (self, other) {
             (Self::I32(x), Self::I32(y)) => x == y,
-            (Self::F32(x), Self::F32(y)) => x == y,
-            _ => unreachable!(),
+            (x, y) => x.to_ordered_float() == y.to_ordered_float(),
         }
     }
 }
 impl Eq for EdgeWeights {}
 impl PartialOrd for EdgeWeights {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        match (self, other) {
-            (Self::I32(x), Self::I32(y)) => x.partial_cmp(y),
-            (Self::F32(x), Self::F32(y)) => x.partial_cmp(y),
-            _ => unreachable!(),
-        }
+        Some(self.cmp(other))
     }
 }
 impl Ord for EdgeWeights {
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
         match (self, other) {
             (Self::I32(x), Self::I32(y)) => x.cmp(y),
-            (Self::F32(x), Self::F32(y)) => x.cmp(y),
-            _ => unreachable!(),
+            (x, y) => x.to_ordered_float().cmp(&y.to_ordered_float()),
         }
     }
 }
@@ -85,7 +204,23 @@ impl Display for EdgeWeights {
         }
     }
 }
-impl EdgeWeight for EdgeWeights {}
+impl EdgeWeight for EdgeWeights {
+    fn as_f32(&self) -> f32 {
+        match self {
+            EdgeWeights::I32(x) => x.as_f32(),
+            EdgeWeights::F32(x) => x.as_f32(),
+        }
+    }
+    fn to_file_string(&self) -> String {
+        match self {
+            EdgeWeights::I32(x) => x.to_file_string(),
+            EdgeWeights::F32(x) => x.to_file_string(),
+        }
+    }
+    fn checked_add(self, rhs: Self) -> Option<Self> {
+        EdgeWeights::checked_add(self, rhs).ok()
+    }
+}
 
 impl From<i32> for EdgeWeights {
     fn from(x: i32) -> Self {
@@ -98,14 +233,45 @@ impl From<f32> for EdgeWeights {
     }
 }
 
+// Цвет вершины (красная, зелёная и синяя составляющие), в тексте записывается как #rrggbb
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct VertexColor(pub u8, pub u8, pub u8);
+
+impl VertexColor {
+    // Цвет в виде составляющих от 0 до 1 с непрозрачностью (для отрисовки)
+    pub fn to_rgba(self) -> [f32; 4] {
+        let VertexColor(r, g, b) = self;
+        [r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0, 1.0]
+    }
+}
+impl Display for VertexColor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "#{:02x}{:02x}{:02x}", self.0, self.1, self.2)
+    }
+}
+// Цвет - символ # и ровно 6 шестнадцатеричных цифр
+impl FromStr for VertexColor {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let hex = s.strip_prefix('#').ok_or(())?;
+        if hex.len() != 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(());
+        }
+        let component = |k: usize| u8::from_str_radix(&hex[k..k + 2], 16).map_err(|_| ());
+        Ok(Self(component(0)?, component(2)?, component(4)?))
+    }
+}
+
 // Вершина графа
 #[derive(Clone, Debug)]
 pub struct Vertex<I>
 where
     I: VertexKey,
 {
-    pub id: I,                 // Идентификатор вершины
-    pub label: Option<String>, // Метка вершины
+    pub id: I,                      // Идентификатор вершины
+    pub label: Option<String>,      // Метка вершины
+    pub color: Option<VertexColor>, // Цвет вершины
 }
 
 // Ребро (дуга) графа
@@ -147,7 +313,7 @@ where
     W: EdgeWeight,
 {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        self.to.partial_cmp(&other.to)
+        Some(self.cmp(other))
     }
 }
 
@@ -168,6 +334,66 @@ where
     }
 }
 
+// Расположение вершин графа (координаты вершин на изображении)
+pub type VertexPositions<I> = BTreeMap<I, (f32, f32)>;
+
+// Статистика графа
+#[derive(Clone, Debug)]
+pub struct GraphStats {
+    pub vertex_count: usize,    // Количество вершин
+    pub edge_count: usize,      // Количество рёбер
+    pub min_degree: usize,      // Минимальная степень вершины
+    pub max_degree: usize,      // Максимальная степень вершины
+    pub avg_degree: f32,        // Средняя степень вершины
+    pub component_count: usize, // Количество компонент (слабой) связности
+    pub has_loops: bool,        // Есть ли в графе петли
+}
+
+// Текст статистики графа (прочерки, если графа нет)
+pub fn stats_text(stats: &Option<GraphStats>, lang: Lang) -> String {
+    let titles = [
+        lang.tr("Количество вершин", "Vertex count"),
+        lang.tr("Количество рёбер", "Edge count"),
+        lang.tr("Минимальная степень вершины", "Minimum vertex degree"),
+        lang.tr("Максимальная степень вершины", "Maximum vertex degree"),
+        lang.tr("Средняя степень вершины", "Average vertex degree"),
+        lang.tr(
+            "Количество компонент связности",
+            "Connected component count",
+        ),
+        lang.tr("Петли", "Loops"),
+    ];
+    let values = match stats {
+        Some(stats) => vec![
+            stats.vertex_count.to_string(),
+            stats.edge_count.to_string(),
+            stats.min_degree.to_string(),
+            stats.max_degree.to_string(),
+            format!("{:.2}", stats.avg_degree),
+            stats.component_count.to_string(),
+            String::from(if stats.has_loops {
+                lang.tr("есть", "yes")
+            } else {
+                lang.tr("нет", "no")
+            }),
+        ],
+        None => vec![String::from("—"); titles.len()],
+    };
+    titles
+        .iter()
+        .zip(values)
+        .map(|(title, value)| format!("{}: {}", title, value))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+// Способ объединения весов противоположных дуг при преобразовании в неориентированный граф
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WeightMerge {
+    Max,   // берётся максимальный вес
+    Error, // разные веса считаются ошибкой
+}
+
 // Граф
 #[derive(Clone, Debug)]
 pub struct Graph<I, W>
@@ -175,59 +401,343 @@ where
     I: VertexKey,
     W: EdgeWeight,
 {
-    vertices: BTreeMap<I, Vertex<I>>,         // Вершины
-    edges: BTreeMap<I, BTreeSet<Edge<I, W>>>, // Рёбра
-    is_directed: bool,                        // Ориентированный ли граф
-    is_weighted: bool,                        // Взвешенный ли граф
-    is_float_weights: bool,                   // Являются ли веса дробными числами
+    vertices: BTreeMap<I, Vertex<I>>,    // Вершины
+    edges: BTreeMap<I, Vec<Edge<I, W>>>, // Рёбра (по возрастанию конечной вершины)
+    is_directed: bool,                   // Ориентированный ли граф
+    is_weighted: bool,                   // Взвешенный ли граф
+    is_float_weights: bool,              // Являются ли веса дробными числами
+    revision: u64,                       // Версия: новая при каждом изменении графа
+}
+
+// Следующая версия графа (версии всех графов различны, совпадают только у копий)
+fn next_revision() -> u64 {
+    static NEXT_REVISION: AtomicU64 = AtomicU64::new(0);
+    NEXT_REVISION.fetch_add(1, atomic::Ordering::Relaxed)
 }
 
 impl<I> Graph<I, EdgeWeights>
 where
     I: VertexKey,
 {
-    // Создание графа из файла
+    /// Создание графа из файла
+    ///
+    /// ```
+    /// use graph_visualizer::{Graph, VertexId};
+    ///
+    /// let text = "undirected unweighted int\nvertices\n1\n2\n3\nedges\n1 2\n2 3\n";
+    /// let g: Graph<VertexId, _> = Graph::from_file(text.as_bytes()).unwrap();
+    /// assert!(!g.get_is_directed());
+    /// assert_eq!(g.get_vertices().len(), 3);
+    ///
+    /// // В заголовке не хватает аргументов
+    /// assert!(Graph::<VertexId, _>::from_file("directed\nvertices\n".as_bytes()).is_err());
+    /// ```
     pub fn from_file<Reader: BufRead>(reader: Reader) -> Result<Self, GraphError> {
+        Self::from_file_with_positions(reader).map(|(g, _)| g)
+    }
+
+    // Создание графа из файла вместе с расположением вершин, если оно сохранено в файле
+    // (вместо вершин и рёбер после строки "matrix" может быть задана матрица смежности:
+    // строка с идентификаторами вершин и по строке матрицы на каждую вершину)
+    pub fn from_file_with_positions<Reader: BufRead>(
+        reader: Reader,
+    ) -> Result<(Self, Option<VertexPositions<I>>), GraphError> {
         enum ReadingState {
             NotCreated,
             ParsingVerticesStart,
             ParsingVertices,
             ParsingEdges,
+            ParsingMatrixVertices,
+            ParsingMatrix,
+            ParsingLayout,
         }
 
         let mut state = ReadingState::NotCreated;
         let mut g = None;
-        for line in reader.lines() {
-            let line_str = line?;
+        let mut positions = None;
+        // Вершины матрицы смежности и количество прочитанных строк матрицы
+        let mut matrix_ids = Vec::new();
+        let mut matrix_row = 0;
+        let mut line_count = 0;
+        for (n, line) in reader.lines().enumerate() {
+            line_count = n + 1;
+            // Ошибка чтения (например, текст не в кодировке UTF-8) тоже дополняется номером строки
+            let line_str = line.map_err(|e| GraphInterfaceError::AtLine {
+                line: n + 1,
+                source: Box::new(e.into()),
+            })?;
             let line_split: Vec<_> = line_str.split_ascii_whitespace().collect();
-            match state {
+            // Пустые строки пропускаются
+            if line_split.is_empty() {
+                continue;
+            }
+            let result: Result<(), GraphError> = match state {
                 // Создание графа
                 ReadingState::NotCreated => {
-                    new_graph(&line_split, &mut g)?;
                     state = ReadingState::ParsingVerticesStart;
+                    new_graph(&line_split, &mut g).map_err(|e| e.into())
                 }
                 // Начало чтения вершин
-                ReadingState::ParsingVerticesStart => match &line_str[..] {
+                ReadingState::ParsingVerticesStart => match line_str.trim() {
                     "vertices" => {
                         state = ReadingState::ParsingVertices;
                         Ok(())
                     }
-                    _ => Err(GraphInterfaceError::WrongParsingVerticesStart),
-                }?,
+                    "matrix" => {
+                        state = ReadingState::ParsingMatrixVertices;
+                        Ok(())
+                    }
+                    _ => Err(GraphInterfaceError::WrongParsingVerticesStart.into()),
+                },
                 // Чтение вершин
-                ReadingState::ParsingVertices => match &line_str[..] {
+                ReadingState::ParsingVertices => match line_str.trim() {
                     "edges" => {
                         state = ReadingState::ParsingEdges;
                         Ok(())
                     }
                     _ => add_vertex(&line_split, &mut g),
-                }?,
+                },
                 // Чтение рёбер
-                ReadingState::ParsingEdges => add_edge(&line_split, &mut g)?,
+                ReadingState::ParsingEdges => match line_str.trim() {
+                    "layout" => {
+                        state = ReadingState::ParsingLayout;
+                        positions = Some(BTreeMap::new());
+                        Ok(())
+                    }
+                    _ => add_edge(&line_split, &mut g),
+                },
+                // Чтение вершин матрицы смежности
+                ReadingState::ParsingMatrixVertices => {
+                    state = ReadingState::ParsingMatrix;
+                    add_matrix_vertices(&line_split, &mut g).map(|ids| matrix_ids = ids)
+                }
+                // После всех строк матрицы может следовать только расположение вершин
+                ReadingState::ParsingMatrix if matrix_row == matrix_ids.len() => {
+                    match line_str.trim() {
+                        "layout" => {
+                            state = ReadingState::ParsingLayout;
+                            positions = Some(BTreeMap::new());
+                            Ok(())
+                        }
+                        _ => Err(GraphInterfaceError::IncorrectArgumentCount.into()),
+                    }
+                }
+                // Чтение строки матрицы смежности
+                ReadingState::ParsingMatrix => {
+                    matrix_row += 1;
+                    add_matrix_row(&line_split, &matrix_ids, matrix_row - 1, &mut g)
+                }
+                // Чтение координат вершин
+                ReadingState::ParsingLayout => {
+                    add_position(&line_split, &g, positions.as_mut().unwrap())
+                }
+            };
+            // Ошибка дополняется номером строки
+            result.map_err(|e| GraphInterfaceError::AtLine {
+                line: n + 1,
+                source: Box::new(e),
+            })?;
+        }
+        // Матрица смежности задана не полностью
+        if matches!(state, ReadingState::ParsingMatrixVertices)
+            || (matches!(state, ReadingState::ParsingMatrix) && matrix_row < matrix_ids.len())
+        {
+            return Err(GraphInterfaceError::AtLine {
+                line: line_count + 1,
+                source: Box::new(GraphInterfaceError::IncorrectArgumentCount.into()),
             }
+            .into());
+        }
+        match g {
+            Some(g) => Ok((g, positions)),
+            None => Err(GraphInterfaceError::EmptyFile.into()),
         }
-        g.ok_or_else(|| GraphInterfaceError::EmptyFile.into())
     }
+
+    // Сохранение графа в формате DOT (вес ребра записывается и как подпись,
+    // чтобы он отображался в Graphviz)
+    pub fn to_dot<Writer: Write>(&self, writer: &mut Writer) -> Result<(), GraphError> {
+        let (graph_str, edge_str) = if self.is_directed {
+            ("digraph", "->")
+        } else {
+            ("graph", "--")
+        };
+        writeln!(writer, "{} G {{", graph_str)?;
+        for v in self.vertices.values() {
+            let id = dot_quote(&v.id.to_string());
+            match &v.label {
+                Some(l) => writeln!(writer, "    {} [label={}];", id, dot_quote(l))?,
+                None => writeln!(writer, "    {};", id)?,
+            };
+        }
+        for (from, edge_list) in &self.edges {
+            for e in edge_list {
+                if !self.is_directed && from > &e.to {
+                    continue;
+                }
+                let (from_id, to_id) = (dot_quote(&from.to_string()), dot_quote(&e.to.to_string()));
+                match &e.weight {
+                    // Дробные веса записываются с точкой, чтобы при чтении тип весов сохранился
+                    Some(w) => writeln!(
+                        writer,
+                        "    {} {} {} [weight=\"{}\", label=\"{}\"];",
+                        from_id,
+                        edge_str,
+                        to_id,
+                        w.to_file_string(),
+                        w
+                    )?,
+                    None => writeln!(writer, "    {} {} {};", from_id, edge_str, to_id)?,
+                }
+            }
+        }
+        writeln!(writer, "}}")?;
+        Ok(())
+    }
+
+    // Сохранение графа в формате GraphML (метки вершин и веса рёбер записываются
+    // как данные с ключами "label" и "weight")
+    pub fn to_graphml<Writer: Write>(&self, writer: &mut Writer) -> Result<(), GraphError> {
+        writeln!(writer, "<?xml version=\"1.0\" encoding=\"UTF-8\"?>")?;
+        writeln!(
+            writer,
+            "<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">"
+        )?;
+        writeln!(
+            writer,
+            "  <key id=\"label\" for=\"node\" attr.name=\"label\" attr.type=\"string\"/>"
+        )?;
+        if self.is_weighted {
+            let weight_type = if self.is_float_weights {
+                "double"
+            } else {
+                "int"
+            };
+            writeln!(
+                writer,
+                "  <key id=\"weight\" for=\"edge\" attr.name=\"weight\" attr.type=\"{}\"/>",
+                weight_type
+            )?;
+        }
+        let edge_default = if self.is_directed {
+            "directed"
+        } else {
+            "undirected"
+        };
+        writeln!(writer, "  <graph edgedefault=\"{}\">", edge_default)?;
+        for v in self.vertices.values() {
+            let id = xml_escape(&v.id.to_string());
+            match &v.label {
+                Some(l) => writeln!(
+                    writer,
+                    "    <node id=\"{}\"><data key=\"label\">{}</data></node>",
+                    id,
+                    xml_escape(l)
+                )?,
+                None => writeln!(writer, "    <node id=\"{}\"/>", id)?,
+            };
+        }
+        for (from, edge_list) in &self.edges {
+            for e in edge_list {
+                if !self.is_directed && from > &e.to {
+                    continue;
+                }
+                let (from_id, to_id) =
+                    (xml_escape(&from.to_string()), xml_escape(&e.to.to_string()));
+                match &e.weight {
+                    Some(w) => writeln!(
+                        writer,
+                        "    <edge source=\"{}\" target=\"{}\"><data key=\"weight\">{}</data></edge>",
+                        from_id,
+                        to_id,
+                        w.to_file_string()
+                    )?,
+                    None => writeln!(
+                        writer,
+                        "    <edge source=\"{}\" target=\"{}\"/>",
+                        from_id, to_id
+                    )?,
+                }
+            }
+        }
+        writeln!(writer, "  </graph>")?;
+        writeln!(writer, "</graphml>")?;
+        Ok(())
+    }
+}
+
+// Графы равны, если совпадают их тип, вершины с метками и рёбра с весами
+impl<I, W> PartialEq for Graph<I, W>
+where
+    I: VertexKey,
+    W: EdgeWeight,
+{
+    fn eq(&self, other: &Self) -> bool {
+        // Рёбра сравниваются только по конечной вершине, поэтому веса сравниваются отдельно
+        let edge_list_eq = |a: &Vec<Edge<I, W>>, b: &Vec<Edge<I, W>>| {
+            a.len() == b.len()
+                && a.iter()
+                    .zip(b)
+                    .all(|(x, y)| x.to == y.to && x.weight == y.weight)
+        };
+        self.is_directed == other.is_directed
+            && self.is_weighted == other.is_weighted
+            && self.is_float_weights == other.is_float_weights
+            && self.vertices.len() == other.vertices.len()
+            && self
+                .vertices
+                .iter()
+                .zip(&other.vertices)
+                .all(|((i, v), (j, u))| i == j && v.label == u.label && v.color == u.color)
+            && self.edges.len() == other.edges.len()
+            && self
+                .edges
+                .iter()
+                .zip(&other.edges)
+                .all(|((i, a), (j, b))| i == j && edge_list_eq(a, b))
+    }
+}
+
+// Номер каждого ребра списка смежности среди параллельных ему рёбер и количество этих рёбер
+pub fn parallel_numbers<I, W>(edge_list: &[Edge<I, W>]) -> Vec<(usize, usize)>
+where
+    I: VertexKey,
+    W: EdgeWeight,
+{
+    let mut numbers = Vec::with_capacity(edge_list.len());
+    let mut start = 0;
+    while start < edge_list.len() {
+        let to = &edge_list[start].to;
+        let count = edge_list[start..]
+            .iter()
+            .take_while(|e| &e.to == to)
+            .count();
+        numbers.extend((0..count).map(|index| (index, count)));
+        start += count;
+    }
+    numbers
+}
+
+// Номера в списке смежности рёбер, ведущих в вершину to (параллельные рёбра идут подряд)
+fn parallel_range<I, W>(edge_list: &[Edge<I, W>], to: &I) -> Range<usize>
+where
+    I: VertexKey,
+    W: EdgeWeight,
+{
+    edge_list.partition_point(|e| &e.to < to)..edge_list.partition_point(|e| &e.to <= to)
+}
+
+// Строка в кавычках для формата DOT
+fn dot_quote(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+// Текст с заменой специальных символов XML ссылками на них
+pub fn xml_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
 }
 
 impl<I, W> Graph<I, W>
@@ -243,6 +753,7 @@ where
             is_directed,
             is_weighted,
             is_float_weights,
+            revision: next_revision(),
         }
     }
 
@@ -270,19 +781,23 @@ where
         )?;
         writeln!(writer, "vertices")?;
         for v in self.vertices.values() {
-            match &v.label {
-                Some(l) => writeln!(writer, "{} {}", v.id, l)?,
-                None => writeln!(writer, "{}", v.id)?,
-            };
+            write!(writer, "{}", v.id)?;
+            if let Some(l) = &v.label {
+                write!(writer, " {}", l)?;
+            }
+            if let Some(c) = &v.color {
+                write!(writer, " {}", c)?;
+            }
+            writeln!(writer)?;
         }
         writeln!(writer, "edges")?;
-        for (from, edge_set) in &self.edges {
-            for e in edge_set {
+        for (from, edge_list) in &self.edges {
+            for e in edge_list {
                 if !self.is_directed && from > &e.to {
                     continue;
                 }
                 match &e.weight {
-                    Some(w) => writeln!(writer, "{} {} {}", from, e.to, w)?,
+                    Some(w) => writeln!(writer, "{} {} {}", from, e.to, w.to_file_string())?,
                     None => writeln!(writer, "{} {}", from, e.to)?,
                 }
             }
@@ -302,6 +817,11 @@ where
         self.is_float_weights
     }
 
+    // Версия графа: алгоритмы сравнивают её вместо всего графа, чтобы обнаружить изменение
+    pub fn get_revision(&self) -> u64 {
+        self.revision
+    }
+
     // Получение вершин
     pub fn get_vertices(&self) -> &BTreeMap<I, Vertex<I>> {
         &self.vertices
@@ -319,43 +839,70 @@ where
         if self.vertices.contains_key(&v.id) {
             Err(GraphOperationError::VertexExists)
         } else {
-            self.edges.insert(v.id.clone(), BTreeSet::new());
+            self.edges.insert(v.id.clone(), Vec::new());
             self.vertices.insert(v.id.clone(), v);
+            self.revision = next_revision();
             Ok(())
         }
     }
 
+    // Изменение метки вершины
+    pub fn set_vertex_label(
+        &mut self,
+        i: &I,
+        label: Option<String>,
+    ) -> Result<(), GraphOperationError> {
+        self.vertices
+            .get_mut(i)
+            .ok_or(GraphOperationError::VertexNotFound)?
+            .label = label;
+        self.revision = next_revision();
+        Ok(())
+    }
+
     // Удаление вершины
     pub fn remove_vertex(&mut self, i: &I) -> Result<(), GraphOperationError> {
         if !self.vertices.contains_key(i) {
             return Err(GraphOperationError::VertexNotFound);
         }
-        let rev_e = Edge::new(i.clone(), None);
-        for to in self.vertices.keys() {
-            if let Some(x) = self.edges.get_mut(to) {
-                x.remove(&rev_e);
-            }
+        for edge_list in self.edges.values_mut() {
+            edge_list.retain(|e| &e.to != i);
         }
         self.edges.remove(i);
         self.vertices.remove(i);
+        self.revision = next_revision();
         Ok(())
     }
 
-    // Получение списка смежности вершины
-    pub fn get_edge_list(&self, from: &I) -> Result<&BTreeSet<Edge<I, W>>, GraphOperationError> {
+    // Получение списка смежности вершины (параллельные рёбра идут подряд в порядке добавления)
+    pub fn get_edge_list(&self, from: &I) -> Result<&Vec<Edge<I, W>>, GraphOperationError> {
         self.edges
             .get(from)
             .ok_or(GraphOperationError::VertexNotFound)
     }
 
-    // Получение ребра
+    // Получение ребра (первого из параллельных рёбер)
     pub fn get_edge(&self, from: &I, to: &I) -> Result<&Edge<I, W>, GraphOperationError> {
-        self.get_edge_list(from)?
-            .get(&Edge::new(to.clone(), None))
+        self.get_edges(from, to)?
+            .first()
             .ok_or(GraphOperationError::EdgeNotFound)
     }
 
-    // Добавление ребра
+    // Получение всех параллельных рёбер между вершинами (пустой список, если рёбер нет)
+    pub fn get_edges(&self, from: &I, to: &I) -> Result<&[Edge<I, W>], GraphOperationError> {
+        let edge_list = self.get_edge_list(from)?;
+        Ok(&edge_list[parallel_range(edge_list, to)])
+    }
+
+    // Добавление ребра в список смежности после параллельных ему рёбер
+    fn insert_edge(&mut self, from: &I, e: Edge<I, W>) {
+        let edge_list = self.edges.get_mut(from).unwrap();
+        let pos = edge_list.partition_point(|x| x.to <= e.to);
+        edge_list.insert(pos, e);
+        self.revision = next_revision();
+    }
+
+    // Добавление ребра (если такое ребро уже есть, то добавляется параллельное ему ребро)
     pub fn add_edge(&mut self, from: I, e: Edge<I, W>) -> Result<(), GraphOperationError> {
         if e.weight.is_some() && !self.is_weighted {
             return Err(GraphOperationError::WeightedEdgeInUnweightedGraph);
@@ -366,37 +913,986 @@ where
         if !self.vertices.contains_key(&from) || !self.vertices.contains_key(&e.to) {
             return Err(GraphOperationError::SomeVerticesNotFound);
         }
-        if self.is_directed {
-            if self.edges[&from].contains(&e) {
-                return Err(GraphOperationError::EdgeExists);
-            }
-            self.edges.get_mut(&from).unwrap().insert(e);
-            Ok(())
-        } else {
+        // Ребро неориентированного графа хранится в обоих направлениях, а петля один раз
+        if !self.is_directed && from != e.to {
             let rev_e = Edge::new(from.clone(), e.weight.clone());
-            if self.edges[&from].contains(&e) || self.edges[&e.to].contains(&rev_e) {
-                return Err(GraphOperationError::EdgeExists);
-            }
-            self.edges.get_mut(&e.to).unwrap().insert(rev_e);
-            self.edges.get_mut(&from).unwrap().insert(e);
-            Ok(())
+            self.insert_edge(&e.to.clone(), rev_e);
         }
+        self.insert_edge(&from, e);
+        Ok(())
     }
 
-    // Удаление ребра
-    pub fn remove_edge(&mut self, from: &I, to: &I) -> Result<(), GraphOperationError> {
+    // Изменение веса ребра (первого из параллельных рёбер)
+    pub fn set_edge_weight(
+        &mut self,
+        from: &I,
+        to: &I,
+        weight: Option<W>,
+    ) -> Result<(), GraphOperationError> {
+        self.set_parallel_edge_weight(from, to, 0, weight)
+    }
+
+    // Изменение веса параллельного ребра с заданным номером (у ребра неориентированного графа
+    // номера в обоих направлениях совпадают, так как параллельные рёбра хранятся
+    // в порядке добавления)
+    pub fn set_parallel_edge_weight(
+        &mut self,
+        from: &I,
+        to: &I,
+        index: usize,
+        weight: Option<W>,
+    ) -> Result<(), GraphOperationError> {
+        if weight.is_some() && !self.is_weighted {
+            return Err(GraphOperationError::WeightedEdgeInUnweightedGraph);
+        }
+        if weight.is_none() && self.is_weighted {
+            return Err(GraphOperationError::UnweightedEdgeInWeightedGraph);
+        }
         if !self.vertices.contains_key(from) || !self.vertices.contains_key(to) {
             return Err(GraphOperationError::SomeVerticesNotFound);
         }
-        let e = Edge::new(to.clone(), None);
-        if !self.edges[from].contains(&e) {
+        let range = parallel_range(&self.edges[from], to);
+        if index >= range.len() {
             return Err(GraphOperationError::EdgeNotFound);
         }
-        self.edges.get_mut(from).unwrap().remove(&e);
+        self.edges.get_mut(from).unwrap()[range.start + index].weight = weight.clone();
         if !self.is_directed {
-            let rev_e = Edge::new(from.clone(), None);
-            self.edges.get_mut(to).unwrap().remove(&rev_e);
+            let rev_start = parallel_range(&self.edges[to], from).start;
+            self.edges.get_mut(to).unwrap()[rev_start + index].weight = weight;
         }
+        self.revision = next_revision();
         Ok(())
     }
+
+    // Удаление ребра вместе со всеми параллельными ему рёбрами
+    pub fn remove_edge(&mut self, from: &I, to: &I) -> Result<(), GraphOperationError> {
+        if !self.vertices.contains_key(from) || !self.vertices.contains_key(to) {
+            return Err(GraphOperationError::SomeVerticesNotFound);
+        }
+        let range = parallel_range(&self.edges[from], to);
+        if range.is_empty() {
+            return Err(GraphOperationError::EdgeNotFound);
+        }
+        self.edges.get_mut(from).unwrap().drain(range);
+        if !self.is_directed && from != to {
+            let rev_range = parallel_range(&self.edges[to], from);
+            self.edges.get_mut(to).unwrap().drain(rev_range);
+        }
+        self.revision = next_revision();
+        Ok(())
+    }
+
+    // Слияние вершины remove с вершиной keep: рёбра удаляемой вершины переносятся на keep,
+    // совпавшие с уже существующими рёбрами отбрасываются (или, если sum_weights и граф
+    // взвешенный, их веса прибавляются к весам существующих), а получившиеся петли
+    // остаются, только если keep_loops; при переполнении суммы весов граф не изменяется
+    pub fn merge_vertices(
+        &mut self,
+        keep: &I,
+        remove: &I,
+        sum_weights: bool,
+        keep_loops: bool,
+    ) -> Result<(), GraphOperationError> {
+        if !self.vertices.contains_key(keep) || !self.vertices.contains_key(remove) {
+            return Err(GraphOperationError::SomeVerticesNotFound);
+        }
+        if keep == remove {
+            return Err(GraphOperationError::SameVertices);
+        }
+        // Рёбра удаляемой вершины: ребро неориентированного графа хранится у обоих концов,
+        // поэтому достаточно списка удаляемой вершины, а входящие дуги ориентированного графа
+        // хранятся у их начал
+        let mut moved: Vec<(I, I, Option<W>)> = self.edges[remove]
+            .iter()
+            .map(|e| (remove.clone(), e.to.clone(), e.weight.clone()))
+            .collect();
+        if self.is_directed {
+            for (from, edge_list) in &self.edges {
+                if from == remove {
+                    continue;
+                }
+                for e in &edge_list[parallel_range(edge_list, remove)] {
+                    moved.push((from.clone(), remove.clone(), e.weight.clone()));
+                }
+            }
+        }
+        // Слияние выполняется на копии графа, которая заменяет граф только при успехе
+        let mut g = self.clone();
+        g.remove_vertex(remove)?;
+
+        let replace = |i: I| if &i == remove { keep.clone() } else { i };
+        for (from, to, weight) in moved {
+            let (from, to) = (replace(from), replace(to));
+            if from == to && !keep_loops {
+                continue;
+            }
+            match g.get_edge(&from, &to).map(|e| e.weight.clone()) {
+                Ok(Some(old_weight)) if sum_weights && g.is_weighted => {
+                    let weight = match weight {
+                        Some(w) => Some(
+                            old_weight
+                                .checked_add(w)
+                                .ok_or(GraphOperationError::WeightOverflow)?,
+                        ),
+                        None => None,
+                    };
+                    g.set_edge_weight(&from, &to, weight)?;
+                }
+                Ok(_) => {}
+                Err(_) => g.add_edge(from, Edge::new(to, weight))?,
+            }
+        }
+        *self = g;
+        Ok(())
+    }
+
+    // Ориентированный граф, в котором каждое ребро заменено двумя противоположными дугами
+    pub fn to_directed(&self) -> Self {
+        // Рёбра неориентированного графа уже хранятся в обоих направлениях, петли один раз
+        Self {
+            is_directed: true,
+            revision: next_revision(),
+            ..self.clone()
+        }
+    }
+
+    // Неориентированный граф, в котором противоположные дуги объединены в одно ребро
+    // (k-я из параллельных дуг объединяется с k-й из противоположных им дуг)
+    pub fn to_undirected(&self, merge: WeightMerge) -> Result<Self, GraphOperationError> {
+        let mut g = Self::new(false, self.is_weighted, self.is_float_weights);
+        g.vertices = self.vertices.clone();
+        for i in self.vertices.keys() {
+            g.edges.insert(i.clone(), Vec::new());
+        }
+        for (from, edge_list) in &self.edges {
+            let mut ends: Vec<&I> = edge_list.iter().map(|e| &e.to).collect();
+            ends.dedup();
+            for to in ends {
+                let (edges, rev_edges) = (
+                    &edge_list[parallel_range(edge_list, to)],
+                    &self.edges[to][parallel_range(&self.edges[to], from)],
+                );
+                // Пара вершин, между которыми есть дуги в обе стороны, рассматривается один раз
+                if from > to && !rev_edges.is_empty() {
+                    continue;
+                }
+                for k in 0..edges.len().max(rev_edges.len()) {
+                    // Вес дуги, объединённый с весом противоположной дуги, если она есть
+                    let weight = match (edges.get(k), rev_edges.get(k)) {
+                        (Some(e), Some(rev_e)) => match merge {
+                            WeightMerge::Max => e.weight.clone().max(rev_e.weight.clone()),
+                            WeightMerge::Error if rev_e.weight != e.weight => {
+                                return Err(GraphOperationError::ConflictingWeights {
+                                    from: from.to_string(),
+                                    to: to.to_string(),
+                                })
+                            }
+                            WeightMerge::Error => e.weight.clone(),
+                        },
+                        (Some(e), None) | (None, Some(e)) => e.weight.clone(),
+                        (None, None) => unreachable!(),
+                    };
+                    g.add_edge(from.clone(), Edge::new(to.clone(), weight))?;
+                }
+            }
+        }
+        Ok(g)
+    }
+
+    // Транспонированный граф, в котором все дуги заменены противоположными с теми же весами
+    // (петли не изменяются, неориентированный граф совпадает со своим транспонированным)
+    pub fn transpose(&self) -> Self {
+        if !self.is_directed {
+            return self.clone();
+        }
+        let mut g = Self::new(true, self.is_weighted, self.is_float_weights);
+        g.vertices = self.vertices.clone();
+        for i in self.vertices.keys() {
+            g.edges.insert(i.clone(), Vec::new());
+        }
+        for (from, edge_list) in &self.edges {
+            for e in edge_list {
+                g.insert_edge(&e.to, Edge::new(from.clone(), e.weight.clone()));
+            }
+        }
+        g
+    }
+
+    // Компоненты слабой связности: номер компоненты для каждой вершины
+    pub fn components(&self) -> BTreeMap<I, usize> {
+        // Соседи вершин без учёта направления рёбер
+        let mut adj: BTreeMap<&I, Vec<&I>> =
+            self.vertices.keys().map(|i| (i, Vec::new())).collect();
+        for (from, edges) in &self.edges {
+            for e in edges {
+                adj.get_mut(from).unwrap().push(&e.to);
+                adj.get_mut(&e.to).unwrap().push(from);
+            }
+        }
+
+        // Обход в глубину из каждой ещё не посещённой вершины
+        let mut component = BTreeMap::new();
+        let mut cnt = 0;
+        for s in self.vertices.keys() {
+            if component.contains_key(s) {
+                continue;
+            }
+            component.insert(s.clone(), cnt);
+            let mut stack = vec![s];
+            while let Some(i) = stack.pop() {
+                for &to in &adj[i] {
+                    if !component.contains_key(to) {
+                        component.insert(to.clone(), cnt);
+                        stack.push(to);
+                    }
+                }
+            }
+            cnt += 1;
+        }
+        component
+    }
+
+    // Компоненты сильной связности (алгоритм Косарайю): номер компоненты для каждой вершины;
+    // у неориентированного графа они совпадают с компонентами связности
+    pub fn strong_components(&self) -> BTreeMap<I, usize> {
+        if !self.is_directed {
+            return self.components();
+        }
+
+        // Вершины в порядке выхода из них при обходе в глубину
+        let mut visited = BTreeSet::new();
+        let mut order = Vec::new();
+        for s in self.vertices.keys() {
+            if !visited.insert(s) {
+                continue;
+            }
+            // Стек вершин текущего пути с оставшимися исходящими дугами
+            let mut stack = vec![(s, self.edges[s].iter())];
+            while let Some((i, edges)) = stack.last_mut() {
+                match edges.next() {
+                    Some(e) => {
+                        if visited.insert(&e.to) {
+                            stack.push((&e.to, self.edges[&e.to].iter()));
+                        }
+                    }
+                    None => {
+                        order.push(*i);
+                        stack.pop();
+                    }
+                }
+            }
+        }
+
+        // Обход транспонированного графа из вершин в порядке, обратном порядку выхода
+        let gt = self.transpose();
+        let mut component = BTreeMap::new();
+        let mut cnt = 0;
+        for s in order.into_iter().rev() {
+            if component.contains_key(s) {
+                continue;
+            }
+            component.insert(s.clone(), cnt);
+            let mut stack = vec![s];
+            while let Some(i) = stack.pop() {
+                for e in &gt.edges[i] {
+                    if !component.contains_key(&e.to) {
+                        component.insert(e.to.clone(), cnt);
+                        stack.push(&e.to);
+                    }
+                }
+            }
+            cnt += 1;
+        }
+        component
+    }
+
+    // Топологическая сортировка вершин ориентированного графа алгоритмом Кана (вершины
+    // без входящих дуг по очереди удаляются из графа); если есть цикл, то возвращается
+    // ошибка с вершинами одного из циклов в порядке обхода по дугам
+    pub fn topological_sort(&self) -> Result<Vec<I>, GraphAlgorithmError> {
+        if !self.is_directed {
+            return Err(GraphAlgorithmError::GraphNotDirected);
+        }
+        // Количество входящих дуг в ещё не удалённые вершины из ещё не удалённых вершин
+        let mut in_degree: BTreeMap<&I, usize> = self.vertices.keys().map(|i| (i, 0)).collect();
+        for edge_list in self.edges.values() {
+            for e in edge_list {
+                *in_degree.get_mut(&e.to).unwrap() += 1;
+            }
+        }
+        let mut queue: VecDeque<&I> = in_degree
+            .iter()
+            .filter(|(_, &d)| d == 0)
+            .map(|(&i, _)| i)
+            .collect();
+        let mut order = Vec::new();
+        while let Some(i) = queue.pop_front() {
+            order.push(i.clone());
+            for e in &self.edges[i] {
+                let d = in_degree.get_mut(&e.to).unwrap();
+                *d -= 1;
+                if *d == 0 {
+                    queue.push_back(&e.to);
+                }
+            }
+        }
+        if order.len() == self.vertices.len() {
+            return Ok(order);
+        }
+
+        // В каждую оставшуюся вершину входит дуга из оставшейся вершины, поэтому переход
+        // по таким дугам назад из любой оставшейся вершины приводит в цикл
+        let mut prev: BTreeMap<&I, &I> = BTreeMap::new();
+        for (i, edge_list) in &self.edges {
+            if in_degree[i] > 0 {
+                for e in edge_list {
+                    if in_degree[&e.to] > 0 {
+                        prev.insert(&e.to, i);
+                    }
+                }
+            }
+        }
+        let mut path = vec![*prev.keys().next().unwrap()];
+        let mut visited: BTreeMap<&I, usize> = BTreeMap::from([(path[0], 0)]);
+        loop {
+            let i = prev[path.last().unwrap()];
+            if let Some(&start) = visited.get(i) {
+                // Путь пройден против дуг, поэтому цикл разворачивается
+                let cycle = path[start..].iter().rev().map(|i| i.to_string()).collect();
+                return Err(GraphAlgorithmError::GraphHasCycle { cycle });
+            }
+            visited.insert(i, path.len());
+            path.push(i);
+        }
+    }
+
+    // Поиск вершин по тексту: вершина с таким идентификатором, а если её нет, то вершины,
+    // метки которых содержат текст (в порядке идентификаторов)
+    pub fn find_vertices(&self, text: &str) -> Vec<I> {
+        if let Ok(i) = text.parse::<I>() {
+            if self.vertices.contains_key(&i) {
+                return vec![i];
+            }
+        }
+        self.vertices
+            .values()
+            .filter(|v| matches!(&v.label, Some(label) if label.contains(text)))
+            .map(|v| v.id.clone())
+            .collect()
+    }
+
+    // Степени вершин (для ориентированного графа сумма полустепеней захода и исхода,
+    // петля увеличивает степень на 2)
+    pub fn degrees(&self) -> BTreeMap<I, usize> {
+        let mut degree: BTreeMap<I, usize> = self.vertices.keys().map(|i| (i.clone(), 0)).collect();
+        for (from, edges) in &self.edges {
+            for e in edges {
+                *degree.get_mut(from).unwrap() += 1;
+                // В неориентированном графе ребро хранится в обоих направлениях, а петля один раз
+                if self.is_directed || &e.to == from {
+                    *degree.get_mut(&e.to).unwrap() += 1;
+                }
+            }
+        }
+        degree
+    }
+
+    // Статистика графа
+    pub fn stats(&self) -> GraphStats {
+        let degree = self.degrees();
+        let has_loops = self
+            .edges
+            .iter()
+            .any(|(from, edges)| edges.iter().any(|e| &e.to == from));
+
+        let degree_sum: usize = degree.values().sum();
+        let vertex_count = self.vertices.len();
+        GraphStats {
+            vertex_count,
+            edge_count: degree_sum / 2,
+            min_degree: degree.values().copied().min().unwrap_or(0),
+            max_degree: degree.values().copied().max().unwrap_or(0),
+            avg_degree: if vertex_count == 0 {
+                0.0
+            } else {
+                degree_sum as f32 / vertex_count as f32
+            },
+            component_count: self.components().values().max().map_or(0, |x| x + 1),
+            has_loops,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type TestGraph = Graph<VertexId, EdgeWeights>;
+
+    // Идентификатор вершины
+    fn id(name: &str) -> VertexId {
+        name.parse().unwrap()
+    }
+
+    // Граф из текста в собственном формате
+    fn graph(text: &str) -> TestGraph {
+        Graph::from_file(text.as_bytes()).unwrap()
+    }
+
+    #[test]
+    fn stats_count_undirected_edges_once() {
+        let g = graph(
+            "undirected unweighted int\nvertices\n1\n2\n3\n4\n5\nedges\n1 2\n2 3\n3 1\n4 4\n",
+        );
+        let stats = g.stats();
+        assert_eq!(stats.vertex_count, 5);
+        assert_eq!(stats.edge_count, 4);
+        assert_eq!((stats.min_degree, stats.max_degree), (0, 2));
+        assert_eq!(stats.avg_degree, 1.6);
+        assert_eq!(stats.component_count, 3);
+        assert!(stats.has_loops);
+        assert_eq!(g.degrees()[&id("1")], 2);
+        assert_eq!(g.degrees()[&id("4")], 2);
+    }
+
+    #[test]
+    fn stats_count_directed_arcs() {
+        let g = graph("directed unweighted int\nvertices\n1\n2\n3\n4\nedges\n1 2\n2 1\n2 3\n");
+        let stats = g.stats();
+        assert_eq!(stats.vertex_count, 4);
+        assert_eq!(stats.edge_count, 3);
+        assert_eq!((stats.min_degree, stats.max_degree), (0, 3));
+        assert_eq!(stats.avg_degree, 1.5);
+        assert_eq!(stats.component_count, 2);
+        assert!(!stats.has_loops);
+
+        // Те же рёбра в неориентированном графе: противоположные дуги дают одно ребро
+        let g = graph("undirected unweighted int\nvertices\n1\n2\n3\n4\nedges\n1 2\n2 3\n");
+        let stats = g.stats();
+        assert_eq!(stats.edge_count, 2);
+        assert_eq!((stats.min_degree, stats.max_degree), (0, 2));
+    }
+
+    #[test]
+    fn stats_of_empty_graph() {
+        let stats = TestGraph::new(true, false, false).stats();
+        assert_eq!(stats.vertex_count, 0);
+        assert_eq!(stats.edge_count, 0);
+        assert_eq!(stats.avg_degree, 0.0);
+        assert_eq!(stats.component_count, 0);
+    }
+
+    // Номер строки и ошибка разбора текста графа
+    fn parse_error(text: &str) -> (usize, GraphError) {
+        match TestGraph::from_file(text.as_bytes()) {
+            Err(GraphError::InterfaceError(GraphInterfaceError::AtLine { line, source })) => {
+                (line, *source)
+            }
+            res => panic!("ожидалась ошибка с номером строки: {:?}", res.err()),
+        }
+    }
+
+    #[test]
+    fn parse_errors_have_line_numbers() {
+        // Неправильный вес
+        let (line, e) = parse_error("directed weighted int\nvertices\n1\n2\nedges\n1 2 x\n");
+        assert_eq!(line, 6);
+        assert!(matches!(
+            e,
+            GraphError::InterfaceError(GraphInterfaceError::IncorrectArgument { i: 3 })
+        ));
+        // Дробный вес в графе с целыми весами (пустые строки тоже считаются)
+        let (line, _) = parse_error("directed weighted int\n\nvertices\n1\n2\nedges\n1 2 1.5\n");
+        assert_eq!(line, 7);
+
+        // Неизвестный раздел вместо вершин
+        let (line, e) = parse_error("directed weighted int\nnodes\n1\n");
+        assert_eq!(line, 2);
+        assert!(matches!(
+            e,
+            GraphError::InterfaceError(GraphInterfaceError::WrongParsingVerticesStart)
+        ));
+        // Неизвестный раздел после рёбер
+        let (line, e) = parse_error("directed unweighted int\nvertices\n1\nedges\npositions\n");
+        assert_eq!(line, 5);
+        assert!(matches!(
+            e,
+            GraphError::InterfaceError(GraphInterfaceError::IncorrectArgumentCount)
+        ));
+
+        // Повторяющаяся вершина
+        let (line, e) = parse_error("undirected unweighted int\nvertices\n1\n2\n1\nedges\n");
+        assert_eq!(line, 5);
+        assert!(matches!(
+            e,
+            GraphError::OperationError(GraphOperationError::VertexExists)
+        ));
+
+        // Лишние слова в строке ребра и лишняя строка после матрицы смежности
+        let (line, e) = parse_error("directed weighted int\nvertices\n1\n2\nedges\n1 2 3 4\n");
+        assert_eq!(line, 6);
+        assert!(matches!(
+            e,
+            GraphError::InterfaceError(GraphInterfaceError::IncorrectArgumentCount)
+        ));
+        let (line, e) = parse_error("directed unweighted int\nmatrix\n1 2\n0 1\n0 0\n1 0\n");
+        assert_eq!(line, 6);
+        assert!(matches!(
+            e,
+            GraphError::InterfaceError(GraphInterfaceError::IncorrectArgumentCount)
+        ));
+    }
+
+    #[test]
+    fn error_text_contains_line_number() {
+        let e =
+            TestGraph::from_file("directed weighted int\nvertices\n1\n1\n".as_bytes()).unwrap_err();
+        assert!(e.to_string().contains(" 4: "));
+    }
+
+    // Вес ребра
+    fn weight(g: &TestGraph, from: &str, to: &str) -> Option<EdgeWeights> {
+        g.get_edge(&id(from), &id(to)).unwrap().weight.clone()
+    }
+
+    #[test]
+    fn set_edge_weight_is_symmetric_in_undirected_graph() {
+        let mut g = graph("undirected weighted int\nvertices\n1\n2\n3\nedges\n1 2 5\n2 3 7\n");
+        g.set_edge_weight(&id("2"), &id("1"), Some(9.into()))
+            .unwrap();
+        assert_eq!(weight(&g, "1", "2"), Some(9.into()));
+        assert_eq!(weight(&g, "2", "1"), Some(9.into()));
+        assert_eq!(weight(&g, "3", "2"), Some(7.into()));
+
+        // В ориентированном графе изменяется только одна дуга
+        let mut g = graph("directed weighted int\nvertices\n1\n2\nedges\n1 2 5\n2 1 5\n");
+        g.set_edge_weight(&id("1"), &id("2"), Some(9.into()))
+            .unwrap();
+        assert_eq!(weight(&g, "1", "2"), Some(9.into()));
+        assert_eq!(weight(&g, "2", "1"), Some(5.into()));
+    }
+
+    #[test]
+    fn set_parallel_edge_weight_changes_one_edge() {
+        let mut g = graph("undirected weighted int\nvertices\n1\n2\nedges\n1 2 5\n1 2 6\n");
+        g.set_parallel_edge_weight(&id("2"), &id("1"), 1, Some(8.into()))
+            .unwrap();
+        let weights = |from: &str, to: &str| -> Vec<_> {
+            g.get_edges(&id(from), &id(to))
+                .unwrap()
+                .iter()
+                .map(|e| e.weight.clone().unwrap())
+                .collect()
+        };
+        assert_eq!(weights("1", "2"), [5.into(), 8.into()]);
+        assert_eq!(weights("2", "1"), [5.into(), 8.into()]);
+    }
+
+    #[test]
+    fn set_edge_weight_errors() {
+        let mut g = graph("undirected weighted int\nvertices\n1\n2\n3\nedges\n1 2 5\n");
+        assert!(matches!(
+            g.set_edge_weight(&id("1"), &id("2"), None),
+            Err(GraphOperationError::UnweightedEdgeInWeightedGraph)
+        ));
+        assert!(matches!(
+            g.set_edge_weight(&id("1"), &id("4"), Some(1.into())),
+            Err(GraphOperationError::SomeVerticesNotFound)
+        ));
+        assert!(matches!(
+            g.set_edge_weight(&id("1"), &id("3"), Some(1.into())),
+            Err(GraphOperationError::EdgeNotFound)
+        ));
+        assert!(matches!(
+            g.set_parallel_edge_weight(&id("1"), &id("2"), 1, Some(1.into())),
+            Err(GraphOperationError::EdgeNotFound)
+        ));
+        assert_eq!(weight(&g, "2", "1"), Some(5.into()));
+
+        let mut g = graph("directed unweighted int\nvertices\n1\n2\nedges\n1 2\n");
+        assert!(matches!(
+            g.set_edge_weight(&id("1"), &id("2"), Some(1.into())),
+            Err(GraphOperationError::WeightedEdgeInUnweightedGraph)
+        ));
+        g.set_edge_weight(&id("1"), &id("2"), None).unwrap();
+    }
+
+    #[test]
+    fn set_vertex_label() {
+        let mut g = graph("directed unweighted int\nvertices\n1 a\n2\nedges\n");
+        g.set_vertex_label(&id("2"), Some(String::from("b")))
+            .unwrap();
+        g.set_vertex_label(&id("1"), None).unwrap();
+        assert_eq!(g.get_vertices()[&id("1")].label, None);
+        assert_eq!(g.get_vertices()[&id("2")].label.as_deref(), Some("b"));
+        assert!(matches!(
+            g.set_vertex_label(&id("3"), None),
+            Err(GraphOperationError::VertexNotFound)
+        ));
+    }
+
+    // Текст графа в собственном формате
+    fn to_text(g: &TestGraph) -> String {
+        let mut text = Vec::new();
+        g.to_file(&mut text).unwrap();
+        String::from_utf8(text).unwrap()
+    }
+
+    #[test]
+    fn float_weights_round_trip() {
+        let g = graph(
+            "directed weighted float\nvertices\n1\n2\n3\n4\nedges\n\
+             1 2 0.1\n2 3 1e-7\n3 4 1e9\n4 1 0.30000001\n1 3 1\n",
+        );
+        let text = to_text(&g);
+        let g2 = graph(&text);
+        assert_eq!(g, g2);
+        assert_eq!(to_text(&g2), text);
+        assert_eq!(weight(&g2, "1", "2"), Some(0.1.into()));
+        assert_eq!(weight(&g2, "2", "3"), Some(1e-7.into()));
+        assert_eq!(weight(&g2, "3", "4"), Some(1e9.into()));
+        assert_eq!(weight(&g2, "1", "3"), Some(1.0.into()));
+    }
+
+    #[test]
+    fn changed_weight_makes_graphs_different() {
+        let g = graph("directed weighted float\nvertices\n1\n2\nedges\n1 2 0.1\n");
+        let mut g2 = g.clone();
+        g2.set_edge_weight(&id("1"), &id("2"), Some(0.100_001.into()))
+            .unwrap();
+        assert_ne!(g, g2);
+    }
+
+    #[test]
+    fn revision_changes_on_every_modification() {
+        let mut g = graph("directed weighted int\nvertices\n1\n2\nedges\n1 2 5\n");
+        let mut revision = g.get_revision();
+        assert_eq!(g.clone().get_revision(), revision);
+        let mut assert_changed = |g: &TestGraph| {
+            assert_ne!(g.get_revision(), revision);
+            revision = g.get_revision();
+        };
+
+        g.add_vertex(Vertex {
+            id: id("3"),
+            label: None,
+            color: None,
+        })
+        .unwrap();
+        assert_changed(&g);
+        g.add_edge(id("2"), Edge::new(id("3"), Some(1.into())))
+            .unwrap();
+        assert_changed(&g);
+        g.set_edge_weight(&id("1"), &id("2"), Some(6.into()))
+            .unwrap();
+        assert_changed(&g);
+        g.set_vertex_label(&id("1"), Some(String::from("a")))
+            .unwrap();
+        assert_changed(&g);
+        g.remove_edge(&id("2"), &id("3")).unwrap();
+        assert_changed(&g);
+        g.merge_vertices(&id("1"), &id("3"), false, false).unwrap();
+        assert_changed(&g);
+        g.remove_vertex(&id("2")).unwrap();
+        assert_changed(&g);
+        assert_changed(&g.to_directed());
+
+        // Неудачное изменение не меняет версию
+        let revision = g.get_revision();
+        assert!(g.remove_vertex(&id("2")).is_err());
+        assert_eq!(g.get_revision(), revision);
+    }
+
+    // Граф после слияния вершины 3 с вершиной 1
+    fn merged(text: &str, sum_weights: bool, keep_loops: bool) -> TestGraph {
+        let mut g = graph(text);
+        g.merge_vertices(&id("1"), &id("3"), sum_weights, keep_loops)
+            .unwrap();
+        g
+    }
+
+    #[test]
+    fn merge_vertices_in_undirected_graph() {
+        let text =
+            "undirected weighted int\nvertices\n1\n2\n3\n4\nedges\n1 2 3\n3 2 4\n3 4 5\n1 3 7\n";
+        // Совпавшее ребро отбрасывается, ребро между слитыми вершинами исчезает
+        assert_eq!(
+            merged(text, false, false),
+            graph("undirected weighted int\nvertices\n1\n2\n4\nedges\n1 2 3\n1 4 5\n")
+        );
+        // Веса совпавших рёбер складываются
+        assert_eq!(
+            merged(text, true, false),
+            graph("undirected weighted int\nvertices\n1\n2\n4\nedges\n1 2 7\n1 4 5\n")
+        );
+        // Ребро между слитыми вершинами становится петлёй
+        assert_eq!(
+            merged(text, true, true),
+            graph("undirected weighted int\nvertices\n1\n2\n4\nedges\n1 2 7\n1 4 5\n1 1 7\n")
+        );
+    }
+
+    #[test]
+    fn merge_vertices_in_directed_graph() {
+        let text =
+            "directed weighted int\nvertices\n1\n2\n3\nedges\n1 2 3\n3 2 4\n2 3 1\n1 3 7\n3 1 2\n";
+        // Входящие и исходящие дуги удаляемой вершины переносятся с сохранением направления
+        assert_eq!(
+            merged(text, false, false),
+            graph("directed weighted int\nvertices\n1\n2\nedges\n1 2 3\n2 1 1\n")
+        );
+        // Обе дуги между слитыми вершинами становятся одной петлёй с суммарным весом
+        assert_eq!(
+            merged(text, true, true),
+            graph("directed weighted int\nvertices\n1\n2\nedges\n1 2 7\n2 1 1\n1 1 9\n")
+        );
+    }
+
+    #[test]
+    fn merge_vertices_in_unweighted_graph() {
+        // Складывать нечего, совпавшие рёбра просто отбрасываются
+        let text = "undirected unweighted int\nvertices\n1\n2\n3\nedges\n1 2\n3 2\n1 3\n";
+        assert_eq!(
+            merged(text, true, true),
+            graph("undirected unweighted int\nvertices\n1\n2\nedges\n1 2\n1 1\n")
+        );
+    }
+
+    #[test]
+    fn merge_vertices_errors() {
+        for text in [
+            "undirected weighted int\nvertices\n1\n2\nedges\n1 2 3\n",
+            "directed weighted int\nvertices\n1\n2\nedges\n1 2 3\n",
+        ] {
+            let mut g = graph(text);
+            let revision = g.get_revision();
+            assert!(matches!(
+                g.merge_vertices(&id("1"), &id("1"), true, true),
+                Err(GraphOperationError::SameVertices)
+            ));
+            assert!(matches!(
+                g.merge_vertices(&id("1"), &id("5"), true, true),
+                Err(GraphOperationError::SomeVerticesNotFound)
+            ));
+            assert_eq!(g, graph(text));
+            assert_eq!(g.get_revision(), revision);
+        }
+    }
+
+    #[test]
+    fn merge_vertices_weight_overflow() {
+        let text =
+            "undirected weighted int\nvertices\n1\n2\n3\nedges\n1 2 2000000000\n3 2 2000000000\n";
+        let mut g = graph(text);
+        let revision = g.get_revision();
+        assert!(matches!(
+            g.merge_vertices(&id("1"), &id("3"), true, false),
+            Err(GraphOperationError::WeightOverflow)
+        ));
+        // Граф не изменился
+        assert_eq!(g, graph(text));
+        assert_eq!(g.get_revision(), revision);
+        // Без сложения весов переполнения нет
+        g.merge_vertices(&id("1"), &id("3"), false, false).unwrap();
+    }
+
+    // Проверка, что порядок содержит все вершины и все дуги ведут вперёд
+    fn assert_topological(g: &TestGraph, order: &[VertexId]) {
+        assert_eq!(order.len(), g.get_vertices().len());
+        let pos: BTreeMap<_, _> = order.iter().enumerate().map(|(k, i)| (i, k)).collect();
+        for i in g.get_vertices().keys() {
+            for e in g.get_edge_list(i).unwrap() {
+                assert!(pos[i] < pos[&e.to], "{} -> {}", i, e.to);
+            }
+        }
+    }
+
+    // Цикл, найденный топологической сортировкой
+    fn find_cycle(g: &TestGraph) -> Vec<VertexId> {
+        match g.topological_sort() {
+            Err(GraphAlgorithmError::GraphHasCycle { cycle }) => {
+                cycle.iter().map(|i| id(i)).collect()
+            }
+            _ => panic!("цикл не найден"),
+        }
+    }
+
+    // Проверка, что вершины образуют простой цикл по дугам графа
+    fn assert_cycle(g: &TestGraph, cycle: &[VertexId]) {
+        assert!(!cycle.is_empty());
+        assert_eq!(cycle.iter().collect::<BTreeSet<_>>().len(), cycle.len());
+        for (k, i) in cycle.iter().enumerate() {
+            let to = &cycle[(k + 1) % cycle.len()];
+            assert!(g.get_edge(i, to).is_ok(), "{} -> {}", i, to);
+        }
+    }
+
+    #[test]
+    fn topological_sort_of_dag() {
+        let g = graph(
+            "directed unweighted int\nvertices\n1\n2\n3\n4\n5\n6\nedges\n\
+             5 1\n1 2\n1 3\n3 2\n2 4\n6 4\n1 4\n",
+        );
+        let order = g.topological_sort().unwrap();
+        assert_topological(&g, &order);
+        // Граф без рёбер: любой порядок вершин
+        let g = graph("directed unweighted int\nvertices\n1\n2\nedges\n");
+        assert_eq!(g.topological_sort().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn topological_sort_finds_loop() {
+        let g = graph("directed unweighted int\nvertices\n1\n2\nedges\n1 2\n2 2\n");
+        assert_eq!(find_cycle(&g), [id("2")]);
+    }
+
+    #[test]
+    fn topological_sort_finds_cycle() {
+        // Цикл 2 -> 3 -> 4 -> 2 и вершины вне его
+        let g = graph(
+            "directed unweighted int\nvertices\n1\n2\n3\n4\n5\nedges\n\
+             1 2\n2 3\n3 4\n4 2\n4 5\n",
+        );
+        let cycle = find_cycle(&g);
+        assert_cycle(&g, &cycle);
+        assert_eq!(
+            cycle.iter().collect::<BTreeSet<_>>(),
+            [id("2"), id("3"), id("4")].iter().collect()
+        );
+    }
+
+    #[test]
+    fn cycle_error_lists_cycle() {
+        let g = graph("directed unweighted int\nvertices\n1\n2\n3\nedges\n1 2\n2 3\n3 1\n");
+        let text = g.topological_sort().unwrap_err().to_string();
+        assert!(
+            text.ends_with(": 1 → 2 → 3 → 1!")
+                || text.ends_with(": 2 → 3 → 1 → 2!")
+                || text.ends_with(": 3 → 1 → 2 → 3!"),
+            "{}",
+            text
+        );
+    }
+
+    #[test]
+    fn topological_sort_of_undirected_graph_is_error() {
+        let g = graph("undirected unweighted int\nvertices\n1\n2\nedges\n1 2\n");
+        assert!(matches!(
+            g.topological_sort(),
+            Err(GraphAlgorithmError::GraphNotDirected)
+        ));
+    }
+
+    #[test]
+    fn topological_sort_finds_long_cycle() {
+        const N: usize = 10_000;
+        let mut text = String::from("directed unweighted int\nvertices\n");
+        text += &(1..=N).map(|k| format!("{}\n", k)).collect::<String>();
+        text += "edges\n";
+        text += &(1..=N)
+            .map(|k| format!("{} {}\n", k, k % N + 1))
+            .collect::<String>();
+        let g = graph(&text);
+        let cycle = find_cycle(&g);
+        assert_eq!(cycle.len(), N);
+        assert_cycle(&g, &cycle);
+    }
+
+    #[test]
+    fn transpose_reverses_arcs() {
+        let g = graph(
+            "directed weighted int\nvertices\n1 a\n2\n3\nedges\n\
+             1 2 4\n2 1 6\n2 3 1\n2 3 2\n3 3 5\n",
+        );
+        let t = g.transpose();
+        assert_eq!(t.transpose(), g);
+        // Противоположные дуги остаются противоположными, веса меняются местами
+        assert_eq!(weight(&t, "2", "1"), Some(4.into()));
+        assert_eq!(weight(&t, "1", "2"), Some(6.into()));
+        // Параллельные дуги сохраняют порядок, петля не изменяется
+        let weights: Vec<_> = t
+            .get_edges(&id("3"), &id("2"))
+            .unwrap()
+            .iter()
+            .map(|e| e.weight.clone().unwrap())
+            .collect();
+        assert_eq!(weights, [1.into(), 2.into()]);
+        assert!(t.get_edge(&id("2"), &id("3")).is_err());
+        assert_eq!(weight(&t, "3", "3"), Some(5.into()));
+        assert_eq!(t.get_vertices()[&id("1")].label.as_deref(), Some("a"));
+        assert_eq!(t.stats().edge_count, g.stats().edge_count);
+    }
+
+    #[test]
+    fn transpose_of_undirected_graph_is_same() {
+        let g = graph("undirected weighted int\nvertices\n1\n2\nedges\n1 2 3\n2 2 1\n");
+        assert_eq!(g.transpose(), g);
+    }
+
+    #[test]
+    fn to_directed_and_back() {
+        let g = graph("undirected weighted int\nvertices\n1\n2\n3\nedges\n1 2 3\n3 3 1\n");
+        let d = g.to_directed();
+        assert!(d.get_is_directed());
+        assert_eq!(weight(&d, "1", "2"), Some(3.into()));
+        assert_eq!(weight(&d, "2", "1"), Some(3.into()));
+        assert_eq!(d.get_edges(&id("3"), &id("3")).unwrap().len(), 1);
+        assert_eq!(d.stats().edge_count, 3);
+        assert_eq!(d.transpose(), d);
+        assert_eq!(d.to_undirected(WeightMerge::Error).unwrap(), g);
+
+        // Противоположные дуги с разными весами
+        let d = graph("directed weighted int\nvertices\n1\n2\nedges\n1 2 3\n2 1 5\n");
+        assert!(matches!(
+            d.to_undirected(WeightMerge::Error),
+            Err(GraphOperationError::ConflictingWeights { .. })
+        ));
+        let g = d.to_undirected(WeightMerge::Max).unwrap();
+        assert_eq!(weight(&g, "1", "2"), Some(5.into()));
+        assert_eq!(g.stats().edge_count, 1);
+    }
+
+    #[test]
+    fn checked_weight_arithmetic() {
+        let w = |x: i32| EdgeWeights::from(x);
+        assert_eq!(w(2).checked_add(w(3)).unwrap(), w(5));
+        assert_eq!(w(2).checked_sub(w(3)).unwrap(), w(-1));
+        assert!(matches!(
+            w(i32::MAX).checked_add(w(1)),
+            Err(GraphAlgorithmError::WeightOverflow)
+        ));
+        assert!(matches!(
+            w(i32::MIN).checked_sub(w(1)),
+            Err(GraphAlgorithmError::WeightOverflow)
+        ));
+        assert!(matches!(
+            w(1).checked_add(1.5.into()),
+            Err(GraphAlgorithmError::MixedWeightTypes)
+        ));
+        assert_eq!(
+            EdgeWeights::from(1.5).checked_add(2.0.into()).unwrap(),
+            3.5.into()
+        );
+    }
+
+    #[test]
+    fn edge_weights_must_match_graph_type() {
+        // Ребро без веса во взвешенном графе (пропускная способность не задана)
+        let (line, e) = parse_error("directed weighted int\nvertices\ns\nt\nedges\ns t 2\nt s\n");
+        assert_eq!(line, 7);
+        assert!(matches!(
+            e,
+            GraphError::OperationError(GraphOperationError::UnweightedEdgeInWeightedGraph)
+        ));
+        // Ребро с весом в невзвешенном графе
+        let (line, e) = parse_error("directed unweighted int\nvertices\ns\nt\nedges\ns t 2\n");
+        assert_eq!(line, 6);
+        assert!(matches!(
+            e,
+            GraphError::OperationError(GraphOperationError::WeightedEdgeInUnweightedGraph)
+        ));
+        // Отрицательный вес допустим в графе, но не как пропускная способность
+        let g = graph("directed weighted int\nvertices\ns\nt\nedges\ns t -2\n");
+        assert_eq!(weight(&g, "s", "t"), Some((-2).into()));
+    }
 }
@@ -0,0 +1,105 @@
+use crate::{
+    graph::{EdgeWeight, EdgeWeights, Graph, VertexKey},
+    graph_errors::GraphError,
+    graph_flows::{self, AlgorithmState, MatchingState, PathStrategy},
+    graph_paths::{self, ShortestPathState},
+    graph_trees::{self, SpanningTreeState},
+};
+
+// Алгоритм, выполняемый над графом
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GraphAlgorithm {
+    MaxFlow,      // нахождение максимального потока
+    ShortestPath, // нахождение кратчайшего пути
+    Matching,     // нахождение максимального паросочетания в двудольном графе
+    SpanningTree, // нахождение минимального остова
+}
+
+// Состояние выполнения выбранного алгоритма
+#[derive(Debug, Clone)]
+pub enum GraphAlgorithmState<I, W>
+where
+    I: VertexKey,
+    W: EdgeWeight,
+{
+    MaxFlow(AlgorithmState<I, W>),
+    ShortestPath(ShortestPathState<I, W>),
+    Matching(MatchingState<I>),
+    SpanningTree(SpanningTreeState<I, W>),
+}
+
+impl<I, W> GraphAlgorithmState<I, W>
+where
+    I: VertexKey,
+    W: EdgeWeight,
+{
+    // Состояние незапущенного алгоритма
+    pub fn new(algorithm: GraphAlgorithm) -> Self {
+        match algorithm {
+            GraphAlgorithm::MaxFlow => Self::MaxFlow(AlgorithmState::NotStarted),
+            GraphAlgorithm::ShortestPath => Self::ShortestPath(ShortestPathState::NotStarted),
+            GraphAlgorithm::Matching => Self::Matching(MatchingState::NotStarted),
+            GraphAlgorithm::SpanningTree => Self::SpanningTree(SpanningTreeState::NotStarted),
+        }
+    }
+
+    // Выполняемый алгоритм
+    pub fn get_algorithm(&self) -> GraphAlgorithm {
+        match self {
+            Self::MaxFlow(_) => GraphAlgorithm::MaxFlow,
+            Self::ShortestPath(_) => GraphAlgorithm::ShortestPath,
+            Self::Matching(_) => GraphAlgorithm::Matching,
+            Self::SpanningTree(_) => GraphAlgorithm::SpanningTree,
+        }
+    }
+
+    pub fn is_not_started(&self) -> bool {
+        matches!(
+            self,
+            Self::MaxFlow(AlgorithmState::NotStarted)
+                | Self::ShortestPath(ShortestPathState::NotStarted)
+                | Self::Matching(MatchingState::NotStarted)
+                | Self::SpanningTree(SpanningTreeState::NotStarted)
+        )
+    }
+
+    pub fn is_finished(&self) -> bool {
+        matches!(
+            self,
+            Self::MaxFlow(AlgorithmState::Finished(_))
+                | Self::ShortestPath(ShortestPathState::Finished(_))
+                | Self::Matching(MatchingState::Finished(_))
+                | Self::SpanningTree(SpanningTreeState::Finished(_))
+        )
+    }
+}
+
+// Шаг выбранного алгоритма (вершины левой доли используются только при нахождении паросочетания,
+// способ поиска пути и единичные пропускные способности — только при нахождении потока)
+pub fn algorithm_step<I>(
+    state: GraphAlgorithmState<I, EdgeWeights>,
+    g: &Option<Graph<I, EdgeWeights>>,
+    s_str: &str,
+    t_str: &str,
+    left_str: &str,
+    strategy: PathStrategy,
+    unit_capacities: bool,
+) -> Result<GraphAlgorithmState<I, EdgeWeights>, GraphError>
+where
+    I: VertexKey,
+{
+    match state {
+        GraphAlgorithmState::MaxFlow(state) => Ok(GraphAlgorithmState::MaxFlow(
+            graph_flows::algorithm_step(state, g, s_str, t_str, strategy, unit_capacities)?,
+        )),
+        GraphAlgorithmState::ShortestPath(state) => Ok(GraphAlgorithmState::ShortestPath(
+            graph_paths::algorithm_step(state, g, s_str, t_str)?,
+        )),
+        GraphAlgorithmState::Matching(state) => Ok(GraphAlgorithmState::Matching(
+            graph_flows::matching_step(state, g, left_str)?,
+        )),
+        GraphAlgorithmState::SpanningTree(state) => Ok(GraphAlgorithmState::SpanningTree(
+            graph_trees::algorithm_step(state, g)?,
+        )),
+    }
+}
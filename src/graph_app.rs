@@ -1,12 +1,20 @@
 use std::{
-    cell::RefCell,
-    fs::File,
-    io::{BufReader, BufWriter},
-    path::PathBuf,
+    cell::{Cell, RefCell},
+    collections::{BTreeMap, BTreeSet, VecDeque},
+    path::{Path, PathBuf},
+    str::FromStr,
+    time::Duration,
 };
 
 use glutin::event_loop::EventLoopProxy;
-use gtk::{traits::TextBufferExt, TextBuffer};
+use gtk::{
+    gdk::RGBA,
+    gio,
+    glib::{self, translate::IntoGlib, Receiver, SourceId},
+    prelude::{Cast, FileChooserExt, GtkListStoreExtManual, IsA, ListModelExt},
+    traits::{GtkListStoreExt, TextBufferExt},
+    FileChooser, FileFilter, ListStore, TextBuffer,
+};
 use relm4::{AppUpdate, Components, Model, RelmComponent, Sender};
 use relm4_components::{
     open_dialog::{OpenDialogModel, OpenDialogMsg},
@@ -14,14 +22,31 @@ use relm4_components::{
 };
 
 use crate::{
-    graph::{EdgeWeights, Graph},
-    graph_errors::{GraphError, GraphInterfaceError},
-    graph_flows::{algorithm_step, AlgorithmState},
-    graph_parser::{add_edge, add_vertex, remove_edge, remove_vertex},
+    graph::{
+        parallel_numbers, Edge, EdgeWeights, Graph, GraphStats, VertexColor, VertexId,
+        VertexPositions, WeightMerge,
+    },
+    graph_algorithms::{algorithm_step, GraphAlgorithm, GraphAlgorithmState},
+    graph_errors::{GraphAlgorithmError, GraphError, GraphInterfaceError, GraphOperationError},
+    graph_examples::EXAMPLES,
+    graph_flows::{AlgorithmState, PathStrategy},
+    graph_generators::{gen_complete, gen_grid, gen_random_gnp},
+    graph_loader::{GraphLoader, GraphSource, ParseResult},
+    graph_parser::{
+        add_edge_random, add_edges_bulk, add_vertex, graph_to_path, merge_vertices, remove_edge,
+        remove_vertex, set_edge_weight, set_random_weights, set_vertex_label, FileFormat,
+    },
+    graph_report::{flow_rows, FlowReport},
+    graph_runner::{AlgorithmRunner, RunProgress, RunResult},
+    settings::Settings,
+    strings::Lang,
+    text_diff::line_edits,
 };
 
 use self::{
     app_widgets::AppWidgets,
+    confirm_dialog::{ConfirmDialogModel, ConfirmDialogMsg},
+    discard_dialog::{DiscardDialogModel, DiscardDialogMsg},
     error_dialog::{ErrorDialogModel, ErrorDialogMsg},
     graph_window::GraphWindowMsg,
     open_dialog::OpenDialogConfig,
@@ -29,6 +54,8 @@ use self::{
 };
 
 mod app_widgets;
+mod confirm_dialog;
+mod discard_dialog;
 mod error_dialog;
 pub mod graph_window;
 mod open_dialog;
@@ -43,6 +70,152 @@ pub struct AppComponents {
     save_dialog: RelmComponent<SaveDialogModel<SaveDialogConfig>, AppModel>,
     // Диалог сообщения об ошибке
     error_dialog: RelmComponent<ErrorDialogModel, AppModel>,
+    // Диалог подтверждения замены изменённого текста графа
+    confirm_dialog: RelmComponent<ConfirmDialogModel, AppModel>,
+    // Диалог подтверждения загрузки примера вместо несохранённого графа
+    discard_dialog: RelmComponent<DiscardDialogModel, AppModel>,
+}
+
+// Максимальное количество сохранённых изменений для отмены
+const UNDO_LIMIT: usize = 100;
+// Максимальное количество сохранённых шагов алгоритма для возврата
+const ALGORITHM_HISTORY_LIMIT: usize = 100;
+// Имя тега для выделения строки текста графа с ошибкой
+const ERROR_LINE_TAG: &str = "error-line";
+
+// Сохранённое состояние алгоритма вместе с отчётом о нахождении потока на этот момент
+type AlgorithmHistoryEntry = (
+    GraphAlgorithmState<VertexId, EdgeWeights>,
+    FlowReport<VertexId>,
+);
+
+// Что сохраняется в файл, выбранный в диалоге сохранения
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum SaveFileKind {
+    Graph,  // граф
+    Image,  // изображение графа
+    Svg,    // изображение графа в формате SVG
+    Report, // отчёт о выполнении алгоритма нахождения потока
+}
+
+// Поле, в которое записывается вершина, выбранная мышью в окне графа
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum VertexPickTarget {
+    FirstEmpty, // первое пустое поле
+    Vertex0,    // поле вершины
+    Vertex1,    // поле вершины №1
+    Vertex2,    // поле вершины №2
+    Source,     // поле истока
+    Sink,       // поле стока
+    LeftPart,   // поле вершин левой доли (вершина добавляется к списку)
+}
+
+// Генератор графа
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum GraphGenerator {
+    RandomGnp, // случайный граф G(n, p)
+    Grid,      // решётка
+    Complete,  // полный граф
+}
+
+// Разбор числового значения поля с номером i
+fn parse_field<T: FromStr>(text: &str, i: usize) -> Result<T, GraphInterfaceError> {
+    text.trim()
+        .parse()
+        .map_err(|_| GraphInterfaceError::IncorrectArgument { i })
+}
+
+// Следующий свободный числовой идентификатор вершины (больше всех числовых идентификаторов)
+fn next_vertex_id(g: &Graph<VertexId, EdgeWeights>) -> String {
+    g.get_vertices()
+        .keys()
+        .filter_map(|i| i.as_str().parse::<i64>().ok())
+        .max()
+        .map_or(1, |max| max.saturating_add(1))
+        .to_string()
+}
+
+// Рёбра в порядке строк списка рёбер: начало, ребро и его номер среди параллельных ему рёбер
+// (ребро неориентированного графа выводится один раз)
+fn edge_rows(
+    g: &Graph<VertexId, EdgeWeights>,
+) -> Vec<(&VertexId, &Edge<VertexId, EdgeWeights>, usize)> {
+    let mut rows = Vec::new();
+    for i in g.get_vertices().keys() {
+        let edge_list = g.get_edge_list(i).unwrap();
+        for (e, (index, _)) in edge_list.iter().zip(parallel_numbers(edge_list)) {
+            if g.get_is_directed() || i <= &e.to {
+                rows.push((i, e, index));
+            }
+        }
+    }
+    rows
+}
+
+// Преобразование цвета GTK в цвет для отрисовки графа
+fn femtovg_color(color: &RGBA) -> femtovg::Color {
+    femtovg::Color::rgbaf(color.red(), color.green(), color.blue(), color.alpha())
+}
+
+// Преобразование цвета GTK в цвет для файла настроек
+fn settings_color(color: &RGBA) -> [f32; 4] {
+    [color.red(), color.green(), color.blue(), color.alpha()]
+}
+
+// Преобразование цвета GTK в цвет вершины графа
+fn vertex_color(color: &RGBA) -> VertexColor {
+    let byte = |x: f32| (x.clamp(0.0, 1.0) * 255.0).round() as u8;
+    VertexColor(byte(color.red()), byte(color.green()), byte(color.blue()))
+}
+
+// Преобразование цвета из файла настроек в цвет GTK
+fn rgba_color(color: &[f32; 4]) -> RGBA {
+    RGBA::new(color[0], color[1], color[2], color[3])
+}
+
+// Загрузка графа, выполняемая в фоновом потоке
+struct GraphLoading {
+    loader: GraphLoader, // фоновый разбор графа (результаты отменённых загрузок пропускаются)
+    from_text: bool,     // загружается ли граф из текста (иначе из файла)
+}
+
+// Выполнение алгоритма до конца в фоновом потоке
+struct AlgorithmRun {
+    runner: AlgorithmRunner, // фоновое выполнение шагов
+    progress: RunProgress,   // ход выполнения из последнего сообщения
+    prev_state: GraphAlgorithmState<VertexId, EdgeWeights>, // состояние до запуска
+    prev_report: FlowReport<VertexId>, // отчёт до запуска
+}
+
+// Изменяет ли сообщение граф
+fn changes_graph(msg: &AppMsg) -> bool {
+    matches!(
+        msg,
+        AppMsg::OpenFile(_)
+            | AppMsg::OpenExample
+            | AppMsg::LoadExample(_)
+            | AppMsg::UpdateGraph
+            | AppMsg::NewGraph
+            | AppMsg::ToDirected
+            | AppMsg::ToUndirected
+            | AppMsg::Transpose
+            | AppMsg::GenerateGraph
+            | AppMsg::RandomizeWeights
+            | AppMsg::AddVertex
+            | AppMsg::AddVertexAt(..)
+            | AppMsg::DeleteVertex
+            | AppMsg::AddEdge
+            | AppMsg::AddEdgeBetween(..)
+            | AppMsg::AddEdgeList
+            | AppMsg::SetVertexLabel(..)
+            | AppMsg::SetEdgeWeight(..)
+            | AppMsg::EditEdgeWeight(..)
+            | AppMsg::ChangeEdgeWeight
+            | AppMsg::DeleteEdge
+            | AppMsg::MergeVertices
+            | AppMsg::Undo
+            | AppMsg::Redo
+    )
 }
 
 // Модель данных приложения
@@ -50,43 +223,145 @@ pub struct AppModel {
     new_graph_is_directed: bool,      // будет ли новый граф ориентированным
     new_graph_is_weighted: bool,      // будет ли новый граф взвешенным
     new_graph_is_float_weights: bool, // будут ли у нового графа дробные веса
+    merge_max_weight: bool,           // брать ли максимальный вес противоположных дуг
+    merge_sum_weights: bool,          // складывать ли веса совпавших рёбер при слиянии вершин
+    merge_keep_loops: bool,           // оставлять ли петли при слиянии вершин
     vertex0_text: String,             // текст поля №0 вершины (для создания/удаления вершины)
     vertex1_text: String,             // текст поля №1
     vertex2_text: String,             // и текст поля №2 вершин (для создания/удаления рёбер)
     label_text: String,               // текст поля метки вершины (для создания/удаления вершины)
+    vertex_color_set: bool,           // задаётся ли цвет новой вершины
+    vertex_color: RGBA,               // цвет новой вершины
     weight_text: String,              // текст поля веса ребра (для создания/удаления рёбер)
+    edited_edge: Option<(VertexId, VertexId, usize)>, // ребро, вес которого изменяется
+    focus_weight_entry: Cell<bool>,   // нужно ли передать фокус полю веса
+    edge_list_text: String,           // текст списка рёбер (для добавления нескольких рёбер)
     source_text: String,              // текст поля истока
     sink_text: String,                // текст поля стока
+    left_text: String,                // текст поля вершин левой доли (для паросочетания)
+    save_file_kind: SaveFileKind,     // что сохраняется в выбранный файл
+    path_strategy: PathStrategy,      // способ поиска дополняющего пути
+    unit_capacities: bool,            // равны ли 1 пропускные способности невзвешенного графа
+    graph_updates_stopped: bool,      // прекращены ли обновления изображения графа
+    vertex_pick_target: VertexPickTarget, // поле для вершины, выбранной мышью
+    front_color: RGBA,                // основной цвет изображения графа
+    back_color: RGBA,                 // фоновый цвет изображения графа
+    selection_color: RGBA,            // цвет выделения на изображении графа
+    graph_generator: GraphGenerator,  // выбранный генератор графа
+    gen_param1_text: String,          // текст поля количества вершин или ширины решётки
+    gen_param2_text: String,          // текст поля вероятности ребра или высоты решётки
+    gen_weight_min_text: String,      // текст поля минимального веса генерируемых рёбер
+    gen_weight_max_text: String,      // текст поля максимального веса генерируемых рёбер
+    gen_seed_text: String,            // текст поля зерна генератора случайных чисел
+    search_text: String,              // текст поля поиска вершины
+    last_search_text: String,         // текст последнего поиска вершины
+    search_index: usize,              // номер показанной вершины среди найденных
+    example: usize,                   // номер выбранного примера графа
+    lang: Lang,                       // язык интерфейса
 
-    graph: Option<Graph<i32, EdgeWeights>>,  // граф
-    graph_text: RefCell<Option<TextBuffer>>, // граф в текстовом виде
-    graph_algorithm_state: AlgorithmState<i32, EdgeWeights>, // состояние выполнения алгоритма
-    graph_algorithm_started: bool,           // запущен ли алгоритм
+    graph: Option<Graph<VertexId, EdgeWeights>>, // граф
+    graph_text: RefCell<Option<TextBuffer>>,     // граф в текстовом виде
+    graph_text_modified: bool,                   // изменён ли текст графа пользователем
+    graph_unsaved: bool,                         // изменён ли граф после загрузки или сохранения
+    vertex_list: RefCell<Option<ListStore>>,     // список вершин графа
+    edge_list: RefCell<Option<ListStore>>,       // список рёбер графа
+    flow_list: RefCell<Option<ListStore>>,       // таблица потоков через дуги
+    show_residual_arcs: bool,                    // выводить ли в таблице потоков обратные дуги
+    show_topological_numbers: bool,              // выводить ли номера вершин в порядке сортировки
+    graph_stats: Option<GraphStats>,             // статистика графа
+    topological_sort: Option<Result<Vec<VertexId>, GraphAlgorithmError>>, // результат топологической сортировки
+    undo_stack: VecDeque<Option<Graph<VertexId, EdgeWeights>>>, // графы до изменений (для отмены)
+    redo_stack: Vec<Option<Graph<VertexId, EdgeWeights>>>,      // отменённые графы (для повтора)
+    graph_algorithm_state: GraphAlgorithmState<VertexId, EdgeWeights>, // состояние выполнения алгоритма
+    graph_algorithm_started: bool,                                     // запущен ли алгоритм
+    algorithm_history: VecDeque<AlgorithmHistoryEntry>, // предыдущие состояния алгоритма
+    flow_report: FlowReport<VertexId>, // отчёт о шагах нахождения максимального потока
+    algorithm_play_timer: Option<SourceId>, // таймер автоматического выполнения алгоритма
+    settings: Settings,                // настройки, сохраняемые между запусками
+    closing: bool,                     // закрывается ли приложение (после закрытия окна графа)
+    graph_loading: Option<GraphLoading>, // текущая загрузка графа в фоновом потоке
+    graph_loading_cnt: u64,            // количество запущенных загрузок графа
+    algorithm_run: Option<AlgorithmRun>, // выполнение алгоритма до конца
+    algorithm_run_cnt: u64,            // количество запущенных выполнений алгоритма до конца
 
     graph_window_proxy: EventLoopProxy<GraphWindowMsg>, // Прокси для передачи событий в поток окна графа
+    graph_window_receiver: RefCell<Option<Receiver<AppMsg>>>, // Получатель событий из потока окна графа
 }
 
 impl AppModel {
     // Инициализация модели данных
-    pub fn new(graph_window_proxy: EventLoopProxy<GraphWindowMsg>) -> Self {
+    pub fn new(
+        graph_window_proxy: EventLoopProxy<GraphWindowMsg>,
+        graph_window_receiver: Receiver<AppMsg>,
+        settings: Settings,
+    ) -> Self {
         Self {
             new_graph_is_directed: false,
             new_graph_is_weighted: false,
             new_graph_is_float_weights: false,
+            merge_max_weight: false,
+            merge_sum_weights: false,
+            merge_keep_loops: false,
             vertex0_text: String::new(),
             vertex1_text: String::new(),
             vertex2_text: String::new(),
             label_text: String::new(),
+            vertex_color_set: false,
+            vertex_color: RGBA::new(1.0, 0.0, 0.0, 1.0),
             weight_text: String::new(),
+            edited_edge: None,
+            focus_weight_entry: Cell::new(false),
+            edge_list_text: String::new(),
             source_text: String::new(),
             sink_text: String::new(),
+            left_text: String::new(),
+            save_file_kind: SaveFileKind::Graph,
+            path_strategy: PathStrategy::Dfs,
+            unit_capacities: false,
+            graph_updates_stopped: false,
+            vertex_pick_target: VertexPickTarget::FirstEmpty,
+            front_color: RGBA::new(1.0, 1.0, 1.0, 1.0),
+            back_color: RGBA::new(0.0, 0.0, 0.0, 1.0),
+            selection_color: rgba_color(&settings.selection_color),
+            graph_generator: GraphGenerator::RandomGnp,
+            gen_param1_text: String::new(),
+            gen_param2_text: String::new(),
+            gen_weight_min_text: String::new(),
+            gen_weight_max_text: String::new(),
+            gen_seed_text: String::new(),
+            search_text: String::new(),
+            last_search_text: String::new(),
+            search_index: 0,
+            example: 0,
+            lang: Lang::current(),
 
             graph: None,
             graph_text: RefCell::new(None),
-            graph_algorithm_state: AlgorithmState::NotStarted,
+            graph_text_modified: false,
+            graph_unsaved: false,
+            vertex_list: RefCell::new(None),
+            edge_list: RefCell::new(None),
+            flow_list: RefCell::new(None),
+            show_residual_arcs: false,
+            graph_stats: None,
+            topological_sort: None,
+            show_topological_numbers: false,
+            undo_stack: VecDeque::new(),
+            redo_stack: Vec::new(),
+            graph_algorithm_state: GraphAlgorithmState::new(GraphAlgorithm::MaxFlow),
             graph_algorithm_started: false,
+            algorithm_history: VecDeque::new(),
+            flow_report: FlowReport::new(),
+            algorithm_play_timer: None,
+            settings,
+            closing: false,
+            graph_loading: None,
+            graph_loading_cnt: 0,
+            algorithm_run: None,
+            algorithm_run_cnt: 0,
 
             graph_window_proxy,
+            graph_window_receiver: RefCell::new(Some(graph_window_receiver)),
         }
     }
 }
@@ -96,37 +371,119 @@ pub enum AppMsg {
     ToggleNewGraphIsDirected(bool), // переключение флага ориентированности нового графа
     ToggleNewGraphIsWeighted(bool), // переключение флага взвешенности нового графа
     ToggleNewGraphIsFloatWeights(bool), // переключение флага типа весов нового графа
+    ToggleMergeMaxWeight(bool),     // переключение флага объединения весов противоположных дуг
+    ToggleMergeSumWeights(bool),    // переключение флага сложения весов при слиянии вершин
+    ToggleMergeKeepLoops(bool),     // переключение флага сохранения петель при слиянии вершин
     ChangeVertex0Text(String),      // изменение текста поля №0 вершины
     ChangeVertex1Text(String),      // изменение текста поля №1 вершины
     ChangeVertex2Text(String),      // изменение текста поля №2 вершины
     ChangeLabelText(String),        // изменение текста поля метки вершины
     ChangeWeightText(String),       // изменение текста поля веса ребра
+    ChangeEdgeListText(String),     // изменение текста списка рёбер
     ChangeSourceText(String),       // изменение текста поля истока
     ChangeSinkText(String),         // изменение текста поля стока
+    ChangeLeftText(String),         // изменение текста поля вершин левой доли
+    SetAlgorithm(GraphAlgorithm),   // выбор алгоритма
+    SetPathStrategy(PathStrategy),  // выбор способа поиска дополняющего пути
+    ToggleUnitCapacities(bool),     // переключение единичных пропускных способностей
+    SetVertexPickTarget(VertexPickTarget), // выбор поля для вершины, выбранной мышью
+    SetLang(Lang),                  // выбор языка интерфейса
     ChangeCenterGravityValue(f32),  // изменение значения гравитации к центру
     ChangeRepulsiveForceValue(f32), // изменение значения силы отталкивания вершин
     ChangeTimeStepValue(f32),       // изменение значения скорости изменений
     ChangeThetaValue(f32),          // изменение значения погрешности симуляции
+    ChangeSettleEpsilonValue(f32),  // изменение значения порога остановки симуляции
     ToggleFullRender(bool),         // переключение флага полной отрисовки
+    ToggleShowEdgeLabels(bool),     // переключение флага вывода подписей рёбер
+    ToggleColorByComponent(bool),   // переключение флага раскраски по компонентам связности
+    ToggleEdgeWidthByWeight(bool),  // переключение флага толщины рёбер по весу
+    ToggleSizeByDegree(bool),       // переключение флага размера вершин по степени
+    ToggleShowGrid(bool),           // переключение флага вывода сетки
+    ToggleShowCursorCoords(bool),   // переключение флага вывода координат курсора
+    ToggleAnimateFlow(bool),        // переключение флага анимации направления потока
+    ChangeEdgeLabelOffsetValue(f32), // изменение значения отступа подписей рёбер
+    ChangeArrowSizeValue(f32),      // изменение значения длины стрелок дуг
+    ChangeLodLabelDiameterValue(f32), // изменение порога диаметра вершин для скрытия текста
+    ChangeImageScaleValue(f32),     // изменение масштаба сохраняемого изображения PNG
     ToggleGraphUpdateStop(bool),    // переключение флага прекращения обновлений графа
+    GraphUpdateStopChanged(bool),   // флаг прекращения обновлений графа изменён в окне графа
+    VertexPicked(VertexId),         // вершина выбрана мышью в окне графа
+    AddVertexAt(f32, f32),          // добавление вершины в точке окна графа (двойным щелчком)
+    AddEdgeBetween(VertexId, VertexId), // добавление ребра, протянутого мышью в окне графа
+    EditEdgeWeight(VertexId, VertexId, usize), // начало изменения веса ребра (двойным щелчком)
+    ThemeColorChanged(RGBA),        // изменение цвета текста темы GTK
+    SetFrontColor(RGBA),            // выбор основного цвета изображения графа
+    SetBackColor(RGBA),             // выбор фонового цвета изображения графа
+    SetSelectionColor(RGBA),        // выбор цвета выделения
+    ToggleVertexColorSet(bool),     // переключение флага задания цвета новой вершины
+    SetVertexColor(RGBA),           // выбор цвета новой вершины
+    SetGraphGenerator(GraphGenerator), // выбор генератора графа
+    ChangeGenParam1Text(String),    // изменение текста поля количества вершин или ширины решётки
+    ChangeGenParam2Text(String),    // изменение текста поля вероятности ребра или высоты решётки
+    ChangeGenWeightMinText(String), // изменение текста поля минимального веса
+    ChangeGenWeightMaxText(String), // изменение текста поля максимального веса
+    ChangeGenSeedText(String),      // изменение текста поля зерна
+    SetPositions(VertexPositions<VertexId>), // установка координат вершин, загруженных из файла
+    PositionsResponse(PathBuf, VertexPositions<VertexId>), // координаты вершин для сохранения в файл
+    GraphTextModified(bool), // изменение флага изменённости текста графа
+    ParseFinished(u64, ParseResult), // завершение загрузки графа с заданным номером
+    AlgorithmRunProgress(u64, RunProgress), // ход выполнения алгоритма до конца с заданным номером
+    AlgorithmRunFinished(u64, RunResult), // завершение выполнения алгоритма до конца с заданным номером
+
+    OpenFile(PathBuf),                // открытие файла с путём, выбранном в диалоге
+    SetExample(usize),                // выбор примера графа
+    OpenExample,                      // открытие выбранного примера (с подтверждением)
+    LoadExample(usize),               // загрузка примера графа
+    SaveFile(PathBuf),                // сохранение файла с путём, выбранном в диалоге
+    UpdateGraph,                      // обновление графа из текстового представления
+    NewGraph,                         // создание нового графа
+    ToDirected,                       // преобразование графа в ориентированный
+    ToUndirected,                     // преобразование графа в неориентированный
+    Transpose,                        // транспонирование графа
+    GenerateGraph,                    // генерация графа
+    RandomizeWeights,                 // замена весов всех рёбер случайными
+    AddVertex,                        // добавление вершины
+    DeleteVertex,                     // удаление вершины
+    AddEdge,                          // добавление ребра
+    AddEdgeList,                      // добавление рёбер списком
+    SetVertexLabel(String, String),   // изменение метки вершины в списке вершин
+    SetEdgeWeight(usize, String),     // изменение веса ребра в строке списка рёбер
+    ChangeEdgeWeight,                 // изменение веса ребра из полей вершин и веса
+    DeleteEdge,                       // удаление ребра
+    MergeVertices,                    // слияние вершины №2 с вершиной №1
+    UnpinAll,                         // открепление всех вершин
+    ResetImage,                       // сброс изображения графа
+    FitView,                          // масштабирование камеры по всему графу
+    ChangeSearchText(String),         // изменение текста поля поиска вершины
+    FocusVertex,                      // поиск вершины и фокусировка камеры на ней
+    LayoutCircular,                   // расположение вершин по окружности
+    LayoutGrid,                       // расположение вершин по сетке
+    LayoutLayered,                    // расположение вершин ациклического графа по слоям
+    Undo,                             // отмена изменения графа
+    Redo,                             // повтор отменённого изменения графа
+    AlgorithmStep,                    // шаг алгоритма
+    AlgorithmStepBack,                // возврат к предыдущему шагу алгоритма
+    AlgorithmReset,                   // сброс алгоритма
+    AlgorithmRestart,                 // сброс алгоритма и запуск заново с первым шагом
+    AlgorithmFullRun,                 // запуск алгоритма до конца
+    AlgorithmStop,                    // остановка выполнения алгоритма до конца
+    ToggleAlgorithmPlay(bool),        // запуск или пауза автоматического выполнения алгоритма
+    ChangeAlgorithmPlayInterval(u64), // изменение интервала между шагами автоматического выполнения
+    AlgorithmPlayTick,                // шаг автоматического выполнения алгоритма по таймеру
+    TopologicalSort,                  // топологическая сортировка
+    ToggleTopologicalNumbers(bool),   // переключение вывода номеров вершин в топологическом порядке
+    ToggleShowResidualArcs(bool),     // переключение вывода обратных дуг в таблице потоков
 
-    OpenFile(PathBuf), // открытие файла с путём, выбранном в диалоге
-    SaveFile(PathBuf), // сохранение файла с путём, выбранном в диалоге
-    UpdateGraph,       // обновление графа из текстового представления
-    NewGraph,          // создание нового графа
-    AddVertex,         // добавление вершины
-    DeleteVertex,      // удаление вершины
-    AddEdge,           // добавление ребра
-    DeleteEdge,        // удаление ребра
-    ResetImage,        // сброс изображения графа
-    AlgorithmStep,     // шаг алгоритма
-    AlgorithmFullRun,  // запуск алгоритма до конца
-
-    GraphChanged,      // граф изменился
-    OpenFileDialog,    // вызов диалога открытия файла
-    SaveFileDialog,    // вызов диалога сохранения файла
-    ShowError(String), // показ сообщения об ошибке
-    WindowClosing,     // закрытие окна
+    GraphChanged,            // граф изменился
+    ResetGraphText,          // замена текста графа его текстовым представлением
+    OpenFileDialog,          // вызов диалога открытия файла
+    SaveFileDialog,          // вызов диалога сохранения файла
+    SaveImageDialog,         // вызов диалога сохранения изображения
+    SaveSvgDialog,           // вызов диалога сохранения изображения в формате SVG
+    SaveReportDialog,        // вызов диалога сохранения отчёта о выполнении алгоритма
+    ShowError(String),       // показ сообщения об ошибке
+    WindowClosing(i32, i32), // закрытие окна управления с его размером
+    GraphWindowClosing,      // запрос закрытия окна графа
 }
 
 impl Model for AppModel {
@@ -136,6 +493,320 @@ impl Model for AppModel {
 }
 
 impl AppModel {
+    // Сохранение графа до изменения для его отмены
+    fn save_undo(&mut self, prev: Option<Graph<VertexId, EdgeWeights>>) {
+        self.graph_unsaved = true;
+        self.undo_stack.push_back(prev);
+        if self.undo_stack.len() > UNDO_LIMIT {
+            self.undo_stack.pop_front();
+        }
+        self.redo_stack.clear();
+    }
+
+    // Замена графа с сохранением предыдущего для отмены
+    fn set_graph(&mut self, graph: Option<Graph<VertexId, EdgeWeights>>) {
+        let prev = std::mem::replace(&mut self.graph, graph);
+        self.save_undo(prev);
+    }
+
+    // Замена текста графа его текстовым представлением; изменяются только отличающиеся строки,
+    // чтобы курсор и прокрутка не сбрасывались при изменении других строк
+    fn reset_graph_text(&self) {
+        let buf_ref = self.graph_text.borrow();
+        let buf = buf_ref.as_ref().unwrap();
+        let mut text = Vec::new();
+        if let Some(g) = self.graph.as_ref() {
+            g.to_file(&mut text).unwrap();
+        }
+        let text = String::from_utf8(text).unwrap();
+        let old_text = buf.text(&buf.start_iter(), &buf.end_iter(), true);
+        // Замены применяются с конца, чтобы номера предыдущих строк не менялись
+        for edit in line_edits(&old_text, &text).iter().rev() {
+            let line_iter = |line: usize| {
+                buf.iter_at_line(line as i32)
+                    .unwrap_or_else(|| buf.end_iter())
+            };
+            let (mut start, mut end) = (line_iter(edit.start), line_iter(edit.end));
+            buf.delete(&mut start, &mut end);
+            buf.insert(&mut start, &edit.text);
+        }
+        // Текст соответствует графу, выделять строку с ошибкой больше не нужно
+        buf.remove_tag_by_name(ERROR_LINE_TAG, &buf.start_iter(), &buf.end_iter());
+        buf.set_modified(false);
+    }
+
+    // Запуск загрузки графа в фоновом потоке (с отменой текущей загрузки),
+    // результат передаётся сообщением с номером загрузки
+    fn start_loading(&mut self, source: GraphSource, sender: &Sender<AppMsg>) {
+        if let Some(loading) = self.graph_loading.take() {
+            loading.loader.cancel();
+        }
+        self.graph_loading_cnt += 1;
+        let id = self.graph_loading_cnt;
+        let from_text = matches!(source, GraphSource::Text(_));
+        let loader_sender = sender.clone();
+        let loader = GraphLoader::spawn(id, source, move |id, result| {
+            // Приложение могло быть закрыто до завершения загрузки
+            let _ = loader_sender.send(AppMsg::ParseFinished(id, result));
+        });
+        self.graph_loading = Some(GraphLoading { loader, from_text });
+    }
+
+    // Выделение в тексте графа строки, в которой произошла ошибка разбора
+    fn highlight_error_line(&self, e: &GraphError) {
+        let line = match e {
+            GraphError::InterfaceError(GraphInterfaceError::AtLine { line, .. }) => *line,
+            _ => return,
+        };
+        let buf_ref = self.graph_text.borrow();
+        let buf = buf_ref.as_ref().unwrap();
+        if let Some(start) = buf.iter_at_line(line as i32 - 1) {
+            let mut end = start.clone();
+            if !end.ends_line() {
+                end.forward_to_line_end();
+            }
+            buf.apply_tag_by_name(ERROR_LINE_TAG, &start, &end);
+        }
+    }
+
+    // Заполнение списков вершин и рёбер (в порядке идентификаторов)
+    fn update_graph_lists(&self) {
+        let vertex_list_ref = self.vertex_list.borrow();
+        let vertex_list = vertex_list_ref.as_ref().unwrap();
+        let edge_list_ref = self.edge_list.borrow();
+        let edge_list = edge_list_ref.as_ref().unwrap();
+        vertex_list.clear();
+        edge_list.clear();
+        let g = match self.graph.as_ref() {
+            Some(g) => g,
+            None => return,
+        };
+        for v in g.get_vertices().values() {
+            let label = v.label.clone().unwrap_or_default();
+            vertex_list.set(
+                &vertex_list.append(),
+                &[(0, &v.id.to_string()), (1, &label)],
+            );
+        }
+        for (i, e, _) in edge_rows(g) {
+            let weight = e.weight.as_ref().map(|w| w.to_string()).unwrap_or_default();
+            edge_list.set(
+                &edge_list.append(),
+                &[(0, &i.to_string()), (1, &e.to.to_string()), (2, &weight)],
+            );
+        }
+    }
+
+    // Передача основного и фонового цветов в окно графа
+    fn send_colors(&self) {
+        self.graph_window_proxy
+            .send_event(GraphWindowMsg::SetColor(
+                femtovg_color(&self.front_color),
+                femtovg_color(&self.back_color),
+            ))
+            .unwrap();
+    }
+
+    // Открытие диалога выбора файла в последней использованной папке
+    fn set_dialog_folder(&self, dialog: &impl IsA<FileChooser>) {
+        if let Some(dir) = &self.settings.last_directory {
+            // Если папка больше не существует, диалог открывается в папке по умолчанию
+            let _ = dialog.set_current_folder(Some(&gio::File::for_path(dir)));
+        }
+    }
+
+    // Фильтр файлов диалога по расширению (без фильтра, если расширение не задано)
+    fn set_dialog_filter(dialog: &impl IsA<FileChooser>, extension: Option<&str>) {
+        let filters = dialog.filters();
+        while let Some(filter) = filters.item(0) {
+            dialog.remove_filter(&filter.downcast::<FileFilter>().unwrap());
+        }
+        if let Some(extension) = extension {
+            let filter = FileFilter::new();
+            filter.set_name(Some(&extension.to_uppercase()));
+            filter.add_pattern(&format!("*.{}", extension));
+            dialog.add_filter(&filter);
+        }
+    }
+
+    // Сохранение состояния алгоритма и отчёта до шага для возврата к ним
+    fn push_algorithm_history(
+        &mut self,
+        prev_state: GraphAlgorithmState<VertexId, EdgeWeights>,
+        prev_report: FlowReport<VertexId>,
+        new_state: &GraphAlgorithmState<VertexId, EdgeWeights>,
+    ) {
+        // После сброса алгоритма возвращаться некуда
+        if new_state.is_not_started() {
+            self.algorithm_history.clear();
+            return;
+        }
+        self.algorithm_history.push_back((prev_state, prev_report));
+        if self.algorithm_history.len() > ALGORITHM_HISTORY_LIMIT {
+            self.algorithm_history.pop_front();
+        }
+    }
+
+    // Заполнение таблицы потоков через дуги по состоянию алгоритма нахождения
+    // максимального потока (до запуска алгоритма таблица пуста)
+    fn update_flow_list(&self) {
+        let flow_list_ref = self.flow_list.borrow();
+        let flow_list = flow_list_ref.as_ref().unwrap();
+        flow_list.clear();
+        let data = match &self.graph_algorithm_state {
+            GraphAlgorithmState::MaxFlow(AlgorithmState::Step(data))
+            | GraphAlgorithmState::MaxFlow(AlgorithmState::Finished(data)) => data,
+            _ => return,
+        };
+        for row in flow_rows(data, self.show_residual_arcs) {
+            // Насыщенные дуги выделяются жирным шрифтом
+            let weight = if row.saturated {
+                gtk::pango::Weight::Bold
+            } else {
+                gtk::pango::Weight::Normal
+            };
+            flow_list.set(
+                &flow_list.append(),
+                &[
+                    (0, &row.from.to_string()),
+                    (1, &row.to.to_string()),
+                    (2, &row.capacity.to_string()),
+                    (3, &row.flow.to_string()),
+                    (4, &row.residual.to_string()),
+                    (5, &weight.into_glib()),
+                ],
+            );
+        }
+    }
+
+    // Передача графа и состояния выполнения алгоритма в окно графа
+    // с обновлением таблицы потоков
+    fn send_graph_state(&self) {
+        self.update_flow_list();
+        self.graph_window_proxy
+            .send_event(GraphWindowMsg::GraphStateChanged(
+                self.graph.clone(),
+                self.graph_algorithm_state.clone(),
+            ))
+            .unwrap();
+    }
+
+    // Передача в окно графа номеров вершин в топологическом порядке (начиная с 1),
+    // если их вывод включён и сортировка выполнена успешно
+    fn send_topological_numbers(&self) {
+        let numbers = match &self.topological_sort {
+            Some(Ok(order)) if self.show_topological_numbers => order
+                .iter()
+                .enumerate()
+                .map(|(k, i)| (i.clone(), k + 1))
+                .collect(),
+            _ => BTreeMap::new(),
+        };
+        self.graph_window_proxy
+            .send_event(GraphWindowMsg::SetVertexNumbers(numbers))
+            .unwrap();
+    }
+
+    // Запись шага алгоритма нахождения потока в отчёт
+    fn record_flow_report(&mut self, state: &GraphAlgorithmState<VertexId, EdgeWeights>) {
+        if let GraphAlgorithmState::MaxFlow(state) = state {
+            self.flow_report.record(state);
+        }
+    }
+
+    // Отмена изменения веса ребра, если поля вершин больше не содержат его концы
+    fn check_edited_edge(&mut self) {
+        let is_edited = match &self.edited_edge {
+            Some((from, to, _)) => {
+                self.vertex1_text == from.to_string() && self.vertex2_text == to.to_string()
+            }
+            None => false,
+        };
+        if !is_edited {
+            self.edited_edge = None;
+        }
+    }
+
+    // Сброс состояния алгоритма (по кнопке или после ошибки при его выполнении)
+    fn reset_algorithm(&mut self) {
+        self.graph_algorithm_state =
+            GraphAlgorithmState::new(self.graph_algorithm_state.get_algorithm());
+        self.graph_algorithm_started = false;
+        self.algorithm_history.clear();
+        self.flow_report = FlowReport::new();
+        self.send_graph_state();
+    }
+
+    // Выполнение одного шага алгоритма
+    fn step_algorithm(&mut self) -> Result<(), GraphError> {
+        self.advance_algorithm()?;
+        self.send_graph_state();
+        Ok(())
+    }
+
+    // Сброс алгоритма и запуск заново: инициализация и первый шаг выполняются сразу,
+    // а окно графа получает только итоговое состояние
+    fn restart_algorithm(&mut self) -> Result<(), GraphError> {
+        self.graph_algorithm_state =
+            GraphAlgorithmState::new(self.graph_algorithm_state.get_algorithm());
+        self.algorithm_history.clear();
+        self.flow_report = FlowReport::new();
+        self.advance_algorithm()?;
+        if !self.graph_algorithm_state.is_finished() {
+            self.advance_algorithm()?;
+        }
+        self.send_graph_state();
+        Ok(())
+    }
+
+    // Выполнение одного шага алгоритма без передачи состояния в окно графа
+    // (при ошибке алгоритм сбрасывается)
+    fn advance_algorithm(&mut self) -> Result<(), GraphError> {
+        let mut curr_state = GraphAlgorithmState::new(self.graph_algorithm_state.get_algorithm());
+        std::mem::swap(&mut curr_state, &mut self.graph_algorithm_state);
+        let prev_state = curr_state.clone();
+        let new_state = match algorithm_step(
+            curr_state,
+            &self.graph,
+            &self.source_text,
+            &self.sink_text,
+            &self.left_text,
+            self.path_strategy,
+            self.unit_capacities,
+        ) {
+            Ok(new_state) => new_state,
+            Err(e) => {
+                self.reset_algorithm();
+                return Err(e);
+            }
+        };
+        self.push_algorithm_history(prev_state, self.flow_report.clone(), &new_state);
+        self.record_flow_report(&new_state);
+        self.graph_algorithm_started = !new_state.is_not_started();
+        self.graph_algorithm_state = new_state;
+        Ok(())
+    }
+
+    // Запуск таймера автоматического выполнения алгоритма (с заменой запущенного)
+    fn start_play_timer(&mut self, sender: &Sender<AppMsg>) {
+        self.stop_play_timer();
+        let sender = sender.clone();
+        self.algorithm_play_timer = Some(glib::timeout_add_local(
+            Duration::from_millis(self.settings.algorithm_play_interval),
+            move || {
+                sender.send(AppMsg::AlgorithmPlayTick).unwrap();
+                glib::Continue(true)
+            },
+        ));
+    }
+
+    // Остановка таймера автоматического выполнения алгоритма
+    fn stop_play_timer(&mut self) {
+        if let Some(timer) = self.algorithm_play_timer.take() {
+            timer.remove();
+        }
+    }
+
     // Обновление модели данных при получении сообщения
     fn update_with_result(
         &mut self,
@@ -143,88 +814,531 @@ impl AppModel {
         components: &AppComponents,
         sender: &Sender<AppMsg>,
     ) -> Result<(), GraphError> {
+        // Во время выполнения алгоритма граф не изменяется
+        if self.graph_algorithm_started && changes_graph(&msg) {
+            return Err(GraphInterfaceError::AlgorithmRunning.into());
+        }
+        // Во время выполнения алгоритма до конца другие шаги алгоритма не выполняются
+        if self.algorithm_run.is_some()
+            && matches!(
+                msg,
+                AppMsg::AlgorithmStep
+                    | AppMsg::AlgorithmStepBack
+                    | AppMsg::AlgorithmReset
+                    | AppMsg::AlgorithmRestart
+                    | AppMsg::AlgorithmFullRun
+                    | AppMsg::ToggleAlgorithmPlay(true)
+                    | AppMsg::AlgorithmPlayTick
+            )
+        {
+            return Ok(());
+        }
+        // Во время загрузки граф не изменяется, новая загрузка отменяет текущую
+        if self.graph_loading.is_some()
+            && changes_graph(&msg)
+            && !matches!(msg, AppMsg::OpenFile(_) | AppMsg::UpdateGraph)
+        {
+            return Err(GraphInterfaceError::GraphLoading.into());
+        }
+        // Папка выбранного файла запоминается для следующего вызова диалогов
+        if let AppMsg::OpenFile(path) | AppMsg::SaveFile(path) = &msg {
+            self.settings.last_directory = path.parent().map(Path::to_path_buf);
+        }
         match msg {
             // Обновление модели данными, полученными из интерфейса
             AppMsg::ToggleNewGraphIsDirected(x) => self.new_graph_is_directed = x,
             AppMsg::ToggleNewGraphIsWeighted(x) => self.new_graph_is_weighted = x,
             AppMsg::ToggleNewGraphIsFloatWeights(x) => self.new_graph_is_float_weights = x,
+            AppMsg::ToggleMergeMaxWeight(x) => self.merge_max_weight = x,
+            AppMsg::ToggleMergeSumWeights(x) => self.merge_sum_weights = x,
+            AppMsg::ToggleMergeKeepLoops(x) => self.merge_keep_loops = x,
+            AppMsg::SetGraphGenerator(x) => self.graph_generator = x,
+            AppMsg::ChangeGenParam1Text(x) => self.gen_param1_text = x,
+            AppMsg::ChangeGenParam2Text(x) => self.gen_param2_text = x,
+            AppMsg::ChangeGenWeightMinText(x) => self.gen_weight_min_text = x,
+            AppMsg::ChangeGenWeightMaxText(x) => self.gen_weight_max_text = x,
+            AppMsg::ChangeGenSeedText(x) => self.gen_seed_text = x,
+            AppMsg::GraphTextModified(x) => self.graph_text_modified = x,
+            // Цвета, выбранные пользователем и сохранённые в настройках, от темы не зависят
+            AppMsg::ThemeColorChanged(x) => {
+                // Фоновый цвет подбирается по яркости цвета текста
+                let back_color = if x.red() > 0.5 {
+                    // Светлый основной цвет, тёмный фоновый цвет
+                    RGBA::new(53.0 / 255.0, 53.0 / 255.0, 53.0 / 255.0, 1.0)
+                } else {
+                    // Наоборот
+                    RGBA::new(246.0 / 255.0, 245.0 / 255.0, 244.0 / 255.0, 1.0)
+                };
+                self.front_color = self.settings.front_color.as_ref().map_or(x, rgba_color);
+                self.back_color = self
+                    .settings
+                    .back_color
+                    .as_ref()
+                    .map_or(back_color, rgba_color);
+                self.send_colors();
+            }
+            AppMsg::SetFrontColor(x) => {
+                self.front_color = x;
+                self.settings.front_color = Some(settings_color(&x));
+                self.send_colors();
+            }
+            AppMsg::SetBackColor(x) => {
+                self.back_color = x;
+                self.settings.back_color = Some(settings_color(&x));
+                self.send_colors();
+            }
+            AppMsg::SetSelectionColor(x) => {
+                self.selection_color = x;
+                self.settings.selection_color = settings_color(&x);
+                self.graph_window_proxy
+                    .send_event(GraphWindowMsg::SetSelectionColor(femtovg_color(&x)))
+                    .unwrap();
+            }
+            AppMsg::ToggleVertexColorSet(x) => self.vertex_color_set = x,
+            AppMsg::SetVertexColor(x) => self.vertex_color = x,
             AppMsg::ChangeVertex0Text(x) => self.vertex0_text = x,
-            AppMsg::ChangeVertex1Text(x) => self.vertex1_text = x,
-            AppMsg::ChangeVertex2Text(x) => self.vertex2_text = x,
+            AppMsg::ChangeVertex1Text(x) => {
+                self.vertex1_text = x;
+                self.check_edited_edge();
+            }
+            AppMsg::ChangeVertex2Text(x) => {
+                self.vertex2_text = x;
+                self.check_edited_edge();
+            }
             AppMsg::ChangeLabelText(x) => self.label_text = x,
             AppMsg::ChangeWeightText(x) => self.weight_text = x,
+            AppMsg::ChangeEdgeListText(x) => self.edge_list_text = x,
             AppMsg::ChangeSourceText(x) => self.source_text = x,
             AppMsg::ChangeSinkText(x) => self.sink_text = x,
-            AppMsg::ChangeCenterGravityValue(x) => self
-                .graph_window_proxy
-                .send_event(GraphWindowMsg::ChangeCenterGravityValue(x))
-                .unwrap(),
-            AppMsg::ChangeRepulsiveForceValue(x) => self
-                .graph_window_proxy
-                .send_event(GraphWindowMsg::ChangeRepulsiveForceValue(x))
-                .unwrap(),
-            AppMsg::ChangeTimeStepValue(x) => self
-                .graph_window_proxy
-                .send_event(GraphWindowMsg::ChangeTimeStepValue(x))
-                .unwrap(),
-            AppMsg::ChangeThetaValue(x) => self
-                .graph_window_proxy
-                .send_event(GraphWindowMsg::ChangeThetaValue(x))
-                .unwrap(),
-            AppMsg::ToggleFullRender(x) => self
-                .graph_window_proxy
-                .send_event(GraphWindowMsg::ToggleFullRender(x))
-                .unwrap(),
-            AppMsg::ToggleGraphUpdateStop(x) => self
-                .graph_window_proxy
-                .send_event(GraphWindowMsg::ToggleGraphUpdateStop(x))
-                .unwrap(),
-
-            // Открытие файла
-            AppMsg::OpenFile(path) => {
-                let file = File::open(path).map_err(|_| GraphInterfaceError::FileError)?;
-                self.graph = Some(Graph::from_file(BufReader::new(file))?);
+            AppMsg::ChangeLeftText(x) => self.left_text = x,
+            AppMsg::ChangeSearchText(x) => self.search_text = x,
+            AppMsg::SetAlgorithm(x) => {
+                if !self.graph_algorithm_started {
+                    self.graph_algorithm_state = GraphAlgorithmState::new(x);
+                }
+            }
+            AppMsg::SetPathStrategy(x) => self.path_strategy = x,
+            AppMsg::ToggleUnitCapacities(x) => self.unit_capacities = x,
+            AppMsg::SetVertexPickTarget(x) => self.vertex_pick_target = x,
+            // Выбор языка интерфейса: подписи виджетов обновляются по модели,
+            // а подписи диалогов выбора файла и заголовок окна графа изменяются здесь
+            AppMsg::SetLang(lang) => {
+                self.lang = lang;
+                lang.set_current();
+                let open_dialog = components.open_dialog.root_widget();
+                open_dialog.set_accept_label(Some(lang.tr("Открыть", "Open")));
+                open_dialog.set_cancel_label(Some(lang.tr("Отмена", "Cancel")));
+                let save_dialog = components.save_dialog.root_widget();
+                save_dialog.set_accept_label(Some(lang.tr("Сохранить", "Save")));
+                save_dialog.set_cancel_label(Some(lang.tr("Отмена", "Cancel")));
+                self.graph_window_proxy
+                    .send_event(GraphWindowMsg::SetLang(lang))
+                    .unwrap();
+            }
+            AppMsg::ChangeCenterGravityValue(x) => {
+                self.settings.center_gravity = x;
+                self.graph_window_proxy
+                    .send_event(GraphWindowMsg::ChangeCenterGravityValue(x))
+                    .unwrap()
+            }
+            AppMsg::ChangeRepulsiveForceValue(x) => {
+                self.settings.repulsive_force = x;
+                self.graph_window_proxy
+                    .send_event(GraphWindowMsg::ChangeRepulsiveForceValue(x))
+                    .unwrap()
+            }
+            AppMsg::ChangeTimeStepValue(x) => {
+                self.settings.time_step = x;
+                self.graph_window_proxy
+                    .send_event(GraphWindowMsg::ChangeTimeStepValue(x))
+                    .unwrap()
+            }
+            AppMsg::ChangeThetaValue(x) => {
+                self.settings.theta = x;
+                self.graph_window_proxy
+                    .send_event(GraphWindowMsg::ChangeThetaValue(x))
+                    .unwrap()
+            }
+            AppMsg::ChangeSettleEpsilonValue(x) => {
+                self.settings.settle_epsilon = x;
+                self.graph_window_proxy
+                    .send_event(GraphWindowMsg::ChangeSettleEpsilonValue(x))
+                    .unwrap()
+            }
+            AppMsg::ToggleFullRender(x) => {
+                self.settings.full_render = x;
+                self.graph_window_proxy
+                    .send_event(GraphWindowMsg::ToggleFullRender(x))
+                    .unwrap()
+            }
+            AppMsg::ToggleShowEdgeLabels(x) => {
+                self.settings.show_edge_labels = x;
+                self.graph_window_proxy
+                    .send_event(GraphWindowMsg::ToggleShowEdgeLabels(x))
+                    .unwrap()
+            }
+            AppMsg::ToggleColorByComponent(x) => {
+                self.settings.color_by_component = x;
+                self.graph_window_proxy
+                    .send_event(GraphWindowMsg::ToggleColorByComponent(x))
+                    .unwrap()
+            }
+            AppMsg::ToggleEdgeWidthByWeight(x) => {
+                self.settings.edge_width_by_weight = x;
+                self.graph_window_proxy
+                    .send_event(GraphWindowMsg::ToggleEdgeWidthByWeight(x))
+                    .unwrap()
+            }
+            AppMsg::ToggleShowGrid(x) => {
+                self.settings.show_grid = x;
+                self.graph_window_proxy
+                    .send_event(GraphWindowMsg::ToggleShowGrid(x))
+                    .unwrap()
+            }
+            AppMsg::ToggleSizeByDegree(x) => {
+                self.settings.size_by_degree = x;
+                self.graph_window_proxy
+                    .send_event(GraphWindowMsg::ToggleSizeByDegree(x))
+                    .unwrap()
+            }
+            AppMsg::ToggleShowCursorCoords(x) => {
+                self.settings.show_cursor_coords = x;
+                self.graph_window_proxy
+                    .send_event(GraphWindowMsg::ToggleShowCursorCoords(x))
+                    .unwrap()
+            }
+            AppMsg::ToggleAnimateFlow(x) => {
+                self.settings.animate_flow = x;
+                self.graph_window_proxy
+                    .send_event(GraphWindowMsg::ToggleAnimateFlow(x))
+                    .unwrap()
+            }
+            AppMsg::ChangeEdgeLabelOffsetValue(x) => {
+                self.settings.edge_label_offset = x;
+                self.graph_window_proxy
+                    .send_event(GraphWindowMsg::ChangeEdgeLabelOffsetValue(x))
+                    .unwrap()
+            }
+            AppMsg::ChangeArrowSizeValue(x) => {
+                self.settings.arrow_size = x;
+                self.graph_window_proxy
+                    .send_event(GraphWindowMsg::ChangeArrowSizeValue(x))
+                    .unwrap()
+            }
+            AppMsg::ChangeLodLabelDiameterValue(x) => {
+                self.settings.lod_label_diameter = x;
+                self.graph_window_proxy
+                    .send_event(GraphWindowMsg::ChangeLodLabelDiameterValue(x))
+                    .unwrap()
+            }
+            AppMsg::ChangeImageScaleValue(x) => self.settings.image_scale = x,
+            AppMsg::ToggleGraphUpdateStop(x) => {
+                self.graph_updates_stopped = x;
+                self.graph_window_proxy
+                    .send_event(GraphWindowMsg::ToggleGraphUpdateStop(x))
+                    .unwrap()
+            }
+            AppMsg::GraphUpdateStopChanged(x) => self.graph_updates_stopped = x,
+            // Добавление вершины, выбранной мышью, к вершинам левой доли (если её там ещё нет)
+            AppMsg::VertexPicked(i) if self.vertex_pick_target == VertexPickTarget::LeftPart => {
+                let id = i.to_string();
+                let mut ids: Vec<&str> = self.left_text.split_whitespace().collect();
+                if !ids.contains(&id.as_str()) {
+                    ids.push(&id);
+                    self.left_text = ids.join(" ");
+                }
+            }
+            // Запись вершины, выбранной мышью, в поле
+            AppMsg::VertexPicked(i) => {
+                let field = match self.vertex_pick_target {
+                    VertexPickTarget::FirstEmpty => [
+                        &mut self.vertex0_text,
+                        &mut self.vertex1_text,
+                        &mut self.vertex2_text,
+                        &mut self.source_text,
+                        &mut self.sink_text,
+                    ]
+                    .into_iter()
+                    .find(|text| text.is_empty()),
+                    VertexPickTarget::Vertex0 => Some(&mut self.vertex0_text),
+                    VertexPickTarget::Vertex1 => Some(&mut self.vertex1_text),
+                    VertexPickTarget::Vertex2 => Some(&mut self.vertex2_text),
+                    VertexPickTarget::Source => Some(&mut self.source_text),
+                    VertexPickTarget::Sink => Some(&mut self.sink_text),
+                    VertexPickTarget::LeftPart => None,
+                };
+                if let Some(text) = field {
+                    *text = i.to_string();
+                }
+            }
+
+            // Открытие файла (в фоновом потоке)
+            AppMsg::OpenFile(path) => self.start_loading(GraphSource::File(path), sender),
+            AppMsg::SetExample(x) => self.example = x,
+            // Открытие выбранного примера; если текущий граф изменён и не сохранён,
+            // пример загружается только после подтверждения
+            AppMsg::OpenExample => {
+                if self.graph_unsaved && self.graph.is_some() {
+                    components
+                        .discard_dialog
+                        .send(DiscardDialogMsg::Show(self.example))
+                        .unwrap();
+                } else {
+                    sender.send(AppMsg::LoadExample(self.example)).unwrap();
+                }
+            }
+            // Загрузка встроенного примера (у сети для нахождения потока
+            // исток и сток записываются в поля)
+            AppMsg::LoadExample(k) => {
+                let example = &EXAMPLES[k];
+                let graph = Graph::from_file(example.text.as_bytes())?;
+                self.set_graph(Some(graph));
+                self.graph_unsaved = false;
+                if let Some((source, sink)) = example.source_sink {
+                    self.source_text = String::from(source);
+                    self.sink_text = String::from(sink);
+                }
                 sender.send(AppMsg::GraphChanged).unwrap();
             }
+            // Сохранение изображения
+            AppMsg::SaveFile(path) if self.save_file_kind == SaveFileKind::Image => {
+                self.graph_window_proxy
+                    .send_event(GraphWindowMsg::ExportImage(path, self.settings.image_scale))
+                    .unwrap();
+            }
+            // Сохранение изображения в формате SVG
+            AppMsg::SaveFile(path) if self.save_file_kind == SaveFileKind::Svg => {
+                self.graph_window_proxy
+                    .send_event(GraphWindowMsg::ExportSvg(path))
+                    .unwrap();
+            }
+            // Сохранение отчёта о выполнении алгоритма нахождения потока
+            AppMsg::SaveFile(path) if self.save_file_kind == SaveFileKind::Report => {
+                let g = self
+                    .graph
+                    .as_ref()
+                    .ok_or(GraphInterfaceError::GraphNotExist)?;
+                let state = match &self.graph_algorithm_state {
+                    GraphAlgorithmState::MaxFlow(state) if self.graph_algorithm_started => state,
+                    _ => return Err(GraphInterfaceError::NoFlowReport.into()),
+                };
+                std::fs::write(&path, self.flow_report.to_text(g, state, self.lang))?;
+            }
             // Сохранение файла
             AppMsg::SaveFile(path) => {
                 let g = self
                     .graph
                     .as_ref()
                     .ok_or(GraphInterfaceError::GraphNotExist)?;
-                let file = File::create(path).map_err(|_| GraphInterfaceError::FileError)?;
-                g.to_file(&mut BufWriter::new(file))?;
+                match FileFormat::from_path(&path) {
+                    // Граф сохраняется вместе с координатами вершин, полученными из окна графа
+                    FileFormat::Native => self
+                        .graph_window_proxy
+                        .send_event(GraphWindowMsg::RequestPositions(path))
+                        .unwrap(),
+                    FileFormat::Dot | FileFormat::GraphMl => {
+                        graph_to_path(g, None, &path)?;
+                        self.graph_unsaved = false;
+                    }
+                }
+            }
+            // Сохранение графа с координатами вершин в собственном формате
+            AppMsg::PositionsResponse(path, mut positions) => {
+                let g = self
+                    .graph
+                    .as_ref()
+                    .ok_or(GraphInterfaceError::GraphNotExist)?;
+                positions.retain(|i, _| g.get_vertices().contains_key(i));
+                graph_to_path(g, Some(&positions), &path)?;
+                self.graph_unsaved = false;
+            }
+            // Установка координат вершин с фиксированием изображения, чтобы сохранить расположение
+            AppMsg::SetPositions(positions) => {
+                self.graph_window_proxy
+                    .send_event(GraphWindowMsg::SetPositions(positions))
+                    .unwrap();
+                sender.send(AppMsg::ToggleGraphUpdateStop(true)).unwrap();
             }
             // Обновление графа из текстового представления
+            // (повторное нажатие во время загрузки отменяет её)
             AppMsg::UpdateGraph => {
+                if let Some(loading) = self.graph_loading.take() {
+                    loading.loader.cancel();
+                    return Ok(());
+                }
                 let buf_ref = self.graph_text.borrow();
                 let buf = buf_ref.as_ref().unwrap();
-                let text_gstr = buf.text(&buf.start_iter(), &buf.end_iter(), true);
-                let text_bytes = text_gstr.as_bytes();
-                self.graph = Some(Graph::from_file(BufReader::new(text_bytes))?);
+                let text = buf.text(&buf.start_iter(), &buf.end_iter(), true);
+                buf.remove_tag_by_name(ERROR_LINE_TAG, &buf.start_iter(), &buf.end_iter());
+                drop(buf_ref);
+                self.start_loading(GraphSource::Text(text.to_string()), sender);
+            }
+            // Завершение загрузки графа (результаты отменённых загрузок пропускаются)
+            AppMsg::ParseFinished(id, result) => {
+                let from_text = match &self.graph_loading {
+                    Some(loading) if loading.loader.get_id() == id => loading.from_text,
+                    _ => return Ok(()),
+                };
+                self.graph_loading = None;
+                let (graph, positions) = match result {
+                    Ok(result) => result,
+                    // При ошибке текст не изменяется, строка с ошибкой выделяется
+                    Err(e) => {
+                        if from_text {
+                            self.highlight_error_line(&e);
+                        }
+                        return Err(e);
+                    }
+                };
+                // Текст применён, его можно заменить представлением нового графа
+                if from_text {
+                    self.graph_text
+                        .borrow()
+                        .as_ref()
+                        .unwrap()
+                        .set_modified(false);
+                }
+                self.set_graph(Some(graph));
+                // Граф, загруженный из файла, совпадает с сохранённым
+                if !from_text {
+                    self.graph_unsaved = false;
+                }
                 sender.send(AppMsg::GraphChanged).unwrap();
+                // Координаты вершин из файла передаются после графа, чтобы они
+                // не были удалены как устаревшие
+                if let (false, Some(positions)) = (from_text, positions) {
+                    sender.send(AppMsg::SetPositions(positions)).unwrap();
+                }
             }
             // Создание нового графа
             AppMsg::NewGraph => {
-                self.graph = Some(Graph::new(
+                self.set_graph(Some(Graph::new(
                     self.new_graph_is_directed,
                     self.new_graph_is_weighted,
                     self.new_graph_is_float_weights,
-                ));
+                )));
+                sender.send(AppMsg::GraphChanged).unwrap();
+            }
+            // Преобразование графа в ориентированный
+            AppMsg::ToDirected => {
+                let g = self
+                    .graph
+                    .as_ref()
+                    .ok_or(GraphInterfaceError::GraphNotExist)?
+                    .to_directed();
+                self.set_graph(Some(g));
+                sender.send(AppMsg::GraphChanged).unwrap();
+            }
+            // Транспонирование графа
+            AppMsg::Transpose => {
+                let g = self
+                    .graph
+                    .as_ref()
+                    .ok_or(GraphInterfaceError::GraphNotExist)?
+                    .transpose();
+                self.set_graph(Some(g));
+                sender.send(AppMsg::GraphChanged).unwrap();
+            }
+            // Преобразование графа в неориентированный
+            AppMsg::ToUndirected => {
+                let merge = if self.merge_max_weight {
+                    WeightMerge::Max
+                } else {
+                    WeightMerge::Error
+                };
+                let g = self
+                    .graph
+                    .as_ref()
+                    .ok_or(GraphInterfaceError::GraphNotExist)?
+                    .to_undirected(merge)?;
+                self.set_graph(Some(g));
+                sender.send(AppMsg::GraphChanged).unwrap();
+            }
+            // Генерация графа
+            AppMsg::GenerateGraph => {
+                let n: usize = parse_field(&self.gen_param1_text, 1)?;
+                let weights = if self.new_graph_is_weighted {
+                    Some((
+                        parse_field(&self.gen_weight_min_text, 3)?,
+                        parse_field(&self.gen_weight_max_text, 4)?,
+                    ))
+                } else {
+                    None
+                };
+                // Без зерна граф генерируется случайно
+                let seed = if self.gen_seed_text.trim().is_empty() {
+                    rand::random()
+                } else {
+                    parse_field(&self.gen_seed_text, 5)?
+                };
+                let is_directed = self.new_graph_is_directed;
+                let g = match self.graph_generator {
+                    GraphGenerator::RandomGnp => gen_random_gnp(
+                        n,
+                        parse_field(&self.gen_param2_text, 2)?,
+                        is_directed,
+                        weights,
+                        seed,
+                    )?,
+                    GraphGenerator::Grid => gen_grid(
+                        n,
+                        parse_field(&self.gen_param2_text, 2)?,
+                        is_directed,
+                        weights,
+                        seed,
+                    )?,
+                    GraphGenerator::Complete => gen_complete(n, is_directed, weights, seed)?,
+                };
+                self.set_graph(Some(g));
+                sender.send(AppMsg::GraphChanged).unwrap();
+            }
+            // Замена весов всех рёбер случайными из отрезка генератора
+            AppMsg::RandomizeWeights => {
+                let prev = self.graph.clone();
+                set_random_weights(
+                    &self.gen_weight_min_text,
+                    &self.gen_weight_max_text,
+                    &mut self.graph,
+                )?;
+                self.save_undo(prev);
                 sender.send(AppMsg::GraphChanged).unwrap();
             }
             // Добавление вершины
             AppMsg::AddVertex => {
+                let color = vertex_color(&self.vertex_color).to_string();
                 let mut args = vec![&self.vertex0_text[..]];
                 if !self.label_text.is_empty() {
                     args.push(&self.label_text[..]);
                 }
+                if self.vertex_color_set {
+                    args.push(&color[..]);
+                }
+                let prev = self.graph.clone();
                 add_vertex(&args[..], &mut self.graph)?;
+                self.save_undo(prev);
+                sender.send(AppMsg::GraphChanged).unwrap();
+            }
+            // Добавление вершины со следующим свободным числовым идентификатором
+            // в точке окна графа
+            AppMsg::AddVertexAt(x, y) => {
+                let id = next_vertex_id(
+                    self.graph
+                        .as_ref()
+                        .ok_or(GraphInterfaceError::GraphNotExist)?,
+                );
+                let prev = self.graph.clone();
+                add_vertex(&[id.as_str()], &mut self.graph)?;
+                self.save_undo(prev);
+                self.graph_window_proxy
+                    .send_event(GraphWindowMsg::PlaceNewVertex(id.parse().unwrap(), (x, y)))
+                    .unwrap();
                 sender.send(AppMsg::GraphChanged).unwrap();
             }
             // Удаление вершины
             AppMsg::DeleteVertex => {
+                let prev = self.graph.clone();
                 remove_vertex(&self.vertex0_text[..], &mut self.graph)?;
+                self.save_undo(prev);
                 sender.send(AppMsg::GraphChanged).unwrap();
             }
             // Добавление ребра
@@ -233,97 +1347,472 @@ impl AppModel {
                 if !self.weight_text.is_empty() {
                     args.push(&self.weight_text[..]);
                 }
-                add_edge(&args[..], &mut self.graph)?;
+                let prev = self.graph.clone();
+                // Без веса ребро получает случайный вес из отрезка генератора, если он задан
+                let default_range = (!self.gen_weight_min_text.trim().is_empty()
+                    && !self.gen_weight_max_text.trim().is_empty())
+                .then_some((&self.gen_weight_min_text[..], &self.gen_weight_max_text[..]));
+                add_edge_random(&args[..], &mut self.graph, default_range)?;
+                self.save_undo(prev);
+                sender.send(AppMsg::GraphChanged).unwrap();
+            }
+            // Добавление ребра, протянутого мышью: вес берётся из поля веса,
+            // а если оно пустое, то во взвешенном графе вес равен 1
+            AppMsg::AddEdgeBetween(from, to) => {
+                let (from, to) = (from.to_string(), to.to_string());
+                let mut args = vec![&from[..], &to[..]];
+                if !self.weight_text.is_empty() {
+                    args.push(&self.weight_text[..]);
+                } else if matches!(&self.graph, Some(g) if g.get_is_weighted()) {
+                    args.push("1");
+                }
+                let prev = self.graph.clone();
+                add_edge_random(&args[..], &mut self.graph, None)?;
+                self.save_undo(prev);
+                sender.send(AppMsg::GraphChanged).unwrap();
+            }
+            // Добавление рёбер списком
+            AppMsg::AddEdgeList => {
+                if self.graph.is_none() {
+                    return Err(GraphInterfaceError::GraphNotExist.into());
+                }
+                let edge_cnt = self
+                    .edge_list_text
+                    .lines()
+                    .filter(|line| !line.trim().is_empty())
+                    .count();
+                let prev = self.graph.clone();
+                let errors = add_edges_bulk(&self.edge_list_text, &mut self.graph);
+                // Хотя бы одно ребро добавлено
+                if errors.len() < edge_cnt {
+                    self.save_undo(prev);
+                    sender.send(AppMsg::GraphChanged).unwrap();
+                }
+                // Сообщение обо всех ошибках сразу
+                if !errors.is_empty() {
+                    let text = errors
+                        .iter()
+                        .map(|(n, e)| format!("{} {}: {}", self.lang.tr("Строка", "Line"), n, e))
+                        .collect::<Vec<_>>()
+                        .join("\n");
+                    sender
+                        .send(AppMsg::ShowError(format!(
+                            "{}\n{}",
+                            self.lang
+                                .tr("Не удалось добавить рёбра:", "Failed to add edges:"),
+                            text
+                        )))
+                        .unwrap();
+                }
+            }
+            // Изменение метки вершины в списке вершин
+            AppMsg::SetVertexLabel(i, label) => {
+                let prev = self.graph.clone();
+                set_vertex_label(&i, &label, &mut self.graph)?;
+                self.save_undo(prev);
+                sender.send(AppMsg::GraphChanged).unwrap();
+            }
+            // Изменение веса ребра в списке рёбер
+            AppMsg::SetEdgeWeight(row, weight) => {
+                let g = self
+                    .graph
+                    .as_ref()
+                    .ok_or(GraphInterfaceError::GraphNotExist)?;
+                let (i, j, index) = match edge_rows(g).get(row) {
+                    Some((i, e, index)) => (i.to_string(), e.to.to_string(), *index),
+                    None => return Err(GraphOperationError::EdgeNotFound.into()),
+                };
+                let prev = self.graph.clone();
+                set_edge_weight(&i, &j, index, &weight, &mut self.graph)?;
+                self.save_undo(prev);
+                sender.send(AppMsg::GraphChanged).unwrap();
+            }
+            // Начало изменения веса ребра, выбранного в окне графа: концы ребра и его вес
+            // записываются в поля, а поле веса получает фокус (номер ребра среди параллельных
+            // ему запоминается)
+            AppMsg::EditEdgeWeight(from, to, index) => {
+                let g = self
+                    .graph
+                    .as_ref()
+                    .ok_or(GraphInterfaceError::GraphNotExist)?;
+                let weight = g
+                    .get_edges(&from, &to)?
+                    .get(index)
+                    .ok_or(GraphOperationError::EdgeNotFound)?
+                    .weight
+                    .as_ref();
+                self.vertex1_text = from.to_string();
+                self.vertex2_text = to.to_string();
+                self.weight_text = weight.map_or_else(String::new, ToString::to_string);
+                self.edited_edge = Some((from, to, index));
+                self.focus_weight_entry.set(true);
+            }
+            // Изменение веса ребра
+            AppMsg::ChangeEdgeWeight => {
+                let index = self.edited_edge.as_ref().map_or(0, |(_, _, index)| *index);
+                let prev = self.graph.clone();
+                set_edge_weight(
+                    &self.vertex1_text,
+                    &self.vertex2_text,
+                    index,
+                    &self.weight_text,
+                    &mut self.graph,
+                )?;
+                self.save_undo(prev);
+                self.edited_edge = None;
                 sender.send(AppMsg::GraphChanged).unwrap();
             }
             // Удаление ребра
             AppMsg::DeleteEdge => {
+                let prev = self.graph.clone();
                 remove_edge(
                     &self.vertex1_text[..],
                     &self.vertex2_text[..],
                     &mut self.graph,
                 )?;
+                self.save_undo(prev);
                 sender.send(AppMsg::GraphChanged).unwrap();
             }
+            // Слияние вершин
+            AppMsg::MergeVertices => {
+                let prev = self.graph.clone();
+                merge_vertices(
+                    &self.vertex1_text[..],
+                    &self.vertex2_text[..],
+                    self.merge_sum_weights,
+                    self.merge_keep_loops,
+                    &mut self.graph,
+                )?;
+                self.save_undo(prev);
+                sender.send(AppMsg::GraphChanged).unwrap();
+            }
+            // Открепление всех вершин
+            AppMsg::UnpinAll => {
+                self.graph_window_proxy
+                    .send_event(GraphWindowMsg::UnpinAll)
+                    .unwrap();
+            }
             // Сброс изображения графа
             AppMsg::ResetImage => {
                 self.graph_window_proxy
                     .send_event(GraphWindowMsg::ResetImage)
                     .unwrap();
             }
-            // Выполнение шага алгоритма
-            AppMsg::AlgorithmStep => {
-                let mut curr_state = AlgorithmState::NotStarted;
-                std::mem::swap(&mut curr_state, &mut self.graph_algorithm_state);
-                let new_state =
-                    algorithm_step(curr_state, &self.graph, &self.source_text, &self.sink_text)?;
-                self.graph_algorithm_started = !matches!(new_state, AlgorithmState::NotStarted);
-                self.graph_algorithm_state = new_state;
+            // Масштабирование камеры по всему графу
+            AppMsg::FitView => {
+                self.graph_window_proxy
+                    .send_event(GraphWindowMsg::FitView)
+                    .unwrap();
+            }
+            // Фокусировка камеры на вершине с идентификатором из поля поиска или на вершине,
+            // метка которой содержит текст поиска (при повторном поиске того же текста
+            // вершины с такими метками перебираются по очереди)
+            AppMsg::FocusVertex => {
+                if self.search_text.is_empty() {
+                    return Ok(());
+                }
+                let g = self
+                    .graph
+                    .as_ref()
+                    .ok_or(GraphInterfaceError::GraphNotExist)?;
+                let found = g.find_vertices(&self.search_text);
+                if found.is_empty() {
+                    return Err(GraphOperationError::VertexNotFound.into());
+                }
+                self.search_index = if self.search_text == self.last_search_text {
+                    (self.search_index + 1) % found.len()
+                } else {
+                    0
+                };
+                self.last_search_text = self.search_text.clone();
                 self.graph_window_proxy
-                    .send_event(GraphWindowMsg::GraphAlgorithmStateChanged(
-                        self.graph_algorithm_state.clone(),
+                    .send_event(GraphWindowMsg::FocusVertex(
+                        found[self.search_index].clone(),
                     ))
                     .unwrap();
             }
-            // Запуск алгоритма до конца
+            // Готовые расположения вершин
+            AppMsg::LayoutCircular => {
+                self.graph_window_proxy
+                    .send_event(GraphWindowMsg::LayoutCircular)
+                    .unwrap();
+            }
+            AppMsg::LayoutGrid => {
+                self.graph_window_proxy
+                    .send_event(GraphWindowMsg::LayoutGrid)
+                    .unwrap();
+            }
+            AppMsg::LayoutLayered => {
+                self.graph_window_proxy
+                    .send_event(GraphWindowMsg::LayoutLayered)
+                    .unwrap();
+            }
+            // Отмена изменения графа
+            AppMsg::Undo => {
+                if let Some(prev) = self.undo_stack.pop_back() {
+                    let curr = std::mem::replace(&mut self.graph, prev);
+                    self.redo_stack.push(curr);
+                    self.graph_unsaved = true;
+                    sender.send(AppMsg::GraphChanged).unwrap();
+                }
+            }
+            // Повтор отменённого изменения графа
+            AppMsg::Redo => {
+                if let Some(next) = self.redo_stack.pop() {
+                    let curr = std::mem::replace(&mut self.graph, next);
+                    self.undo_stack.push_back(curr);
+                    self.graph_unsaved = true;
+                    sender.send(AppMsg::GraphChanged).unwrap();
+                }
+            }
+            // Выполнение шага алгоритма; результат завершённого алгоритма остаётся
+            // на изображении до явного сброса
+            AppMsg::AlgorithmStep if self.graph_algorithm_state.is_finished() => {}
+            AppMsg::AlgorithmStep => self.step_algorithm()?,
+            // Сброс алгоритма
+            AppMsg::AlgorithmReset => {
+                self.stop_play_timer();
+                self.reset_algorithm();
+            }
+            // Сброс алгоритма и запуск заново (например, после изменения графа)
+            AppMsg::AlgorithmRestart => {
+                self.stop_play_timer();
+                self.restart_algorithm()?;
+            }
+            // Запуск автоматического выполнения алгоритма: первый шаг (для ещё не запущенного
+            // алгоритма — инициализация) выполняется сразу, остальные — по таймеру
+            AppMsg::ToggleAlgorithmPlay(true) => {
+                if self.algorithm_play_timer.is_none() && !self.graph_algorithm_state.is_finished()
+                {
+                    self.start_play_timer(sender);
+                    sender.send(AppMsg::AlgorithmPlayTick).unwrap();
+                }
+            }
+            // Пауза: состояние алгоритма остаётся таким же, как после последнего шага
+            AppMsg::ToggleAlgorithmPlay(false) => self.stop_play_timer(),
+            AppMsg::ChangeAlgorithmPlayInterval(x) => {
+                self.settings.algorithm_play_interval = x;
+                if self.algorithm_play_timer.is_some() {
+                    self.start_play_timer(sender);
+                }
+            }
+            // Шаг по таймеру; выполнение останавливается после завершения алгоритма
+            // или при ошибке (чтобы сообщение о ней было показано один раз)
+            AppMsg::AlgorithmPlayTick => {
+                if self.algorithm_play_timer.is_none() {
+                    return Ok(());
+                }
+                // Алгоритм мог быть завершён вручную между шагами по таймеру
+                if self.graph_algorithm_state.is_finished() {
+                    self.stop_play_timer();
+                    return Ok(());
+                }
+                if let Err(e) = self.step_algorithm() {
+                    self.stop_play_timer();
+                    return Err(e);
+                }
+                if self.graph_algorithm_state.is_finished()
+                    || self.graph_algorithm_state.is_not_started()
+                {
+                    self.stop_play_timer();
+                }
+            }
+            // Запуск алгоритма до конца в фоновом потоке (с копиями графа и полей), чтобы
+            // интерфейс не блокировался; окно графа получает только итоговое состояние
             AppMsg::AlgorithmFullRun => {
-                let mut curr_state = AlgorithmState::NotStarted;
-                std::mem::swap(&mut curr_state, &mut self.graph_algorithm_state);
-                loop {
-                    let new_state = algorithm_step(
-                        curr_state,
-                        &self.graph,
-                        &self.source_text,
-                        &self.sink_text,
-                    )?;
-                    match new_state {
-                        AlgorithmState::Finished(_) | AlgorithmState::NotStarted => {
-                            self.graph_algorithm_started =
-                                !matches!(new_state, AlgorithmState::NotStarted);
-                            self.graph_algorithm_state = new_state;
-                            self.graph_window_proxy
-                                .send_event(GraphWindowMsg::GraphAlgorithmStateChanged(
-                                    self.graph_algorithm_state.clone(),
-                                ))
-                                .unwrap();
-                            break;
-                        }
-                        _ => {
-                            curr_state = new_state;
-                        }
-                    }
+                self.stop_play_timer();
+                let graph = self.graph.clone();
+                let (s_str, t_str) = (self.source_text.clone(), self.sink_text.clone());
+                let left_str = self.left_text.clone();
+                let (strategy, unit_capacities) = (self.path_strategy, self.unit_capacities);
+                let step = move |state| {
+                    algorithm_step(
+                        state,
+                        &graph,
+                        &s_str,
+                        &t_str,
+                        &left_str,
+                        strategy,
+                        unit_capacities,
+                    )
+                };
+                let (progress_sender, done_sender) = (sender.clone(), sender.clone());
+                self.algorithm_run_cnt += 1;
+                let runner = AlgorithmRunner::spawn(
+                    self.algorithm_run_cnt,
+                    self.graph_algorithm_state.clone(),
+                    self.flow_report.clone(),
+                    step,
+                    // Приложение могло быть закрыто до завершения выполнения
+                    move |id, progress| {
+                        let _ = progress_sender.send(AppMsg::AlgorithmRunProgress(id, progress));
+                    },
+                    move |id, result| {
+                        let _ = done_sender.send(AppMsg::AlgorithmRunFinished(id, result));
+                    },
+                );
+                self.algorithm_run = Some(AlgorithmRun {
+                    runner,
+                    progress: RunProgress::new(0, &self.graph_algorithm_state),
+                    prev_state: self.graph_algorithm_state.clone(),
+                    prev_report: self.flow_report.clone(),
+                });
+                // Пока алгоритм выполняется, граф не изменяется
+                self.graph_algorithm_started = true;
+            }
+            // Остановка выполнения алгоритма до конца после текущего шага
+            AppMsg::AlgorithmStop => {
+                if let Some(run) = &self.algorithm_run {
+                    run.runner.cancel();
                 }
             }
-
-            // Граф изменился, обновление текста графа
-            AppMsg::GraphChanged => {
-                match self.graph.as_ref() {
-                    Some(g) => {
-                        let mut buf = Vec::new();
-                        g.to_file(&mut buf).unwrap();
-                        self.graph_text
-                            .borrow()
-                            .as_ref()
-                            .unwrap()
-                            .set_text(std::str::from_utf8(&buf).unwrap());
+            // Ход выполнения алгоритма до конца (сообщения прежних выполнений пропускаются)
+            AppMsg::AlgorithmRunProgress(id, progress) => {
+                if let Some(run) = self
+                    .algorithm_run
+                    .as_mut()
+                    .filter(|run| run.runner.get_id() == id)
+                {
+                    run.progress = progress;
+                }
+            }
+            // Завершение (или остановка) выполнения алгоритма до конца: в истории сохраняется
+            // только состояние до запуска, чтобы не копировать каждый шаг, а в отчёт записаны
+            // все промежуточные шаги
+            AppMsg::AlgorithmRunFinished(id, result) => {
+                let run = match self.algorithm_run.take() {
+                    Some(run) if run.runner.get_id() == id => run,
+                    // Результат прежнего выполнения пропускается
+                    run => {
+                        self.algorithm_run = run;
+                        return Ok(());
+                    }
+                };
+                let (new_state, report) = match result {
+                    Ok(result) => result,
+                    Err(e) => {
+                        self.reset_algorithm();
+                        return Err(e);
+                    }
+                };
+                self.push_algorithm_history(run.prev_state, run.prev_report, &new_state);
+                self.flow_report = report;
+                self.graph_algorithm_started = !new_state.is_not_started();
+                self.graph_algorithm_state = new_state;
+                self.send_graph_state();
+            }
+            // Переключение вывода обратных дуг в таблице потоков
+            AppMsg::ToggleShowResidualArcs(x) => {
+                self.show_residual_arcs = x;
+                self.update_flow_list();
+            }
+            // Топологическая сортировка: вывод порядка вершин или выделение найденного цикла
+            AppMsg::TopologicalSort => {
+                let g = self
+                    .graph
+                    .as_ref()
+                    .ok_or(GraphInterfaceError::GraphNotExist)?;
+                let topological_sort = match g.topological_sort() {
+                    Err(e @ GraphAlgorithmError::GraphNotDirected) => return Err(e.into()),
+                    res => res,
+                };
+                let highlighted_edges = match &topological_sort {
+                    // Дуги цикла, включая замыкающую дугу из последней вершины в первую
+                    Err(GraphAlgorithmError::GraphHasCycle { cycle }) => {
+                        let cycle: Vec<VertexId> =
+                            cycle.iter().filter_map(|i| i.parse().ok()).collect();
+                        cycle
+                            .iter()
+                            .zip(cycle.iter().cycle().skip(1))
+                            .map(|(i, to)| (i.clone(), to.clone()))
+                            .collect()
                     }
-                    None => self.graph_text.borrow().as_ref().unwrap().set_text(""),
+                    _ => BTreeSet::new(),
                 };
+                self.topological_sort = Some(topological_sort);
                 self.graph_window_proxy
-                    .send_event(GraphWindowMsg::GraphChanged(self.graph.clone()))
+                    .send_event(GraphWindowMsg::HighlightEdges(highlighted_edges))
                     .unwrap();
+                self.send_topological_numbers();
             }
+            // Переключение вывода номеров вершин в топологическом порядке
+            AppMsg::ToggleTopologicalNumbers(x) => {
+                self.show_topological_numbers = x;
+                self.send_topological_numbers();
+            }
+            // Возврат к предыдущему шагу алгоритма
+            AppMsg::AlgorithmStepBack => {
+                if let Some((prev_state, prev_report)) = self.algorithm_history.pop_back() {
+                    self.graph_algorithm_started = !prev_state.is_not_started();
+                    self.graph_algorithm_state = prev_state;
+                    self.flow_report = prev_report;
+                    self.send_graph_state();
+                }
+            }
+
+            // Граф изменился, обновление текста, списков и статистики графа
+            // (изменённый пользователем текст заменяется только после подтверждения)
+            AppMsg::GraphChanged => {
+                self.graph_stats = self.graph.as_ref().map(|g| g.stats());
+                self.topological_sort = None;
+                self.update_graph_lists();
+                if self.graph_text_modified {
+                    components
+                        .confirm_dialog
+                        .send(ConfirmDialogMsg::Show)
+                        .unwrap();
+                } else {
+                    self.reset_graph_text();
+                }
+                self.send_graph_state();
+            }
+            // Замена текста графа его текстовым представлением
+            AppMsg::ResetGraphText => self.reset_graph_text(),
             // Вызов диалога открытия файла
             AppMsg::OpenFileDialog => {
+                self.set_dialog_folder(components.open_dialog.root_widget());
                 components.open_dialog.send(OpenDialogMsg::Open).unwrap();
             }
             // Вызов диалога сохранения файла
             AppMsg::SaveFileDialog => {
+                self.save_file_kind = SaveFileKind::Graph;
+                self.set_dialog_folder(components.save_dialog.root_widget());
+                Self::set_dialog_filter(components.save_dialog.root_widget(), None);
                 components
                     .save_dialog
                     .send(SaveDialogMsg::SaveAs(String::new()))
                     .unwrap();
             }
+            // Вызов диалога сохранения изображения
+            AppMsg::SaveImageDialog => {
+                self.save_file_kind = SaveFileKind::Image;
+                self.set_dialog_folder(components.save_dialog.root_widget());
+                Self::set_dialog_filter(components.save_dialog.root_widget(), Some("png"));
+                components
+                    .save_dialog
+                    .send(SaveDialogMsg::SaveAs(String::from("graph.png")))
+                    .unwrap();
+            }
+            // Вызов диалога сохранения изображения в формате SVG
+            AppMsg::SaveSvgDialog => {
+                self.save_file_kind = SaveFileKind::Svg;
+                self.set_dialog_folder(components.save_dialog.root_widget());
+                Self::set_dialog_filter(components.save_dialog.root_widget(), Some("svg"));
+                components
+                    .save_dialog
+                    .send(SaveDialogMsg::SaveAs(String::from("graph.svg")))
+                    .unwrap();
+            }
+            // Вызов диалога сохранения отчёта о выполнении алгоритма
+            AppMsg::SaveReportDialog => {
+                self.save_file_kind = SaveFileKind::Report;
+                self.set_dialog_folder(components.save_dialog.root_widget());
+                Self::set_dialog_filter(components.save_dialog.root_widget(), None);
+                components
+                    .save_dialog
+                    .send(SaveDialogMsg::SaveAs(String::from("report.txt")))
+                    .unwrap();
+            }
             // Показ сообщения об ошибке
             AppMsg::ShowError(error) => {
                 components
@@ -331,11 +1820,16 @@ impl AppModel {
                     .send(ErrorDialogMsg::Show(error))
                     .unwrap();
             }
-            // Закрытие окна
-            AppMsg::WindowClosing => self
-                .graph_window_proxy
-                .send_event(GraphWindowMsg::CloseWindow)
-                .unwrap(),
+            // Закрытие окна управления: настройки передаются окну графа, которое дополняет их
+            // своим размером и положением, сохраняет и завершает работу приложения
+            AppMsg::WindowClosing(width, height) => {
+                self.settings.main_window_size = Some((width, height));
+                self.graph_window_proxy
+                    .send_event(GraphWindowMsg::CloseWindow(self.settings.clone()))
+                    .unwrap()
+            }
+            // Закрытие окна графа: сначала закрывается окно управления
+            AppMsg::GraphWindowClosing => self.closing = true,
         }
         Ok(())
     }
@@ -1,7 +1,9 @@
 use gtk::{
     prelude::{
-        BoxExt, ButtonExt, Cast, CheckButtonExt, EditableExt, EntryBufferExtManual, EntryExt,
-        GtkWindowExt, OrientableExt, StyleContextExt, TextViewExt, WidgetExt,
+        BoxExt, ButtonExt, Cast, CellLayoutExt, CellRendererTextExt, CheckButtonExt,
+        ColorChooserExt, EditableExt, EntryBufferExtManual, EntryExt, GtkWindowExt, ListModelExt,
+        ObjectExt, OrientableExt, StyleContextExt, TextBufferExt, TextBufferExtManual, TextViewExt,
+        TreeModelExt, TreeModelExtManual, TreeViewExt, WidgetExt,
     },
     Inhibit,
 };
@@ -9,19 +11,32 @@ use gtk::{
 use relm4::{send, WidgetPlus, Widgets};
 use relm4_components::ParentWindow;
 
-use crate::{graph_app::AppMsg, graph_flows::AlgorithmState};
+use crate::{
+    graph::{stats_text, EdgeWeights, VertexId},
+    graph_algorithms::{GraphAlgorithm, GraphAlgorithmState},
+    graph_app::AppMsg,
+    graph_errors::GraphAlgorithmError,
+    graph_examples::EXAMPLES,
+    graph_flows::{AlgorithmState, DinicPhase, MatchingState, PathStrategy},
+    graph_paths::ShortestPathState,
+    graph_runner::RunProgress,
+    graph_trees::SpanningTreeState,
+    settings::MAX_IMAGE_SCALE,
+    strings::Lang,
+};
 
-use super::{graph_window::GraphWindowMsg, AppModel};
+use super::{AppModel, GraphGenerator, VertexPickTarget, ERROR_LINE_TAG};
 
 // Интерфейс приложения
 #[relm4_macros::widget(pub)]
 impl Widgets<AppModel, ()> for AppWidgets {
     view! {
         main_window = gtk::ApplicationWindow {
-            set_title: Some("Визуализация графов (управление)"),
+            set_title: watch!(Some(model.lang.tr("Визуализация графов (управление)", "Graph visualizer (control)"))),
 
-            connect_close_request(sender) => move |_| {
-                send!(sender, AppMsg::WindowClosing);
+            connect_close_request(sender) => move |window| {
+                let (width, height) = window.default_size();
+                send!(sender, AppMsg::WindowClosing(width, height));
                 Inhibit(false)
             },
 
@@ -39,21 +54,84 @@ impl Widgets<AppModel, ()> for AppWidgets {
                         set_orientation: gtk::Orientation::Horizontal,
                         set_spacing: 5,
 
-                        append = &gtk::Button::with_label("Открыть") {
+                        append = &gtk::Button {
+                            set_label: watch!(model.lang.tr("Открыть", "Open")),
                             set_hexpand: true,
-                            set_sensitive: watch!(!model.graph_algorithm_started),
+                            set_sensitive: watch!(!model.graph_algorithm_started && model.graph_loading.is_none()),
                             connect_clicked(sender) => move |_| {
                                 send!(sender, AppMsg::OpenFileDialog);
                             },
                         },
 
-                        append = &gtk::Button::with_label("Сохранить") {
+                        append = &gtk::Button {
+                            set_label: watch!(model.lang.tr("Сохранить", "Save")),
                             set_hexpand: true,
                             set_sensitive: watch!(!model.graph_algorithm_started),
                             connect_clicked(sender) => move |_| {
                                 send!(sender, AppMsg::SaveFileDialog);
                             },
                         },
+
+                        append = &gtk::Button {
+                            set_label: watch!(model.lang.tr("Сохранить изображение", "Save image")),
+                            set_hexpand: true,
+                            connect_clicked(sender) => move |_| {
+                                send!(sender, AppMsg::SaveImageDialog);
+                            },
+                        },
+
+                        append = &gtk::Button {
+                            set_label: watch!(model.lang.tr("Экспорт в SVG", "Export to SVG")),
+                            set_hexpand: true,
+                            connect_clicked(sender) => move |_| {
+                                send!(sender, AppMsg::SaveSvgDialog);
+                            },
+                        },
+                    },
+
+                    append = &gtk::Box {
+                        set_orientation: gtk::Orientation::Horizontal,
+                        set_spacing: 5,
+
+                        append = &gtk::Label {
+                            set_label: watch!(model.lang.tr("Примеры:", "Examples:")),
+                        },
+                        append: example_dropdown = &gtk::DropDown::from_strings(&example_strings(model.lang)) {
+                            set_hexpand: true,
+                            connect_selected_notify(sender) => move |dropdown| {
+                                send!(sender, AppMsg::SetExample(dropdown.selected() as usize));
+                            }
+                        },
+                        append = &gtk::Button {
+                            set_label: watch!(model.lang.tr("Открыть пример", "Open example")),
+                            set_sensitive: watch!(!model.graph_algorithm_started && model.graph_loading.is_none()),
+                            connect_clicked(sender) => move |_| {
+                                send!(sender, AppMsg::OpenExample);
+                            },
+                        },
+                    },
+
+                    append = &gtk::Box {
+                        set_orientation: gtk::Orientation::Horizontal,
+                        set_spacing: 5,
+
+                        append = &gtk::Button {
+                            set_label: watch!(model.lang.tr("Отменить", "Undo")),
+                            set_hexpand: true,
+                            set_sensitive: watch!(!model.graph_algorithm_started && !model.undo_stack.is_empty()),
+                            connect_clicked(sender) => move |_| {
+                                send!(sender, AppMsg::Undo);
+                            },
+                        },
+
+                        append = &gtk::Button {
+                            set_label: watch!(model.lang.tr("Повторить", "Redo")),
+                            set_hexpand: true,
+                            set_sensitive: watch!(!model.graph_algorithm_started && !model.redo_stack.is_empty()),
+                            connect_clicked(sender) => move |_| {
+                                send!(sender, AppMsg::Redo);
+                            },
+                        },
                     },
 
                     append = &gtk::ScrolledWindow {
@@ -63,12 +141,36 @@ impl Widgets<AppModel, ()> for AppWidgets {
                         set_vexpand: true,
 
                         set_child: text_view = Some(&gtk::TextView) {
-                            set_editable: true,
+                            set_editable: watch!(!model.graph_algorithm_started && model.graph_loading.is_none()),
                             set_wrap_mode: gtk::WrapMode::None,
                         },
                     },
 
-                    append = &gtk::Button::with_label("Обновить граф по тексту") {
+                    append = &gtk::Box {
+                        set_orientation: gtk::Orientation::Horizontal,
+                        set_spacing: 5,
+                        set_visible: watch!(model.graph_loading.is_some()),
+
+                        append = &gtk::Spinner {
+                            set_spinning: watch!(model.graph_loading.is_some()),
+                        },
+                        append = &gtk::Label {
+                            set_label: watch!(model.lang.tr("загрузка графа...", "loading graph...")),
+                        },
+                    },
+
+                    append = &gtk::Label {
+                        set_label: watch!(model.lang.tr("текст изменён", "text modified")),
+                        set_halign: gtk::Align::Start,
+                        set_visible: watch!(model.graph_text_modified),
+                    },
+
+                    append = &gtk::Button {
+                        set_label: watch!(if model.graph_loading.is_some() {
+                            model.lang.tr("Отменить загрузку", "Cancel loading")
+                        } else {
+                            model.lang.tr("Обновить граф по тексту", "Update graph from text")
+                        }),
                         set_hexpand: true,
                         set_sensitive: watch!(!model.graph_algorithm_started),
                         connect_clicked(sender) => move |_| {
@@ -76,17 +178,18 @@ impl Widgets<AppModel, ()> for AppWidgets {
                         },
                     },
 
-                    append = &gtk::Button::with_label("Сбросить текст") {
+                    append = &gtk::Button {
+                        set_label: watch!(model.lang.tr("Сбросить текст", "Reset text")),
                         set_hexpand: true,
-                        set_sensitive: watch!(!model.graph_algorithm_started),
+                        set_sensitive: watch!(!model.graph_algorithm_started && model.graph_loading.is_none()),
                         connect_clicked(sender) => move |_| {
-                            send!(sender, AppMsg::GraphChanged);
+                            send!(sender, AppMsg::ResetGraphText);
                         },
                     },
                 },
 
-                append = &gtk::Notebook {
-                    append_page(Some(&gtk::Label::new(Some("Граф")))) = &gtk::Box {
+                append: notebook = &gtk::Notebook {
+                    append_page(Some(&gtk::Label::new(Some(tab_titles(model.lang)[0])))) = &gtk::Box {
                         set_orientation: gtk::Orientation::Vertical,
                         set_margin_all: 5,
                         set_spacing: 5,
@@ -95,53 +198,134 @@ impl Widgets<AppModel, ()> for AppWidgets {
                             set_orientation: gtk::Orientation::Horizontal,
                             set_spacing: 5,
 
-                            append = &gtk::CheckButton::with_label("Ориентированный") {
+                            append = &gtk::CheckButton {
+                                set_label: watch!(Some(model.lang.tr("Ориентированный", "Directed"))),
                                 connect_toggled(sender) => move |checkbox| {
                                     send!(sender, AppMsg::ToggleNewGraphIsDirected(checkbox.is_active()));
                                 }
                             },
-                            append = &gtk::CheckButton::with_label("Взвешенный") {
+                            append = &gtk::CheckButton {
+                                set_label: watch!(Some(model.lang.tr("Взвешенный", "Weighted"))),
                                 connect_toggled(sender) => move |checkbox| {
                                     send!(sender, AppMsg::ToggleNewGraphIsWeighted(checkbox.is_active()));
                                 }
                             },
                         },
 
-                        append = &gtk::CheckButton::with_label("Дробные веса") {
+                        append = &gtk::CheckButton {
+                            set_label: watch!(Some(model.lang.tr("Дробные веса", "Float weights"))),
                             connect_toggled(sender) => move |checkbox| {
                                 send!(sender, AppMsg::ToggleNewGraphIsFloatWeights(checkbox.is_active()));
                             }
                         },
 
-                        append = &gtk::Button::with_label("Новый граф") {
+                        append = &gtk::Button {
+                            set_label: watch!(model.lang.tr("Новый граф", "New graph")),
                             set_sensitive: watch!(!model.graph_algorithm_started),
                             connect_clicked(sender) => move |_| {
                                 send!(sender, AppMsg::NewGraph);
                             },
                         },
 
-                        append = &gtk::Entry {
-                            set_placeholder_text: Some("Вершина..."),
+                        append = &gtk::Box {
+                            set_orientation: gtk::Orientation::Horizontal,
+                            set_spacing: 5,
+
+                            append = &gtk::Button {
+                                set_label: watch!(model.lang.tr("В ориентированный", "To directed")),
+                                set_hexpand: true,
+                                set_sensitive: watch!(!model.graph_algorithm_started),
+                                connect_clicked(sender) => move |_| {
+                                    send!(sender, AppMsg::ToDirected);
+                                },
+                            },
+                            append = &gtk::Button {
+                                set_label: watch!(model.lang.tr("В неориентированный", "To undirected")),
+                                set_hexpand: true,
+                                set_sensitive: watch!(!model.graph_algorithm_started),
+                                connect_clicked(sender) => move |_| {
+                                    send!(sender, AppMsg::ToUndirected);
+                                },
+                            },
+                            append = &gtk::Button {
+                                set_label: watch!(model.lang.tr("Транспонировать", "Transpose")),
+                                set_hexpand: true,
+                                set_sensitive: watch!(!model.graph_algorithm_started),
+                                connect_clicked(sender) => move |_| {
+                                    send!(sender, AppMsg::Transpose);
+                                },
+                            },
+                        },
+
+                        append = &gtk::CheckButton {
+                            set_label: watch!(Some(model.lang.tr("Брать больший из разных весов противоположных дуг", "Take the larger of different weights of opposite arcs"))),
+                            connect_toggled(sender) => move |checkbox| {
+                                send!(sender, AppMsg::ToggleMergeMaxWeight(checkbox.is_active()));
+                            }
+                        },
+
+                        append = &gtk::Label {
+                            set_label: watch!(model.lang.tr("Вершина, выбранная мышью, записывается в поле:", "Vertex picked with the mouse is written to the field:")),
+                        },
+
+                        append: pick_target_dropdown = &gtk::DropDown::from_strings(&pick_target_strings(model.lang)) {
+                            connect_selected_notify(sender) => move |dropdown| {
+                                let target = match dropdown.selected() {
+                                    1 => VertexPickTarget::Vertex0,
+                                    2 => VertexPickTarget::Vertex1,
+                                    3 => VertexPickTarget::Vertex2,
+                                    4 => VertexPickTarget::Source,
+                                    5 => VertexPickTarget::Sink,
+                                    6 => VertexPickTarget::LeftPart,
+                                    _ => VertexPickTarget::FirstEmpty,
+                                };
+                                send!(sender, AppMsg::SetVertexPickTarget(target));
+                            }
+                        },
+
+                        append: vertex0_entry = &gtk::Entry {
+                            set_placeholder_text: watch!(Some(model.lang.tr("Вершина...", "Vertex..."))),
                             set_max_length: 20,
                             connect_changed(sender) => move |entry| {
                                 send!(sender, AppMsg::ChangeVertex0Text(entry.buffer().text()));
                             }
                         },
                         append = &gtk::Entry {
-                            set_placeholder_text: Some("Метка..."),
+                            set_placeholder_text: watch!(Some(model.lang.tr("Метка...", "Label..."))),
                             set_max_length: 20,
                             connect_changed(sender) => move |entry| {
                                 send!(sender, AppMsg::ChangeLabelText(entry.buffer().text()));
                             }
                         },
+                        append = &gtk::Box {
+                            set_orientation: gtk::Orientation::Horizontal,
+                            set_spacing: 5,
 
-                        append = &gtk::Button::with_label("Добавить вершину") {
+                            append = &gtk::CheckButton {
+                                set_label: watch!(Some(model.lang.tr("Цвет вершины", "Vertex color"))),
+                                set_active: watch!(model.vertex_color_set),
+                                connect_toggled(sender) => move |checkbox| {
+                                    send!(sender, AppMsg::ToggleVertexColorSet(checkbox.is_active()));
+                                }
+                            },
+                            append = &gtk::ColorButton {
+                                set_rgba: watch!(&model.vertex_color),
+                                set_sensitive: watch!(model.vertex_color_set),
+                                connect_color_set(sender) => move |button| {
+                                    send!(sender, AppMsg::SetVertexColor(button.rgba()));
+                                }
+                            },
+                        },
+
+                        append = &gtk::Button {
+                            set_label: watch!(model.lang.tr("Добавить вершину", "Add vertex")),
                             set_sensitive: watch!(!model.graph_algorithm_started),
                             connect_clicked(sender) => move |_| {
                                 send!(sender, AppMsg::AddVertex);
                             },
                         },
-                        append = &gtk::Button::with_label("Удалить вершину") {
+                        append = &gtk::Button {
+                            set_label: watch!(model.lang.tr("Удалить вершину", "Delete vertex")),
                             set_sensitive: watch!(!model.graph_algorithm_started),
                             connect_clicked(sender) => move |_| {
                                 send!(sender, AppMsg::DeleteVertex);
@@ -152,15 +336,15 @@ impl Widgets<AppModel, ()> for AppWidgets {
                             set_orientation: gtk::Orientation::Horizontal,
                             set_spacing: 5,
 
-                            append = &gtk::Entry {
-                                set_placeholder_text: Some("Вершина 1..."),
+                            append: vertex1_entry = &gtk::Entry {
+                                set_placeholder_text: watch!(Some(model.lang.tr("Вершина 1...", "Vertex 1..."))),
                                 set_max_length: 20,
                                 connect_changed(sender) => move |entry| {
                                     send!(sender, AppMsg::ChangeVertex1Text(entry.buffer().text()));
                                 }
                             },
-                            append = &gtk::Entry {
-                                set_placeholder_text: Some("Вершина 2..."),
+                            append: vertex2_entry = &gtk::Entry {
+                                set_placeholder_text: watch!(Some(model.lang.tr("Вершина 2...", "Vertex 2..."))),
                                 set_max_length: 20,
                                 connect_changed(sender) => move |entry| {
                                     send!(sender, AppMsg::ChangeVertex2Text(entry.buffer().text()));
@@ -168,106 +352,484 @@ impl Widgets<AppModel, ()> for AppWidgets {
                             },
                         },
 
-                        append = &gtk::Entry {
-                            set_placeholder_text: Some("Вес..."),
+                        append: weight_entry = &gtk::Entry {
+                            set_placeholder_text: watch!(Some(model.lang.tr("Вес...", "Weight..."))),
                             set_max_length: 20,
                             connect_changed(sender) => move |entry| {
                                 send!(sender, AppMsg::ChangeWeightText(entry.buffer().text()));
                             }
                         },
 
-                        append = &gtk::Button::with_label("Добавить ребро") {
+                        append = &gtk::Button {
+                            set_label: watch!(model.lang.tr("Добавить ребро", "Add edge")),
+                            set_visible: watch!(model.edited_edge.is_none()),
                             set_sensitive: watch!(!model.graph_algorithm_started),
                             connect_clicked(sender) => move |_| {
                                 send!(sender, AppMsg::AddEdge);
                             },
                         },
-                        append = &gtk::Button::with_label("Удалить ребро") {
+                        append = &gtk::Button {
+                            set_label: watch!(model.lang.tr("Изменить вес", "Change weight")),
+                            set_visible: watch!(model.edited_edge.is_some()),
+                            set_sensitive: watch!(!model.graph_algorithm_started),
+                            connect_clicked(sender) => move |_| {
+                                send!(sender, AppMsg::ChangeEdgeWeight);
+                            },
+                        },
+                        append = &gtk::Button {
+                            set_label: watch!(model.lang.tr("Удалить ребро", "Delete edge")),
                             set_sensitive: watch!(!model.graph_algorithm_started),
                             connect_clicked(sender) => move |_| {
                                 send!(sender, AppMsg::DeleteEdge);
                             },
                         },
+
+                        append = &gtk::Box {
+                            set_orientation: gtk::Orientation::Horizontal,
+                            set_spacing: 5,
+
+                            append = &gtk::CheckButton {
+                                set_label: watch!(Some(model.lang.tr("Складывать веса", "Sum weights"))),
+                                set_active: watch!(model.merge_sum_weights),
+                                connect_toggled(sender) => move |checkbox| {
+                                    send!(sender, AppMsg::ToggleMergeSumWeights(checkbox.is_active()));
+                                }
+                            },
+                            append = &gtk::CheckButton {
+                                set_label: watch!(Some(model.lang.tr("Оставлять петли", "Keep loops"))),
+                                set_active: watch!(model.merge_keep_loops),
+                                connect_toggled(sender) => move |checkbox| {
+                                    send!(sender, AppMsg::ToggleMergeKeepLoops(checkbox.is_active()));
+                                }
+                            },
+                        },
+                        append = &gtk::Button {
+                            set_label: watch!(model.lang.tr("Слить вершины", "Merge vertices")),
+                            set_sensitive: watch!(!model.graph_algorithm_started),
+                            connect_clicked(sender) => move |_| {
+                                send!(sender, AppMsg::MergeVertices);
+                            },
+                        },
+
+                        append = &gtk::Label {
+                            set_label: watch!(model.lang.tr("Рёбра списком (по одному в строке):", "Edge list (one per line):")),
+                        },
+
+                        append = &gtk::ScrolledWindow {
+                            set_hscrollbar_policy: gtk::PolicyType::Automatic,
+                            set_vscrollbar_policy: gtk::PolicyType::Automatic,
+                            set_min_content_height: 100,
+                            set_vexpand: true,
+
+                            set_child: edge_list_text_view = Some(&gtk::TextView) {
+                                set_editable: true,
+                                set_wrap_mode: gtk::WrapMode::None,
+                            },
+                        },
+
+                        append = &gtk::Button {
+                            set_label: watch!(model.lang.tr("Добавить рёбра списком", "Add edge list")),
+                            set_sensitive: watch!(!model.graph_algorithm_started),
+                            connect_clicked(sender) => move |_| {
+                                send!(sender, AppMsg::AddEdgeList);
+                            },
+                        },
+
+                        append = &gtk::Label {
+                            set_label: watch!(model.lang.tr("Генерация графа (ориентированность и взвешенность по флажкам выше):", "Graph generation (directedness and weights from the checkboxes above):")),
+                        },
+
+                        append: generator_dropdown = &gtk::DropDown::from_strings(&generator_strings(model.lang)) {
+                            connect_selected_notify(sender) => move |dropdown| {
+                                let generator = match dropdown.selected() {
+                                    0 => GraphGenerator::RandomGnp,
+                                    1 => GraphGenerator::Grid,
+                                    _ => GraphGenerator::Complete,
+                                };
+                                send!(sender, AppMsg::SetGraphGenerator(generator));
+                            }
+                        },
+
+                        append = &gtk::Box {
+                            set_orientation: gtk::Orientation::Horizontal,
+                            set_spacing: 5,
+
+                            append = &gtk::Entry {
+                                set_placeholder_text: watch!(Some(match model.graph_generator {
+                                    GraphGenerator::Grid => model.lang.tr("Ширина...", "Width..."),
+                                    _ => model.lang.tr("Вершин...", "Vertices..."),
+                                })),
+                                set_max_length: 20,
+                                connect_changed(sender) => move |entry| {
+                                    send!(sender, AppMsg::ChangeGenParam1Text(entry.buffer().text()));
+                                }
+                            },
+                            append = &gtk::Entry {
+                                set_placeholder_text: watch!(Some(match model.graph_generator {
+                                    GraphGenerator::Grid => model.lang.tr("Высота...", "Height..."),
+                                    _ => model.lang.tr("Вероятность...", "Probability..."),
+                                })),
+                                set_sensitive: watch!(model.graph_generator != GraphGenerator::Complete),
+                                set_max_length: 20,
+                                connect_changed(sender) => move |entry| {
+                                    send!(sender, AppMsg::ChangeGenParam2Text(entry.buffer().text()));
+                                }
+                            },
+                        },
+
+                        append = &gtk::Box {
+                            set_orientation: gtk::Orientation::Horizontal,
+                            set_spacing: 5,
+
+                            append = &gtk::Entry {
+                                set_placeholder_text: watch!(Some(model.lang.tr("Мин. вес...", "Min. weight..."))),
+                                set_max_length: 20,
+                                connect_changed(sender) => move |entry| {
+                                    send!(sender, AppMsg::ChangeGenWeightMinText(entry.buffer().text()));
+                                }
+                            },
+                            append = &gtk::Entry {
+                                set_placeholder_text: watch!(Some(model.lang.tr("Макс. вес...", "Max. weight..."))),
+                                set_max_length: 20,
+                                connect_changed(sender) => move |entry| {
+                                    send!(sender, AppMsg::ChangeGenWeightMaxText(entry.buffer().text()));
+                                }
+                            },
+                            append = &gtk::Entry {
+                                set_placeholder_text: watch!(Some(model.lang.tr("Зерно...", "Seed..."))),
+                                set_max_length: 20,
+                                connect_changed(sender) => move |entry| {
+                                    send!(sender, AppMsg::ChangeGenSeedText(entry.buffer().text()));
+                                }
+                            },
+                        },
+
+                        append = &gtk::Button {
+                            set_label: watch!(model.lang.tr("Сгенерировать граф", "Generate graph")),
+                            set_sensitive: watch!(!model.graph_algorithm_started),
+                            connect_clicked(sender) => move |_| {
+                                send!(sender, AppMsg::GenerateGraph);
+                            },
+                        },
+
+                        append = &gtk::Button {
+                            set_label: watch!(model.lang.tr("Случайные веса", "Random weights")),
+                            set_sensitive: watch!(!model.graph_algorithm_started),
+                            connect_clicked(sender) => move |_| {
+                                send!(sender, AppMsg::RandomizeWeights);
+                            },
+                        },
                     },
 
-                    append_page(Some(&gtk::Label::new(Some("Вид")))) = &gtk::Box {
+                    append_page(Some(&gtk::Label::new(Some(tab_titles(model.lang)[1])))) = &gtk::Box {
                         set_orientation: gtk::Orientation::Vertical,
                         set_margin_all: 5,
                         set_spacing: 5,
 
-                        append = &gtk::Label::new(Some("Сила гравитации к центру:")) {},
+                        append = &gtk::Label {
+                            set_label: watch!(model.lang.tr("Сила гравитации к центру:", "Gravity towards the center:")),
+                        },
 
-                        append = &gtk::SpinButton::new(Some(&gtk::Adjustment::new(1.1, 0.0, 10.0, 0.01, 0.1, 0.0)), 0.01, 4) {
+                        append = &gtk::SpinButton::new(Some(&gtk::Adjustment::new(model.settings.center_gravity as f64, 0.0, 10.0, 0.01, 0.1, 0.0)), 0.01, 4) {
                             connect_value_changed(sender) => move |spinbutton| {
                                 send!(sender, AppMsg::ChangeCenterGravityValue(spinbutton.value() as f32))
                             }
                         },
 
-                        append = &gtk::Label::new(Some("Сила отталкивания вершин:")) {},
+                        append = &gtk::Label {
+                            set_label: watch!(model.lang.tr("Сила отталкивания вершин:", "Vertex repulsion force:")),
+                        },
 
-                        append = &gtk::SpinButton::new(Some(&gtk::Adjustment::new(0.1, 0.0, 10.0, 0.001, 0.01, 0.0)), 0.001, 4) {
+                        append = &gtk::SpinButton::new(Some(&gtk::Adjustment::new(model.settings.repulsive_force as f64, 0.0, 10.0, 0.001, 0.01, 0.0)), 0.001, 4) {
                             connect_value_changed(sender) => move |spinbutton| {
                                 send!(sender, AppMsg::ChangeRepulsiveForceValue(spinbutton.value() as f32))
                             }
                         },
 
-                        append = &gtk::Label::new(Some("Скорость изменений:")) {},
+                        append = &gtk::Label {
+                            set_label: watch!(model.lang.tr("Скорость изменений:", "Rate of change:")),
+                        },
 
-                        append = &gtk::SpinButton::new(Some(&gtk::Adjustment::new(0.01, 0.0, 1.0, 0.0001, 0.001, 0.0)), 0.0001, 4) {
+                        append = &gtk::SpinButton::new(Some(&gtk::Adjustment::new(model.settings.time_step as f64, 0.0, 1.0, 0.0001, 0.001, 0.0)), 0.0001, 4) {
                             connect_value_changed(sender) => move |spinbutton| {
                                 send!(sender, AppMsg::ChangeTimeStepValue(spinbutton.value() as f32))
                             }
                         },
 
-                        append = &gtk::Label::new(Some("Погрешность симуляции:")) {},
+                        append = &gtk::Label {
+                            set_label: watch!(model.lang.tr("Погрешность симуляции (θ):", "Simulation error (θ):")),
+                        },
 
-                        append = &gtk::SpinButton::new(Some(&gtk::Adjustment::new(0.0, 0.0, 10.0, 0.01, 0.1, 0.0)), 0.01, 4) {
+                        append = &gtk::SpinButton::new(Some(&gtk::Adjustment::new(model.settings.theta as f64, 0.0, 2.0, 0.01, 0.1, 0.0)), 0.01, 4) {
                             connect_value_changed(sender) => move |spinbutton| {
                                 send!(sender, AppMsg::ChangeThetaValue(spinbutton.value() as f32))
                             }
                         },
 
-                        append = &gtk::CheckButton::with_label("Отрисовывать детали и текст") {
-                            set_active: true,
+                        append = &gtk::Label {
+                            set_label: watch!(model.lang.tr("Порог остановки симуляции:", "Simulation stop threshold:")),
+                        },
+
+                        append = &gtk::SpinButton::new(Some(&gtk::Adjustment::new(model.settings.settle_epsilon as f64, 0.0, 0.1, 0.00001, 0.0001, 0.0)), 0.00001, 5) {
+                            connect_value_changed(sender) => move |spinbutton| {
+                                send!(sender, AppMsg::ChangeSettleEpsilonValue(spinbutton.value() as f32))
+                            }
+                        },
+
+                        append = &gtk::Label {
+                            set_label: watch!(model.lang.tr("Отступ подписей рёбер:", "Edge label offset:")),
+                        },
+
+                        append = &gtk::SpinButton::new(Some(&gtk::Adjustment::new(model.settings.edge_label_offset as f64, 0.0, 5.0, 0.05, 0.5, 0.0)), 0.05, 2) {
+                            connect_value_changed(sender) => move |spinbutton| {
+                                send!(sender, AppMsg::ChangeEdgeLabelOffsetValue(spinbutton.value() as f32))
+                            }
+                        },
+
+                        append = &gtk::Label {
+                            set_label: watch!(model.lang.tr("Длина стрелок дуг:", "Arc arrow length:")),
+                        },
+
+                        append = &gtk::SpinButton::new(Some(&gtk::Adjustment::new(model.settings.arrow_size as f64, 0.1, 3.0, 0.05, 0.5, 0.0)), 0.05, 2) {
+                            connect_value_changed(sender) => move |spinbutton| {
+                                send!(sender, AppMsg::ChangeArrowSizeValue(spinbutton.value() as f32))
+                            }
+                        },
+
+                        append = &gtk::Label {
+                            set_label: watch!(model.lang.tr("Скрывать текст вершин диаметром меньше (пикс.):", "Hide vertex text below diameter (px):")),
+                        },
+
+                        append = &gtk::SpinButton::new(Some(&gtk::Adjustment::new(model.settings.lod_label_diameter as f64, 0.0, 100.0, 1.0, 10.0, 0.0)), 1.0, 0) {
+                            connect_value_changed(sender) => move |spinbutton| {
+                                send!(sender, AppMsg::ChangeLodLabelDiameterValue(spinbutton.value() as f32))
+                            }
+                        },
+
+                        append = &gtk::Label {
+                            set_label: watch!(model.lang.tr("Масштаб сохраняемого изображения PNG:", "Saved PNG image scale:")),
+                        },
+
+                        append = &gtk::SpinButton::new(Some(&gtk::Adjustment::new(model.settings.image_scale as f64, 1.0, MAX_IMAGE_SCALE as f64, 0.5, 1.0, 0.0)), 0.5, 1) {
+                            connect_value_changed(sender) => move |spinbutton| {
+                                send!(sender, AppMsg::ChangeImageScaleValue(spinbutton.value() as f32))
+                            }
+                        },
+
+                        append = &gtk::CheckButton {
+                            set_label: watch!(Some(model.lang.tr("Отрисовывать детали и текст", "Draw details and text"))),
+                            set_active: model.settings.full_render,
                             connect_toggled(sender) => move |checkbox| {
                                 send!(sender, AppMsg::ToggleFullRender(checkbox.is_active()));
                             }
                         },
 
-                        append = &gtk::CheckButton::with_label("Зафиксировать изображение") {
+                        append = &gtk::CheckButton {
+                            set_label: watch!(Some(model.lang.tr("Показывать подписи рёбер", "Show edge labels"))),
+                            set_active: model.settings.show_edge_labels,
+                            connect_toggled(sender) => move |checkbox| {
+                                send!(sender, AppMsg::ToggleShowEdgeLabels(checkbox.is_active()));
+                            }
+                        },
+
+                        append = &gtk::CheckButton {
+                            set_label: watch!(Some(model.lang.tr("Раскрашивать компоненты связности", "Color connected components"))),
+                            set_active: model.settings.color_by_component,
+                            connect_toggled(sender) => move |checkbox| {
+                                send!(sender, AppMsg::ToggleColorByComponent(checkbox.is_active()));
+                            }
+                        },
+
+                        append = &gtk::CheckButton {
+                            set_label: watch!(Some(model.lang.tr("Толщина рёбер по весу (по потоку при его нахождении)", "Edge width by weight (by flow while finding it)"))),
+                            set_active: model.settings.edge_width_by_weight,
+                            connect_toggled(sender) => move |checkbox| {
+                                send!(sender, AppMsg::ToggleEdgeWidthByWeight(checkbox.is_active()));
+                            }
+                        },
+
+                        append = &gtk::CheckButton {
+                            set_label: watch!(Some(model.lang.tr("Размер вершин по степени", "Vertex size by degree"))),
+                            set_active: model.settings.size_by_degree,
+                            connect_toggled(sender) => move |checkbox| {
+                                send!(sender, AppMsg::ToggleSizeByDegree(checkbox.is_active()));
+                            }
+                        },
+
+                        append = &gtk::CheckButton {
+                            set_label: watch!(Some(model.lang.tr("Показывать сетку", "Show grid"))),
+                            set_active: model.settings.show_grid,
+                            connect_toggled(sender) => move |checkbox| {
+                                send!(sender, AppMsg::ToggleShowGrid(checkbox.is_active()));
+                            }
+                        },
+
+                        append = &gtk::CheckButton {
+                            set_label: watch!(Some(model.lang.tr("Показывать координаты курсора", "Show cursor coordinates"))),
+                            set_active: model.settings.show_cursor_coords,
+                            connect_toggled(sender) => move |checkbox| {
+                                send!(sender, AppMsg::ToggleShowCursorCoords(checkbox.is_active()));
+                            }
+                        },
+
+                        append = &gtk::CheckButton {
+                            set_label: watch!(Some(model.lang.tr("Анимировать направление потока", "Animate flow direction"))),
+                            set_active: model.settings.animate_flow,
+                            connect_toggled(sender) => move |checkbox| {
+                                send!(sender, AppMsg::ToggleAnimateFlow(checkbox.is_active()));
+                            }
+                        },
+
+                        append = &gtk::Box {
+                            set_orientation: gtk::Orientation::Horizontal,
+                            set_spacing: 5,
+
+                            append = &gtk::Label {
+                                set_label: watch!(model.lang.tr("Цвета: основной", "Colors: foreground")),
+                            },
+                            append = &gtk::ColorButton {
+                                set_rgba: watch!(&model.front_color),
+                                connect_color_set(sender) => move |button| {
+                                    send!(sender, AppMsg::SetFrontColor(button.rgba()));
+                                }
+                            },
+                            append = &gtk::Label {
+                                set_label: watch!(model.lang.tr("фон", "background")),
+                            },
+                            append = &gtk::ColorButton {
+                                set_rgba: watch!(&model.back_color),
+                                connect_color_set(sender) => move |button| {
+                                    send!(sender, AppMsg::SetBackColor(button.rgba()));
+                                }
+                            },
+                            append = &gtk::Label {
+                                set_label: watch!(model.lang.tr("выделение", "selection")),
+                            },
+                            append = &gtk::ColorButton {
+                                set_rgba: watch!(&model.selection_color),
+                                connect_color_set(sender) => move |button| {
+                                    send!(sender, AppMsg::SetSelectionColor(button.rgba()));
+                                }
+                            },
+                        },
+
+                        append = &gtk::CheckButton {
+                            set_label: watch!(Some(model.lang.tr("Зафиксировать изображение", "Freeze image"))),
+                            set_active: watch!(model.graph_updates_stopped),
                             connect_toggled(sender) => move |checkbox| {
                                 send!(sender, AppMsg::ToggleGraphUpdateStop(checkbox.is_active()));
                             }
                         },
 
-                        append = &gtk::Button::with_label("Сбросить изображение") {
+                        append = &gtk::Button {
+                            set_label: watch!(model.lang.tr("Открепить все вершины", "Unpin all vertices")),
+                            connect_clicked(sender) => move |_| {
+                                send!(sender, AppMsg::UnpinAll);
+                            },
+                        },
+
+                        append = &gtk::Button {
+                            set_label: watch!(model.lang.tr("Сбросить изображение", "Reset image")),
                             connect_clicked(sender) => move |_| {
                                 send!(sender, AppMsg::ResetImage);
                             },
                         },
+
+                        append = &gtk::Button {
+                            set_label: watch!(model.lang.tr("Показать весь граф", "Show whole graph")),
+                            connect_clicked(sender) => move |_| {
+                                send!(sender, AppMsg::FitView);
+                            },
+                        },
+
+                        append = &gtk::Entry {
+                            set_placeholder_text: watch!(Some(model.lang.tr("Найти вершину (идентификатор или метка)...", "Find vertex (id or label)..."))),
+                            connect_changed(sender) => move |entry| {
+                                send!(sender, AppMsg::ChangeSearchText(entry.buffer().text()));
+                            },
+                            connect_activate(sender) => move |_| {
+                                send!(sender, AppMsg::FocusVertex);
+                            }
+                        },
+
+                        append = &gtk::Label {
+                            set_label: watch!(model.lang.tr("Расположить вершины:", "Arrange vertices:")),
+                        },
+                        append = &gtk::Box {
+                            set_orientation: gtk::Orientation::Horizontal,
+                            set_spacing: 5,
+                            append = &gtk::Button {
+                                set_label: watch!(model.lang.tr("По окружности", "Circle")),
+                                connect_clicked(sender) => move |_| {
+                                    send!(sender, AppMsg::LayoutCircular);
+                                },
+                            },
+                            append = &gtk::Button {
+                                set_label: watch!(model.lang.tr("По сетке", "Grid")),
+                                connect_clicked(sender) => move |_| {
+                                    send!(sender, AppMsg::LayoutGrid);
+                                },
+                            },
+                            append = &gtk::Button {
+                                set_label: watch!(model.lang.tr("По слоям", "Layers")),
+                                connect_clicked(sender) => move |_| {
+                                    send!(sender, AppMsg::LayoutLayered);
+                                },
+                            },
+                        },
+
+                        append = &gtk::Label {
+                            set_label: watch!(model.lang.tr("Язык интерфейса:", "Interface language:")),
+                        },
+
+                        append = &gtk::DropDown::from_strings(&["Русский", "English"]) {
+                            set_selected: if model.lang == Lang::En { 1 } else { 0 },
+                            connect_selected_notify(sender) => move |dropdown| {
+                                let lang = match dropdown.selected() {
+                                    1 => Lang::En,
+                                    _ => Lang::Ru,
+                                };
+                                send!(sender, AppMsg::SetLang(lang));
+                            }
+                        },
                     },
 
-                    append_page(Some(&gtk::Label::new(Some("Алгоритм")))) = &gtk::Box {
+                    append_page(Some(&gtk::Label::new(Some(tab_titles(model.lang)[2])))) = &gtk::Box {
                         set_orientation: gtk::Orientation::Vertical,
                         set_margin_all: 5,
                         set_spacing: 5,
 
-                        append = &gtk::Label::new(Some("Алгоритм Форда-Фалкерсона:")) {},
+                        append = &gtk::Label {
+                            set_label: watch!(model.lang.tr("Алгоритм:", "Algorithm:")),
+                        },
+
+                        append: algorithm_dropdown = &gtk::DropDown::from_strings(&algorithm_strings(model.lang)) {
+                            set_sensitive: watch!(!model.graph_algorithm_started),
+                            connect_selected_notify(sender) => move |dropdown| {
+                                let algorithm = match dropdown.selected() {
+                                    0 => GraphAlgorithm::MaxFlow,
+                                    1 => GraphAlgorithm::ShortestPath,
+                                    2 => GraphAlgorithm::Matching,
+                                    _ => GraphAlgorithm::SpanningTree,
+                                };
+                                send!(sender, AppMsg::SetAlgorithm(algorithm));
+                            }
+                        },
 
                         append = &gtk::Box {
                             set_orientation: gtk::Orientation::Horizontal,
                             set_spacing: 5,
 
-                            append = &gtk::Entry {
-                                set_placeholder_text: Some("Исток..."),
+                            append: source_entry = &gtk::Entry {
+                                set_placeholder_text: watch!(Some(model.lang.tr("Исток...", "Source..."))),
                                 set_max_length: 20,
                                 set_sensitive: watch!(!model.graph_algorithm_started),
                                 connect_changed(sender) => move |entry| {
                                     send!(sender, AppMsg::ChangeSourceText(entry.buffer().text()));
                                 }
                             },
-                            append = &gtk::Entry {
-                                set_placeholder_text: Some("Сток..."),
+                            append: sink_entry = &gtk::Entry {
+                                set_placeholder_text: watch!(Some(model.lang.tr("Сток...", "Sink..."))),
                                 set_max_length: 20,
                                 set_sensitive: watch!(!model.graph_algorithm_started),
                                 connect_changed(sender) => move |entry| {
@@ -276,31 +838,267 @@ impl Widgets<AppModel, ()> for AppWidgets {
                             },
                         },
 
+                        append: left_entry = &gtk::Entry {
+                            set_placeholder_text: watch!(Some(model.lang.tr("Вершины левой доли через пробел...", "Left part vertices separated by spaces..."))),
+                            set_sensitive: watch!(!model.graph_algorithm_started
+                                && model.graph_algorithm_state.get_algorithm() == GraphAlgorithm::Matching),
+                            connect_changed(sender) => move |entry| {
+                                send!(sender, AppMsg::ChangeLeftText(entry.buffer().text()));
+                            }
+                        },
+
+                        append: dfs_check_button = &gtk::CheckButton {
+                            set_label: watch!(Some(model.lang.tr("Поиск пути в глубину", "Depth-first path search"))),
+                            set_active: true,
+                            set_sensitive: watch!(!model.graph_algorithm_started
+                                && model.graph_algorithm_state.get_algorithm() == GraphAlgorithm::MaxFlow),
+                            connect_toggled(sender) => move |checkbox| {
+                                if checkbox.is_active() {
+                                    send!(sender, AppMsg::SetPathStrategy(PathStrategy::Dfs));
+                                }
+                            }
+                        },
+                        append = &gtk::CheckButton {
+                            set_label: watch!(Some(model.lang.tr("Поиск пути в ширину (Эдмондс-Карп)", "Breadth-first path search (Edmonds-Karp)"))),
+                            set_group: Some(&dfs_check_button),
+                            set_sensitive: watch!(!model.graph_algorithm_started
+                                && model.graph_algorithm_state.get_algorithm() == GraphAlgorithm::MaxFlow),
+                            connect_toggled(sender) => move |checkbox| {
+                                if checkbox.is_active() {
+                                    send!(sender, AppMsg::SetPathStrategy(PathStrategy::Bfs));
+                                }
+                            }
+                        },
+                        append = &gtk::CheckButton {
+                            set_label: watch!(Some(model.lang.tr("Масштабирование пропускных способностей", "Capacity scaling"))),
+                            set_group: Some(&dfs_check_button),
+                            set_sensitive: watch!(!model.graph_algorithm_started
+                                && model.graph_algorithm_state.get_algorithm() == GraphAlgorithm::MaxFlow),
+                            connect_toggled(sender) => move |checkbox| {
+                                if checkbox.is_active() {
+                                    send!(sender, AppMsg::SetPathStrategy(PathStrategy::Scaling));
+                                }
+                            }
+                        },
+                        append = &gtk::CheckButton {
+                            set_label: watch!(Some(model.lang.tr("Алгоритм Диница", "Dinic's algorithm"))),
+                            set_group: Some(&dfs_check_button),
+                            set_sensitive: watch!(!model.graph_algorithm_started
+                                && model.graph_algorithm_state.get_algorithm() == GraphAlgorithm::MaxFlow),
+                            connect_toggled(sender) => move |checkbox| {
+                                if checkbox.is_active() {
+                                    send!(sender, AppMsg::SetPathStrategy(PathStrategy::Dinic));
+                                }
+                            }
+                        },
+                        append = &gtk::CheckButton {
+                            set_label: watch!(Some(model.lang.tr("Считать пропускные способности равными 1", "Treat capacities as 1"))),
+                            set_sensitive: watch!(!model.graph_algorithm_started
+                                && model.graph_algorithm_state.get_algorithm() == GraphAlgorithm::MaxFlow),
+                            connect_toggled(sender) => move |checkbox| {
+                                send!(sender, AppMsg::ToggleUnitCapacities(checkbox.is_active()));
+                            }
+                        },
+
                         append = &gtk::Button {
-                            set_label: watch!(match model.graph_algorithm_state {
-                                AlgorithmState::NotStarted => "Запуск алгоритма",
-                                AlgorithmState::Step(_) => "Следующий шаг",
-                                AlgorithmState::Finished(_) => "Завершение алгоритма",
+                            set_sensitive: watch!(!model.algorithm_history.is_empty() && model.algorithm_run.is_none()),
+                            set_label: watch!(model.lang.tr("Предыдущий шаг", "Previous step")),
+                            connect_clicked(sender) => move |_| {
+                                send!(sender, AppMsg::AlgorithmStepBack);
+                            },
+                        },
+
+                        append = &gtk::Button {
+                            set_sensitive: watch!(!model.graph_algorithm_state.is_finished() && model.algorithm_run.is_none()),
+                            set_label: watch!(if model.graph_algorithm_state.is_not_started() {
+                                model.lang.tr("Запуск алгоритма", "Start algorithm")
+                            } else if model.graph_algorithm_state.is_finished() {
+                                model.lang.tr("Алгоритм завершён", "Algorithm finished")
+                            } else {
+                                model.lang.tr("Следующий шаг", "Next step")
                             }),
                             connect_clicked(sender) => move |_| {
                                 send!(sender, AppMsg::AlgorithmStep);
                             },
                         },
 
+                        append = &gtk::Box {
+                            set_orientation: gtk::Orientation::Horizontal,
+                            set_spacing: 5,
+
+                            append = &gtk::Button {
+                                set_hexpand: true,
+                                set_sensitive: watch!(!model.graph_algorithm_state.is_not_started() && model.algorithm_run.is_none()),
+                                set_label: watch!(model.lang.tr("Сбросить алгоритм", "Reset algorithm")),
+                                connect_clicked(sender) => move |_| {
+                                    send!(sender, AppMsg::AlgorithmReset);
+                                },
+                            },
+                            append = &gtk::Button {
+                                set_hexpand: true,
+                                set_sensitive: watch!(model.algorithm_run.is_none()),
+                                set_label: watch!(model.lang.tr("Сбросить и запустить заново", "Reset and restart")),
+                                connect_clicked(sender) => move |_| {
+                                    send!(sender, AppMsg::AlgorithmRestart);
+                                },
+                            },
+                        },
+
+                        append = &gtk::Box {
+                            set_orientation: gtk::Orientation::Horizontal,
+                            set_spacing: 5,
+
+                            append = &gtk::Button {
+                                set_hexpand: true,
+                                set_sensitive: watch!(!model.graph_algorithm_state.is_finished() && model.algorithm_run.is_none()),
+                                set_label: watch!(model.lang.tr("Запуск алгоритма до конца", "Run algorithm to the end")),
+                                connect_clicked(sender) => move |_| {
+                                    send!(sender, AppMsg::AlgorithmFullRun);
+                                },
+                            },
+                            append = &gtk::Button {
+                                set_sensitive: watch!(model.algorithm_run.is_some()),
+                                set_label: watch!(model.lang.tr("Остановить", "Stop")),
+                                connect_clicked(sender) => move |_| {
+                                    send!(sender, AppMsg::AlgorithmStop);
+                                },
+                            },
+                        },
+
+                        append = &gtk::Box {
+                            set_orientation: gtk::Orientation::Horizontal,
+                            set_spacing: 5,
+
+                            append = &gtk::ToggleButton {
+                                set_hexpand: true,
+                                set_active: watch!(model.algorithm_play_timer.is_some()),
+                                set_sensitive: watch!(!model.graph_algorithm_state.is_finished() && model.algorithm_run.is_none()),
+                                set_label: watch!(if model.algorithm_play_timer.is_some() {
+                                    model.lang.tr("Пауза", "Pause")
+                                } else {
+                                    model.lang.tr("Автоматическое выполнение", "Play")
+                                }),
+                                connect_toggled(sender) => move |button| {
+                                    send!(sender, AppMsg::ToggleAlgorithmPlay(button.is_active()));
+                                },
+                            },
+                            append = &gtk::Label {
+                                set_label: watch!(model.lang.tr("Интервал (мс):", "Interval (ms):")),
+                            },
+                            append = &gtk::SpinButton::new(Some(&gtk::Adjustment::new(model.settings.algorithm_play_interval as f64, 100.0, 5000.0, 100.0, 1000.0, 0.0)), 100.0, 0) {
+                                connect_value_changed(sender) => move |spinbutton| {
+                                    send!(sender, AppMsg::ChangeAlgorithmPlayInterval(spinbutton.value() as u64))
+                                }
+                            },
+                        },
+
+                        append = &gtk::Label {
+                            set_wrap: true,
+                            set_wrap_mode: gtk::pango::WrapMode::WordChar,
+                            set_label: watch!(&match &model.algorithm_run {
+                                Some(run) => run_progress_text(&run.progress, model.lang),
+                                None => algorithm_status_text(&model.graph_algorithm_state, model.lang),
+                            }),
+                        },
+
                         append = &gtk::Button {
-                            set_sensitive: watch!(!matches!(model.graph_algorithm_state, AlgorithmState::Finished(_))),
-                            set_label: "Запуск алгоритма до конца",
+                            set_sensitive: watch!(model.graph_algorithm_started
+                                && model.graph_algorithm_state.get_algorithm() == GraphAlgorithm::MaxFlow),
+                            set_label: watch!(model.lang.tr("Экспорт отчёта", "Export report")),
                             connect_clicked(sender) => move |_| {
-                                send!(sender, AppMsg::AlgorithmFullRun);
+                                send!(sender, AppMsg::SaveReportDialog);
                             },
                         },
 
+                        append = &gtk::Expander {
+                            set_label: watch!(Some(model.lang.tr("Таблица потоков через дуги", "Arc flow table"))),
+                            set_child = Some(&gtk::Box) {
+                                set_orientation: gtk::Orientation::Vertical,
+                                set_spacing: 5,
+
+                                append = &gtk::CheckButton {
+                                    set_label: watch!(Some(model.lang.tr("Показать остаточные дуги", "Show residual arcs"))),
+                                    connect_toggled(sender) => move |checkbox| {
+                                        send!(sender, AppMsg::ToggleShowResidualArcs(checkbox.is_active()));
+                                    }
+                                },
+
+                                append = &gtk::ScrolledWindow {
+                                    set_hscrollbar_policy: gtk::PolicyType::Automatic,
+                                    set_vscrollbar_policy: gtk::PolicyType::Automatic,
+                                    set_min_content_height: 150,
+
+                                    set_child: flow_tree_view = Some(&gtk::TreeView) {},
+                                },
+                            },
+                        },
+
+                        append = &gtk::Separator::new(gtk::Orientation::Horizontal) {},
+
+                        append = &gtk::Button {
+                            set_label: watch!(model.lang.tr("Топологическая сортировка", "Topological sort")),
+                            connect_clicked(sender) => move |_| {
+                                send!(sender, AppMsg::TopologicalSort);
+                            },
+                        },
+                        append = &gtk::CheckButton {
+                            set_label: watch!(Some(model.lang.tr("Нумеровать вершины в порядке сортировки", "Number vertices in sorted order"))),
+                            connect_toggled(sender) => move |checkbox| {
+                                send!(sender, AppMsg::ToggleTopologicalNumbers(checkbox.is_active()));
+                            }
+                        },
+
                         append = &gtk::Label {
-                            set_label: watch!(&match &model.graph_algorithm_state {
-                                AlgorithmState::NotStarted => String::new(),
-                                AlgorithmState::Step(data) => format!("Поток через дополняющий путь: {}", data.get_last_flow()),
-                                AlgorithmState::Finished(data) => format!("Максимальный поток: {}", data.get_total_flow()),
-                            }),
+                            set_wrap: true,
+                            set_wrap_mode: gtk::pango::WrapMode::WordChar,
+                            set_label: watch!(&topological_sort_text(&model.topological_sort, model.lang)),
+                        },
+                    },
+
+                    append_page(Some(&gtk::Label::new(Some(tab_titles(model.lang)[3])))) = &gtk::Box {
+                        set_orientation: gtk::Orientation::Vertical,
+                        set_margin_all: 5,
+                        set_spacing: 5,
+
+                        append = &gtk::Label {
+                            set_label: watch!(model.lang.tr("Вершины (метка изменяется двойным щелчком):", "Vertices (double-click to change the label):")),
+                        },
+
+                        append = &gtk::ScrolledWindow {
+                            set_hscrollbar_policy: gtk::PolicyType::Automatic,
+                            set_vscrollbar_policy: gtk::PolicyType::Automatic,
+                            set_min_content_height: 100,
+                            set_vexpand: true,
+
+                            set_child: vertex_tree_view = Some(&gtk::TreeView) {
+                                set_sensitive: watch!(!model.graph_algorithm_started),
+                            },
+                        },
+
+                        append = &gtk::Label {
+                            set_label: watch!(model.lang.tr("Рёбра (вес изменяется двойным щелчком):", "Edges (double-click to change the weight):")),
+                        },
+
+                        append = &gtk::ScrolledWindow {
+                            set_hscrollbar_policy: gtk::PolicyType::Automatic,
+                            set_vscrollbar_policy: gtk::PolicyType::Automatic,
+                            set_min_content_height: 100,
+                            set_vexpand: true,
+
+                            set_child: edge_tree_view = Some(&gtk::TreeView) {
+                                set_sensitive: watch!(!model.graph_algorithm_started),
+                            },
+                        },
+                    },
+
+                    append_page(Some(&gtk::Label::new(Some(tab_titles(model.lang)[4])))) = &gtk::Box {
+                        set_orientation: gtk::Orientation::Vertical,
+                        set_margin_all: 5,
+                        set_spacing: 5,
+
+                        append = &gtk::Label {
+                            set_halign: gtk::Align::Start,
+                            set_label: watch!(&stats_text(&model.graph_stats, model.lang)),
                         },
                     },
                 },
@@ -309,19 +1107,491 @@ impl Widgets<AppModel, ()> for AppWidgets {
     }
 
     fn post_init() {
-        // Установка цвета для изображения графа
-        let gtk_color = main_window.style_context().color();
-        let color = femtovg::Color::rgbaf(
-            gtk_color.red(),
-            gtk_color.green(),
-            gtk_color.blue(),
-            gtk_color.alpha(),
+        // Размер окна из сохранённых настроек
+        if let Some((width, height)) = model.settings.main_window_size {
+            main_window.set_default_size(width, height);
+        }
+
+        // Установка цвета для изображения графа по цвету текста темы
+        send!(
+            sender,
+            AppMsg::ThemeColorChanged(main_window.style_context().color())
+        );
+
+        // Обновление цвета при смене темы (после того, как стиль окна обновится)
+        if let Some(settings) = gtk::Settings::default() {
+            for property in ["gtk-theme-name", "gtk-application-prefer-dark-theme"] {
+                let theme_window = main_window.clone();
+                let theme_sender = sender.clone();
+                settings.connect_notify_local(Some(property), move |_, _| {
+                    let theme_window = theme_window.clone();
+                    let theme_sender = theme_sender.clone();
+                    gtk::glib::idle_add_local(move || {
+                        let color = theme_window.style_context().color();
+                        send!(theme_sender, AppMsg::ThemeColorChanged(color));
+                        gtk::glib::Continue(false)
+                    });
+                });
+            }
+        }
+        // Тег для выделения строки с ошибкой и отслеживание изменения текста графа
+        let graph_text = text_view.buffer();
+        graph_text.create_tag(
+            Some(ERROR_LINE_TAG),
+            &[("background", &"rgba(255, 0, 0, 0.3)")],
         );
+        let modified_sender = sender.clone();
+        graph_text.connect_modified_changed(move |buf| {
+            send!(
+                modified_sender,
+                AppMsg::GraphTextModified(buf.is_modified())
+            );
+        });
+        model.graph_text.replace(Some(graph_text));
+
+        // Списки вершин и рёбер, изменения передаются вместе с идентификаторами вершин
+        let vertex_list_sender = sender.clone();
+        let vertex_list = init_list_view(
+            &vertex_tree_view,
+            &vertex_column_titles(model.lang),
+            move |_, keys, text| {
+                send!(
+                    vertex_list_sender,
+                    AppMsg::SetVertexLabel(keys[0].clone(), text.to_string())
+                );
+            },
+        );
+        model.vertex_list.replace(Some(vertex_list));
+        let edge_list_sender = sender.clone();
+        let edge_list = init_list_view(
+            &edge_tree_view,
+            &edge_column_titles(model.lang),
+            move |row, _, text| {
+                send!(
+                    edge_list_sender,
+                    AppMsg::SetEdgeWeight(row, text.to_string())
+                );
+            },
+        );
+        model.edge_list.replace(Some(edge_list));
+        let flow_list = init_flow_view(&flow_tree_view, &flow_column_titles(model.lang));
+        model.flow_list.replace(Some(flow_list));
+
+        // Передача текста списка рёбер при его изменении
+        let edge_list_sender = sender.clone();
+        edge_list_text_view.buffer().connect_changed(move |buf| {
+            let text = buf.text(&buf.start_iter(), &buf.end_iter(), true);
+            send!(
+                edge_list_sender,
+                AppMsg::ChangeEdgeListText(text.to_string())
+            );
+        });
+
+        // Пересылка событий из потока окна графа
+        let app_sender = sender.clone();
         model
-            .graph_window_proxy
-            .send_event(GraphWindowMsg::SetColor(color))
-            .unwrap();
-        model.graph_text.replace(Some(text_view.buffer()));
+            .graph_window_receiver
+            .take()
+            .unwrap()
+            .attach(None, move |msg| {
+                send!(app_sender, msg);
+                gtk::glib::Continue(true)
+            });
+    }
+
+    fn post_view() {
+        // Закрытие окна управления вслед за окном графа
+        if model.closing && self.main_window.is_visible() {
+            self.main_window.close();
+        }
+
+        // Обновление текста полей вершин и веса, если он изменён не из интерфейса
+        // (выбором вершины или ребра мышью)
+        for (entry, text) in [
+            (&self.vertex0_entry, &model.vertex0_text),
+            (&self.vertex1_entry, &model.vertex1_text),
+            (&self.vertex2_entry, &model.vertex2_text),
+            (&self.weight_entry, &model.weight_text),
+            (&self.source_entry, &model.source_text),
+            (&self.sink_entry, &model.sink_text),
+            (&self.left_entry, &model.left_text),
+        ] {
+            if entry.text().as_str() != text {
+                entry.set_text(text);
+            }
+        }
+        // Переход к полю веса ребра, выбранного в окне графа
+        if model.focus_weight_entry.take() {
+            self.notebook.set_current_page(Some(0));
+            self.main_window.present();
+            self.weight_entry.grab_focus();
+        }
+
+        // Обновление подписей, которые нельзя задать через модель, при смене языка
+        for (k, title) in tab_titles(model.lang).into_iter().enumerate() {
+            if let Some(page) = self.notebook.nth_page(Some(k as u32)) {
+                if self.notebook.tab_label_text(&page).as_deref() != Some(title) {
+                    self.notebook.set_tab_label_text(&page, title);
+                }
+            }
+        }
+        set_dropdown_strings(&self.pick_target_dropdown, &pick_target_strings(model.lang));
+        set_dropdown_strings(&self.generator_dropdown, &generator_strings(model.lang));
+        set_dropdown_strings(&self.example_dropdown, &example_strings(model.lang));
+        set_dropdown_strings(&self.algorithm_dropdown, &algorithm_strings(model.lang));
+        set_column_titles(&self.vertex_tree_view, &vertex_column_titles(model.lang));
+        set_column_titles(&self.edge_tree_view, &edge_column_titles(model.lang));
+        set_column_titles(&self.flow_tree_view, &flow_column_titles(model.lang));
+    }
+}
+
+// Заголовки вкладок на заданном языке
+fn tab_titles(lang: Lang) -> [&'static str; 5] {
+    [
+        lang.tr("Граф", "Graph"),
+        lang.tr("Вид", "View"),
+        lang.tr("Алгоритм", "Algorithm"),
+        lang.tr("Вершины и рёбра", "Vertices and edges"),
+        lang.tr("Статистика", "Statistics"),
+    ]
+}
+
+// Названия примеров графов на заданном языке
+fn example_strings(lang: Lang) -> Vec<&'static str> {
+    EXAMPLES
+        .iter()
+        .map(|example| lang.tr(example.name_ru, example.name_en))
+        .collect()
+}
+
+// Варианты поля для вершины, выбранной мышью, на заданном языке
+fn pick_target_strings(lang: Lang) -> [&'static str; 7] {
+    [
+        lang.tr("Первое пустое", "First empty"),
+        lang.tr("Вершина", "Vertex"),
+        lang.tr("Вершина 1", "Vertex 1"),
+        lang.tr("Вершина 2", "Vertex 2"),
+        lang.tr("Исток", "Source"),
+        lang.tr("Сток", "Sink"),
+        lang.tr("Левая доля", "Left part"),
+    ]
+}
+
+// Названия генераторов графа на заданном языке
+fn generator_strings(lang: Lang) -> [&'static str; 3] {
+    [
+        lang.tr("Случайный граф G(n, p)", "Random graph G(n, p)"),
+        lang.tr("Решётка", "Grid"),
+        lang.tr("Полный граф", "Complete graph"),
+    ]
+}
+
+// Названия алгоритмов на заданном языке
+fn algorithm_strings(lang: Lang) -> [&'static str; 4] {
+    [
+        lang.tr("Максимальный поток", "Maximum flow"),
+        lang.tr("Кратчайший путь", "Shortest path"),
+        lang.tr("Паросочетание в двудольном графе", "Bipartite matching"),
+        lang.tr(
+            "Минимальный остов (Краскал)",
+            "Minimum spanning tree (Kruskal)",
+        ),
+    ]
+}
+
+// Заголовки столбцов списка вершин на заданном языке
+fn vertex_column_titles(lang: Lang) -> [&'static str; 2] {
+    [lang.tr("Вершина", "Vertex"), lang.tr("Метка", "Label")]
+}
+
+// Заголовки столбцов списка рёбер на заданном языке
+fn edge_column_titles(lang: Lang) -> [&'static str; 3] {
+    [
+        lang.tr("Начало", "From"),
+        lang.tr("Конец", "To"),
+        lang.tr("Вес", "Weight"),
+    ]
+}
+
+// Заголовки столбцов таблицы потоков на заданном языке
+fn flow_column_titles(lang: Lang) -> [&'static str; 5] {
+    [
+        lang.tr("Начало", "From"),
+        lang.tr("Конец", "To"),
+        lang.tr("Пропускная способность", "Capacity"),
+        lang.tr("Поток", "Flow"),
+        lang.tr("Остаток", "Residual"),
+    ]
+}
+
+// Замена вариантов выпадающего списка с сохранением выбранного варианта
+fn set_dropdown_strings(dropdown: &gtk::DropDown, strings: &[&str]) {
+    let list = match dropdown
+        .model()
+        .and_then(|model| model.downcast::<gtk::StringList>().ok())
+    {
+        Some(list) => list,
+        None => return,
+    };
+    if list.string(0).as_deref() == strings.first().copied() {
+        return;
+    }
+    let selected = dropdown.selected();
+    list.splice(0, list.n_items(), strings);
+    dropdown.set_selected(selected);
+}
+
+// Замена заголовков столбцов таблицы
+fn set_column_titles(tree_view: &gtk::TreeView, titles: &[&str]) {
+    for (k, title) in titles.iter().enumerate() {
+        if let Some(column) = tree_view.column(k as i32) {
+            if column.title().as_str() != *title {
+                column.set_title(title);
+            }
+        }
+    }
+}
+
+// Настройка таблицы со строковыми столбцами-ключами и последним редактируемым столбцом
+// (заголовки столбцов задаются по порядку), при изменении значения вызывается обработчик
+// с номером строки, её ключами и новым текстом
+fn init_list_view<F>(tree_view: &gtk::TreeView, titles: &[&str], edited: F) -> gtk::ListStore
+where
+    F: Fn(usize, &[String], &str) + 'static,
+{
+    let key_cnt = titles.len() - 1;
+    let list = gtk::ListStore::new(&vec![gtk::glib::Type::STRING; key_cnt + 1]);
+    tree_view.set_model(Some(&list));
+
+    // Столбец таблицы с заданным заголовком
+    let append_column = |title: &str, k: usize| {
+        let renderer = gtk::CellRendererText::new();
+        let column = gtk::TreeViewColumn::new();
+        column.set_title(title);
+        column.pack_start(&renderer, true);
+        column.add_attribute(&renderer, "text", k as i32);
+        tree_view.append_column(&column);
+        renderer
+    };
+    for (k, title) in titles[..key_cnt].iter().enumerate() {
+        append_column(title, k);
+    }
+    let renderer = append_column(titles[key_cnt], key_cnt);
+    renderer.set_editable(true);
+
+    // Значение в списке не изменяется, список обновляется вместе с графом,
+    // поэтому при ошибке остаётся прежнее значение
+    let weak_list = list.downgrade();
+    renderer.connect_edited(move |_, path, text| {
+        let list = match weak_list.upgrade() {
+            Some(list) => list,
+            None => return,
+        };
+        if let Some(iter) = list.iter(&path) {
+            let keys: Vec<String> = (0..key_cnt)
+                .map(|k| list.get::<String>(&iter, k as i32))
+                .collect();
+            edited(path.indices()[0] as usize, &keys, text);
+        }
+    });
+    list
+}
+
+// Настройка таблицы только для чтения со строковыми столбцами (заголовки столбцов задаются
+// по порядку) и дополнительным столбцом толщины шрифта строки
+fn init_flow_view(tree_view: &gtk::TreeView, titles: &[&str]) -> gtk::ListStore {
+    let mut types = vec![gtk::glib::Type::STRING; titles.len()];
+    types.push(gtk::glib::Type::I32);
+    let list = gtk::ListStore::new(&types);
+    tree_view.set_model(Some(&list));
+    for (k, title) in titles.iter().enumerate() {
+        let renderer = gtk::CellRendererText::new();
+        let column = gtk::TreeViewColumn::new();
+        column.set_title(title);
+        column.pack_start(&renderer, true);
+        column.add_attribute(&renderer, "text", k as i32);
+        column.add_attribute(&renderer, "weight", titles.len() as i32);
+        tree_view.append_column(&column);
+    }
+    list
+}
+
+// Текст состояния выполнения алгоритма
+fn algorithm_status_text(state: &GraphAlgorithmState<VertexId, EdgeWeights>, lang: Lang) -> String {
+    match state {
+        GraphAlgorithmState::MaxFlow(AlgorithmState::NotStarted) => String::new(),
+        GraphAlgorithmState::MaxFlow(AlgorithmState::Step(data))
+            if data.get_dinic_phase() == Some(DinicPhase::LevelGraph) =>
+        {
+            format!(
+                "{}\n{}: {}",
+                lang.tr("Построена слоистая сеть", "Level graph built"),
+                lang.tr("Уровень стока", "Sink level"),
+                data.get_levels()
+                    .as_ref()
+                    .and_then(|levels| levels.get(data.get_t()))
+                    .map_or(String::new(), |level| level.to_string())
+            )
+        }
+        GraphAlgorithmState::MaxFlow(AlgorithmState::Step(data))
+            if data.get_dinic_phase() == Some(DinicPhase::BlockingFlow) =>
+        {
+            format!(
+                "{}: {}\n{}:\n{}",
+                lang.tr("Блокирующий поток", "Blocking flow"),
+                data.get_last_flow(),
+                lang.tr("Дополняющие пути", "Augmenting paths"),
+                data.get_blocking_paths()
+                    .iter()
+                    .map(|(path, flow)| format!(
+                        "{} ({})",
+                        path.iter()
+                            .map(|i| i.to_string())
+                            .collect::<Vec<_>>()
+                            .join(" → "),
+                        flow
+                    ))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            )
+        }
+        GraphAlgorithmState::MaxFlow(AlgorithmState::Step(data)) => format!(
+            "{}: {}\n{}: {}{}",
+            lang.tr(
+                "Поток через дополняющий путь",
+                "Flow through the augmenting path"
+            ),
+            data.get_last_flow(),
+            lang.tr("Дополняющий путь", "Augmenting path"),
+            data.get_curr_path_vertices()
+                .iter()
+                .map(|i| i.to_string())
+                .collect::<Vec<_>>()
+                .join(" → "),
+            match data.get_delta() {
+                Some(delta) => format!(
+                    "\n{}: {}",
+                    lang.tr(
+                        "Порог остаточной пропускной способности (Δ)",
+                        "Residual capacity threshold (Δ)"
+                    ),
+                    delta
+                ),
+                None => String::new(),
+            }
+        ),
+        GraphAlgorithmState::MaxFlow(AlgorithmState::Finished(data)) => format!(
+            "{}: {}\n{}: {}",
+            lang.tr("Максимальный поток", "Maximum flow"),
+            data.get_total_flow(),
+            lang.tr(
+                "Пропускная способность минимального разреза",
+                "Minimum cut capacity"
+            ),
+            data.get_min_cut_capacity()
+        ),
+        GraphAlgorithmState::ShortestPath(ShortestPathState::NotStarted) => String::new(),
+        GraphAlgorithmState::ShortestPath(ShortestPathState::Step(data)) => {
+            match data.get_last_vertex() {
+                Some(i) => format!(
+                    "{}: {}",
+                    lang.tr("Обработана вершина", "Processed vertex"),
+                    i
+                ),
+                None => String::new(),
+            }
+        }
+        GraphAlgorithmState::ShortestPath(ShortestPathState::Finished(data)) => {
+            match data.get_distance() {
+                Some(d) => format!(
+                    "{}: {}",
+                    lang.tr("Длина кратчайшего пути", "Shortest path length"),
+                    d
+                ),
+                None => String::from(lang.tr("Путь не найден", "Path not found")),
+            }
+        }
+        GraphAlgorithmState::Matching(MatchingState::NotStarted) => String::new(),
+        GraphAlgorithmState::Matching(MatchingState::Finished(data)) => format!(
+            "{}: {}\n{}: {}",
+            lang.tr(
+                "Размер максимального паросочетания",
+                "Maximum matching size"
+            ),
+            data.get_edges().len(),
+            lang.tr("Рёбра паросочетания", "Matching edges"),
+            data.get_edges()
+                .iter()
+                .map(|(i, to)| format!("{} — {}", i, to))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        GraphAlgorithmState::SpanningTree(SpanningTreeState::NotStarted) => String::new(),
+        GraphAlgorithmState::SpanningTree(SpanningTreeState::Step(data)) => format!(
+            "{}: {}{}",
+            lang.tr("Вес остова", "Tree weight"),
+            data.get_total_weight(),
+            match data.get_last_edge() {
+                Some((i, to)) if data.is_accepted(i, to) => format!(
+                    "\n{}: {} — {}",
+                    lang.tr("Добавлено ребро", "Edge added"),
+                    i,
+                    to
+                ),
+                Some((i, to)) => format!(
+                    "\n{}: {} — {}",
+                    lang.tr("Ребро образует цикл", "Edge forms a cycle"),
+                    i,
+                    to
+                ),
+                None => String::new(),
+            }
+        ),
+        GraphAlgorithmState::SpanningTree(SpanningTreeState::Finished(data)) => format!(
+            "{}: {}\n{}: {}",
+            lang.tr("Вес минимального остова", "Minimum spanning tree weight"),
+            data.get_total_weight(),
+            lang.tr("Компонент связности", "Connected components"),
+            data.get_tree_count()
+        ),
+    }
+}
+
+// Текст хода выполнения алгоритма до конца
+fn run_progress_text(progress: &RunProgress, lang: Lang) -> String {
+    let mut text = format!(
+        "{}: {}",
+        lang.tr("Выполнено шагов", "Steps done"),
+        progress.steps
+    );
+    if let Some(total_flow) = &progress.total_flow {
+        text = format!(
+            "{}\n{}: {}",
+            text,
+            lang.tr("Текущий поток", "Current flow"),
+            total_flow
+        );
+    }
+    text
+}
+
+// Текст результата топологической сортировки
+fn topological_sort_text(
+    topological_sort: &Option<Result<Vec<VertexId>, GraphAlgorithmError>>,
+    lang: Lang,
+) -> String {
+    match topological_sort {
+        None => String::new(),
+        Some(Ok(order)) => format!(
+            "{}: {}",
+            lang.tr("Порядок вершин", "Vertex order"),
+            order
+                .iter()
+                .map(|i| i.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        Some(Err(e)) => e.to_string(),
     }
 }
 
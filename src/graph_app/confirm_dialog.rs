@@ -0,0 +1,91 @@
+use gtk::prelude::{ButtonExt, Cast, DialogExt, GtkWindowExt, WidgetExt};
+use relm4::{send, ComponentUpdate, Model, Sender, Widgets};
+
+use super::{AppModel, AppMsg};
+use crate::strings::tr;
+
+// Модель данных для подтверждения замены изменённого текста графа
+pub struct ConfirmDialogModel {
+    hidden: bool, // скрыт ли диалог
+}
+
+// Сообщения к модели данных
+pub enum ConfirmDialogMsg {
+    Show,   // показать диалог
+    Accept, // заменить текст
+    Reject, // оставить изменённый текст
+}
+
+impl Model for ConfirmDialogModel {
+    type Msg = ConfirmDialogMsg;
+    type Widgets = ConfirmDialogWidgets;
+    type Components = ();
+}
+
+impl ComponentUpdate<AppModel> for ConfirmDialogModel {
+    // Инициализация модели
+    fn init_model(_parent_model: &AppModel) -> Self {
+        ConfirmDialogModel { hidden: true }
+    }
+
+    // Обработка сообщений к модели
+    fn update(
+        &mut self,
+        msg: ConfirmDialogMsg,
+        _components: &(),
+        _sender: Sender<ConfirmDialogMsg>,
+        parent_sender: Sender<AppMsg>,
+    ) {
+        match msg {
+            ConfirmDialogMsg::Show => self.hidden = false,
+            ConfirmDialogMsg::Accept => {
+                self.hidden = true;
+                send!(parent_sender, AppMsg::ResetGraphText);
+            }
+            ConfirmDialogMsg::Reject => self.hidden = true,
+        }
+    }
+}
+
+// Интерфейс диалога подтверждения
+#[relm4_macros::widget(pub)]
+impl Widgets<ConfirmDialogModel, AppModel> for ConfirmDialogWidgets {
+    view! {
+        dialog = gtk::MessageDialog {
+            set_transient_for: parent!(Some(&parent_widgets.main_window)),
+            set_modal: true,
+            set_visible: watch!(!model.hidden),
+            set_text: watch!(Some(tr("Текст графа изменён", "Graph text modified"))),
+            set_secondary_text: watch!(Some(tr(
+                "Граф изменился. Заменить изменённый текст текстовым представлением графа?",
+                "The graph has changed. Replace the modified text with the text representation of the graph?"
+            ))),
+            set_message_type: gtk::MessageType::Question,
+            add_button: args!(tr("Заменить", "Replace"), gtk::ResponseType::Accept),
+            add_button: args!(tr("Оставить текст", "Keep text"), gtk::ResponseType::Reject),
+            connect_response(sender) => move |_, response| {
+                if response == gtk::ResponseType::Accept {
+                    send!(sender, ConfirmDialogMsg::Accept);
+                } else {
+                    send!(sender, ConfirmDialogMsg::Reject);
+                }
+            }
+        }
+    }
+
+    fn post_view() {
+        // Подписи кнопок на текущем языке
+        for (response, label) in [
+            (gtk::ResponseType::Accept, tr("Заменить", "Replace")),
+            (gtk::ResponseType::Reject, tr("Оставить текст", "Keep text")),
+        ] {
+            if let Some(button) = self
+                .dialog
+                .widget_for_response(response)
+                .and_then(|widget| widget.downcast::<gtk::Button>().ok())
+            {
+                button.set_label(label);
+            }
+        }
+    }
+}
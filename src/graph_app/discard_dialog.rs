@@ -0,0 +1,103 @@
+use gtk::prelude::{ButtonExt, Cast, DialogExt, GtkWindowExt, WidgetExt};
+use relm4::{send, ComponentUpdate, Model, Sender, Widgets};
+
+use super::{AppModel, AppMsg};
+use crate::strings::tr;
+
+// Модель данных для подтверждения загрузки примера вместо несохранённого графа
+pub struct DiscardDialogModel {
+    hidden: bool,           // скрыт ли диалог
+    example: Option<usize>, // номер загружаемого примера
+}
+
+// Сообщения к модели данных
+pub enum DiscardDialogMsg {
+    Show(usize), // показать диалог для заданного примера
+    Accept,      // загрузить пример
+    Reject,      // оставить текущий граф
+}
+
+impl Model for DiscardDialogModel {
+    type Msg = DiscardDialogMsg;
+    type Widgets = DiscardDialogWidgets;
+    type Components = ();
+}
+
+impl ComponentUpdate<AppModel> for DiscardDialogModel {
+    // Инициализация модели
+    fn init_model(_parent_model: &AppModel) -> Self {
+        DiscardDialogModel {
+            hidden: true,
+            example: None,
+        }
+    }
+
+    // Обработка сообщений к модели
+    fn update(
+        &mut self,
+        msg: DiscardDialogMsg,
+        _components: &(),
+        _sender: Sender<DiscardDialogMsg>,
+        parent_sender: Sender<AppMsg>,
+    ) {
+        match msg {
+            DiscardDialogMsg::Show(example) => {
+                self.hidden = false;
+                self.example = Some(example);
+            }
+            DiscardDialogMsg::Accept => {
+                self.hidden = true;
+                if let Some(example) = self.example.take() {
+                    send!(parent_sender, AppMsg::LoadExample(example));
+                }
+            }
+            DiscardDialogMsg::Reject => {
+                self.hidden = true;
+                self.example = None;
+            }
+        }
+    }
+}
+
+// Интерфейс диалога подтверждения
+#[relm4_macros::widget(pub)]
+impl Widgets<DiscardDialogModel, AppModel> for DiscardDialogWidgets {
+    view! {
+        dialog = gtk::MessageDialog {
+            set_transient_for: parent!(Some(&parent_widgets.main_window)),
+            set_modal: true,
+            set_visible: watch!(!model.hidden),
+            set_text: watch!(Some(tr("Граф не сохранён", "Graph not saved"))),
+            set_secondary_text: watch!(Some(tr(
+                "Текущий граф изменён и не сохранён. Загрузить пример и потерять изменения?",
+                "The current graph has been modified and not saved. Load the example and discard the changes?"
+            ))),
+            set_message_type: gtk::MessageType::Question,
+            add_button: args!(tr("Загрузить", "Load"), gtk::ResponseType::Accept),
+            add_button: args!(tr("Отмена", "Cancel"), gtk::ResponseType::Reject),
+            connect_response(sender) => move |_, response| {
+                if response == gtk::ResponseType::Accept {
+                    send!(sender, DiscardDialogMsg::Accept);
+                } else {
+                    send!(sender, DiscardDialogMsg::Reject);
+                }
+            }
+        }
+    }
+
+    fn post_view() {
+        // Подписи кнопок на текущем языке
+        for (response, label) in [
+            (gtk::ResponseType::Accept, tr("Загрузить", "Load")),
+            (gtk::ResponseType::Reject, tr("Отмена", "Cancel")),
+        ] {
+            if let Some(button) = self
+                .dialog
+                .widget_for_response(response)
+                .and_then(|widget| widget.downcast::<gtk::Button>().ok())
+            {
+                button.set_label(label);
+            }
+        }
+    }
+}
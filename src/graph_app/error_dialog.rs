@@ -1,7 +1,8 @@
-use gtk::prelude::{DialogExt, GtkWindowExt, WidgetExt};
+use gtk::prelude::{ButtonExt, Cast, DialogExt, GtkWindowExt, WidgetExt};
 use relm4::{send, ComponentUpdate, Model, Sender, Widgets};
 
 use super::{AppModel, AppMsg};
+use crate::strings::tr;
 
 // Модель данных для сообщения об ошибке
 pub struct ErrorDialogModel {
@@ -52,17 +53,28 @@ impl ComponentUpdate<AppModel> for ErrorDialogModel {
 #[relm4_macros::widget(pub)]
 impl Widgets<ErrorDialogModel, AppModel> for ErrorDialogWidgets {
     view! {
-        gtk::MessageDialog {
+        dialog = gtk::MessageDialog {
             set_transient_for: parent!(Some(&parent_widgets.main_window)),
             set_modal: true,
             set_visible: watch!(!model.hidden),
-            set_text: Some("Ошибка"),
+            set_text: watch!(Some(tr("Ошибка", "Error"))),
             set_secondary_text: watch!(model.error.as_deref()),
             set_message_type: gtk::MessageType::Error,
-            add_button: args!("ОК", gtk::ResponseType::Accept),
+            add_button: args!(tr("ОК", "OK"), gtk::ResponseType::Accept),
             connect_response(sender) => move |_, _| {
                 send!(sender, ErrorDialogMsg::Accept);
             }
         }
     }
+
+    fn post_view() {
+        // Подпись кнопки на текущем языке
+        if let Some(button) = self
+            .dialog
+            .widget_for_response(gtk::ResponseType::Accept)
+            .and_then(|widget| widget.downcast::<gtk::Button>().ok())
+        {
+            button.set_label(tr("ОК", "OK"));
+        }
+    }
 }
@@ -1,19 +1,35 @@
-use femtovg::{renderer::OpenGl, Canvas, Color, FontId};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs::File,
+    io::{BufWriter, Write},
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+use femtovg::{renderer::OpenGl, Canvas, Color, FontId, ImageFlags, PixelFormat, RenderTarget};
 use glutin::{
-    dpi::PhysicalPosition,
-    event::{ElementState, Event, MouseButton, MouseScrollDelta, TouchPhase, WindowEvent},
+    dpi::{PhysicalPosition, PhysicalSize},
+    event::{
+        ElementState, Event, KeyboardInput, ModifiersState, MouseButton, MouseScrollDelta,
+        TouchPhase, VirtualKeyCode, WindowEvent,
+    },
     event_loop::{ControlFlow, EventLoop, EventLoopBuilder},
     window::{Window, WindowBuilder},
     ContextBuilder, ContextWrapper, PossiblyCurrent,
 };
-use relm4::RelmApp;
+use gtk::glib;
+use relm4::{RelmApp, Sender};
 use resource::resource;
 
 use crate::{
-    graph::{EdgeWeights, Graph},
-    graph_app::AppModel,
-    graph_flows::AlgorithmState,
+    graph::{EdgeWeights, Graph, VertexId, VertexPositions},
+    graph_algorithms::{GraphAlgorithm, GraphAlgorithmState},
+    graph_app::{AppModel, AppMsg},
+    graph_errors::{GraphError, GraphInterfaceError},
     graph_renderer::GraphRenderer,
+    graph_svg::graph_to_svg,
+    settings::Settings,
+    strings::Lang,
 };
 
 // Модель данных окна графа
@@ -21,45 +37,123 @@ struct GraphWindowModel {
     windowed_context: ContextWrapper<PossiblyCurrent, Window>, // контекст окна
     canvas: Canvas<OpenGl>,                                    // поле для рисования
     font: FontId,                                              // шрифт
+    app_sender: Sender<AppMsg>, // отправитель сообщений в окно управления
+    export_image: Option<(PathBuf, f32)>, // путь и масштаб для сохранения изображения при следующей отрисовке
+    cursor_position: (f32, f32),          // координаты курсора в окне (в логических пикселях)
+    press_position: Option<(f32, f32)>,   // координаты начала нажатия левой кнопкой мыши
+    last_click: Option<(Instant, (f32, f32))>, // время и координаты последнего щелчка по свободной области
+    modifiers: ModifiersState,                 // нажатые клавиши-модификаторы
+    edge_by_clicks: bool, // протягивается ли новое ребро щелчками правой кнопкой мыши
+    next_frame_time: Instant, // время следующей отрисовки
+    redraw_needed: bool,  // нужна ли перерисовка из-за произошедших событий
+    vertices_moved: bool, // изменились ли координаты вершин при последнем обновлении
+    animated: bool,       // была ли анимация (потока или фокусировки) в последнем кадре
 
-    graph: Option<Graph<i32, EdgeWeights>>, // граф
-    graph_renderer: GraphRenderer<i32>,     // структура для отрисовки графа
-    graph_algorithm_state: AlgorithmState<i32, EdgeWeights>, // состояние выполнения алгоритма
+    graph: Option<Graph<VertexId, EdgeWeights>>, // граф
+    graph_renderer: GraphRenderer<VertexId>,     // структура для отрисовки графа
+    graph_algorithm_state: GraphAlgorithmState<VertexId, EdgeWeights>, // состояние выполнения алгоритма
 }
 
 // Сообщения к модели данных окна графа
 #[derive(Debug)]
 pub enum GraphWindowMsg {
-    SetColor(Color),                                              // установка цвета
-    GraphChanged(Option<Graph<i32, EdgeWeights>>),                // обновление графа
-    GraphAlgorithmStateChanged(AlgorithmState<i32, EdgeWeights>), // обновление состояния выполнения алгоритма
+    SetColor(Color, Color),   // установка основного и фонового цветов
+    SetSelectionColor(Color), // установка цвета выделения
+    // Обновление графа вместе с состоянием выполнения алгоритма (одним сообщением,
+    // чтобы окно графа не видело граф и состояние алгоритма, не соответствующие друг другу)
+    GraphStateChanged(
+        Option<Graph<VertexId, EdgeWeights>>,
+        GraphAlgorithmState<VertexId, EdgeWeights>,
+    ),
     ChangeCenterGravityValue(f32), // изменение значения гравитации к центру
     ChangeRepulsiveForceValue(f32), // изменение значения силы отталкивания вершин
     ChangeTimeStepValue(f32),      // изменение значения скорости изменений
     ChangeThetaValue(f32),         // изменение значения погрешности симуляции
+    ChangeSettleEpsilonValue(f32), // изменение значения порога остановки симуляции
     ToggleFullRender(bool),        // переключение флага полной отрисовки
+    ToggleShowEdgeLabels(bool),    // переключение флага вывода подписей рёбер
+    ToggleColorByComponent(bool),  // переключение флага раскраски по компонентам связности
+    ToggleEdgeWidthByWeight(bool), // переключение флага толщины рёбер по весу
+    ToggleSizeByDegree(bool),      // переключение флага размера вершин по степени
+    ToggleShowGrid(bool),          // переключение флага вывода сетки
+    ToggleShowCursorCoords(bool),  // переключение флага вывода координат курсора
+    ToggleAnimateFlow(bool),       // переключение флага анимации направления потока
+    ChangeEdgeLabelOffsetValue(f32), // изменение значения отступа подписей рёбер
+    ChangeArrowSizeValue(f32),     // изменение значения длины стрелок дуг
+    ChangeLodLabelDiameterValue(f32), // изменение порога диаметра вершин для скрытия текста
     ToggleGraphUpdateStop(bool),   // переключение флага прекращения обновлений графа
+    UnpinAll,                      // открепление всех вершин
     ResetImage,                    // сброс изображения графа
-    CloseWindow,                   // закрытие окна
+    FitView,                       // масштабирование камеры по всему графу
+    FocusVertex(VertexId),         // фокусировка камеры на вершине
+    LayoutCircular,                // расположение вершин по окружности
+    LayoutGrid,                    // расположение вершин по сетке
+    LayoutLayered,                 // расположение вершин ациклического графа по слоям
+    HighlightEdges(BTreeSet<(VertexId, VertexId)>), // выделение рёбер
+    SetVertexNumbers(BTreeMap<VertexId, usize>), // номера, выводимые рядом с вершинами
+    ExportImage(PathBuf, f32),     // сохранение изображения графа в файл PNG в заданном масштабе
+    ExportSvg(PathBuf),            // сохранение изображения графа в файл SVG
+    SetPositions(VertexPositions<VertexId>), // установка координат вершин
+    PlaceNewVertex(VertexId, (f32, f32)), // установка координат вершины, добавляемой в граф
+    RequestPositions(PathBuf),     // запрос координат вершин для сохранения графа в файл
+    SetLang(Lang),                 // выбор языка интерфейса
+    CloseWindow(Settings),         // закрытие окна с сохранением настроек
+}
+
+// Заголовок окна графа на заданном языке
+fn graph_window_title(lang: Lang) -> &'static str {
+    lang.tr("Визуализация графов (граф)", "Graph visualizer (graph)")
+}
+
+// Установка сохранённых настроек отрисовки графа
+fn apply_settings(graph_renderer: &mut GraphRenderer<VertexId>, settings: &Settings) {
+    graph_renderer.set_center_gravity(settings.center_gravity);
+    graph_renderer.set_repulsive_force(settings.repulsive_force);
+    graph_renderer.set_time_step(settings.time_step);
+    graph_renderer.set_theta(settings.theta);
+    graph_renderer.set_settle_epsilon(settings.settle_epsilon);
+    graph_renderer.set_full_render(settings.full_render);
+    graph_renderer.set_show_edge_labels(settings.show_edge_labels);
+    graph_renderer.set_color_by_component(settings.color_by_component);
+    graph_renderer.set_edge_width_by_weight(settings.edge_width_by_weight);
+    graph_renderer.set_size_by_degree(settings.size_by_degree);
+    graph_renderer.set_show_grid(settings.show_grid);
+    graph_renderer.set_show_cursor_coords(settings.show_cursor_coords);
+    graph_renderer.set_animate_flow(settings.animate_flow);
+    graph_renderer.set_edge_label_offset(settings.edge_label_offset);
+    graph_renderer.set_arrow_size(settings.arrow_size);
+    graph_renderer.set_lod_label_diameter(settings.lod_label_diameter);
+    let [r, g, b, a] = settings.selection_color;
+    graph_renderer.set_selection_color(Color::rgbaf(r, g, b, a));
 }
 
 pub fn init_app() {
+    // Загрузка настроек до создания окон, чтобы окна и виджеты создавались с ними
+    let settings = Settings::load();
+
     // Цикл событий окна графа
     let el: EventLoop<GraphWindowMsg> = EventLoopBuilder::with_user_event().build();
     // Прокси для передачи событий из потока окна управления в поток окна графа
     let proxy = el.create_proxy();
+    // Канал для передачи событий из потока окна графа в поток окна управления
+    let (app_sender, app_receiver) = glib::MainContext::channel(glib::PRIORITY_DEFAULT);
 
     // Запуск основного приложения (окна управления) в отдельном потоке
+    let app_settings = settings.clone();
     std::thread::spawn(move || {
-        let model = AppModel::new(proxy);
+        let model = AppModel::new(proxy, app_receiver, app_settings);
         let app = RelmApp::new(model);
         app.run();
     });
 
     // Создание окна графа
-    let wb = WindowBuilder::new()
-        .with_inner_size(winit::dpi::PhysicalSize::new(800, 600))
-        .with_title("Визуализация графов (граф)");
+    let (width, height) = settings.graph_window_size;
+    let mut wb = WindowBuilder::new()
+        .with_inner_size(PhysicalSize::new(width, height))
+        .with_title(graph_window_title(Lang::current()));
+    if let Some((x, y)) = settings.graph_window_position {
+        wb = wb.with_position(PhysicalPosition::new(x, y));
+    }
 
     // Контекст окна
     let windowed_context = ContextBuilder::new()
@@ -77,14 +171,30 @@ pub fn init_app() {
         .add_font_mem(&resource!("assets/NotoSans-Regular.ttf"))
         .unwrap();
 
+    // Создание структуры для отрисовки графа
+    let mut graph_renderer = GraphRenderer::new();
+    graph_renderer.set_scale_factor(windowed_context.window().scale_factor() as f32);
+    apply_settings(&mut graph_renderer, &settings);
+
     // Создание модели данных
     let mut model = GraphWindowModel {
         windowed_context,
         canvas,
         font,
+        app_sender,
+        export_image: None,
+        cursor_position: (0.0, 0.0),
+        press_position: None,
+        modifiers: ModifiersState::empty(),
+        edge_by_clicks: false,
+        next_frame_time: Instant::now(),
+        redraw_needed: true,
+        vertices_moved: false,
+        last_click: None,
+        animated: false,
         graph: None,
-        graph_renderer: GraphRenderer::new(),
-        graph_algorithm_state: AlgorithmState::NotStarted,
+        graph_renderer,
+        graph_algorithm_state: GraphAlgorithmState::new(GraphAlgorithm::MaxFlow),
     };
 
     // Запуск обработки событий
@@ -97,56 +207,249 @@ fn handle_events(
     event: Event<GraphWindowMsg>,
     control_flow: &mut ControlFlow,
 ) {
+    // Промежуток времени между отрисовками (60 кадров в секунду)
+    const FRAME_DURATION: Duration = Duration::from_micros(1_000_000 / 60);
+    // Максимальное перемещение мыши в логических пикселях, при котором нажатие считается
+    // выбором вершины
+    const CLICK_DISTANCE: f32 = 3.0;
+    // Максимальный промежуток между щелчками двойного щелчка
+    const DOUBLE_CLICK_DURATION: Duration = Duration::from_millis(400);
+
     let window = model.windowed_context.window();
 
-    *control_flow = ControlFlow::Poll;
+    *control_flow = ControlFlow::WaitUntil(model.next_frame_time);
+
+    // События окна и сообщения, которые могут изменить изображение (перемещение курсора
+    // изменяет выделение объекта под курсором, поэтому тоже требует перерисовки)
+    let changes_image = match &event {
+        Event::WindowEvent { event, .. } => matches!(
+            event,
+            WindowEvent::Resized(_)
+                | WindowEvent::ScaleFactorChanged { .. }
+                | WindowEvent::CursorMoved { .. }
+                | WindowEvent::CursorLeft { .. }
+                | WindowEvent::MouseInput { .. }
+                | WindowEvent::MouseWheel { .. }
+                | WindowEvent::KeyboardInput {
+                    input: KeyboardInput {
+                        state: ElementState::Pressed,
+                        ..
+                    },
+                    ..
+                }
+        ),
+        Event::UserEvent(msg) => !matches!(
+            msg,
+            GraphWindowMsg::RequestPositions(_)
+                | GraphWindowMsg::ExportSvg(_)
+                | GraphWindowMsg::PlaceNewVertex(..)
+                | GraphWindowMsg::SetLang(_)
+                | GraphWindowMsg::CloseWindow(_)
+        ),
+        _ => false,
+    };
+    if changes_image {
+        model.redraw_needed = true;
+    }
 
     match event {
         // Завершение работы
         Event::LoopDestroyed => {}
         Event::WindowEvent { ref event, .. } => match event {
-            // Изменение размера окна
+            // Изменение размера окна: поле для рисования сразу получает новый размер,
+            // чтобы изображение не растягивалось до следующей отрисовки
             WindowEvent::Resized(physical_size) => {
                 model.windowed_context.resize(*physical_size);
+                model.canvas.set_size(
+                    physical_size.width,
+                    physical_size.height,
+                    window.scale_factor() as f32,
+                );
             }
-            // Перемещение мыши
+            // Изменение масштаба экрана (например, при переносе окна на другой монитор)
+            WindowEvent::ScaleFactorChanged {
+                scale_factor,
+                new_inner_size,
+            } => {
+                model.windowed_context.resize(**new_inner_size);
+                model.canvas.set_size(
+                    new_inner_size.width,
+                    new_inner_size.height,
+                    *scale_factor as f32,
+                );
+                model.graph_renderer.set_scale_factor(*scale_factor as f32);
+            }
+            // Перемещение мыши (координаты переводятся в логические пиксели)
             WindowEvent::CursorMoved { position, .. } => {
-                model
-                    .graph_renderer
-                    .set_mouse_move((position.x as f32, position.y as f32));
+                let position = position.to_logical::<f32>(window.scale_factor());
+                model.cursor_position = (position.x, position.y);
+                model.graph_renderer.set_mouse_move(model.cursor_position);
             }
+            // Уход курсора из окна
+            WindowEvent::CursorLeft { .. } => model.graph_renderer.set_mouse_left(),
+            // Изменение нажатых клавиш-модификаторов
+            WindowEvent::ModifiersChanged(modifiers) => model.modifiers = *modifiers,
             // Начало/конец нажатия мышью
             WindowEvent::MouseInput {
                 button: MouseButton::Left,
                 state,
                 ..
             } => match state {
-                ElementState::Pressed => model.graph_renderer.set_mouse_dragging(true),
-                ElementState::Released => model.graph_renderer.set_mouse_dragging(false),
+                // С зажатым Shift вершины выделяются прямоугольником
+                ElementState::Pressed if model.modifiers.shift() => model
+                    .graph_renderer
+                    .start_rect_selection(model.cursor_position),
+                // С зажатым Ctrl из вершины протягивается новое ребро
+                ElementState::Pressed
+                    if model.modifiers.ctrl()
+                        && model.graph_renderer.start_edge_drag(model.cursor_position) => {}
+                ElementState::Pressed => {
+                    model.press_position = Some(model.cursor_position);
+                    model.graph_renderer.set_mouse_dragging(true);
+                }
+                ElementState::Released if model.graph_renderer.is_rect_selecting() => {
+                    model.graph_renderer.finish_rect_selection()
+                }
+                ElementState::Released
+                    if model.graph_renderer.is_edge_dragging() && !model.edge_by_clicks =>
+                {
+                    if let Some((from, to)) =
+                        model.graph_renderer.finish_edge_drag(model.cursor_position)
+                    {
+                        model
+                            .app_sender
+                            .send(AppMsg::AddEdgeBetween(from, to))
+                            .unwrap();
+                    }
+                }
+                ElementState::Released => {
+                    model.graph_renderer.set_mouse_dragging(false);
+                    // Нажатие без перемещения мыши выбирает вершину
+                    if let Some((x, y)) = model.press_position.take() {
+                        let (x_curr, y_curr) = model.cursor_position;
+                        if (x_curr - x).powi(2) + (y_curr - y).powi(2) <= CLICK_DISTANCE.powi(2) {
+                            if let Some(i) = model.graph_renderer.pick_vertex((x, y)) {
+                                model.app_sender.send(AppMsg::VertexPicked(i)).unwrap();
+                                model.last_click = None;
+                            } else {
+                                // Двойной щелчок по ребру или его подписи открывает изменение
+                                // веса ребра, по свободной области — добавляет вершину
+                                let now = Instant::now();
+                                let is_double_click = match model.last_click.take() {
+                                    Some((time, (x_last, y_last))) => {
+                                        now - time <= DOUBLE_CLICK_DURATION
+                                            && (x - x_last).powi(2) + (y - y_last).powi(2)
+                                                <= CLICK_DISTANCE.powi(2)
+                                    }
+                                    None => false,
+                                };
+                                if !is_double_click {
+                                    model.last_click = Some((now, (x, y)));
+                                } else if let Some((from, to, index)) =
+                                    model.graph_renderer.edge_at((x, y))
+                                {
+                                    model
+                                        .app_sender
+                                        .send(AppMsg::EditEdgeWeight(from, to, index))
+                                        .unwrap();
+                                } else if let Some((x, y)) =
+                                    model.graph_renderer.to_graph_coords((x, y))
+                                {
+                                    model.app_sender.send(AppMsg::AddVertexAt(x, y)).unwrap();
+                                }
+                            }
+                        }
+                    }
+                }
             },
+            // Нажатие правой кнопкой мыши: с зажатым Shift закрепляет/открепляет вершину,
+            // по свободной области предлагает окну управления новую вершину, а по двум
+            // вершинам подряд — новое ребро между ними (граф изменяется только в окне
+            // управления, которое затем передаёт изменённый граф)
+            WindowEvent::MouseInput {
+                button: MouseButton::Right,
+                state: ElementState::Pressed,
+                ..
+            } => {
+                if model.modifiers.shift() {
+                    model.graph_renderer.toggle_pin_at_cursor();
+                } else if model.graph_renderer.is_edge_dragging() {
+                    // Щелчок не по вершине отменяет новое ребро
+                    model.edge_by_clicks = false;
+                    if let Some((from, to)) =
+                        model.graph_renderer.finish_edge_drag(model.cursor_position)
+                    {
+                        model
+                            .app_sender
+                            .send(AppMsg::AddEdgeBetween(from, to))
+                            .unwrap();
+                    }
+                } else if model.graph_renderer.start_edge_drag(model.cursor_position) {
+                    model.edge_by_clicks = true;
+                } else if let Some((x, y)) =
+                    model.graph_renderer.to_graph_coords(model.cursor_position)
+                {
+                    model.app_sender.send(AppMsg::AddVertexAt(x, y)).unwrap();
+                }
+            }
             // Прокрутка колесом мыши
             WindowEvent::MouseWheel {
                 delta,
                 phase: TouchPhase::Moved,
                 ..
-            } => match delta {
-                MouseScrollDelta::LineDelta(_, y) => model.graph_renderer.update_zoom(*y),
-                MouseScrollDelta::PixelDelta(PhysicalPosition { y, .. }) => {
-                    model.graph_renderer.update_zoom(*y as f32)
-                }
-            },
-            // Запрос закрытия окна
-            WindowEvent::CloseRequested => {}
+            } => {
+                // Количество логических пикселей прокрутки сенсорной панели, соответствующее строке
+                const PIXELS_PER_LINE: f32 = 40.0;
+
+                let scroll = match delta {
+                    MouseScrollDelta::LineDelta(_, y) => *y,
+                    MouseScrollDelta::PixelDelta(position) => {
+                        position.to_logical::<f32>(window.scale_factor()).y / PIXELS_PER_LINE
+                    }
+                };
+                // Координаты курсора относительно центра окна (в логических пикселях)
+                let size = window.inner_size().to_logical::<f32>(window.scale_factor());
+                let cursor = (
+                    model.cursor_position.0 - size.width / 2.0,
+                    model.cursor_position.1 - size.height / 2.0,
+                );
+                model.graph_renderer.update_zoom(scroll, cursor);
+            }
+            // Нажатие клавиши
+            WindowEvent::KeyboardInput {
+                input:
+                    KeyboardInput {
+                        state: ElementState::Pressed,
+                        virtual_keycode: Some(key),
+                        ..
+                    },
+                ..
+            } => handle_key(model, *key),
+            // Запрос закрытия окна: сначала закрывается окно управления, которое передаёт
+            // настройки для сохранения, после чего окно графа закрывается и процесс завершается
+            WindowEvent::CloseRequested => {
+                model.app_sender.send(AppMsg::GraphWindowClosing).unwrap()
+            }
             _ => (),
         },
         // Перерисовка окна
         Event::RedrawRequested(_) => {
-            let dpi_factor = window.scale_factor();
+            model.redraw_needed = false;
             let size = window.inner_size();
             let (width, height) = (size.width as f32, size.height as f32);
 
             // Обновление координат вершин
-            model.graph_renderer.update(&model.graph);
+            model.vertices_moved = model.graph_renderer.update(&model.graph);
+            // Сохранение изображения, если оно было запрошено (до отрисовки в окно, чтобы
+            // положения подписей для выбора мышью остались от отрисовки в окно)
+            if let Some((path, scale)) = model.export_image.take() {
+                if let Err(e) = export_image(model, &path, width, height, scale) {
+                    model
+                        .app_sender
+                        .send(AppMsg::ShowError(e.to_string()))
+                        .unwrap();
+                }
+            }
             // Отрисовка графа
             model
                 .graph_renderer
@@ -155,23 +458,38 @@ fn handle_events(
                     model.font,
                     width,
                     height,
-                    dpi_factor as f32,
                     &model.graph,
                     &model.graph_algorithm_state,
                 )
                 .unwrap();
+            model.animated = model.graph_renderer.is_animated();
 
             // Завершение отрисовки
             model.canvas.flush();
             model.windowed_context.swap_buffers().unwrap();
         }
         Event::UserEvent(event) => match event {
-            // Установка цвета
-            GraphWindowMsg::SetColor(color) => model.graph_renderer.set_color(color),
-            // Обновление графа
-            GraphWindowMsg::GraphChanged(x) => model.graph = x,
-            // Обновление состояния выполнения алгоритма
-            GraphWindowMsg::GraphAlgorithmStateChanged(x) => model.graph_algorithm_state = x,
+            // Установка цветов
+            GraphWindowMsg::SetColor(front_color, back_color) => {
+                model.graph_renderer.set_color(front_color, back_color)
+            }
+            GraphWindowMsg::SetSelectionColor(color) => {
+                model.graph_renderer.set_selection_color(color)
+            }
+            // Обновление графа и состояния выполнения алгоритма
+            GraphWindowMsg::GraphStateChanged(graph, algorithm_state) => {
+                // Симуляция возобновляется только при изменении самого графа
+                if model.graph != graph {
+                    model.graph = graph;
+                    model.graph_renderer.reset_edges();
+                    model.graph_renderer.update_components(&model.graph);
+                    model.graph_renderer.update_degrees(&model.graph);
+                    model.graph_renderer.set_highlighted_edges(BTreeSet::new());
+                    model.graph_renderer.set_vertex_numbers(BTreeMap::new());
+                    model.graph_renderer.resume_simulation();
+                }
+                model.graph_algorithm_state = algorithm_state;
+            }
             // Изменение значения гравитации к центру
             GraphWindowMsg::ChangeCenterGravityValue(x) => {
                 model.graph_renderer.set_center_gravity(x)
@@ -184,17 +502,273 @@ fn handle_events(
             GraphWindowMsg::ChangeTimeStepValue(x) => model.graph_renderer.set_time_step(x),
             // Изменение значения погрешности симуляции
             GraphWindowMsg::ChangeThetaValue(x) => model.graph_renderer.set_theta(x),
+            // Изменение порога остановки симуляции
+            GraphWindowMsg::ChangeSettleEpsilonValue(x) => {
+                model.graph_renderer.set_settle_epsilon(x)
+            }
             // Переключение флага полной отрисовки
             GraphWindowMsg::ToggleFullRender(x) => model.graph_renderer.set_full_render(x),
+            // Переключение флага вывода подписей рёбер
+            GraphWindowMsg::ToggleShowEdgeLabels(x) => model.graph_renderer.set_show_edge_labels(x),
+            // Переключение флага раскраски по компонентам связности
+            GraphWindowMsg::ToggleColorByComponent(x) => {
+                model.graph_renderer.set_color_by_component(x)
+            }
+            // Переключение флага толщины рёбер по весу
+            GraphWindowMsg::ToggleEdgeWidthByWeight(x) => {
+                model.graph_renderer.set_edge_width_by_weight(x)
+            }
+            // Переключение флага размера вершин по степени
+            GraphWindowMsg::ToggleSizeByDegree(x) => model.graph_renderer.set_size_by_degree(x),
+            // Переключение флага вывода сетки
+            GraphWindowMsg::ToggleShowGrid(x) => model.graph_renderer.set_show_grid(x),
+            // Переключение флага вывода координат курсора
+            GraphWindowMsg::ToggleShowCursorCoords(x) => {
+                model.graph_renderer.set_show_cursor_coords(x)
+            }
+            // Переключение флага анимации направления потока
+            GraphWindowMsg::ToggleAnimateFlow(x) => model.graph_renderer.set_animate_flow(x),
+            // Изменение значения отступа подписей рёбер
+            GraphWindowMsg::ChangeEdgeLabelOffsetValue(x) => {
+                model.graph_renderer.set_edge_label_offset(x)
+            }
+            // Изменение значения длины стрелок дуг
+            GraphWindowMsg::ChangeArrowSizeValue(x) => model.graph_renderer.set_arrow_size(x),
+            // Изменение порога диаметра вершин для скрытия текста
+            GraphWindowMsg::ChangeLodLabelDiameterValue(x) => {
+                model.graph_renderer.set_lod_label_diameter(x)
+            }
             // Переключение флага прекращения обновлений графа
             GraphWindowMsg::ToggleGraphUpdateStop(x) => model.graph_renderer.set_updates_stopped(x),
+            // Открепление всех вершин
+            GraphWindowMsg::UnpinAll => model.graph_renderer.unpin_all(),
             // Cброс изображения графа
             GraphWindowMsg::ResetImage => model.graph_renderer.reset_image(),
-            // Закрытие окна
-            GraphWindowMsg::CloseWindow => *control_flow = ControlFlow::Exit,
+            // Масштабирование камеры по всему графу
+            GraphWindowMsg::FitView => {
+                let size = window.inner_size();
+                model
+                    .graph_renderer
+                    .fit_view(size.width as f32, size.height as f32);
+            }
+            // Фокусировка камеры на вершине
+            GraphWindowMsg::FocusVertex(i) => {
+                if let Err(e) = model.graph_renderer.focus_vertex(i) {
+                    model
+                        .app_sender
+                        .send(AppMsg::ShowError(e.to_string()))
+                        .unwrap();
+                }
+            }
+            // Готовые расположения вершин с масштабированием камеры по результату
+            // и передачей прекращения обновлений в окно управления
+            GraphWindowMsg::LayoutCircular
+            | GraphWindowMsg::LayoutGrid
+            | GraphWindowMsg::LayoutLayered => {
+                match event {
+                    GraphWindowMsg::LayoutCircular => model.graph_renderer.layout_circular(),
+                    GraphWindowMsg::LayoutGrid => model.graph_renderer.layout_grid(),
+                    _ => model.graph_renderer.layout_layered(&model.graph),
+                }
+                let size = window.inner_size();
+                model
+                    .graph_renderer
+                    .fit_view(size.width as f32, size.height as f32);
+                model
+                    .app_sender
+                    .send(AppMsg::GraphUpdateStopChanged(true))
+                    .unwrap();
+            }
+            // Выделение рёбер
+            GraphWindowMsg::HighlightEdges(edges) => {
+                model.graph_renderer.set_highlighted_edges(edges)
+            }
+            GraphWindowMsg::SetVertexNumbers(numbers) => {
+                model.graph_renderer.set_vertex_numbers(numbers)
+            }
+            // Сохранение изображения графа при следующей отрисовке
+            GraphWindowMsg::ExportImage(path, scale) => model.export_image = Some((path, scale)),
+            // Сохранение изображения графа в формате SVG
+            GraphWindowMsg::ExportSvg(path) => {
+                if let Err(e) = export_svg(model, &path) {
+                    model
+                        .app_sender
+                        .send(AppMsg::ShowError(e.to_string()))
+                        .unwrap();
+                }
+            }
+            // Установка координат вершин
+            GraphWindowMsg::SetPositions(x) => model.graph_renderer.set_positions(x),
+            // Установка координат вершины, добавленной двойным щелчком
+            GraphWindowMsg::PlaceNewVertex(i, position) => {
+                model.graph_renderer.place_new_vertex(i, position)
+            }
+            // Отправка координат вершин в окно управления для сохранения графа
+            GraphWindowMsg::RequestPositions(path) => model
+                .app_sender
+                .send(AppMsg::PositionsResponse(
+                    path,
+                    model.graph_renderer.get_positions().clone(),
+                ))
+                .unwrap(),
+            // Выбор языка интерфейса
+            GraphWindowMsg::SetLang(lang) => window.set_title(graph_window_title(lang)),
+            // Закрытие окна (при закрытии окна управления) с сохранением настроек,
+            // дополненных размером и положением окна графа
+            GraphWindowMsg::CloseWindow(mut settings) => {
+                let size = window.inner_size();
+                settings.graph_window_size = (size.width, size.height);
+                settings.graph_window_position = window
+                    .outer_position()
+                    .ok()
+                    .map(|position| (position.x, position.y));
+                settings.save();
+                *control_flow = ControlFlow::Exit;
+            }
         },
-        // События обработаны, начало перерисовки
-        Event::MainEventsCleared => window.request_redraw(),
+        // События обработаны, начало перерисовки, если наступило время следующего кадра
+        Event::MainEventsCleared => {
+            // Изображение не изменяется (вершины не двигаются, анимации нет и событий не было),
+            // ожидание следующего события без отрисовки кадров
+            if !model.vertices_moved && !model.animated && !model.redraw_needed {
+                *control_flow = ControlFlow::Wait;
+                return;
+            }
+            let now = Instant::now();
+            if now >= model.next_frame_time {
+                model.next_frame_time = now + FRAME_DURATION;
+                *control_flow = ControlFlow::WaitUntil(model.next_frame_time);
+                window.request_redraw();
+            }
+        }
         _ => (),
     }
 }
+
+// Обработка нажатия клавиши
+fn handle_key(model: &mut GraphWindowModel, key: VirtualKeyCode) {
+    // Сдвиг камеры стрелками в логических пикселях
+    const PAN_STEP: f32 = 50.0;
+
+    match key {
+        // Сдвиг камеры
+        VirtualKeyCode::Left => model.graph_renderer.pan((PAN_STEP, 0.0)),
+        VirtualKeyCode::Right => model.graph_renderer.pan((-PAN_STEP, 0.0)),
+        VirtualKeyCode::Up => model.graph_renderer.pan((0.0, PAN_STEP)),
+        VirtualKeyCode::Down => model.graph_renderer.pan((0.0, -PAN_STEP)),
+        // Масштабирование относительно центра окна
+        VirtualKeyCode::Plus | VirtualKeyCode::Equals | VirtualKeyCode::NumpadAdd => {
+            model.graph_renderer.update_zoom(1.0, (0.0, 0.0))
+        }
+        VirtualKeyCode::Minus | VirtualKeyCode::NumpadSubtract => {
+            model.graph_renderer.update_zoom(-1.0, (0.0, 0.0))
+        }
+        // Сброс камеры без изменения расположения вершин
+        VirtualKeyCode::Home | VirtualKeyCode::Key0 | VirtualKeyCode::Numpad0 => {
+            model.graph_renderer.reset_camera()
+        }
+        // Переключение прекращения обновлений графа с передачей в окно управления
+        VirtualKeyCode::Space => {
+            let stopped = !model.graph_renderer.get_updates_stopped();
+            model.graph_renderer.set_updates_stopped(stopped);
+            model
+                .app_sender
+                .send(AppMsg::GraphUpdateStopChanged(stopped))
+                .unwrap();
+        }
+        // Полный сброс изображения
+        VirtualKeyCode::R => model.graph_renderer.reset_image(),
+        // Снятие выделения вершин прямоугольником и отмена нового ребра
+        VirtualKeyCode::Escape => {
+            model.graph_renderer.clear_selection();
+            model.graph_renderer.cancel_edge_drag();
+            model.edge_by_clicks = false;
+        }
+        _ => (),
+    }
+}
+
+// Сохранение изображения графа в файл PNG: граф отрисовывается в отдельное изображение
+// в scale раз больше окна размером width на height
+fn export_image(
+    model: &mut GraphWindowModel,
+    path: &Path,
+    width: f32,
+    height: f32,
+    scale: f32,
+) -> Result<(), GraphError> {
+    let (image_width, image_height) = ((width * scale) as usize, (height * scale) as usize);
+    if image_width == 0 || image_height == 0 {
+        return Err(GraphInterfaceError::ImageExportError.into());
+    }
+    let image_id = model
+        .canvas
+        .create_image_empty(
+            image_width,
+            image_height,
+            PixelFormat::Rgba8,
+            ImageFlags::empty(),
+        )
+        .map_err(|_| GraphInterfaceError::ImageExportError)?;
+
+    // Отрисовка в изображение и чтение его пикселей, после чего отрисовка снова идёт в окно
+    model
+        .canvas
+        .set_render_target(RenderTarget::Image(image_id));
+    let res = model.graph_renderer.draw_scaled(
+        &mut model.canvas,
+        model.font,
+        width,
+        height,
+        scale,
+        &model.graph,
+        &model.graph_algorithm_state,
+    );
+    model.canvas.flush();
+    let image = model.canvas.screenshot();
+    model.canvas.set_render_target(RenderTarget::Screen);
+    model.canvas.delete_image(image_id);
+    res?;
+    let image = image.map_err(|_| GraphInterfaceError::ImageExportError)?;
+    let data: Vec<u8> = image
+        .as_ref()
+        .pixels()
+        .flat_map(|p| [p.r, p.g, p.b, p.a])
+        .collect();
+
+    let file = File::create(path)?;
+    let mut encoder = png::Encoder::new(
+        BufWriter::new(file),
+        image.width() as u32,
+        image.height() as u32,
+    );
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder
+        .write_header()
+        .and_then(|mut writer| writer.write_image_data(&data))
+        .map_err(|_| GraphInterfaceError::ImageExportError)?;
+    Ok(())
+}
+
+// Сохранение изображения графа в файл SVG с настройками и масштабом последней отрисовки
+fn export_svg(model: &GraphWindowModel, path: &Path) -> Result<(), GraphError> {
+    let g = model
+        .graph
+        .as_ref()
+        .ok_or(GraphInterfaceError::GraphNotExist)?;
+    let style = model
+        .graph_renderer
+        .svg_style()
+        .ok_or(GraphInterfaceError::ImageExportError)?;
+    let mut writer = BufWriter::new(File::create(path)?);
+    graph_to_svg(
+        g,
+        model.graph_renderer.get_positions(),
+        &model.graph_algorithm_state,
+        &style,
+        &mut writer,
+    )?;
+    writer.flush()?;
+    Ok(())
+}
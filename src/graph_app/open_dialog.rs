@@ -10,10 +10,10 @@ pub struct OpenDialogConfig {}
 impl relm4_components::open_dialog::OpenDialogConfig for OpenDialogConfig {
     type Model = AppModel;
 
-    fn open_dialog_config(_model: &Self::Model) -> OpenDialogSettings {
+    fn open_dialog_config(model: &Self::Model) -> OpenDialogSettings {
         OpenDialogSettings {
-            accept_label: "Открыть",
-            cancel_label: "Отмена",
+            accept_label: model.lang.tr("Открыть", "Open"),
+            cancel_label: model.lang.tr("Отмена", "Cancel"),
             create_folders: true,
             is_modal: true,
             filters: Vec::new(),
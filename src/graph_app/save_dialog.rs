@@ -10,10 +10,10 @@ pub struct SaveDialogConfig {}
 impl relm4_components::save_dialog::SaveDialogConfig for SaveDialogConfig {
     type Model = AppModel;
 
-    fn dialog_config(_model: &Self::Model) -> SaveDialogSettings {
+    fn dialog_config(model: &Self::Model) -> SaveDialogSettings {
         SaveDialogSettings {
-            accept_label: "Сохранить",
-            cancel_label: "Отмена",
+            accept_label: model.lang.tr("Сохранить", "Save"),
+            cancel_label: model.lang.tr("Отмена", "Cancel"),
             create_folders: true,
             is_modal: true,
             filters: Vec::new(),
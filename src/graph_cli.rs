@@ -0,0 +1,200 @@
+use crate::{
+    graph::{stats_text, EdgeWeights, Graph, VertexId},
+    graph_errors::GraphError,
+    graph_flows::{algorithm_step, AlgorithmState, PathStrategy},
+    graph_parser::{graph_from_path, graph_to_path},
+    graph_report::flow_lines,
+    strings::{tr, Lang},
+};
+
+// Коды завершения программы в режиме командной строки
+const EXIT_USAGE: i32 = 1; // неправильные аргументы командной строки
+const EXIT_FILE_ERROR: i32 = 2; // ошибка чтения, разбора или записи файла
+const EXIT_ALGORITHM_ERROR: i32 = 3; // ошибка выполнения алгоритма
+
+// Ошибка команды: код завершения и сообщение
+struct CliError {
+    code: i32,
+    message: String,
+}
+
+impl CliError {
+    // Ошибка чтения, разбора или записи файла графа
+    fn file(e: GraphError) -> Self {
+        Self {
+            code: EXIT_FILE_ERROR,
+            message: format!("{} {}", tr("Ошибка файла графа:", "Graph file error:"), e),
+        }
+    }
+
+    // Ошибка выполнения алгоритма
+    fn algorithm(e: GraphError) -> Self {
+        Self {
+            code: EXIT_ALGORITHM_ERROR,
+            message: format!("{} {}", tr("Ошибка алгоритма:", "Algorithm error:"), e),
+        }
+    }
+
+    // Неправильные аргументы командной строки
+    fn usage() -> Self {
+        Self {
+            code: EXIT_USAGE,
+            message: String::from(usage_text()),
+        }
+    }
+}
+
+// Справка по командам
+fn usage_text() -> &'static str {
+    tr(
+        "Использование:
+  graph_visualizer convert <вход> <выход>
+  graph_visualizer maxflow <файл> <исток> <сток> [--flows] [--bfs | --scaling | --dinic] [--unit]
+  graph_visualizer stats <файл>
+Формат файла определяется по расширению (.dot и .gv — DOT, .graphml — GraphML, иначе собственный формат).",
+        "Usage:
+  graph_visualizer convert <input> <output>
+  graph_visualizer maxflow <file> <source> <sink> [--flows] [--bfs | --scaling | --dinic] [--unit]
+  graph_visualizer stats <file>
+The file format is determined by the extension (.dot and .gv for DOT, .graphml for GraphML, otherwise the native format).",
+    )
+}
+
+// Выполнение команды без графического интерфейса, возвращает код завершения программы
+pub fn run(args: &[String]) -> i32 {
+    let args: Vec<&str> = args.iter().map(|arg| arg.as_str()).collect();
+    let result = match args.as_slice() {
+        ["convert", input, output] => convert(input, output),
+        ["maxflow", path, s, t, options @ ..] => max_flow(path, s, t, options),
+        ["stats", path] => stats(path),
+        _ => Err(CliError::usage()),
+    };
+    match result {
+        Ok(()) => 0,
+        Err(e) => {
+            eprintln!("{}", e.message);
+            e.code
+        }
+    }
+}
+
+// Загрузка графа из файла
+fn read_graph(path: &str) -> Result<Graph<VertexId, EdgeWeights>, CliError> {
+    graph_from_path(path)
+        .map(|(g, _)| g)
+        .map_err(CliError::file)
+}
+
+// Преобразование файла графа в другой формат (расположение вершин сохраняется,
+// если оба файла в собственном формате)
+fn convert(input: &str, output: &str) -> Result<(), CliError> {
+    let (g, positions) = graph_from_path::<VertexId, _>(input).map_err(CliError::file)?;
+    graph_to_path(&g, positions.as_ref(), output).map_err(CliError::file)
+}
+
+// Нахождение максимального потока с выводом его величины и, при флаге --flows,
+// потоков через дуги (при флаге --unit пропускные способности дуг невзвешенного графа
+// равны 1)
+fn max_flow(path: &str, s: &str, t: &str, options: &[&str]) -> Result<(), CliError> {
+    let mut show_flows = false;
+    let mut strategy = PathStrategy::Dfs;
+    let mut unit_capacities = false;
+    for &option in options {
+        match option {
+            "--flows" => show_flows = true,
+            "--bfs" => strategy = PathStrategy::Bfs,
+            "--scaling" => strategy = PathStrategy::Scaling,
+            "--dinic" => strategy = PathStrategy::Dinic,
+            "--unit" => unit_capacities = true,
+            _ => return Err(CliError::usage()),
+        }
+    }
+
+    let g = Some(read_graph(path)?);
+    let mut state = AlgorithmState::NotStarted;
+    let data = loop {
+        state = match algorithm_step(state, &g, s, t, strategy, unit_capacities)
+            .map_err(CliError::algorithm)?
+        {
+            AlgorithmState::Finished(data) => break data,
+            state => state,
+        };
+    };
+    println!("{}", data.get_total_flow());
+
+    if show_flows {
+        for line in flow_lines(g.as_ref().unwrap(), &data) {
+            println!("{}", line);
+        }
+    }
+    Ok(())
+}
+
+// Вывод статистики графа
+fn stats(path: &str) -> Result<(), CliError> {
+    let g = read_graph(path)?;
+    println!("{}", stats_text(&Some(g.stats()), Lang::current()));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Путь к временному файлу теста
+    fn temp_path(name: &str) -> String {
+        std::env::temp_dir()
+            .join(format!(
+                "graph_visualizer_cli_{}_{}",
+                std::process::id(),
+                name
+            ))
+            .to_str()
+            .unwrap()
+            .to_string()
+    }
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn missing_file_is_file_error() {
+        let path = temp_path("missing.txt");
+        assert_eq!(run(&args(&["stats", &path])), EXIT_FILE_ERROR);
+        match read_graph(&path) {
+            Err(e) => assert!(e.message.contains(tr("Файл не найден!", "File not found!"))),
+            Ok(_) => panic!("файл не должен существовать"),
+        }
+    }
+
+    #[test]
+    fn invalid_utf8_is_file_error() {
+        let path = temp_path("invalid.txt");
+        std::fs::write(&path, b"1 2\n\xff 3\n").unwrap();
+        let code = run(&args(&["stats", &path]));
+        let result = read_graph(&path);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(code, EXIT_FILE_ERROR);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn stats_of_valid_file() {
+        let path = temp_path("valid.txt");
+        std::fs::write(&path, "1 2\n2 3\n").unwrap();
+        let code = run(&args(&["stats", &path]));
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(code, 0);
+    }
+
+    #[test]
+    fn wrong_arguments_are_usage_error() {
+        assert_eq!(run(&args(&[])), EXIT_USAGE);
+        assert_eq!(run(&args(&["stats"])), EXIT_USAGE);
+        assert_eq!(
+            run(&args(&["maxflow", "a.txt", "1", "2", "--unknown"])),
+            EXIT_USAGE
+        );
+    }
+}
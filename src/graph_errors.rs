@@ -1,48 +1,226 @@
+use std::io;
+
 use thiserror::Error;
 
+use crate::strings::tr;
+
 // Ошибки при работе с графом
 #[derive(Error, Debug)]
 pub enum GraphOperationError {
-    #[error("Вершина уже есть в графе!")]
+    #[error(
+        "{}",
+        tr("Вершина уже есть в графе!", "Vertex is already in the graph!")
+    )]
     VertexExists,
-    #[error("Такой вершины нет в графе!")]
+    #[error("{}", tr("Такой вершины нет в графе!", "No such vertex in the graph!"))]
     VertexNotFound,
-    #[error("Ребро уже есть в графе!")]
-    EdgeExists,
-    #[error("Такого ребра нет в графе!")]
+    #[error("{}", tr("Такого ребра нет в графе!", "No such edge in the graph!"))]
     EdgeNotFound,
-    #[error("Одной из вершин нет в графе!")]
+    #[error(
+        "{}",
+        tr(
+            "Одной из вершин нет в графе!",
+            "One of the vertices is not in the graph!"
+        )
+    )]
     SomeVerticesNotFound,
-    #[error("Взвешенное ребро в невзвешенном графе!")]
+    #[error("{}", tr("Вершины совпадают!", "The vertices are the same!"))]
+    SameVertices,
+    #[error(
+        "{}",
+        tr(
+            "Взвешенное ребро в невзвешенном графе!",
+            "Weighted edge in an unweighted graph!"
+        )
+    )]
     WeightedEdgeInUnweightedGraph,
-    #[error("Невзвешенное ребро во взвешенном графе!")]
+    #[error(
+        "{}",
+        tr(
+            "Невзвешенное ребро во взвешенном графе!",
+            "Unweighted edge in a weighted graph!"
+        )
+    )]
     UnweightedEdgeInWeightedGraph,
+    #[error(
+        "{} ({from}, {to}) {}",
+        tr("У противоположных дуг", "Opposite arcs"),
+        tr("разные веса!", "have different weights!")
+    )]
+    ConflictingWeights { from: String, to: String },
+    #[error(
+        "{}",
+        tr(
+            "Переполнение при сложении весов рёбер!",
+            "Overflow when adding edge weights!"
+        )
+    )]
+    WeightOverflow,
 }
 
 // Ошибки при работе с интерфейсом графа
 #[derive(Error, Debug)]
 pub enum GraphInterfaceError {
-    #[error("Неправильное количество аргументов!")]
+    #[error(
+        "{}",
+        tr(
+            "Неправильное количество аргументов!",
+            "Incorrect number of arguments!"
+        )
+    )]
     IncorrectArgumentCount,
-    #[error("Неправильное значение аргумента №{i}!")]
+    #[error(
+        "{}{i}!",
+        tr("Неправильное значение аргумента №", "Incorrect value of argument #")
+    )]
     IncorrectArgument { i: usize },
-    #[error("Граф ещё не создан!")]
+    #[error("{}", tr("Граф ещё не создан!", "Graph has not been created yet!"))]
     GraphNotExist,
-    #[error("Не удалось открыть файл!")]
-    FileError,
-    #[error("Перед объявлением вершин должна быть строка \"vertices\"!")]
+    #[error(
+        "{}",
+        tr("Файл не в кодировке UTF-8!", "The file is not in UTF-8 encoding!")
+    )]
+    InvalidEncoding,
+    #[error(
+        "{}",
+        tr(
+            "Перед объявлением вершин должна быть строка \"vertices\" или \"matrix\"!",
+            "The line \"vertices\" or \"matrix\" must precede the vertex declarations!"
+        )
+    )]
     WrongParsingVerticesStart,
-    #[error("В файле не задан граф!")]
+    #[error(
+        "{}",
+        tr("В файле не задан граф!", "The file does not contain a graph!")
+    )]
     EmptyFile,
+    #[error(
+        "{}",
+        tr("Неправильный формат файла DOT!", "Incorrect DOT file format!")
+    )]
+    WrongDotFormat,
+    #[error(
+        "{}",
+        tr("Неправильный формат файла GraphML!", "Incorrect GraphML file format!")
+    )]
+    WrongGraphMlFormat,
+    #[error(
+        "{}",
+        tr("Не удалось сохранить изображение!", "Failed to save the image!")
+    )]
+    ImageExportError,
+    #[error(
+        "{}",
+        tr(
+            "Граф нельзя изменять во время выполнения алгоритма!",
+            "The graph cannot be changed while an algorithm is running!"
+        )
+    )]
+    AlgorithmRunning,
+    #[error(
+        "{}",
+        tr(
+            "Граф нельзя изменять во время его загрузки!",
+            "The graph cannot be changed while it is loading!"
+        )
+    )]
+    GraphLoading,
+    #[error(
+        "{}",
+        tr(
+            "Неправильный отрезок случайных весов (нужны числа мин. ≤ макс.)!",
+            "Incorrect random weight range (numbers min. ≤ max. are required)!"
+        )
+    )]
+    IncorrectWeightRange,
+    #[error(
+        "{}",
+        tr(
+            "Нет отчёта: алгоритм нахождения максимального потока не запущен!",
+            "No report: the maximum flow algorithm has not been started!"
+        )
+    )]
+    NoFlowReport,
+    #[error("{} {line}: {source}", tr("Строка", "Line"))]
+    AtLine {
+        line: usize,
+        source: Box<GraphError>,
+    },
 }
 
 // Ошибки при работе алгоритма
 #[derive(Error, Debug)]
 pub enum GraphAlgorithmError {
-    #[error("Граф неориентированный!")]
+    #[error("{}", tr("Граф неориентированный!", "Graph is undirected!"))]
     GraphNotDirected,
-    #[error("Граф невзвешенный!")]
+    #[error("{}", tr("Граф ориентированный!", "Graph is directed!"))]
+    GraphDirected,
+    #[error("{}", tr("Граф невзвешенный!", "Graph is unweighted!"))]
     GraphNotWeighted,
+    #[error(
+        "{}",
+        tr(
+            "В графе есть ребро с отрицательным весом!",
+            "Graph has an edge with negative weight!"
+        )
+    )]
+    NegativeWeight,
+    #[error(
+        "{} ({from}, {to})!",
+        tr(
+            "Отрицательная пропускная способность у ребра",
+            "Negative capacity of edge"
+        )
+    )]
+    NegativeCapacity { from: String, to: String },
+    #[error("{}", tr("Исток совпадает со стоком!", "Source is the same as sink!"))]
+    SourceIsSink,
+    #[error(
+        "{}",
+        tr(
+            "Граф изменён во время выполнения алгоритма!",
+            "Graph was changed while the algorithm was running!"
+        )
+    )]
+    GraphModifiedDuringAlgorithm,
+    #[error(
+        "{}",
+        tr(
+            "Масштабирование пропускных способностей возможно только для целых весов!",
+            "Capacity scaling is only possible with integer weights!"
+        )
+    )]
+    ScalingNotForFloatWeights,
+    #[error(
+        "{}",
+        tr(
+            "В графе есть веса разных типов (целые и дробные)!",
+            "Graph has weights of different types (integer and fractional)!"
+        )
+    )]
+    MixedWeightTypes,
+    #[error(
+        "{}",
+        tr(
+            "Переполнение при сложении целых весов!",
+            "Overflow when adding integer weights!"
+        )
+    )]
+    WeightOverflow,
+    #[error(
+        "{}",
+        tr(
+            "Граф не двудольный: есть ребро между вершинами одной доли!",
+            "Graph is not bipartite: an edge connects vertices of the same part!"
+        )
+    )]
+    GraphNotBipartite,
+    #[error(
+        "{}: {}!",
+        tr("В графе есть цикл", "Graph has a cycle"),
+        cycle_text(.cycle)
+    )]
+    GraphHasCycle { cycle: Vec<String> },
 }
 
 // Все ошибки
@@ -54,6 +232,35 @@ pub enum GraphError {
     InterfaceError(#[from] GraphInterfaceError),
     #[error(transparent)]
     AlgorithmError(#[from] GraphAlgorithmError),
-    #[error("Ошибка ввода/вывода!")]
-    IOError(#[from] std::io::Error),
+    #[error("{}", io_error_text(.0))]
+    IOError(io::Error),
+}
+
+// Ошибки чтения текста не в кодировке UTF-8 выводятся как ошибка формата файла
+impl From<io::Error> for GraphError {
+    fn from(e: io::Error) -> Self {
+        match e.kind() {
+            io::ErrorKind::InvalidData => GraphInterfaceError::InvalidEncoding.into(),
+            _ => Self::IOError(e),
+        }
+    }
+}
+
+// Текст цикла: вершины в порядке обхода по дугам с возвратом в первую вершину
+fn cycle_text(cycle: &[String]) -> String {
+    let mut text = cycle.join(" → ");
+    if let Some(first) = cycle.first() {
+        text += " → ";
+        text += first;
+    }
+    text
+}
+
+// Текст ошибки ввода/вывода по её виду
+fn io_error_text(e: &io::Error) -> &'static str {
+    match e.kind() {
+        io::ErrorKind::NotFound => tr("Файл не найден!", "File not found!"),
+        io::ErrorKind::PermissionDenied => tr("Нет доступа к файлу!", "Permission denied!"),
+        _ => tr("Ошибка ввода/вывода!", "Input/output error!"),
+    }
 }
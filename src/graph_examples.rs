@@ -0,0 +1,42 @@
+// Встроенный пример графа
+pub struct Example {
+    pub name_ru: &'static str, // название на русском языке
+    pub name_en: &'static str, // название на английском языке
+    pub text: &'static str,    // граф в собственном формате
+    pub source_sink: Option<(&'static str, &'static str)>, // исток и сток для потока
+}
+
+// Примеры графов, встроенные в программу (для нового примера достаточно добавить файл
+// в папку examples и строку в этот массив)
+pub const EXAMPLES: [Example; 5] = [
+    Example {
+        name_ru: "Маленький граф с метками",
+        name_en: "Small labeled graph",
+        text: include_str!("../examples/11.txt"),
+        source_sink: None,
+    },
+    Example {
+        name_ru: "Сеть для потока (максимальный поток 23)",
+        name_en: "Flow network (max flow 23)",
+        text: include_str!("../examples/7.txt"),
+        source_sink: Some(("1", "6")),
+    },
+    Example {
+        name_ru: "Сеть с истоком s и стоком t (максимальный поток 19)",
+        name_en: "Network with source s and sink t (max flow 19)",
+        text: include_str!("../examples/10.txt"),
+        source_sink: Some(("s", "t")),
+    },
+    Example {
+        name_ru: "Противоположные дуги и петли",
+        name_en: "Opposite arcs and loops",
+        text: include_str!("../examples/12.txt"),
+        source_sink: None,
+    },
+    Example {
+        name_ru: "Случайный граф из 500 вершин",
+        name_en: "Random graph with 500 vertices",
+        text: include_str!("../examples/13.txt"),
+        source_sink: None,
+    },
+];
@@ -1,15 +1,31 @@
 use std::{
     cmp::min,
-    collections::{BTreeMap, BTreeSet},
+    collections::{BTreeMap, BTreeSet, VecDeque},
 };
 
 use crate::{
-    graph::{Edge, EdgeWeight, EdgeWeights, Graph, VertexKey},
-    graph_errors::{GraphAlgorithmError, GraphError, GraphInterfaceError},
+    graph::{Edge, EdgeWeight, EdgeWeights, Graph, Vertex, VertexKey},
+    graph_errors::{GraphAlgorithmError, GraphError, GraphInterfaceError, GraphOperationError},
 };
 
 pub struct GraphFlows {}
 
+// Способ поиска дополняющего пути
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PathStrategy {
+    Dfs,     // поиск в глубину (алгоритм Форда-Фалкерсона)
+    Bfs,     // поиск в ширину (алгоритм Эдмондса-Карпа)
+    Scaling, // поиск в глубину с масштабированием пропускных способностей
+    Dinic,   // блокирующие потоки в слоистой сети (алгоритм Диница)
+}
+
+// Фаза алгоритма Диница, выполненная на последнем шаге
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DinicPhase {
+    LevelGraph,   // построение слоистой сети
+    BlockingFlow, // нахождение блокирующего потока
+}
+
 // Состояние выполнения алгоритма
 #[derive(Debug, Clone)]
 pub enum AlgorithmState<I, W>
@@ -31,11 +47,20 @@ where
 {
     s: I,
     t: I,
+    g: Graph<I, W>,
     gc: Graph<I, W>,
     gf: Graph<I, W>,
     curr_path: Option<BTreeMap<(I, I), W>>,
+    curr_path_vertices: Vec<I>,
     last_flow: W,
     total_flow: W,
+    delta: Option<W>,
+    min_cut_edges: BTreeSet<(I, I)>,
+    min_cut_capacity: W,
+    min_cut_vertices: BTreeSet<I>,
+    levels: Option<BTreeMap<I, u32>>,
+    dinic_phase: Option<DinicPhase>,
+    blocking_paths: Vec<(Vec<I>, W)>,
 }
 
 impl<I, W> AlgorithmData<I, W>
@@ -43,6 +68,22 @@ where
     I: VertexKey,
     W: EdgeWeight,
 {
+    pub fn get_s(&self) -> &I {
+        &self.s
+    }
+
+    pub fn get_t(&self) -> &I {
+        &self.t
+    }
+
+    pub fn get_g(&self) -> &Graph<I, W> {
+        &self.g
+    }
+
+    pub fn get_gc(&self) -> &Graph<I, W> {
+        &self.gc
+    }
+
     pub fn get_gf(&self) -> &Graph<I, W> {
         &self.gf
     }
@@ -51,6 +92,11 @@ where
         &self.curr_path
     }
 
+    // Вершины текущего дополняющего пути в порядке обхода от истока к стоку
+    pub fn get_curr_path_vertices(&self) -> &[I] {
+        &self.curr_path_vertices
+    }
+
     pub fn get_last_flow(&self) -> &W {
         &self.last_flow
     }
@@ -58,14 +104,65 @@ where
     pub fn get_total_flow(&self) -> &W {
         &self.total_flow
     }
+
+    // Текущий порог остаточной пропускной способности при масштабировании
+    pub fn get_delta(&self) -> &Option<W> {
+        &self.delta
+    }
+
+    pub fn get_min_cut_edges(&self) -> &BTreeSet<(I, I)> {
+        &self.min_cut_edges
+    }
+
+    pub fn get_min_cut_capacity(&self) -> &W {
+        &self.min_cut_capacity
+    }
+
+    // Вершины, достижимые из истока в остаточной сети (сторона истока минимального разреза)
+    pub fn get_min_cut_vertices(&self) -> &BTreeSet<I> {
+        &self.min_cut_vertices
+    }
+
+    // Уровни вершин текущей слоистой сети в алгоритме Диница
+    pub fn get_levels(&self) -> &Option<BTreeMap<I, u32>> {
+        &self.levels
+    }
+
+    pub fn get_dinic_phase(&self) -> Option<DinicPhase> {
+        self.dinic_phase
+    }
+
+    // Дополняющие пути последнего блокирующего потока и потоки через них
+    pub fn get_blocking_paths(&self) -> &[(Vec<I>, W)] {
+        &self.blocking_paths
+    }
 }
 
-// Алгоритм Форда-Фалкерсона
+/// Алгоритм Форда-Фалкерсона (при unit_capacities пропускные способности всех дуг
+/// невзвешенного графа считаются равными 1)
+///
+/// ```
+/// use graph_visualizer::{algorithm_step, AlgorithmState, Graph, PathStrategy, VertexId};
+///
+/// let text = "directed weighted int\nvertices\ns\na\nt\nedges\ns a 3\na t 2\n";
+/// let g = Some(Graph::<VertexId, _>::from_file(text.as_bytes()).unwrap());
+/// let mut state = AlgorithmState::NotStarted;
+/// // Шаги выполняются, пока дополняющие пути находятся
+/// let flow = loop {
+///     state = algorithm_step(state, &g, "s", "t", PathStrategy::Bfs, false).unwrap();
+///     if let AlgorithmState::Finished(data) = &state {
+///         break data.get_total_flow().clone();
+///     }
+/// };
+/// assert_eq!(flow, 2.into());
+/// ```
 pub fn algorithm_step<I>(
     state: AlgorithmState<I, EdgeWeights>,
     g: &Option<Graph<I, EdgeWeights>>,
     s_str: &str,
     t_str: &str,
+    strategy: PathStrategy,
+    unit_capacities: bool,
 ) -> Result<AlgorithmState<I, EdgeWeights>, GraphError>
 where
     I: VertexKey,
@@ -77,19 +174,31 @@ where
                 return Err(GraphInterfaceError::GraphNotExist.into());
             }
             let g = g.as_ref().unwrap();
-            let zero: EdgeWeights = if g.get_is_float_weights() {
-                0.0.into()
-            } else {
-                0.into()
-            };
+            // Единичные пропускные способности невзвешенного графа целые
+            let is_float = g.get_is_weighted() && g.get_is_float_weights();
+            let zero: EdgeWeights = if is_float { 0.0.into() } else { 0.into() };
 
-            // Граф неориентированный или невзвешенный
+            // Граф неориентированный или невзвешенный (если пропускные способности
+            // его дуг не считаются равными 1)
             if !g.get_is_directed() {
                 return Err(GraphAlgorithmError::GraphNotDirected.into());
             }
-            if !g.get_is_weighted() {
+            if !g.get_is_weighted() && !unit_capacities {
                 return Err(GraphAlgorithmError::GraphNotWeighted.into());
             }
+            // Все пропускные способности должны иметь тип весов графа,
+            // иначе в вычислениях смешались бы целые и дробные потоки
+            if g.get_vertices()
+                .keys()
+                .flat_map(|i| g.get_edge_list(i).unwrap())
+                .any(|e| matches!(&e.weight, Some(c) if c.is_float() != g.get_is_float_weights()))
+            {
+                return Err(GraphAlgorithmError::MixedWeightTypes.into());
+            }
+            // Степени двойки порога определены только для целых пропускных способностей
+            if strategy == PathStrategy::Scaling && is_float {
+                return Err(GraphAlgorithmError::ScalingNotForFloatWeights.into());
+            }
 
             let s: I = s_str
                 .parse()
@@ -97,22 +206,70 @@ where
             let t: I = t_str
                 .parse()
                 .map_err(|_| GraphInterfaceError::IncorrectArgument { i: 2 })?;
+            if !g.get_vertices().contains_key(&s) || !g.get_vertices().contains_key(&t) {
+                return Err(GraphOperationError::SomeVerticesNotFound.into());
+            }
+            if s == t {
+                return Err(GraphAlgorithmError::SourceIsSink.into());
+            }
 
-            // Все рёбра графа
-            let edges: Vec<(&I, &I)> = g
+            // Все рёбра графа, кроме петель, которые не влияют на поток
+            // (параллельные дуги дают одну пару вершин)
+            let mut edges: Vec<(&I, &I)> = g
                 .get_vertices()
-                .iter()
-                .flat_map(|(i, _)| g.get_edge_list(i).unwrap().iter().map(move |e| (i, &e.to)))
+                .keys()
+                .flat_map(|i| g.get_edge_list(i).unwrap().iter().map(move |e| (i, &e.to)))
+                .filter(|(i, to)| i != to)
                 .collect();
+            edges.dedup();
+
+            // Пропускные способности не могут быть отрицательными
+            // (дуга без веса считается дугой с нулевой пропускной способностью)
+            for &(i, to) in &edges {
+                if g.get_edges(i, to)
+                    .unwrap()
+                    .iter()
+                    .any(|e| matches!(&e.weight, Some(c) if c < &zero))
+                {
+                    return Err(GraphAlgorithmError::NegativeCapacity {
+                        from: i.to_string(),
+                        to: to.to_string(),
+                    }
+                    .into());
+                }
+            }
 
-            // Граф пропускных способностей
-            let mut gc = g.clone();
+            // Граф пропускных способностей без петель: пропускная способность дуги — сумма
+            // весов параллельных ей дуг (у невзвешенного графа каждая дуга даёт 1)
+            let mut gc = Graph::new(true, true, is_float);
+            for v in g.get_vertices().values() {
+                gc.add_vertex(v.clone()).unwrap();
+            }
+            let one: EdgeWeights = 1.into();
+            for &(i, to) in &edges {
+                let c =
+                    g.get_edges(i, to)
+                        .unwrap()
+                        .iter()
+                        .try_fold(zero.clone(), |c, e| {
+                            match (g.get_is_weighted(), &e.weight) {
+                                (true, Some(w)) => c.checked_add(w.clone()),
+                                (true, None) => Ok(c),
+                                (false, _) => c.checked_add(one.clone()),
+                            }
+                        })?;
+                gc.add_edge(i.clone(), Edge::new(to.clone(), Some(c)))
+                    .unwrap();
+            }
             for &(i, to) in &edges {
-                let _ = gc.add_edge(to.clone(), Edge::new(i.clone(), Some(zero.clone())));
+                if gc.get_edge(to, i).is_err() {
+                    gc.add_edge(to.clone(), Edge::new(i.clone(), Some(zero.clone())))
+                        .unwrap();
+                }
             }
 
             // Граф потоков
-            let mut gf = Graph::new(true, true, g.get_is_float_weights());
+            let mut gf = Graph::new(true, true, is_float);
             for v in g.get_vertices().values() {
                 gf.add_vertex(v.clone()).unwrap();
             }
@@ -121,48 +278,105 @@ where
                     .unwrap();
             }
             for &(i, to) in &edges {
-                let _ = gf.add_edge(to.clone(), Edge::new(i.clone(), Some(zero.clone())));
+                if gf.get_edge(to, i).is_err() {
+                    gf.add_edge(to.clone(), Edge::new(i.clone(), Some(zero.clone())))
+                        .unwrap();
+                }
             }
 
+            // Начальный порог масштабирования: наибольшая степень двойки,
+            // не превосходящая максимальной пропускной способности
+            let delta = (strategy == PathStrategy::Scaling).then(|| {
+                let max_c = edges
+                    .iter()
+                    .map(|&(i, to)| match gc.get_edge(i, to).unwrap().weight {
+                        Some(EdgeWeights::I32(c)) => c,
+                        _ => 0,
+                    })
+                    .max()
+                    .unwrap_or(0);
+                if max_c > 0 {
+                    EdgeWeights::I32(1 << (31 - max_c.leading_zeros()))
+                } else {
+                    EdgeWeights::I32(0)
+                }
+            });
+
             // Данные состояния
             let data = AlgorithmData {
                 s,
                 t,
+                g: g.clone(),
                 gc,
                 gf,
                 curr_path: None,
+                curr_path_vertices: Vec::new(),
                 last_flow: zero.clone(),
-                total_flow: zero,
+                total_flow: zero.clone(),
+                delta,
+                min_cut_edges: BTreeSet::new(),
+                min_cut_capacity: zero,
+                min_cut_vertices: BTreeSet::new(),
+                levels: None,
+                dinic_phase: None,
+                blocking_paths: Vec::new(),
             };
             // Алгоритм запущен
             Ok(AlgorithmState::Step(data))
         }
         AlgorithmState::Step(mut data) => {
-            let (zero, inf): (EdgeWeights, EdgeWeights) = if data.gc.get_is_float_weights() {
-                (0.0.into(), f32::INFINITY.into())
+            // Граф не должен изменяться во время выполнения алгоритма
+            if g.as_ref().map(Graph::get_revision) != Some(data.g.get_revision()) {
+                return Err(GraphAlgorithmError::GraphModifiedDuringAlgorithm.into());
+            }
+            let zero: EdgeWeights = if data.gc.get_is_float_weights() {
+                0.0.into()
             } else {
-                (0.into(), i32::MAX.into())
+                0.into()
             };
+            if strategy == PathStrategy::Dinic {
+                return dinic_step(data);
+            }
 
             // Шаг алгоритма
-            let mut used = BTreeSet::new();
             data.curr_path = Some(BTreeMap::new());
-            let f = dfs(
-                &data.gc,
-                &mut data.gf,
-                &mut used,
-                data.curr_path.as_mut().unwrap(),
-                &data.s,
-                &data.t,
-                &data.s,
-                inf,
-            );
-            data.total_flow = data.total_flow + f.clone();
+            data.curr_path_vertices.clear();
+            let f = loop {
+                let f = match strategy {
+                    PathStrategy::Dfs | PathStrategy::Scaling => dfs(
+                        &data.gc,
+                        &mut data.gf,
+                        data.curr_path.as_mut().unwrap(),
+                        &mut data.curr_path_vertices,
+                        &data.s,
+                        &data.t,
+                        data.delta.as_ref(),
+                    )?,
+                    PathStrategy::Bfs | PathStrategy::Dinic => bfs(
+                        &data.gc,
+                        &mut data.gf,
+                        data.curr_path.as_mut().unwrap(),
+                        &mut data.curr_path_vertices,
+                        &data.s,
+                        &data.t,
+                    )?,
+                };
+                match data.delta {
+                    // Пути с остаточными пропускными способностями не меньше порога нет,
+                    // порог уменьшается вдвое (алгоритм завершается, когда он становится нулевым)
+                    Some(EdgeWeights::I32(d)) if f == zero && d > 0 => {
+                        data.delta = Some(EdgeWeights::I32(d / 2))
+                    }
+                    _ => break f,
+                }
+            };
+            data.total_flow = data.total_flow.checked_add(f.clone())?;
             data.last_flow = f.clone();
 
             if f == zero {
                 // Дополняющих путей нет, завершение алгоритма
                 data.curr_path = None;
+                min_cut(&mut data)?;
                 Ok(AlgorithmState::Finished(data))
             } else {
                 // Путь найден
@@ -176,63 +390,1128 @@ where
     }
 }
 
+// Шаг алгоритма Диница: построение слоистой сети поиском в ширину, если на предыдущем шаге
+// она не строилась, иначе нахождение в ней блокирующего потока
+fn dinic_step<I>(
+    mut data: AlgorithmData<I, EdgeWeights>,
+) -> Result<AlgorithmState<I, EdgeWeights>, GraphError>
+where
+    I: VertexKey,
+{
+    let zero: EdgeWeights = if data.gc.get_is_float_weights() {
+        0.0.into()
+    } else {
+        0.into()
+    };
+    data.curr_path_vertices.clear();
+    data.blocking_paths.clear();
+
+    if data.dinic_phase != Some(DinicPhase::LevelGraph) {
+        let levels = residual_levels(&data.gc, &data.gf, &data.s);
+        data.curr_path = None;
+        data.last_flow = zero;
+        if !levels.contains_key(&data.t) {
+            // Сток недостижим, завершение алгоритма
+            data.levels = None;
+            data.dinic_phase = None;
+            min_cut(&mut data)?;
+            return Ok(AlgorithmState::Finished(data));
+        }
+        data.levels = Some(levels);
+        data.dinic_phase = Some(DinicPhase::LevelGraph);
+        return Ok(AlgorithmState::Step(data));
+    }
+
+    // Поиск путей в слоистой сети, пока сток достижим (сток достижим в построенной сети,
+    // поэтому блокирующий поток положителен)
+    let levels = data.levels.as_ref().unwrap();
+    let curr_path = data.curr_path.insert(BTreeMap::new());
+    let mut dead = BTreeSet::new();
+    let mut flow = zero.clone();
+    loop {
+        let mut path = Vec::new();
+        let f = level_dfs(
+            &data.gc,
+            &mut data.gf,
+            curr_path,
+            &mut path,
+            levels,
+            &mut dead,
+            &data.s,
+            &data.t,
+        )?;
+        if f == zero {
+            break;
+        }
+        flow = flow.checked_add(f.clone())?;
+        data.blocking_paths.push((path, f));
+    }
+    data.total_flow = data.total_flow.checked_add(flow.clone())?;
+    data.last_flow = flow;
+    data.dinic_phase = Some(DinicPhase::BlockingFlow);
+    Ok(AlgorithmState::Step(data))
+}
+
+// Уровни вершин слоистой сети: расстояния от истока по дугам
+// с положительной остаточной пропускной способностью
+fn residual_levels<I>(
+    gc: &Graph<I, EdgeWeights>,
+    gf: &Graph<I, EdgeWeights>,
+    s: &I,
+) -> BTreeMap<I, u32>
+where
+    I: VertexKey,
+{
+    let zero: EdgeWeights = if gc.get_is_float_weights() {
+        0.0.into()
+    } else {
+        0.into()
+    };
+
+    let mut levels = BTreeMap::new();
+    let mut queue = VecDeque::new();
+    levels.insert(s.clone(), 0);
+    queue.push_back(s.clone());
+    while let Some(i) = queue.pop_front() {
+        let level = levels[&i];
+        for Edge { to, weight: c } in gc.get_edge_list(&i).unwrap() {
+            let c = c.as_ref().unwrap();
+            let f = gf.get_edge(&i, to).unwrap().weight.as_ref().unwrap();
+            if !levels.contains_key(to) && residual_capacity(c, f) > zero {
+                levels.insert(to.clone(), level + 1);
+                queue.push_back(to.clone());
+            }
+        }
+    }
+    levels
+}
+
+// Нахождение дополняющего пути поиском в глубину по дугам слоистой сети (вершины,
+// из которых сток недостижим, запоминаются и больше не посещаются в текущей фазе)
+#[allow(clippy::too_many_arguments)]
+fn level_dfs<I>(
+    gc: &Graph<I, EdgeWeights>,
+    gf: &mut Graph<I, EdgeWeights>,
+    curr_path: &mut BTreeMap<(I, I), EdgeWeights>,
+    path_vertices: &mut Vec<I>,
+    levels: &BTreeMap<I, u32>,
+    dead: &mut BTreeSet<I>,
+    s: &I,
+    t: &I,
+) -> Result<EdgeWeights, GraphAlgorithmError>
+where
+    I: VertexKey,
+{
+    let (zero, inf): (EdgeWeights, EdgeWeights) = if gc.get_is_float_weights() {
+        (0.0.into(), f32::INFINITY.into())
+    } else {
+        (0.into(), i32::MAX.into())
+    };
+
+    // Стек вершин текущего пути: вершина, поток до неё, оставшиеся исходящие дуги
+    let mut stack = vec![(s.clone(), inf, gc.get_edge_list(s).unwrap().iter())];
+    while let Some((i, flow, edges)) = stack.last_mut() {
+        // Все дуги, исходящие из вершины, просмотрены: сток из неё недостижим
+        let Edge { to, weight: c } = match edges.next() {
+            Some(e) => e,
+            None => {
+                dead.insert(i.clone());
+                stack.pop();
+                continue;
+            }
+        };
+        // Используются только дуги в следующий уровень
+        if dead.contains(to) || levels.get(to) != Some(&(levels[&*i] + 1)) {
+            continue;
+        }
+
+        // Поток в дополняющем пути
+        let c = c.as_ref().unwrap();
+        let f = gf.get_edge(i, to).unwrap().weight.as_ref().unwrap();
+        let next_f = min(flow.clone(), residual_capacity(c, f));
+        if next_f <= zero {
+            continue;
+        }
+
+        // Достигнут сток, добавление потока на всех дугах пути
+        if to == t {
+            let mut path: Vec<_> = stack.iter().map(|(i, _, _)| i.clone()).collect();
+            path.push(t.clone());
+            for pair in path.windows(2) {
+                add_flow(gf, curr_path, &pair[0], &pair[1], next_f.clone())?;
+            }
+            *path_vertices = path;
+            return Ok(next_f);
+        }
+        stack.push((to.clone(), next_f, gc.get_edge_list(to).unwrap().iter()));
+    }
+    Ok(zero)
+}
+
+// Нахождение минимального разреза по остаточной сети
+fn min_cut<I>(data: &mut AlgorithmData<I, EdgeWeights>) -> Result<(), GraphAlgorithmError>
+where
+    I: VertexKey,
+{
+    let zero: EdgeWeights = if data.gc.get_is_float_weights() {
+        0.0.into()
+    } else {
+        0.into()
+    };
+
+    // Вершины, достижимые из истока по дугам с положительной остаточной пропускной способностью
+    let mut reachable = BTreeSet::new();
+    let mut stack = vec![data.s.clone()];
+    reachable.insert(data.s.clone());
+    while let Some(i) = stack.pop() {
+        for Edge { to, weight: c } in data.gc.get_edge_list(&i).unwrap() {
+            let c = c.as_ref().unwrap();
+            let f = data.gf.get_edge(&i, to).unwrap().weight.as_ref().unwrap();
+            if !reachable.contains(to) && residual_capacity(c, f) > zero {
+                reachable.insert(to.clone());
+                stack.push(to.clone());
+            }
+        }
+    }
+
+    // Рёбра из достижимых вершин в недостижимые
+    data.min_cut_edges.clear();
+    data.min_cut_capacity = zero.clone();
+    for i in &reachable {
+        for Edge { to, weight: c } in data.gc.get_edge_list(i).unwrap() {
+            let c = c.as_ref().unwrap();
+            if !reachable.contains(to) && c > &zero {
+                data.min_cut_edges.insert((i.clone(), to.clone()));
+                data.min_cut_capacity = data.min_cut_capacity.clone().checked_add(c.clone())?;
+            }
+        }
+    }
+    data.min_cut_vertices = reachable;
+    Ok(())
+}
+
 // Нахождение дополняющего пути поиском в глубину
 fn dfs<I>(
     gc: &Graph<I, EdgeWeights>,
     gf: &mut Graph<I, EdgeWeights>,
-    used: &mut BTreeSet<I>,
     curr_path: &mut BTreeMap<(I, I), EdgeWeights>,
+    path_vertices: &mut Vec<I>,
+    s: &I,
+    t: &I,
+    delta: Option<&EdgeWeights>,
+) -> Result<EdgeWeights, GraphAlgorithmError>
+where
+    I: VertexKey,
+{
+    let (zero, inf): (EdgeWeights, EdgeWeights) = if gc.get_is_float_weights() {
+        (0.0.into(), f32::INFINITY.into())
+    } else {
+        (0.into(), i32::MAX.into())
+    };
+    // Исток совпадает со стоком
+    if s == t {
+        return Ok(inf);
+    }
+
+    // Посещённые вершины
+    let mut used = BTreeSet::new();
+    used.insert(s.clone());
+    // Стек вершин текущего пути: вершина, поток до неё, оставшиеся исходящие дуги
+    let mut stack = vec![(s.clone(), inf, gc.get_edge_list(s).unwrap().iter())];
+    while let Some((i, flow, edges)) = stack.last_mut() {
+        // Все дуги, исходящие из вершины, просмотрены
+        let Edge { to, weight: c } = match edges.next() {
+            Some(e) => e,
+            None => {
+                stack.pop();
+                continue;
+            }
+        };
+
+        // Пропускная способность, поток, остаточная пропускная способность
+        let c = c.as_ref().unwrap();
+        let f = gf.get_edge(i, to).unwrap().weight.as_ref().unwrap();
+        let r = residual_capacity(c, f);
+        // При масштабировании дуги с остаточной пропускной способностью меньше порога не используются
+        if matches!(delta, Some(d) if &r < d) {
+            continue;
+        }
+        // Поток в дополняющем пути
+        let next_f = min(flow.clone(), r);
+
+        // Достигнут сток
+        if to == t {
+            if next_f == zero {
+                continue;
+            }
+            // Добавление потока на всех дугах пути
+            let mut path: Vec<_> = stack.iter().map(|(i, _, _)| i.clone()).collect();
+            path.push(t.clone());
+            for pair in path.windows(2) {
+                add_flow(gf, curr_path, &pair[0], &pair[1], next_f.clone())?;
+            }
+            *path_vertices = path;
+            return Ok(next_f);
+        }
+        // Потока нет или вершина уже посещена
+        if next_f == zero || used.contains(to) {
+            continue;
+        }
+        // Вершина посещена
+        used.insert(to.clone());
+        stack.push((to.clone(), next_f, gc.get_edge_list(to).unwrap().iter()));
+    }
+    Ok(zero)
+}
+
+// Нахождение кратчайшего дополняющего пути поиском в ширину
+fn bfs<I>(
+    gc: &Graph<I, EdgeWeights>,
+    gf: &mut Graph<I, EdgeWeights>,
+    curr_path: &mut BTreeMap<(I, I), EdgeWeights>,
+    path_vertices: &mut Vec<I>,
     s: &I,
     t: &I,
+) -> Result<EdgeWeights, GraphAlgorithmError>
+where
+    I: VertexKey,
+{
+    let (zero, inf): (EdgeWeights, EdgeWeights) = if gc.get_is_float_weights() {
+        (0.0.into(), f32::INFINITY.into())
+    } else {
+        (0.into(), i32::MAX.into())
+    };
+    // Исток совпадает со стоком
+    if s == t {
+        return Ok(inf);
+    }
+
+    // Предки вершин в дереве поиска
+    let mut parent = BTreeMap::new();
+    let mut used = BTreeSet::new();
+    let mut queue = VecDeque::new();
+    used.insert(s.clone());
+    queue.push_back(s.clone());
+    while let Some(i) = queue.pop_front() {
+        if &i == t {
+            break;
+        }
+        // Все дуги с положительной остаточной пропускной способностью
+        for Edge { to, weight: c } in gc.get_edge_list(&i).unwrap() {
+            if used.contains(to) {
+                continue;
+            }
+            let c = c.as_ref().unwrap();
+            let f = gf.get_edge(&i, to).unwrap().weight.as_ref().unwrap();
+            if residual_capacity(c, f) > zero {
+                used.insert(to.clone());
+                parent.insert(to.clone(), i.clone());
+                queue.push_back(to.clone());
+            }
+        }
+    }
+    // Сток недостижим
+    if !parent.contains_key(t) {
+        return Ok(zero);
+    }
+
+    // Восстановление пути от стока к истоку
+    let mut path = Vec::new();
+    let mut to = t.clone();
+    while let Some(i) = parent.get(&to) {
+        path.push((i.clone(), to));
+        to = i.clone();
+    }
+    path.reverse();
+
+    // Поток в дополняющем пути равен минимальной остаточной пропускной способности
+    let mut path_f = inf;
+    for (i, to) in &path {
+        let c = gc.get_edge(i, to).unwrap().weight.as_ref().unwrap();
+        let f = gf.get_edge(i, to).unwrap().weight.as_ref().unwrap();
+        path_f = min(path_f, residual_capacity(c, f));
+    }
+    for (i, to) in &path {
+        add_flow(gf, curr_path, i, to, path_f.clone())?;
+    }
+    *path_vertices = std::iter::once(s.clone())
+        .chain(path.into_iter().map(|(_, to)| to))
+        .collect();
+    Ok(path_f)
+}
+
+// Остаточная пропускная способность дуги с пропускной способностью c и потоком f;
+// у целых весов она может не помещаться в i32 (по дуге с обратной дугой идёт
+// отрицательный поток), поэтому вычисляется в i64 и ограничивается значением i32::MAX,
+// больше которого поток в дополняющем пути не бывает
+pub fn residual_capacity(c: &EdgeWeights, f: &EdgeWeights) -> EdgeWeights {
+    match (c, f) {
+        (EdgeWeights::I32(c), EdgeWeights::I32(f)) => {
+            EdgeWeights::I32(i64::min(*c as i64 - *f as i64, i32::MAX as i64) as i32)
+        }
+        _ => c.clone() - f.clone(),
+    }
+}
+
+// Добавление потока через дугу дополняющего пути
+fn add_flow<I>(
+    gf: &mut Graph<I, EdgeWeights>,
+    curr_path: &mut BTreeMap<(I, I), EdgeWeights>,
     i: &I,
+    to: &I,
     flow: EdgeWeights,
-) -> EdgeWeights
+) -> Result<(), GraphAlgorithmError>
 where
     I: VertexKey,
 {
-    // Достигнут сток
-    if i == t {
-        return flow;
-    }
-    let zero: EdgeWeights = if gc.get_is_float_weights() {
+    let zero: EdgeWeights = if gf.get_is_float_weights() {
         0.0.into()
     } else {
         0.into()
     };
-    // Потока нет или текущая вершина уже посещена
-    if flow == zero || used.contains(i) {
-        return zero;
+
+    // Добавление потока на прямой дуге (в блокирующем потоке дуга может входить в несколько путей)
+    let path_f = curr_path
+        .entry((i.clone(), to.clone()))
+        .or_insert(zero.clone());
+    *path_f = path_f.clone().checked_add(flow.clone())?;
+    let f = gf.get_edge(i, to).unwrap().weight.as_ref().unwrap().clone();
+    gf.set_edge_weight(i, to, Some(f.checked_add(flow.clone())?))
+        .unwrap();
+
+    // Вычитание потока на обратной дуге
+    let path_rev_f = curr_path.entry((to.clone(), i.clone())).or_insert(zero);
+    *path_rev_f = path_rev_f.clone().checked_sub(flow.clone())?;
+    let rev_f = gf.get_edge(to, i).unwrap().weight.as_ref().unwrap().clone();
+    gf.set_edge_weight(to, i, Some(rev_f.checked_sub(flow)?))
+        .unwrap();
+    Ok(())
+}
+
+// Вспомогательная сеть для нахождения максимального паросочетания в двудольном графе:
+// из нового истока в каждую вершину левой доли, из каждой вершины правой доли в новый сток
+// и из левой доли в правую по рёбрам графа ведут дуги единичной пропускной способности
+#[derive(Debug, Clone)]
+pub struct MatchingNetwork<I>
+where
+    I: VertexKey,
+{
+    pub network: Graph<I, EdgeWeights>,  // сеть
+    pub s: I,                            // новый исток
+    pub t: I,                            // новый сток
+    pub edges: BTreeMap<(I, I), (I, I)>, // ребро графа для каждой дуги сети из левой доли в правую
+}
+
+// Новый идентификатор вершины, которого нет в графе: name, name1, name2, ...
+// (если имя не является идентификатором, как для числовых идентификаторов, —
+// отрицательные числа -1, -2, ...)
+fn new_vertex_id<I>(g: &Graph<I, EdgeWeights>, name: &str) -> I
+where
+    I: VertexKey,
+{
+    let is_named = name.parse::<I>().is_ok();
+    (0..)
+        .map(|k: usize| {
+            if k == 0 {
+                String::from(name)
+            } else {
+                format!("{}{}", name, k)
+            }
+        })
+        .take_while(|_| is_named)
+        .chain((1..).map(|k: usize| format!("-{}", k)))
+        .filter_map(|id| id.parse().ok())
+        .find(|i| !g.get_vertices().contains_key(i))
+        .unwrap()
+}
+
+// Построение вспомогательной сети по графу и вершинам левой доли (остальные вершины
+// относятся к правой доле); рёбра между вершинами одной доли не допускаются
+pub fn build_matching_network<I>(
+    g: &Graph<I, EdgeWeights>,
+    left: &BTreeSet<I>,
+) -> Result<MatchingNetwork<I>, GraphError>
+where
+    I: VertexKey,
+{
+    let one: EdgeWeights = 1.into();
+    let mut network = Graph::new(true, true, false);
+    for v in g.get_vertices().values() {
+        network.add_vertex(v.clone())?;
     }
-    // Текущая вершина посещена
-    used.insert(i.clone());
 
-    // Все дуги, исходящие из вершины
-    for Edge { to, weight: c } in gc.get_edge_list(i).unwrap() {
-        // Пропускная способность, поток, остаточная пропускная способность
-        let c = c.as_ref().unwrap();
-        let f = gf.get_edge(i, to).unwrap().weight.as_ref().unwrap().clone();
-        let r = c.clone() - f.clone();
+    // Дуги из левой доли в правую (у ориентированного графа направление дуги не важно)
+    let mut edges = BTreeMap::new();
+    for i in g.get_vertices().keys() {
+        for Edge { to, .. } in g.get_edge_list(i).unwrap() {
+            let (l, r) = match (left.contains(i), left.contains(to)) {
+                (true, false) => (i, to),
+                (false, true) => (to, i),
+                _ => return Err(GraphAlgorithmError::GraphNotBipartite.into()),
+            };
+            // Параллельные и противоположные рёбра дают одну дугу сети
+            if network.get_edge(l, r).is_err() {
+                network.add_edge(l.clone(), Edge::new(r.clone(), Some(one.clone())))?;
+                edges.insert((l.clone(), r.clone()), (i.clone(), to.clone()));
+            }
+        }
+    }
 
-        // Поток в дополняющем пути
-        let next_f = dfs(gc, gf, used, curr_path, s, t, to, min(flow.clone(), r));
-        if next_f != zero {
-            // Добавление потока на прямой дуге
-            curr_path.insert((i.clone(), to.clone()), next_f.clone());
-            gf.remove_edge(i, to).unwrap();
-            gf.add_edge(i.clone(), Edge::new(to.clone(), Some(f + next_f.clone())))
-                .unwrap();
+    // Новые исток и сток
+    let s = new_vertex_id(&network, "s");
+    network.add_vertex(Vertex {
+        id: s.clone(),
+        label: None,
+        color: None,
+    })?;
+    let t = new_vertex_id(&network, "t");
+    network.add_vertex(Vertex {
+        id: t.clone(),
+        label: None,
+        color: None,
+    })?;
+    for i in g.get_vertices().keys() {
+        if left.contains(i) {
+            network.add_edge(s.clone(), Edge::new(i.clone(), Some(one.clone())))?;
+        } else {
+            network.add_edge(i.clone(), Edge::new(t.clone(), Some(one.clone())))?;
+        }
+    }
+
+    Ok(MatchingNetwork {
+        network,
+        s,
+        t,
+        edges,
+    })
+}
 
-            // Вычитание потока на обратной дуге
-            curr_path.insert((to.clone(), i.clone()), zero - next_f.clone());
-            let rev_f = gf.get_edge(to, i).unwrap().weight.as_ref().unwrap().clone();
-            gf.remove_edge(to, i).unwrap();
-            gf.add_edge(
-                to.clone(),
-                Edge::new(i.clone(), Some(rev_f - next_f.clone())),
+// Состояние нахождения максимального паросочетания
+#[derive(Debug, Clone)]
+pub enum MatchingState<I>
+where
+    I: VertexKey,
+{
+    NotStarted,
+    Finished(MatchingData<I>),
+}
+
+// Найденное максимальное паросочетание
+#[derive(Debug, Clone)]
+pub struct MatchingData<I>
+where
+    I: VertexKey,
+{
+    edges: BTreeSet<(I, I)>,
+}
+
+impl<I> MatchingData<I>
+where
+    I: VertexKey,
+{
+    // Рёбра паросочетания в том направлении, в котором они заданы в графе
+    pub fn get_edges(&self) -> &BTreeSet<(I, I)> {
+        &self.edges
+    }
+}
+
+// Нахождение максимального паросочетания как максимального потока во вспомогательной сети
+// (вершины левой доли перечисляются через пробел)
+pub fn matching_step<I>(
+    state: MatchingState<I>,
+    g: &Option<Graph<I, EdgeWeights>>,
+    left_str: &str,
+) -> Result<MatchingState<I>, GraphError>
+where
+    I: VertexKey,
+{
+    match state {
+        MatchingState::NotStarted => {
+            let g = g.as_ref().ok_or(GraphInterfaceError::GraphNotExist)?;
+            let left = left_str
+                .split_whitespace()
+                .map(|id| id.parse())
+                .collect::<Result<BTreeSet<I>, _>>()
+                .map_err(|_| GraphInterfaceError::IncorrectArgument { i: 1 })?;
+            if left.iter().any(|i| !g.get_vertices().contains_key(i)) {
+                return Err(GraphOperationError::SomeVerticesNotFound.into());
+            }
+            let MatchingNetwork {
+                network,
+                s,
+                t,
+                edges,
+            } = build_matching_network(g, &left)?;
+
+            // Поток находится поиском путей в ширину до завершения алгоритма
+            let network = Some(network);
+            let (s_str, t_str) = (s.to_string(), t.to_string());
+            let mut state = AlgorithmState::NotStarted;
+            let data = loop {
+                state = match algorithm_step(
+                    state,
+                    &network,
+                    &s_str,
+                    &t_str,
+                    PathStrategy::Bfs,
+                    false,
+                )? {
+                    AlgorithmState::Finished(data) => break data,
+                    state => state,
+                };
+            };
+
+            // В паросочетание входят рёбра, соответствующие дугам с единичным потоком
+            let one: EdgeWeights = 1.into();
+            let edges = edges
+                .into_iter()
+                .filter(|((l, r), _)| {
+                    data.get_gf().get_edge(l, r).unwrap().weight.as_ref() == Some(&one)
+                })
+                .map(|(_, edge)| edge)
+                .collect();
+            Ok(MatchingState::Finished(MatchingData { edges }))
+        }
+        MatchingState::Finished(_) => {
+            // Сброс состояния
+            Ok(MatchingState::NotStarted)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{graph::VertexId, graph_examples::EXAMPLES, graph_generators::gen_random_gnp};
+
+    type TestGraph = Option<Graph<VertexId, EdgeWeights>>;
+
+    // Идентификатор вершины
+    fn id(name: &str) -> VertexId {
+        name.parse().unwrap()
+    }
+
+    // Граф из текста в собственном формате
+    fn graph(text: &str) -> TestGraph {
+        Some(Graph::from_file(text.as_bytes()).unwrap())
+    }
+
+    // Запуск алгоритма (первый шаг из начального состояния)
+    fn start(
+        g: &TestGraph,
+        s: &str,
+        t: &str,
+        strategy: PathStrategy,
+    ) -> Result<AlgorithmState<VertexId, EdgeWeights>, GraphError> {
+        algorithm_step(AlgorithmState::NotStarted, g, s, t, strategy, false)
+    }
+
+    // Выполнение алгоритма до конца: величина потока и количество шагов
+    // (включая последний шаг, на котором дополняющий путь не найден)
+    fn run(g: &TestGraph, s: &str, t: &str, strategy: PathStrategy) -> (EdgeWeights, usize) {
+        let mut state = start(g, s, t, strategy).unwrap();
+        let mut steps = 0;
+        loop {
+            state = algorithm_step(state, g, s, t, strategy, false).unwrap();
+            steps += 1;
+            if let AlgorithmState::Finished(data) = state {
+                assert_eq!(data.get_total_flow(), data.get_min_cut_capacity());
+                return (data.get_total_flow().clone(), steps);
+            }
+        }
+    }
+
+    // Сеть, в которой поиск в глубину сначала проходит по n дугам единичной пропускной
+    // способности из вершины a (их номера меньше стока), а поиск в ширину сразу находит
+    // путь s -> a -> t, насыщающий дугу из истока
+    fn fan_network(n: usize, c: i32) -> TestGraph {
+        let mut text = String::from("directed weighted int\nvertices\ns\na\nt\n");
+        text += &(1..=n).map(|k| format!("{}\n", k)).collect::<String>();
+        text += &format!("edges\ns a {}\na t {}\n", c, c);
+        text += &(1..=n)
+            .map(|k| format!("a {} 1\n{} t {}\n", k, k, c))
+            .collect::<String>();
+        graph(&text)
+    }
+
+    // Путь из вершин 1, 2, ..., n с пропускной способностью дуги (k, k + 1), равной c(k)
+    fn chain_network(n: usize, c: impl Fn(usize) -> i32) -> TestGraph {
+        let mut g = Graph::new(true, true, false);
+        for k in 1..=n {
+            g.add_vertex(Vertex {
+                id: id(&k.to_string()),
+                label: None,
+                color: None,
+            })
+            .unwrap();
+        }
+        for k in 1..n {
+            g.add_edge(
+                id(&k.to_string()),
+                Edge::new(id(&(k + 1).to_string()), Some(c(k).into())),
             )
             .unwrap();
-            return next_f;
         }
+        Some(g)
+    }
+
+    #[test]
+    fn dfs_and_bfs_find_same_flow() {
+        for example in &EXAMPLES {
+            if let Some((s, t)) = example.source_sink {
+                let g = graph(example.text);
+                let (dfs_flow, _) = run(&g, s, t, PathStrategy::Dfs);
+                let (bfs_flow, _) = run(&g, s, t, PathStrategy::Bfs);
+                assert_eq!(dfs_flow, bfs_flow);
+            }
+        }
+        let g = graph(EXAMPLES[1].text);
+        assert_eq!(run(&g, "1", "6", PathStrategy::Dfs).0, 23.into());
+        let g = graph(EXAMPLES[2].text);
+        assert_eq!(run(&g, "s", "t", PathStrategy::Bfs).0, 19.into());
+    }
+
+    #[test]
+    fn dfs_needs_more_augmentations_than_bfs() {
+        let g = fan_network(50, 1000);
+        let (dfs_flow, dfs_steps) = run(&g, "s", "t", PathStrategy::Dfs);
+        let (bfs_flow, bfs_steps) = run(&g, "s", "t", PathStrategy::Bfs);
+        assert_eq!(dfs_flow, 1000.into());
+        assert_eq!(bfs_flow, 1000.into());
+        assert_eq!(dfs_steps, 52);
+        assert_eq!(bfs_steps, 2);
+    }
+
+    #[test]
+    fn bfs_paths_do_not_get_shorter() {
+        // Дополняющие пути алгоритма Эдмондса-Карпа кратчайшие, поэтому их длины не убывают
+        let mut paths = 0;
+        for seed in 0..20 {
+            let g = random_network(12, 100, seed);
+            let mut state = start(&g, "1", "12", PathStrategy::Bfs).unwrap();
+            let mut prev_len = 0;
+            while let AlgorithmState::Step(data) = state {
+                let path = data.get_curr_path_vertices();
+                if let Some(curr_path) = data.get_curr_path() {
+                    assert_eq!(path.first(), Some(&id("1")), "{}", seed);
+                    assert_eq!(path.last(), Some(&id("12")), "{}", seed);
+                    for pair in path.windows(2) {
+                        assert!(curr_path.contains_key(&(pair[0].clone(), pair[1].clone())));
+                    }
+                    assert!(path.len() >= prev_len, "{}", seed);
+                    prev_len = path.len();
+                }
+                state = algorithm_step(
+                    AlgorithmState::Step(data),
+                    &g,
+                    "1",
+                    "12",
+                    PathStrategy::Bfs,
+                    false,
+                )
+                .unwrap();
+            }
+            paths += usize::from(prev_len > 0);
+            let (dfs_flow, _) = run(&g, "1", "12", PathStrategy::Dfs);
+            assert_eq!(
+                run(&g, "1", "12", PathStrategy::Bfs).0,
+                dfs_flow,
+                "{}",
+                seed
+            );
+        }
+        // В большинстве случайных сетей сток достижим из истока
+        assert!(paths > 10);
+    }
+
+    #[test]
+    fn long_chain_does_not_overflow_stack() {
+        const N: usize = 100_000;
+        let g = chain_network(N, |_| 5);
+        let (flow, steps) = run(&g, "1", &N.to_string(), PathStrategy::Dfs);
+        assert_eq!(flow, 5.into());
+        assert_eq!(steps, 2);
+    }
+
+    #[test]
+    fn long_chain_path_is_found_without_recursion() {
+        const N: usize = 100_000;
+        let g = chain_network(N, |k| 5 + (k % 7) as i32);
+        // Поиск в ширину и алгоритм Диница находят поток по цепочке,
+        // равный её наименьшей пропускной способности
+        for strategy in [PathStrategy::Bfs, PathStrategy::Dinic] {
+            assert_eq!(run(&g, "1", &N.to_string(), strategy).0, 5.into());
+        }
+        // Дополняющий путь проходит по всей цепочке
+        let state = start(&g, "1", &N.to_string(), PathStrategy::Dfs).unwrap();
+        match algorithm_step(state, &g, "1", &N.to_string(), PathStrategy::Dfs, false).unwrap() {
+            AlgorithmState::Step(data) => {
+                assert_eq!(data.get_curr_path_vertices().len(), N);
+                // Поток по каждой дуге пути записывается и для обратной дуги
+                assert_eq!(data.get_curr_path().as_ref().unwrap().len(), 2 * (N - 1));
+                assert_eq!(data.get_last_flow(), &5.into());
+            }
+            _ => panic!("путь не найден"),
+        }
+    }
+
+    #[test]
+    fn dfs_takes_arcs_in_order() {
+        // Из каждой вершины дуги просматриваются в порядке идентификаторов концов
+        let g = graph("directed weighted int\nvertices\ns\na\nb\nt\nedges\ns b 1\ns a 1\nb t 1\na b 1\na t 1\n");
+        let mut state = start(&g, "s", "t", PathStrategy::Dfs).unwrap();
+        let mut paths = Vec::new();
+        loop {
+            state = algorithm_step(state, &g, "s", "t", PathStrategy::Dfs, false).unwrap();
+            match &state {
+                AlgorithmState::Step(data) => paths.push(
+                    data.get_curr_path_vertices()
+                        .iter()
+                        .map(|i| i.to_string())
+                        .collect::<Vec<_>>()
+                        .join(" "),
+                ),
+                _ => break,
+            }
+        }
+        assert_eq!(paths, ["s a b t", "s b a t"]);
+    }
+
+    #[test]
+    fn negative_capacity_is_rejected() {
+        for text in [
+            "directed weighted int\nvertices\ns\na\nt\nedges\ns a 3\na t -1\n",
+            "directed weighted float\nvertices\ns\na\nt\nedges\ns a 3.0\na t -0.5\n",
+        ] {
+            let g = graph(text);
+            match start(&g, "s", "t", PathStrategy::Dfs) {
+                Err(GraphError::AlgorithmError(GraphAlgorithmError::NegativeCapacity {
+                    from,
+                    to,
+                })) => assert_eq!((from.as_str(), to.as_str()), ("a", "t")),
+                _ => panic!("отрицательная пропускная способность не обнаружена"),
+            }
+        }
+    }
+
+    #[test]
+    fn zero_capacities_give_zero_flow_in_one_step() {
+        let g = graph("directed weighted int\nvertices\ns\na\nt\nedges\ns a 0\na t 0\ns t 0\n");
+        for strategy in [PathStrategy::Dfs, PathStrategy::Bfs, PathStrategy::Scaling] {
+            assert_eq!(run(&g, "s", "t", strategy), (0.into(), 1));
+        }
+    }
+
+    #[test]
+    fn loops_at_source_and_sink_are_ignored() {
+        let g =
+            graph("directed weighted int\nvertices\ns\na\nt\nedges\ns s 7\ns a 2\na t 3\nt t 4\n");
+        for strategy in [PathStrategy::Dfs, PathStrategy::Bfs] {
+            assert_eq!(run(&g, "s", "t", strategy), (2.into(), 2));
+        }
+        match start(&g, "s", "t", PathStrategy::Dfs).unwrap() {
+            AlgorithmState::Step(data) => {
+                assert!(data.get_gc().get_edge(&id("s"), &id("s")).is_err());
+                assert!(data.get_gf().get_edge(&id("t"), &id("t")).is_err());
+            }
+            _ => panic!("алгоритм не запущен"),
+        }
+    }
+
+    #[test]
+    fn add_flow_keeps_skew_symmetry() {
+        let g = graph("directed weighted int\nvertices\n1\n2\nedges\n1 2 5\n");
+        let mut data = match start(&g, "1", "2", PathStrategy::Dfs).unwrap() {
+            AlgorithmState::Step(data) => data,
+            _ => panic!("алгоритм не запущен"),
+        };
+        let mut curr_path = BTreeMap::new();
+        add_flow(&mut data.gf, &mut curr_path, &id("1"), &id("2"), 3.into()).unwrap();
+        add_flow(&mut data.gf, &mut curr_path, &id("2"), &id("1"), 1.into()).unwrap();
+        let flow = |i: &str, to: &str| data.gf.get_edge(&id(i), &id(to)).unwrap().weight.clone();
+        assert_eq!(flow("1", "2"), Some(2.into()));
+        assert_eq!(flow("2", "1"), Some((-2).into()));
+        assert_eq!(curr_path[&(id("1"), id("2"))], 2.into());
+        assert_eq!(curr_path[&(id("2"), id("1"))], (-2).into());
+    }
+
+    #[test]
+    fn graph_change_between_steps_is_detected() {
+        let mut g = graph(EXAMPLES[2].text);
+        let state = start(&g, "s", "t", PathStrategy::Bfs).unwrap();
+        // Копия графа не считается изменением
+        let copy = g.clone();
+        let state = algorithm_step(state, &copy, "s", "t", PathStrategy::Bfs, false).unwrap();
+
+        g.as_mut()
+            .unwrap()
+            .set_edge_weight(&id("s"), &id("a"), Some(1.into()))
+            .unwrap();
+        let res = algorithm_step(state.clone(), &g, "s", "t", PathStrategy::Bfs, false);
+        assert!(matches!(
+            res,
+            Err(GraphError::AlgorithmError(
+                GraphAlgorithmError::GraphModifiedDuringAlgorithm
+            ))
+        ));
+        let res = algorithm_step(state, &None, "s", "t", PathStrategy::Bfs, false);
+        assert!(matches!(
+            res,
+            Err(GraphError::AlgorithmError(
+                GraphAlgorithmError::GraphModifiedDuringAlgorithm
+            ))
+        ));
+    }
+
+    // Случайная сеть из n вершин с целыми пропускными способностями от 1 до max_c
+    fn random_network(n: usize, max_c: i32, seed: u64) -> TestGraph {
+        Some(gen_random_gnp(n, 0.3, true, Some((1, max_c)), seed).unwrap())
+    }
+
+    #[test]
+    fn scaling_finds_same_flow_as_dfs() {
+        for seed in 0..20 {
+            let g = random_network(12, 1000, seed);
+            let (dfs_flow, _) = run(&g, "1", "12", PathStrategy::Dfs);
+            let (scaling_flow, _) = run(&g, "1", "12", PathStrategy::Scaling);
+            assert_eq!(dfs_flow, scaling_flow, "{}", seed);
+        }
+        // Масштабирование обходит пути через дуги единичной пропускной способности
+        let g = fan_network(50, 1000);
+        let (scaling_flow, scaling_steps) = run(&g, "s", "t", PathStrategy::Scaling);
+        assert_eq!(scaling_flow, 1000.into());
+        assert!(scaling_steps < run(&g, "s", "t", PathStrategy::Dfs).1);
+    }
+
+    #[test]
+    fn dinic_finds_same_flow_as_dfs_and_bfs() {
+        for seed in 0..20 {
+            let g = random_network(12, 1000, seed);
+            let (dfs_flow, _) = run(&g, "1", "12", PathStrategy::Dfs);
+            let (bfs_flow, _) = run(&g, "1", "12", PathStrategy::Bfs);
+            let (dinic_flow, _) = run(&g, "1", "12", PathStrategy::Dinic);
+            assert_eq!(dfs_flow, bfs_flow, "{}", seed);
+            assert_eq!(dfs_flow, dinic_flow, "{}", seed);
+        }
+        for example in &EXAMPLES {
+            if let Some((s, t)) = example.source_sink {
+                let g = graph(example.text);
+                assert_eq!(
+                    run(&g, s, t, PathStrategy::Dinic).0,
+                    run(&g, s, t, PathStrategy::Dfs).0
+                );
+            }
+        }
+        let g = graph(
+            "directed weighted float\nvertices\ns\na\nt\nedges\ns a 1.5\na t 2.5\ns t 0.25\n",
+        );
+        assert_eq!(run(&g, "s", "t", PathStrategy::Dinic).0, 1.75.into());
+    }
+
+    #[test]
+    fn dinic_alternates_level_graph_and_blocking_flow() {
+        let g = random_network(12, 1000, 1);
+        let mut state = start(&g, "1", "12", PathStrategy::Dinic).unwrap();
+        let mut prev_phase = Some(DinicPhase::BlockingFlow);
+        let mut prev_t_level = 0;
+        loop {
+            state = algorithm_step(state, &g, "1", "12", PathStrategy::Dinic, false).unwrap();
+            let data = match &state {
+                AlgorithmState::Step(data) => data,
+                _ => break,
+            };
+            let levels = data.get_levels().as_ref().unwrap();
+            match data.get_dinic_phase().unwrap() {
+                DinicPhase::LevelGraph => {
+                    assert_eq!(prev_phase, Some(DinicPhase::BlockingFlow));
+                    // Расстояние до стока в остаточной сети растёт с каждой фазой
+                    assert!(levels[&id("12")] > prev_t_level);
+                    prev_t_level = levels[&id("12")];
+                    assert!(data.get_blocking_paths().is_empty());
+                }
+                DinicPhase::BlockingFlow => {
+                    assert_eq!(prev_phase, Some(DinicPhase::LevelGraph));
+                    // Пути блокирующего потока идут по слоям слоистой сети
+                    assert!(!data.get_blocking_paths().is_empty());
+                    for (path, _) in data.get_blocking_paths() {
+                        assert_eq!(path.len() as u32, prev_t_level + 1);
+                        for (k, i) in path.iter().enumerate() {
+                            assert_eq!(levels[i], k as u32);
+                        }
+                    }
+                }
+            }
+            prev_phase = data.get_dinic_phase();
+        }
+        assert!(prev_t_level > 0);
+        match state {
+            AlgorithmState::Finished(data) => {
+                assert_eq!(
+                    data.get_total_flow(),
+                    &run(&g, "1", "12", PathStrategy::Dfs).0
+                );
+                assert_eq!(data.get_levels(), &None);
+            }
+            _ => panic!("алгоритм не завершён"),
+        }
+    }
+
+    #[test]
+    fn scaling_threshold_starts_at_power_of_two() {
+        let g = graph(EXAMPLES[2].text);
+        match start(&g, "s", "t", PathStrategy::Scaling).unwrap() {
+            AlgorithmState::Step(data) => assert_eq!(data.get_delta(), &Some(8.into())),
+            _ => panic!("алгоритм не запущен"),
+        }
+        match start(&g, "s", "t", PathStrategy::Dfs).unwrap() {
+            AlgorithmState::Step(data) => assert_eq!(data.get_delta(), &None),
+            _ => panic!("алгоритм не запущен"),
+        }
+    }
+
+    #[test]
+    fn scaling_rejects_float_capacities() {
+        let g = graph("directed weighted float\nvertices\ns\nt\nedges\ns t 1.5\n");
+        assert!(matches!(
+            start(&g, "s", "t", PathStrategy::Scaling),
+            Err(GraphError::AlgorithmError(
+                GraphAlgorithmError::ScalingNotForFloatWeights
+            ))
+        ));
+        assert_eq!(run(&g, "s", "t", PathStrategy::Dfs).0, 1.5.into());
+    }
+
+    #[test]
+    fn parallel_capacities_are_summed() {
+        let g =
+            graph("directed weighted int\nvertices\ns\na\nt\nedges\ns a 2\ns a 3\na t 4\na t 4\n");
+        for strategy in [
+            PathStrategy::Dfs,
+            PathStrategy::Bfs,
+            PathStrategy::Scaling,
+            PathStrategy::Dinic,
+        ] {
+            assert_eq!(run(&g, "s", "t", strategy).0, 5.into());
+        }
+        match start(&g, "s", "t", PathStrategy::Dfs).unwrap() {
+            AlgorithmState::Step(data) => {
+                assert_eq!(
+                    data.get_gc().get_edges(&id("s"), &id("a")).unwrap().len(),
+                    1
+                );
+                assert_eq!(
+                    data.get_gc().get_edge(&id("s"), &id("a")).unwrap().weight,
+                    Some(5.into())
+                );
+            }
+            _ => panic!("алгоритм не запущен"),
+        }
+    }
+
+    #[test]
+    fn overflowing_capacities_are_rejected() {
+        // Сумма пропускных способностей параллельных дуг не помещается в i32
+        let max = i32::MAX;
+        let g = graph(&format!(
+            "directed weighted int\nvertices\ns\nt\nedges\ns t {}\ns t {}\n",
+            max, max
+        ));
+        assert!(matches!(
+            start(&g, "s", "t", PathStrategy::Bfs),
+            Err(GraphError::AlgorithmError(
+                GraphAlgorithmError::WeightOverflow
+            ))
+        ));
+        // Величина потока не помещается в i32
+        let g = graph(&format!(
+            "directed weighted int\nvertices\ns\na\nb\nt\nedges\n\
+             s a {}\ns b {}\na t {}\nb t {}\n",
+            max, max, max, max
+        ));
+        let mut state = start(&g, "s", "t", PathStrategy::Bfs).unwrap();
+        state = algorithm_step(state, &g, "s", "t", PathStrategy::Bfs, false).unwrap();
+        assert!(matches!(
+            algorithm_step(state, &g, "s", "t", PathStrategy::Bfs, false),
+            Err(GraphError::AlgorithmError(
+                GraphAlgorithmError::WeightOverflow
+            ))
+        ));
+        // Наибольшая пропускная способность без переполнения
+        let g = graph(&format!(
+            "directed weighted int\nvertices\ns\nt\nedges\ns t {}\ns t 0\nt s {}\n",
+            max, max
+        ));
+        for strategy in [PathStrategy::Dfs, PathStrategy::Bfs, PathStrategy::Dinic] {
+            assert_eq!(run(&g, "s", "t", strategy).0, max.into());
+        }
+    }
+
+    #[test]
+    fn large_residual_capacities_do_not_overflow() {
+        // Остаточная пропускная способность дуги (a, s) после потока по (s, a)
+        // больше i32::MAX
+        let max = i32::MAX;
+        let g = graph(&format!(
+            "directed weighted int\nvertices\ns\na\nb\nt\nedges\n\
+             s a {}\na s {}\ns b 1\nb a 1\na t {}\n",
+            max, max, max
+        ));
+        for strategy in [
+            PathStrategy::Dfs,
+            PathStrategy::Bfs,
+            PathStrategy::Scaling,
+            PathStrategy::Dinic,
+        ] {
+            assert_eq!(run(&g, "s", "t", strategy).0, max.into());
+        }
+    }
+
+    #[test]
+    fn negative_parallel_capacity_is_rejected() {
+        let g = graph("directed weighted int\nvertices\ns\nt\nedges\ns t 5\ns t -2\n");
+        assert!(matches!(
+            start(&g, "s", "t", PathStrategy::Bfs),
+            Err(GraphError::AlgorithmError(
+                GraphAlgorithmError::NegativeCapacity { .. }
+            ))
+        ));
+    }
+
+    #[test]
+    fn unweighted_network_needs_unit_capacities() {
+        let g = graph("directed unweighted int\nvertices\ns\na\nt\nedges\ns a\ns t\na t\n");
+        assert!(matches!(
+            start(&g, "s", "t", PathStrategy::Dfs),
+            Err(GraphError::AlgorithmError(
+                GraphAlgorithmError::GraphNotWeighted
+            ))
+        ));
+        let mut state = algorithm_step(
+            AlgorithmState::NotStarted,
+            &g,
+            "s",
+            "t",
+            PathStrategy::Dfs,
+            true,
+        )
+        .unwrap();
+        while !matches!(state, AlgorithmState::Finished(_)) {
+            state = algorithm_step(state, &g, "s", "t", PathStrategy::Dfs, true).unwrap();
+        }
+        match state {
+            AlgorithmState::Finished(data) => assert_eq!(data.get_total_flow(), &2.into()),
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn new_vertex_ids_are_named_first() {
+        let g = graph("directed weighted int\nvertices\ns\ns1\n-1\nedges\n");
+        assert_eq!(new_vertex_id(g.as_ref().unwrap(), "s"), id("s2"));
+        assert_eq!(new_vertex_id(g.as_ref().unwrap(), "t"), id("t"));
+        // Для числовых идентификаторов — отрицательные числа
+        let g: Graph<i32, EdgeWeights> =
+            Graph::from_file("directed weighted int\nvertices\n-1\n1\nedges\n".as_bytes()).unwrap();
+        assert_eq!(new_vertex_id(&g, "s"), -2);
     }
-    zero
 }
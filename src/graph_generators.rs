@@ -0,0 +1,259 @@
+use rand::{distributions::Uniform, rngs::StdRng, Rng, SeedableRng};
+
+use crate::{
+    graph::{Edge, EdgeWeights, Graph, Vertex, VertexKey},
+    graph_errors::{GraphError, GraphInterfaceError},
+};
+
+// Максимальное количество вершин сгенерированного графа
+pub const MAX_GENERATED_VERTICES: usize = 10_000;
+// Максимальное количество рёбер полного графа
+pub const MAX_COMPLETE_EDGES: usize = 1_000_000;
+
+// Генератор весов рёбер из заданного отрезка (для невзвешенного графа веса не создаются)
+struct WeightGenerator {
+    rng: StdRng,                        // генератор случайных чисел
+    distribution: Option<Uniform<i32>>, // распределение весов
+}
+
+impl WeightGenerator {
+    fn new(weights: Option<(i32, i32)>, seed: u64) -> Result<Self, GraphError> {
+        let distribution = match weights {
+            Some((min, max)) if min > max => {
+                return Err(GraphInterfaceError::IncorrectArgument { i: 4 }.into())
+            }
+            Some((min, max)) => Some(Uniform::new_inclusive(min, max)),
+            None => None,
+        };
+        Ok(Self {
+            rng: StdRng::seed_from_u64(seed),
+            distribution,
+        })
+    }
+
+    // Случайный вес следующего ребра
+    fn next_weight(&mut self) -> Option<EdgeWeights> {
+        let distribution = self.distribution?;
+        Some(self.rng.sample(distribution).into())
+    }
+}
+
+// Идентификатор вершины с заданным номером
+fn vertex_id<I>(k: usize) -> I
+where
+    I: VertexKey,
+{
+    k.to_string().parse().ok().unwrap()
+}
+
+// Проверка количества вершин, заданного аргументом с номером i
+fn check_vertex_count(n: usize, i: usize) -> Result<usize, GraphError> {
+    if n > MAX_GENERATED_VERTICES {
+        return Err(GraphInterfaceError::IncorrectArgument { i }.into());
+    }
+    Ok(n)
+}
+
+// Граф с вершинами 1..=n без рёбер
+fn graph_with_vertices<I>(n: usize, is_directed: bool, is_weighted: bool) -> Graph<I, EdgeWeights>
+where
+    I: VertexKey,
+{
+    let mut g = Graph::new(is_directed, is_weighted, false);
+    for k in 1..=n {
+        g.add_vertex(Vertex {
+            id: vertex_id(k),
+            label: None,
+            color: None,
+        })
+        .unwrap();
+    }
+    g
+}
+
+// Случайный граф G(n, p): каждое ребро (дуга) присутствует с вероятностью p
+pub fn gen_random_gnp<I>(
+    n: usize,
+    p: f64,
+    is_directed: bool,
+    weights: Option<(i32, i32)>,
+    seed: u64,
+) -> Result<Graph<I, EdgeWeights>, GraphError>
+where
+    I: VertexKey,
+{
+    check_vertex_count(n, 1)?;
+    if !(0.0..=1.0).contains(&p) {
+        return Err(GraphInterfaceError::IncorrectArgument { i: 2 }.into());
+    }
+    let mut weight_gen = WeightGenerator::new(weights, seed)?;
+    let mut g = graph_with_vertices(n, is_directed, weights.is_some());
+    for i in 1..=n {
+        // В неориентированном графе каждая пара вершин рассматривается один раз
+        let first_j = if is_directed { 1 } else { i + 1 };
+        for j in first_j..=n {
+            if i == j || !weight_gen.rng.gen_bool(p) {
+                continue;
+            }
+            let weight = weight_gen.next_weight();
+            g.add_edge(vertex_id(i), Edge::new(vertex_id(j), weight))?;
+        }
+    }
+    Ok(g)
+}
+
+// Решётка w x h: каждая вершина соединена с соседями справа и снизу
+pub fn gen_grid<I>(
+    w: usize,
+    h: usize,
+    is_directed: bool,
+    weights: Option<(i32, i32)>,
+    seed: u64,
+) -> Result<Graph<I, EdgeWeights>, GraphError>
+where
+    I: VertexKey,
+{
+    check_vertex_count(w, 1)?;
+    let n = w
+        .checked_mul(h)
+        .ok_or(GraphInterfaceError::IncorrectArgument { i: 2 })?;
+    check_vertex_count(n, 2)?;
+    let mut weight_gen = WeightGenerator::new(weights, seed)?;
+    let mut g = graph_with_vertices(n, is_directed, weights.is_some());
+    // Номер вершины в строке r и столбце c
+    let k = |r: usize, c: usize| r * w + c + 1;
+    for r in 0..h {
+        for c in 0..w {
+            if c + 1 < w {
+                let weight = weight_gen.next_weight();
+                g.add_edge(
+                    vertex_id(k(r, c)),
+                    Edge::new(vertex_id(k(r, c + 1)), weight),
+                )?;
+            }
+            if r + 1 < h {
+                let weight = weight_gen.next_weight();
+                g.add_edge(
+                    vertex_id(k(r, c)),
+                    Edge::new(vertex_id(k(r + 1, c)), weight),
+                )?;
+            }
+        }
+    }
+    Ok(g)
+}
+
+// Полный граф на n вершинах
+pub fn gen_complete<I>(
+    n: usize,
+    is_directed: bool,
+    weights: Option<(i32, i32)>,
+    seed: u64,
+) -> Result<Graph<I, EdgeWeights>, GraphError>
+where
+    I: VertexKey,
+{
+    check_vertex_count(n, 1)?;
+    // Количество рёбер (дуг) полного графа
+    let edge_cnt = n * n.saturating_sub(1) / if is_directed { 1 } else { 2 };
+    if edge_cnt > MAX_COMPLETE_EDGES {
+        return Err(GraphInterfaceError::IncorrectArgument { i: 1 }.into());
+    }
+    gen_random_gnp(n, 1.0, is_directed, weights, seed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::VertexId;
+
+    type TestGraph = Graph<VertexId, EdgeWeights>;
+
+    // Количество рёбер графа (ребро неориентированного графа хранится у обоих концов)
+    fn edge_count(g: &TestGraph) -> usize {
+        let cnt: usize = g
+            .get_vertices()
+            .keys()
+            .map(|i| g.get_edge_list(i).unwrap().len())
+            .sum();
+        if g.get_is_directed() {
+            cnt
+        } else {
+            cnt / 2
+        }
+    }
+
+    // Номер аргумента из ошибки неправильного аргумента
+    fn incorrect_argument(result: Result<TestGraph, GraphError>) -> usize {
+        match result {
+            Err(GraphError::InterfaceError(GraphInterfaceError::IncorrectArgument { i })) => i,
+            _ => panic!("нет ошибки неправильного аргумента"),
+        }
+    }
+
+    #[test]
+    fn same_seed_gives_same_graph() {
+        let weights = Some((-5, 5));
+        let g1: TestGraph = gen_random_gnp(50, 0.2, true, weights, 7).unwrap();
+        let g2: TestGraph = gen_random_gnp(50, 0.2, true, weights, 7).unwrap();
+        let g3: TestGraph = gen_random_gnp(50, 0.2, true, weights, 8).unwrap();
+        assert_eq!(g1, g2);
+        assert_ne!(g1, g3);
+
+        let g1: TestGraph = gen_grid(6, 4, false, weights, 7).unwrap();
+        let g2: TestGraph = gen_grid(6, 4, false, weights, 7).unwrap();
+        assert_eq!(g1, g2);
+    }
+
+    #[test]
+    fn generated_edge_counts() {
+        let g: TestGraph = gen_grid(6, 4, false, None, 1).unwrap();
+        assert_eq!(g.get_vertices().len(), 24);
+        assert_eq!(edge_count(&g), 5 * 4 + 6 * 3);
+
+        let g: TestGraph = gen_complete(7, false, Some((1, 1)), 1).unwrap();
+        assert_eq!(edge_count(&g), 7 * 6 / 2);
+        assert!(g.get_is_weighted());
+        let g: TestGraph = gen_complete(7, true, None, 1).unwrap();
+        assert_eq!(edge_count(&g), 7 * 6);
+        assert!(!g.get_is_weighted());
+
+        let g: TestGraph = gen_random_gnp(20, 0.0, true, None, 1).unwrap();
+        assert_eq!(g.get_vertices().len(), 20);
+        assert_eq!(edge_count(&g), 0);
+    }
+
+    #[test]
+    fn incorrect_generator_arguments() {
+        assert_eq!(
+            incorrect_argument(gen_grid(usize::MAX / 2, 3, false, None, 1)),
+            1
+        );
+        assert_eq!(
+            incorrect_argument(gen_grid(3, usize::MAX / 2, false, None, 1)),
+            2
+        );
+        assert_eq!(
+            incorrect_argument(gen_grid(MAX_GENERATED_VERTICES + 1, 1, false, None, 1)),
+            1
+        );
+        assert_eq!(incorrect_argument(gen_grid(1000, 1000, false, None, 1)), 2);
+        assert_eq!(
+            incorrect_argument(gen_complete(usize::MAX, false, None, 1)),
+            1
+        );
+        assert_eq!(incorrect_argument(gen_complete(2000, false, None, 1)), 1);
+        assert_eq!(
+            incorrect_argument(gen_random_gnp(usize::MAX, 0.5, false, None, 1)),
+            1
+        );
+        assert_eq!(
+            incorrect_argument(gen_random_gnp(10, 1.5, false, None, 1)),
+            2
+        );
+        assert_eq!(
+            incorrect_argument(gen_random_gnp(10, 0.5, false, Some((2, 1)), 1)),
+            4
+        );
+    }
+}
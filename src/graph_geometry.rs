@@ -0,0 +1,852 @@
+// Геометрия и тексты изображения графа, общие для отрисовки в окне и экспорта в SVG
+
+use std::{
+    collections::BTreeSet,
+    f32::consts::{FRAC_1_SQRT_2, FRAC_PI_4},
+};
+
+use crate::{
+    graph::{EdgeWeight, Graph, VertexKey},
+    graph_errors::GraphOperationError,
+    graph_flows::AlgorithmData,
+    graph_paths::ShortestPathData,
+};
+
+// Цвет рёбер минимального разреза и доля этого цвета в заливке вершин со стороны истока
+pub const MIN_CUT_COLOR: [f32; 4] = [1.0, 0.5, 0.0, 1.0];
+pub const MIN_CUT_FILL_PART: f32 = 0.2;
+// Цвет рёбер найденного паросочетания
+pub const MATCHING_COLOR: [f32; 4] = [0.2, 0.7, 0.2, 1.0];
+// Множитель непрозрачности рёбер, отброшенных алгоритмом Краскала
+pub const REJECTED_EDGE_ALPHA: f32 = 0.25;
+// Цвет достигнутых, но ещё не обработанных вершин в алгоритме Дейкстры
+// и вершин слоистой сети в алгоритме Диница
+pub const FRONTIER_COLOR: [f32; 4] = [0.0, 0.6, 1.0, 1.0];
+// Цвета истока и стока в алгоритме нахождения максимального потока
+pub const SOURCE_COLOR: [f32; 4] = [0.1, 0.7, 0.1, 1.0];
+pub const SINK_COLOR: [f32; 4] = [0.2, 0.3, 1.0, 1.0];
+
+// Радиус кольца вокруг истока и стока и размер шрифта их меток (в радиусах вершины)
+pub const TERMINAL_RING_COEFF: f32 = 1.15;
+pub const TERMINAL_MARKER_COEFF: f32 = 0.8;
+// Максимальный коэффициент толщины рёбер
+pub const MAX_EDGE_WIDTH_COEFF: f32 = 5.0;
+
+// Геометрия ребра-петли
+pub struct LoopGeometry {
+    pub dir: (f32, f32),       // единичный вектор от центра вершины к центру петли
+    pub center: (f32, f32),    // центр окружности петли
+    pub radius: f32,           // радиус окружности петли
+    pub arrow_tip: (f32, f32), // точка пересечения окружностей петли и вершины (острие стрелки)
+    pub back_dir: (f32, f32),  // касательная к петле в острие стрелки, направленная от вершины
+}
+
+// Направление ребра-петли: противоположно среднему направлению на соседние вершины,
+// если соседей нет или они расположены равномерно, то вверх и влево
+pub fn loop_direction(vertex: (f32, f32), neighbors: &[(f32, f32)]) -> (f32, f32) {
+    // Минимальная длина среднего направления, при которой оно учитывается
+    const MIN_DIR_LEN: f32 = 1e-3;
+
+    let mut sum = (0.0, 0.0);
+    for (x, y) in neighbors {
+        let dir = (x - vertex.0, y - vertex.1);
+        let len = (dir.0 * dir.0 + dir.1 * dir.1).sqrt();
+        if len > 0.0 {
+            sum.0 += dir.0 / len;
+            sum.1 += dir.1 / len;
+        }
+    }
+    let len = (sum.0 * sum.0 + sum.1 * sum.1).sqrt();
+    if len < MIN_DIR_LEN {
+        (-FRAC_1_SQRT_2, -FRAC_1_SQRT_2)
+    } else {
+        (-sum.0 / len, -sum.1 / len)
+    }
+}
+
+// Геометрия ребра-петли вершины с заданным направлением петли
+pub fn loop_geometry(vertex: (f32, f32), dir: (f32, f32), vertex_radius: f32) -> LoopGeometry {
+    // Радиус петли и расстояние от центра вершины до центра петли (в радиусах вершины)
+    const LOOP_RADIUS: f32 = 2.0 / 3.0;
+    const LOOP_DISTANCE: f32 = 4.0 / 3.0;
+
+    let (r, rho, d) = (
+        vertex_radius,
+        LOOP_RADIUS * vertex_radius,
+        LOOP_DISTANCE * vertex_radius,
+    );
+    let center = (vertex.0 + dir.0 * d, vertex.1 + dir.1 * d);
+    // Точка пересечения окружностей: проекция на направление петли и смещение по перпендикуляру
+    let a = (d * d + r * r - rho * rho) / (2.0 * d);
+    let h = (r * r - a * a).max(0.0).sqrt();
+    let normal = (-dir.1, dir.0);
+    let arrow_tip = (
+        vertex.0 + dir.0 * a - normal.0 * h,
+        vertex.1 + dir.1 * a - normal.1 * h,
+    );
+    // Касательная к петле перпендикулярна радиусу петли в острие
+    let radial = (arrow_tip.0 - center.0, arrow_tip.1 - center.1);
+    let tangent = (-radial.1, radial.0);
+    let back_dir =
+        if tangent.0 * (arrow_tip.0 - vertex.0) + tangent.1 * (arrow_tip.1 - vertex.1) >= 0.0 {
+            tangent
+        } else {
+            (-tangent.0, -tangent.1)
+        };
+    LoopGeometry {
+        dir,
+        center,
+        radius: rho,
+        arrow_tip,
+        back_dir,
+    }
+}
+
+// Направление петли с номером index среди count петель вершины: петли расходятся веером
+// вокруг направления dir
+pub fn loop_fan_direction(dir: (f32, f32), index: usize, count: usize) -> (f32, f32) {
+    // Угол между соседними петлями
+    const LOOP_FAN_ANGLE: f32 = FRAC_PI_4;
+
+    let angle = LOOP_FAN_ANGLE * (index as f32 - (count - 1) as f32 / 2.0);
+    let (sin, cos) = angle.sin_cos();
+    (dir.0 * cos - dir.1 * sin, dir.0 * sin + dir.1 * cos)
+}
+
+// Изгиб ребра (i, to) с номером index среди count параллельных рёбер (в сдвигах контрольной
+// точки кривой Безье, 0 — отрезок): если есть противоположные дуги, то параллельные дуги
+// изгибаются в одну сторону, а противоположные в другую, иначе параллельные рёбра
+// расходятся веером (изгиб задаётся от меньшей вершины к большей, чтобы ребро
+// неориентированного графа в обоих направлениях имело одну форму)
+pub fn edge_bend<I>(i: &I, to: &I, index: usize, count: usize, is_antiparallel: bool) -> f32
+where
+    I: VertexKey,
+{
+    if is_antiparallel {
+        return (index + 1) as f32;
+    }
+    let bend = index as f32 - (count - 1) as f32 / 2.0;
+    if i > to {
+        -bend
+    } else {
+        bend
+    }
+}
+
+// Контрольная точка кривой Безье изогнутого ребра (дуги, у которой есть обратная дуга,
+// или одного из параллельных рёбер): центр ребра со сдвигом по перпендикуляру,
+// пропорциональным длине ребра и изгибу bend
+pub fn curve_control_point(
+    from: (f32, f32),
+    to: (f32, f32),
+    vertex_radius: f32,
+    bend: f32,
+) -> (f32, f32) {
+    // Доля длины ребра для сдвига и его ограничения в радиусах вершины
+    const CURVE_OFFSET: f32 = 0.08;
+    const MIN_OFFSET: f32 = 0.25;
+    const MAX_OFFSET: f32 = 2.0;
+
+    // Вектор от начальной к конечной вершине
+    let dir = (to.0 - from.0, to.1 - from.1);
+    let len = (dir.0 * dir.0 + dir.1 * dir.1).sqrt();
+    if len == 0.0 {
+        return from;
+    }
+    // Единичный перпендикуляр к вектору
+    let normal = (-dir.1 / len, dir.0 / len);
+    let offset =
+        (CURVE_OFFSET * len).clamp(MIN_OFFSET * vertex_radius, MAX_OFFSET * vertex_radius) * bend;
+    (
+        from.0 + dir.0 / 2.0 + normal.0 * offset,
+        from.1 + dir.1 / 2.0 + normal.1 * offset,
+    )
+}
+
+// Точка пересечения окружности конечной вершины и кривой Безье дуги (острие стрелки)
+// и вектор от конечной вершины к контрольной точке кривой
+pub fn curve_arrow_tip(
+    from: (f32, f32),
+    control: (f32, f32),
+    to: (f32, f32),
+    to_radius: f32,
+) -> ((f32, f32), (f32, f32)) {
+    let ((x_i, y_i), (edge_center_x, edge_center_y), (x_to, y_to)) = (from, control, to);
+
+    // Функция отклонения точки кривой Безье от пересечения с окружностью конечной вершины
+    let f_bezier = |t: f32| {
+        let x =
+            (1.0 - t).powi(2) * x_to + 2.0 * t * (1.0 - t) * edge_center_x + t.powi(2) * x_i - x_to;
+        let y =
+            (1.0 - t).powi(2) * y_to + 2.0 * t * (1.0 - t) * edge_center_y + t.powi(2) * y_i - y_to;
+        x.powi(2) + y.powi(2) - to_radius.powi(2)
+    };
+    // Производная этой функции
+    let df_bezier = |t: f32| {
+        let x = 2.0
+            * (2.0 * t * (x_i - edge_center_x) + 2.0 * (1.0 - t) * (edge_center_x - x_to))
+            * (x_i * t.powi(2) + 2.0 * edge_center_x * t * (1.0 - t) + x_to * (1.0 - t).powi(2)
+                - x_to);
+        let y = 2.0
+            * (2.0 * t * (y_i - edge_center_y) + 2.0 * (1.0 - t) * (edge_center_y - y_to))
+            * (y_i * t.powi(2) + 2.0 * edge_center_y * t * (1.0 - t) + y_to * (1.0 - t).powi(2)
+                - y_to);
+        x + y
+    };
+
+    // Вычисление параметра кривой Безье алгоритмом Ньютона: начальное приближение — доля
+    // радиуса вершины в длине хорды (у длинных рёбер острие близко к концу кривой)
+    let chord = ((x_i - x_to).powi(2) + (y_i - y_to).powi(2)).sqrt();
+    let mut t = if chord > 0.0 {
+        (to_radius / chord).min(0.5)
+    } else {
+        0.5
+    };
+    for _ in 0..5 {
+        t -= f_bezier(t) / df_bezier(t);
+    }
+
+    // Точка пересечения окружности конечной вершины и кривой Безье
+    let vertex_edge = (
+        (1.0 - t).powi(2) * x_to + 2.0 * t * (1.0 - t) * edge_center_x + t.powi(2) * x_i,
+        (1.0 - t).powi(2) * y_to + 2.0 * t * (1.0 - t) * edge_center_y + t.powi(2) * y_i,
+    );
+    // Вектор от конечной вершины к центральной точке
+    (vertex_edge, (edge_center_x - x_to, edge_center_y - y_to))
+}
+
+// Точка пересечения контура конечной вершины и прямой дуги (острие стрелки)
+// и вектор от конечной к начальной вершине
+pub fn line_arrow_tip(
+    from: (f32, f32),
+    to: (f32, f32),
+    to_radius: f32,
+) -> ((f32, f32), (f32, f32)) {
+    let rev_dir = (from.0 - to.0, from.1 - to.1);
+    let len = (rev_dir.0 * rev_dir.0 + rev_dir.1 * rev_dir.1).sqrt();
+    let vertex_edge = (
+        to.0 + rev_dir.0 * to_radius / len,
+        to.1 + rev_dir.1 * to_radius / len,
+    );
+    (vertex_edge, rev_dir)
+}
+
+// Вершины треугольника стрелки дуги с острием в точке tip и основанием в направлении
+// back_dir на расстоянии size от острия (угол при острие 60 градусов);
+// None, если направление не определено
+pub fn arrowhead_points(
+    tip: (f32, f32),
+    back_dir: (f32, f32),
+    size: f32,
+) -> Option<[(f32, f32); 3]> {
+    let len = (back_dir.0 * back_dir.0 + back_dir.1 * back_dir.1).sqrt();
+    if len == 0.0 {
+        return None;
+    }
+    // Единичный вектор вдоль дуги и перпендикуляр к нему
+    let dir = (back_dir.0 / len, back_dir.1 / len);
+    let normal = (-dir.1, dir.0);
+    let base = (tip.0 + dir.0 * size, tip.1 + dir.1 * size);
+    let half_width = size / 3.0_f32.sqrt();
+    Some([
+        tip,
+        (
+            base.0 + normal.0 * half_width,
+            base.1 + normal.1 * half_width,
+        ),
+        (
+            base.0 - normal.0 * half_width,
+            base.1 - normal.1 * half_width,
+        ),
+    ])
+}
+
+// Перевод координат точки окна из логических пикселей в физические
+pub fn logical_to_physical(coords: (f32, f32), scale_factor: f32) -> (f32, f32) {
+    (coords.0 * scale_factor, coords.1 * scale_factor)
+}
+
+// Расстояние от точки до отрезка
+pub fn point_segment_distance(p: (f32, f32), a: (f32, f32), b: (f32, f32)) -> f32 {
+    let ab = (b.0 - a.0, b.1 - a.1);
+    let len_sqr = ab.0 * ab.0 + ab.1 * ab.1;
+    // Параметр проекции точки на прямую, ограниченный концами отрезка
+    let t = if len_sqr == 0.0 {
+        0.0
+    } else {
+        (((p.0 - a.0) * ab.0 + (p.1 - a.1) * ab.1) / len_sqr).clamp(0.0, 1.0)
+    };
+    let closest = (a.0 + ab.0 * t, a.1 + ab.1 * t);
+    ((p.0 - closest.0).powi(2) + (p.1 - closest.1).powi(2)).sqrt()
+}
+
+// Расстояние от точки до квадратичной кривой Безье (по ломаной из точек кривой)
+pub fn point_curve_distance(
+    p: (f32, f32),
+    from: (f32, f32),
+    control: (f32, f32),
+    to: (f32, f32),
+) -> f32 {
+    // Количество отрезков ломаной
+    const CURVE_SEGMENT_CNT: usize = 16;
+
+    let point = |t: f32| {
+        (
+            (1.0 - t).powi(2) * from.0 + 2.0 * t * (1.0 - t) * control.0 + t.powi(2) * to.0,
+            (1.0 - t).powi(2) * from.1 + 2.0 * t * (1.0 - t) * control.1 + t.powi(2) * to.1,
+        )
+    };
+    (0..CURVE_SEGMENT_CNT)
+        .map(|k| {
+            let t0 = k as f32 / CURVE_SEGMENT_CNT as f32;
+            let t1 = (k + 1) as f32 / CURVE_SEGMENT_CNT as f32;
+            point_segment_distance(p, point(t0), point(t1))
+        })
+        .fold(f32::INFINITY, f32::min)
+}
+
+// Расстояние от точки до окружности
+pub fn point_circle_distance(p: (f32, f32), center: (f32, f32), radius: f32) -> f32 {
+    (((p.0 - center.0).powi(2) + (p.1 - center.1).powi(2)).sqrt() - radius).abs()
+}
+
+// Точка подписи ребра: за ребром-петлёй в направлении петли или середина ребра
+// (кривой Безье с контрольной точкой control) с отступом по перпендикуляру
+pub fn edge_label_position(
+    from: (f32, f32),
+    to: (f32, f32),
+    control: Option<(f32, f32)>,
+    loop_geometry: Option<&LoopGeometry>,
+    label_offset: f32,
+) -> (f32, f32) {
+    if let Some(lg) = loop_geometry {
+        let dist = lg.radius + label_offset;
+        return (lg.center.0 + lg.dir.0 * dist, lg.center.1 + lg.dir.1 * dist);
+    }
+    // Вектор от начальной к конечной вершине
+    let dir = (to.0 - from.0, to.1 - from.1);
+    let len = (dir.0 * dir.0 + dir.1 * dir.1).sqrt();
+    // Единичный перпендикуляр к вектору
+    let normal = (-dir.1 / len, dir.0 / len);
+    let (x_center, y_center) = match control {
+        Some((x_c, y_c)) => (
+            (from.0 + 2.0 * x_c + to.0) / 4.0,
+            (from.1 + 2.0 * y_c + to.1) / 4.0,
+        ),
+        None => ((from.0 + to.0) / 2.0, (from.1 + to.1) / 2.0),
+    };
+    (
+        x_center + normal.0 * label_offset,
+        y_center + normal.1 * label_offset,
+    )
+}
+
+// Количество рёбер, инцидентных вершине, кроме петель (параллельные рёбра считаются
+// по отдельности, у ориентированного графа учитываются входящие и исходящие дуги)
+pub fn incident_edge_count<I, W>(g: &Graph<I, W>, i: &I) -> usize
+where
+    I: VertexKey,
+    W: EdgeWeight,
+{
+    let edge_list = match g.get_edge_list(i) {
+        Ok(edge_list) => edge_list,
+        Err(_) => return 0,
+    };
+    let outgoing = edge_list.iter().filter(|e| &e.to != i).count();
+    if !g.get_is_directed() {
+        return outgoing;
+    }
+    let incoming: usize = g
+        .get_vertices()
+        .keys()
+        .filter(|j| j != &i)
+        .map(|j| g.get_edges(j, i).map_or(0, |edges| edges.len()))
+        .sum();
+    outgoing + incoming
+}
+
+// Отступ подписи петли от петли: у вершины со многими инцидентными рёбрами подпись
+// отодвигается дальше, чтобы не попадать на стрелки и подписи этих рёбер
+pub fn loop_label_offset(label_offset: f32, vertex_radius: f32, incident_edges: usize) -> f32 {
+    // Количество рёбер, при котором отступ не увеличивается, увеличение отступа на каждое
+    // следующее ребро (в радиусах вершины) и наибольшее количество учитываемых рёбер
+    const FREE_EDGES: usize = 2;
+    const EDGE_OFFSET: f32 = 0.2;
+    const MAX_EDGES: usize = 8;
+
+    let extra = incident_edges.saturating_sub(FREE_EDGES).min(MAX_EDGES);
+    label_offset + EDGE_OFFSET * extra as f32 * vertex_radius
+}
+
+// Размер шрифта подписи ребра у вершины радиуса screen_radius на экране: поток
+// и пропускная способность выводятся маленьким шрифтом, вес — обычным; слишком мелкая
+// подпись не читается и только закрывает соседние рёбра, поэтому не выводится
+pub fn edge_label_font_size(
+    screen_radius: f32,
+    with_flow: bool,
+    min_font_size: f32,
+) -> Option<f32> {
+    let font_size = if with_flow {
+        screen_radius / 2.0
+    } else {
+        screen_radius
+    };
+    Some(font_size).filter(|&font_size| font_size >= min_font_size)
+}
+
+// Сдвиг фона подписи петли заданного размера наружу, чтобы подпись не закрывала петлю
+pub fn loop_label_shift(lg: &LoopGeometry, w: f32, h: f32) -> (f32, f32) {
+    let extent = lg.dir.0.abs() * w / 2.0 + lg.dir.1.abs() * h / 2.0;
+    (lg.dir.0 * extent, lg.dir.1 * extent)
+}
+
+// Дуги ориентированного графа (i, to), i ≠ to, у которых есть противоположная дуга (to, i)
+// и которые поэтому рисуются кривыми; у неориентированного графа таких дуг нет
+pub fn antiparallel_edges<I, W>(g: &Graph<I, W>) -> BTreeSet<(I, I)>
+where
+    I: VertexKey,
+    W: EdgeWeight,
+{
+    if !g.get_is_directed() {
+        return BTreeSet::new();
+    }
+    g.get_vertices()
+        .keys()
+        .flat_map(|i| {
+            g.get_edge_list(i)
+                .unwrap()
+                .iter()
+                .filter(move |e| &e.to != i && g.get_edge(&e.to, i).is_ok())
+                .map(move |e| (i.clone(), e.to.clone()))
+        })
+        .collect()
+}
+
+// Минимальный и максимальный веса рёбер графа (None, если у рёбер нет весов)
+pub fn weight_range<I, W>(g: &Graph<I, W>) -> Option<(f32, f32)>
+where
+    I: VertexKey,
+    W: EdgeWeight,
+{
+    g.get_vertices()
+        .keys()
+        .flat_map(|i| g.get_edge_list(i).unwrap())
+        .filter_map(|e| e.weight.as_ref().map(|w| w.as_f32()))
+        .fold(None, |acc, w| match acc {
+            None => Some((w, w)),
+            Some((min_w, max_w)) => Some((f32::min(min_w, w), f32::max(max_w, w))),
+        })
+}
+
+// Доля увеличения толщины ребра от 0 до 1 (если толщина зависит от веса): при нахождении
+// потока зависит от заполненности ребра, иначе от веса относительно минимального
+// и максимального весов
+pub fn edge_width_part<W>(
+    weight_range: Option<(f32, f32)>,
+    weight: Option<&W>,
+    total_flow: Option<&W>,
+) -> f32
+where
+    W: EdgeWeight,
+{
+    match (weight_range, weight, total_flow) {
+        (Some(_), Some(w), Some(f)) if w.as_f32() > 0.0 => {
+            (f.as_f32() / w.as_f32()).clamp(0.0, 1.0)
+        }
+        (Some((min_w, max_w)), Some(w), _) if max_w > min_w => {
+            (w.as_f32() - min_w) / (max_w - min_w)
+        }
+        _ => 0.0,
+    }
+}
+
+// Число в подписи ребра: при нахождении потока — пропускная способность из графа
+// пропускных способностей (у дуг невзвешенного графа она может быть равна 1),
+// иначе вес ребра (у петель, которых нет в графе пропускных способностей, тоже вес)
+pub fn edge_label_weight<'a, I, W>(
+    flow_data: Option<&'a AlgorithmData<I, W>>,
+    i: &I,
+    to: &I,
+    weight: Option<&'a W>,
+) -> Option<&'a W>
+where
+    I: VertexKey,
+    W: EdgeWeight,
+{
+    flow_data
+        .and_then(|data| data.get_gc().get_edge(i, to).ok())
+        .and_then(|e| e.weight.as_ref())
+        .or(weight)
+}
+
+// Суммарный поток через ребро и поток через него в последнем дополняющем пути
+// (None, если поток не находится или ребра нет в графе потоков, например когда граф
+// изменился раньше, чем состояние алгоритма)
+pub fn edge_flows<'a, I, W>(
+    flow_data: Option<&'a AlgorithmData<I, W>>,
+    i: &I,
+    to: &I,
+) -> (Option<&'a W>, Option<&'a W>)
+where
+    I: VertexKey,
+    W: EdgeWeight,
+{
+    let total_flow = flow_data
+        .and_then(|data| data.get_gf().get_edge(i, to).ok())
+        .and_then(|e| e.weight.as_ref());
+    let curr_flow = flow_data
+        .and_then(|data| data.get_curr_path().as_ref())
+        .and_then(|path| path.get(&(i.clone(), to.clone())));
+    (total_flow, curr_flow)
+}
+
+// Текст подписи ребра: вес, а при известном потоке через ребро — поток
+// (и поток в последнем дополняющем пути, если он есть) и пропускная способность
+pub fn edge_label_text<W>(weight: &W, total_flow: Option<&W>, curr_flow: Option<&W>) -> String
+where
+    W: EdgeWeight,
+{
+    match (total_flow, curr_flow) {
+        (None, _) => format!("{}", weight),
+        (Some(f), Some(curr_f)) => format!("{} ({:+}) / {}", f, curr_f, weight),
+        (Some(f), None) => format!("{} / {}", f, weight),
+    }
+}
+
+// Смешение двух цветов с заданной долей второго цвета
+pub fn mix_colors(a: [f32; 4], b: [f32; 4], part: f32) -> [f32; 4] {
+    let mut color = a;
+    for (x, y) in color.iter_mut().zip(b) {
+        *x += (y - *x) * part;
+    }
+    color
+}
+
+// Текст вершины: идентификатор, метка, уровень в слоистой сети алгоритма Диница
+// и текущее расстояние в алгоритме Дейкстры
+pub fn vertex_text<I, W>(
+    g: &Graph<I, W>,
+    i: &I,
+    flow_data: Option<&AlgorithmData<I, W>>,
+    path_data: Option<&ShortestPathData<I, W>>,
+) -> Result<String, GraphOperationError>
+where
+    I: VertexKey,
+    W: EdgeWeight,
+{
+    let mut text = match &g
+        .get_vertices()
+        .get(i)
+        .ok_or(GraphOperationError::VertexNotFound)?
+        .label
+    {
+        Some(s) => format!("{} ({})", i, s),
+        None => format!("{}", i),
+    };
+    if let Some(level) = flow_data
+        .and_then(|data| data.get_levels().as_ref())
+        .and_then(|levels| levels.get(i))
+    {
+        text = format!("{} [{}]", text, level);
+    }
+    if let Some(d) = path_data.and_then(|data| data.get_dist(i)) {
+        text = format!("{} [{}]", text, d);
+    }
+    Ok(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Сравнение точек с погрешностью вычислений
+    fn assert_close(a: (f32, f32), b: (f32, f32)) {
+        assert!(
+            (a.0 - b.0).abs() < 1e-3 && (a.1 - b.1).abs() < 1e-3,
+            "{:?} != {:?}",
+            a,
+            b
+        );
+    }
+
+    // Расстояние между точками
+    fn dist(a: (f32, f32), b: (f32, f32)) -> f32 {
+        ((a.0 - b.0).powi(2) + (a.1 - b.1).powi(2)).sqrt()
+    }
+
+    #[test]
+    fn curve_offset_is_proportional_to_length_and_clamped() {
+        // Короткое ребро: сдвиг не меньше четверти радиуса вершины
+        assert_close(
+            curve_control_point((0.0, 0.0), (10.0, 0.0), 10.0, 1.0),
+            (5.0, 2.5),
+        );
+        // Среднее ребро: сдвиг равен 0.08 длины
+        assert_close(
+            curve_control_point((0.0, 0.0), (100.0, 0.0), 10.0, 1.0),
+            (50.0, 8.0),
+        );
+        assert_close(
+            curve_control_point((0.0, 0.0), (0.0, 200.0), 10.0, 1.0),
+            (-16.0, 100.0),
+        );
+        // Длинное ребро: сдвиг не больше двух радиусов вершины
+        assert_close(
+            curve_control_point((0.0, 0.0), (1000.0, 0.0), 10.0, 1.0),
+            (500.0, 20.0),
+        );
+        // Изгиб задаёт кратность и сторону сдвига
+        assert_close(
+            curve_control_point((0.0, 0.0), (100.0, 0.0), 10.0, -2.0),
+            (50.0, -16.0),
+        );
+        assert_close(
+            curve_control_point((0.0, 0.0), (100.0, 0.0), 10.0, 0.0),
+            (50.0, 0.0),
+        );
+    }
+
+    #[test]
+    fn curve_control_point_of_zero_length_edge() {
+        assert_close(
+            curve_control_point((3.0, 4.0), (3.0, 4.0), 10.0, 1.0),
+            (3.0, 4.0),
+        );
+    }
+
+    #[test]
+    fn antiparallel_curves_are_on_opposite_sides() {
+        let (a, b) = ((0.0, 0.0), (100.0, 0.0));
+        let forward = curve_control_point(a, b, 10.0, edge_bend(&1, &2, 0, 1, true));
+        let backward = curve_control_point(b, a, 10.0, edge_bend(&2, &1, 0, 1, true));
+        assert!(forward.1 > 0.0 && backward.1 < 0.0);
+    }
+
+    #[test]
+    fn arrow_tips_lie_on_vertex_circle() {
+        for len in [30.0, 100.0, 300.0, 1000.0] {
+            let (from, to) = ((0.0, 0.0), (len, 0.0));
+            for bend in [1.0, -3.0] {
+                let control = curve_control_point(from, to, 10.0, bend);
+                let (tip, _) = curve_arrow_tip(from, control, to, 10.0);
+                assert!((dist(tip, to) - 10.0).abs() < 1e-2, "{} {}", len, bend);
+            }
+            let (tip, back) = line_arrow_tip(from, to, 10.0);
+            assert_close(tip, (len - 10.0, 0.0));
+            assert_close(back, (-len, 0.0));
+        }
+    }
+
+    #[test]
+    fn logical_to_physical_scales_coords() {
+        assert_eq!(logical_to_physical((10.0, -20.0), 1.0), (10.0, -20.0));
+        assert_eq!(logical_to_physical((10.0, -20.0), 2.0), (20.0, -40.0));
+        assert_eq!(logical_to_physical((10.0, 20.0), 1.5), (15.0, 30.0));
+        assert_eq!(logical_to_physical((0.0, 0.0), 3.0), (0.0, 0.0));
+        // Обратный перевод (как у winit) возвращает исходные координаты
+        let (x, y) = logical_to_physical((123.25, 456.5), 1.25);
+        assert_close((x / 1.25, y / 1.25), (123.25, 456.5));
+    }
+
+    #[test]
+    fn point_segment_distance_to_interior_and_ends() {
+        let (a, b) = ((0.0, 0.0), (10.0, 0.0));
+        // Проекция точки попадает внутрь отрезка
+        assert!((point_segment_distance((4.0, 3.0), a, b) - 3.0).abs() < 1e-5);
+        assert!((point_segment_distance((4.0, -3.0), a, b) - 3.0).abs() < 1e-5);
+        assert_eq!(point_segment_distance((7.0, 0.0), a, b), 0.0);
+        // За концами отрезка расстояние считается до ближайшего конца
+        assert!((point_segment_distance((-3.0, 4.0), a, b) - 5.0).abs() < 1e-5);
+        assert!((point_segment_distance((13.0, -4.0), a, b) - 5.0).abs() < 1e-5);
+        // Вырожденный отрезок
+        assert!((point_segment_distance((3.0, 4.0), a, a) - 5.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn point_curve_distance_follows_curve() {
+        let (from, to) = ((0.0, 0.0), (100.0, 0.0));
+        // Кривая с контрольной точкой на отрезке совпадает с отрезком
+        for p in [(50.0, 7.0), (-5.0, 0.0), (30.0, -2.0)] {
+            assert!(
+                (point_curve_distance(p, from, (50.0, 0.0), to)
+                    - point_segment_distance(p, from, to))
+                .abs()
+                    < 1e-3
+            );
+        }
+        // Середина изогнутой кривой находится посередине между хордой и контрольной точкой
+        // (кривая приближается ломаной, поэтому рядом с ней расстояние немного неточное)
+        let control = (50.0, 20.0);
+        assert!(point_curve_distance((50.0, 10.0), from, control, to) < 1e-3);
+        assert!((point_curve_distance((50.0, 0.0), from, control, to) - 10.0).abs() < 0.1);
+        assert!((point_curve_distance((50.0, 15.0), from, control, to) - 5.0).abs() < 0.1);
+        // Точка по другую сторону хорды далеко от кривой
+        assert!(point_curve_distance((50.0, -10.0), from, control, to) > 19.0);
+        // Концы кривой лежат на ней
+        assert!(point_curve_distance(from, from, control, to) < 1e-5);
+        assert!(point_curve_distance(to, from, control, to) < 1e-5);
+    }
+
+    #[test]
+    fn point_circle_distance_inside_and_outside() {
+        let center = (1.0, 2.0);
+        assert_eq!(point_circle_distance((4.0, 6.0), center, 5.0), 0.0);
+        assert!((point_circle_distance((1.0, 10.0), center, 5.0) - 3.0).abs() < 1e-5);
+        // Внутри окружности расстояние до неё, а не до центра
+        assert!((point_circle_distance(center, center, 5.0) - 5.0).abs() < 1e-5);
+        assert!((point_circle_distance((2.0, 2.0), center, 5.0) - 4.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn loop_is_placed_opposite_to_neighbors() {
+        let v = (0.0, 0.0);
+        // Соседей нет или они расположены симметрично: вверх и влево
+        let up_left = (-FRAC_1_SQRT_2, -FRAC_1_SQRT_2);
+        assert_close(loop_direction(v, &[]), up_left);
+        assert_close(loop_direction(v, &[(1.0, 0.0), (-2.0, 0.0)]), up_left);
+        // Совпадающий с вершиной сосед не учитывается
+        assert_close(loop_direction(v, &[(0.0, 0.0)]), up_left);
+        // Один сосед справа: петля слева
+        assert_close(loop_direction(v, &[(5.0, 0.0)]), (-1.0, 0.0));
+        // Соседи справа и снизу: петля вверху слева, расстояние до соседей не важно
+        assert_close(loop_direction(v, &[(1.0, 0.0), (0.0, 10.0)]), up_left);
+        // Соседи сверху, слева и справа: петля внизу
+        assert_close(
+            loop_direction((1.0, 1.0), &[(1.0, -3.0), (-1.0, 1.0), (4.0, 1.0)]),
+            (0.0, 1.0),
+        );
+    }
+
+    #[test]
+    fn loop_geometry_touches_vertex() {
+        let v = (10.0, 20.0);
+        let r = 6.0;
+        for dir in [(1.0, 0.0), (0.0, -1.0), (-FRAC_1_SQRT_2, FRAC_1_SQRT_2)] {
+            let lg = loop_geometry(v, dir, r);
+            // Центр петли лежит в направлении петли, радиус пропорционален радиусу вершины
+            assert_close(lg.center, (v.0 + dir.0 * 8.0, v.1 + dir.1 * 8.0));
+            assert!((lg.radius - 4.0).abs() < 1e-4);
+            // Острие стрелки лежит на обеих окружностях
+            assert!((dist(lg.arrow_tip, v) - r).abs() < 1e-3);
+            assert!((dist(lg.arrow_tip, lg.center) - lg.radius).abs() < 1e-3);
+            // Касательная в острие перпендикулярна радиусу петли и направлена от вершины
+            let radial = (lg.arrow_tip.0 - lg.center.0, lg.arrow_tip.1 - lg.center.1);
+            assert!((radial.0 * lg.back_dir.0 + radial.1 * lg.back_dir.1).abs() < 1e-3);
+            let outward = (lg.arrow_tip.0 - v.0, lg.arrow_tip.1 - v.1);
+            assert!(outward.0 * lg.back_dir.0 + outward.1 * lg.back_dir.1 >= 0.0);
+        }
+    }
+
+    #[test]
+    fn loop_fan_is_symmetric() {
+        let dir = (1.0, 0.0);
+        assert_close(loop_fan_direction(dir, 0, 1), dir);
+        let (a, b, c) = (
+            loop_fan_direction(dir, 0, 3),
+            loop_fan_direction(dir, 1, 3),
+            loop_fan_direction(dir, 2, 3),
+        );
+        assert_close(b, dir);
+        assert_close(a, (FRAC_1_SQRT_2, -FRAC_1_SQRT_2));
+        assert_close(c, (FRAC_1_SQRT_2, FRAC_1_SQRT_2));
+    }
+
+    #[test]
+    fn loop_label_is_outside_loop() {
+        let lg = loop_geometry((0.0, 0.0), (0.0, -1.0), 6.0);
+        let pos = edge_label_position((0.0, 0.0), (0.0, 0.0), None, Some(&lg), 2.0);
+        assert_close(pos, (0.0, -8.0 - 4.0 - 2.0));
+        // Фон подписи сдвигается наружу на половину своего размера вдоль направления петли
+        assert_close(loop_label_shift(&lg, 10.0, 4.0), (0.0, -2.0));
+    }
+
+    #[test]
+    fn antiparallel_labels_are_on_opposite_sides() {
+        let (a, b) = ((0.0, 0.0), (100.0, 0.0));
+        let forward = curve_control_point(a, b, 10.0, edge_bend(&1, &2, 0, 1, true));
+        let backward = curve_control_point(b, a, 10.0, edge_bend(&2, &1, 0, 1, true));
+        let forward = edge_label_position(a, b, Some(forward), None, 2.0);
+        let backward = edge_label_position(b, a, Some(backward), None, 2.0);
+        assert!(forward.1 > 0.0 && backward.1 < 0.0);
+        // Подписи симметричны относительно прямой между вершинами
+        assert_close(forward, (backward.0, -backward.1));
+    }
+
+    #[test]
+    fn incident_edges_are_counted_without_loops() {
+        use crate::graph::{EdgeWeights, VertexId};
+
+        let text = "vertices\na\nb\nc\nedges\na b\nb a\na a\nc a\na c\na c\n";
+        let count = |header: &str, i: &str| {
+            let g: Graph<VertexId, EdgeWeights> =
+                Graph::from_file(format!("{}\n{}", header, text).as_bytes()).unwrap();
+            incident_edge_count(&g, &i.parse().unwrap())
+        };
+        // Входящие и исходящие дуги, параллельные дуги по отдельности
+        assert_eq!(count("directed unweighted int", "a"), 5);
+        assert_eq!(count("directed unweighted int", "b"), 2);
+        assert_eq!(count("directed unweighted int", "c"), 3);
+        // Ребро неориентированного графа считается один раз у каждого конца
+        assert_eq!(count("undirected unweighted int", "a"), 5);
+        assert_eq!(count("undirected unweighted int", "c"), 3);
+        assert_eq!(count("undirected unweighted int", "d"), 0);
+    }
+
+    #[test]
+    fn loop_label_moves_out_with_many_edges() {
+        assert_eq!(loop_label_offset(1.0, 10.0, 0), 1.0);
+        assert_eq!(loop_label_offset(1.0, 10.0, 2), 1.0);
+        assert_eq!(loop_label_offset(1.0, 10.0, 3), 3.0);
+        assert_eq!(loop_label_offset(1.0, 10.0, 6), 9.0);
+        // Отступ ограничен
+        assert_eq!(loop_label_offset(1.0, 10.0, 10), 17.0);
+        assert_eq!(loop_label_offset(1.0, 10.0, 100), 17.0);
+    }
+
+    #[test]
+    fn small_edge_labels_are_hidden() {
+        assert_eq!(edge_label_font_size(20.0, false, 6.0), Some(20.0));
+        assert_eq!(edge_label_font_size(20.0, true, 6.0), Some(10.0));
+        assert_eq!(edge_label_font_size(6.0, false, 6.0), Some(6.0));
+        assert_eq!(edge_label_font_size(10.0, true, 6.0), None);
+        assert_eq!(edge_label_font_size(5.0, false, 6.0), None);
+    }
+
+    #[test]
+    fn edge_labels_with_mismatched_flow_data() {
+        use crate::{
+            graph::{EdgeWeights, VertexId},
+            graph_flows::{algorithm_step, AlgorithmState, PathStrategy},
+        };
+
+        let network = "directed weighted int\nvertices\ns\na\nt\nedges\ns a 3\na t 2\n";
+        let g: Option<Graph<VertexId, EdgeWeights>> =
+            Some(Graph::from_file(network.as_bytes()).unwrap());
+        let mut state = AlgorithmState::NotStarted;
+        for _ in 0..2 {
+            state = algorithm_step(state, &g, "s", "t", PathStrategy::Dfs, false).unwrap();
+        }
+        let data = match &state {
+            AlgorithmState::Step(data) => data,
+            _ => panic!("путь не найден"),
+        };
+        let id = |name: &str| -> VertexId { name.parse().unwrap() };
+        // Подпись ребра с потоком через него
+        let label = |i: &str, to: &str, weight: Option<&EdgeWeights>| {
+            let (i, to) = (id(i), id(to));
+            let (total_flow, curr_flow) = edge_flows(Some(data), &i, &to);
+            edge_label_weight(Some(data), &i, &to, weight)
+                .map(|w| edge_label_text(w, total_flow, curr_flow))
+        };
+
+        assert_eq!(label("s", "a", Some(&3.into())).unwrap(), "2 (+2) / 3");
+        // Рёбер и вершин нет в графах алгоритма (граф изменился раньше, чем состояние
+        // алгоритма): выводится только вес ребра
+        assert_eq!(label("s", "x", Some(&7.into())).unwrap(), "7");
+        assert_eq!(label("x", "y", Some(&5.into())).unwrap(), "5");
+        assert_eq!(label("t", "t", Some(&1.into())).unwrap(), "1");
+        assert_eq!(label("x", "y", None), None);
+        // Пропускная способность берётся из графа пропускных способностей
+        assert_eq!(label("a", "t", Some(&9.into())).unwrap(), "2 (+2) / 2");
+    }
+}
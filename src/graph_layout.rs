@@ -0,0 +1,213 @@
+// Силы силового алгоритма расположения вершин: вершины отталкиваются друг от друга
+// (силы вычисляются приближённо деревом квадрантов), рёбра притягивают соединённые вершины,
+// а все вершины притягиваются к центру
+
+use rayon::prelude::{
+    IndexedParallelIterator, IntoParallelRefIterator, IntoParallelRefMutIterator, ParallelIterator,
+};
+
+use crate::{
+    graph::{EdgeWeight, Graph, VertexKey},
+    quad_tree::QuadTree,
+};
+
+// Минимальное количество вершин в части, на которые делятся вершины при параллельном
+// вычислении сил отталкивания (на маленьких графах распараллеливание только замедляет)
+const MIN_PARALLEL_CHUNK: usize = 256;
+
+// Номера концов рёбер графа в порядке идентификаторов вершин (в этом же порядке
+// задаются координаты вершин при вычислении сил)
+pub fn edge_indices<I, W>(g: &Graph<I, W>) -> Vec<(usize, usize)>
+where
+    I: VertexKey,
+    W: EdgeWeight,
+{
+    let vertex_indices: std::collections::BTreeMap<&I, usize> = g
+        .get_vertices()
+        .keys()
+        .enumerate()
+        .map(|(k, i)| (i, k))
+        .collect();
+    g.get_vertices()
+        .keys()
+        .flat_map(|i| {
+            let vertex_indices = &vertex_indices;
+            g.get_edge_list(i)
+                .unwrap()
+                .iter()
+                .map(move |e| (vertex_indices[i], vertex_indices[&e.to]))
+        })
+        .collect()
+}
+
+// Вычисление сил, действующих на вершины; буфер сил и дерево квадрантов переиспользуются
+// между шагами, чтобы не выделять память каждый кадр
+pub struct ForceLayout {
+    center_gravity: f32,     // гравитация к центру
+    repulsive_force: f32,    // сила отталкивания вершин
+    theta: f32,              // погрешность симуляции
+    parallel: bool,          // вычислять ли силы отталкивания параллельно
+    forces: Vec<(f32, f32)>, // силы, действующие на вершины (в порядке координат)
+    quad_tree: QuadTree,     // дерево квадрантов для вычисления сил отталкивания
+}
+
+impl Default for ForceLayout {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ForceLayout {
+    // Параметры по умолчанию
+    pub fn new() -> Self {
+        Self {
+            center_gravity: 1.1,
+            repulsive_force: 0.1,
+            theta: 0.7,
+            parallel: true,
+            forces: Vec::new(),
+            quad_tree: QuadTree::new(),
+        }
+    }
+
+    // Установка гравитации к центру
+    pub fn set_center_gravity(&mut self, center_gravity: f32) {
+        self.center_gravity = center_gravity;
+    }
+
+    // Установка силы отталкивания вершин
+    pub fn set_repulsive_force(&mut self, repulsive_force: f32) {
+        self.repulsive_force = repulsive_force;
+    }
+
+    // Установка погрешности симуляции
+    pub fn set_theta(&mut self, theta: f32) {
+        self.theta = theta;
+    }
+
+    // Установка флага параллельного вычисления сил отталкивания
+    // (результат не зависит от флага, последовательное вычисление нужно для сравнения)
+    pub fn set_parallel(&mut self, parallel: bool) {
+        self.parallel = parallel;
+    }
+
+    // Силы, вычисленные на последнем шаге (в порядке координат вершин)
+    pub fn get_forces(&self) -> &[(f32, f32)] {
+        &self.forces
+    }
+
+    // Были ли на последнем шаге совпадающие или почти совпадающие вершины
+    // (сила отталкивания между ними не определена или почти равна нулю)
+    pub fn has_coincident_points(&self) -> bool {
+        self.quad_tree.has_coincident_points()
+    }
+
+    // Вычисление сил для вершин с заданными координатами и рёбер между ними
+    // (номерами концов в порядке координат)
+    pub fn compute_forces(&mut self, coords: &[(f32, f32)], edges: &[(usize, usize)]) {
+        // Гравитация к центру
+        self.forces.clear();
+        self.forces.extend(
+            coords
+                .iter()
+                .map(|(x, y)| (-x * self.center_gravity, -y * self.center_gravity)),
+        );
+        if coords.is_empty() {
+            return;
+        }
+
+        // Минимальные и максимальные координаты вершин
+        let (min_x, max_x, min_y, max_y) = coords.iter().fold(
+            (f32::MAX, f32::MIN, f32::MAX, f32::MIN),
+            |(acc_min_x, acc_max_x, acc_min_y, acc_max_y), &(x, y)| {
+                (
+                    f32::min(acc_min_x, x),
+                    f32::max(acc_max_x, x),
+                    f32::min(acc_min_y, y),
+                    f32::max(acc_max_y, y),
+                )
+            },
+        );
+
+        // Построение дерева квадрантов для всех вершин
+        self.quad_tree
+            .build(coords.iter().copied(), min_x, max_x, min_y, max_y);
+
+        // Силы отталкивания между вершинами (вычисляются для каждой вершины независимо,
+        // поэтому параллельно)
+        let (quad_tree, theta, repulsive_force) =
+            (&self.quad_tree, self.theta, self.repulsive_force);
+        let add_repulsion = |(force_i, v): (&mut (f32, f32), &(f32, f32))| {
+            let force = quad_tree.get_force(*v, theta);
+            *force_i = (
+                force_i.0 + repulsive_force * force.0,
+                force_i.1 + repulsive_force * force.1,
+            );
+        };
+        if self.parallel {
+            self.forces
+                .par_iter_mut()
+                .zip(coords.par_iter())
+                .with_min_len(MIN_PARALLEL_CHUNK)
+                .for_each(add_repulsion);
+        } else {
+            self.forces
+                .iter_mut()
+                .zip(coords.iter())
+                .for_each(add_repulsion);
+        }
+
+        // Притяжение/отталкивание вершин, связанных рёбрами
+        for &(i, to) in edges {
+            let (pos_i, pos_to) = (coords[i], coords[to]);
+            let force = (pos_i.0 - pos_to.0, pos_i.1 - pos_to.1);
+
+            let force_i = &mut self.forces[i];
+            *force_i = (force_i.0 - force.0, force_i.1 - force.1);
+            let force_to = &mut self.forces[to];
+            *force_to = (force_to.0 + force.0, force_to.1 + force.1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use super::*;
+    use crate::{
+        graph::{EdgeWeights, VertexId},
+        graph_generators::gen_random_gnp,
+    };
+
+    // Силы для случайного графа из n вершин со случайными координатами
+    fn forces(n: usize, parallel: bool) -> Vec<(f32, f32)> {
+        let g: Graph<VertexId, EdgeWeights> =
+            gen_random_gnp(n, f64::min(1.0, 3.0 / n as f64), false, None, 1).unwrap();
+        let mut rng = StdRng::seed_from_u64(1);
+        let coords: Vec<(f32, f32)> = (0..n)
+            .map(|_| (rng.gen_range(-0.5..0.5), rng.gen_range(-0.5..0.5)))
+            .collect();
+        let mut layout = ForceLayout::new();
+        layout.set_parallel(parallel);
+        layout.compute_forces(&coords, &edge_indices(&g));
+        layout.get_forces().to_vec()
+    }
+
+    #[test]
+    fn parallel_forces_equal_serial_forces() {
+        // Граф больше части, на которые делятся вершины, чтобы вычисление было параллельным
+        for n in [1, 10, 4 * MIN_PARALLEL_CHUNK + 1] {
+            let (parallel, serial) = (forces(n, true), forces(n, false));
+            assert_eq!(parallel.len(), n);
+            assert_eq!(parallel, serial);
+        }
+    }
+
+    #[test]
+    fn no_vertices_no_forces() {
+        let mut layout = ForceLayout::new();
+        layout.compute_forces(&[], &[]);
+        assert!(layout.get_forces().is_empty());
+    }
+}
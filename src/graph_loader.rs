@@ -0,0 +1,178 @@
+use std::{
+    fs::File,
+    io::{self, BufReader, Cursor, Read},
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread,
+};
+
+use crate::{
+    graph::VertexId,
+    graph_errors::GraphError,
+    graph_parser::{graph_from_file_reader, graph_from_reader, FileFormat, GraphWithPositions},
+};
+
+// Источник загружаемого графа
+#[derive(Debug, Clone)]
+pub enum GraphSource {
+    Text(String),  // текст графа в собственном формате
+    File(PathBuf), // файл в формате, определённом по расширению
+}
+
+// Результат разбора графа
+pub type ParseResult = Result<GraphWithPositions<VertexId>, GraphError>;
+
+// Чтение, которое можно прервать из другого потока
+struct CancellableReader<R> {
+    inner: R,                   // исходный поток чтения
+    cancelled: Arc<AtomicBool>, // отменено ли чтение
+}
+
+impl<R: Read> Read for CancellableReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.cancelled.load(Ordering::Relaxed) {
+            return Err(io::Error::other("cancelled"));
+        }
+        self.inner.read(buf)
+    }
+}
+
+// Разбор графа в фоновом потоке, чтобы большие графы не блокировали интерфейс
+pub struct GraphLoader {
+    id: u64,                    // номер загрузки (передаётся обработчику вместе с результатом)
+    cancelled: Arc<AtomicBool>, // отменена ли загрузка
+}
+
+impl GraphLoader {
+    // Запуск разбора графа; по завершении номер загрузки и результат передаются обработчику
+    // (в фоновом потоке), если загрузка не была отменена (отмена может не успеть до
+    // завершения разбора, поэтому результаты старых загрузок отличаются по номеру)
+    pub fn spawn<F>(id: u64, source: GraphSource, done: F) -> Self
+    where
+        F: FnOnce(u64, ParseResult) + Send + 'static,
+    {
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let thread_cancelled = cancelled.clone();
+        thread::spawn(move || {
+            let result = parse_graph(source, thread_cancelled.clone());
+            if !thread_cancelled.load(Ordering::Relaxed) {
+                done(id, result);
+            }
+        });
+        Self { id, cancelled }
+    }
+
+    // Номер загрузки
+    pub fn get_id(&self) -> u64 {
+        self.id
+    }
+
+    // Отмена загрузки: разбор прерывается при следующем чтении, результат не передаётся
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+}
+
+// Разбор графа из источника с чтением, которое прерывается при отмене
+pub fn parse_graph(source: GraphSource, cancelled: Arc<AtomicBool>) -> ParseResult {
+    match source {
+        GraphSource::Text(text) => {
+            let inner = Cursor::new(text.into_bytes());
+            let reader = BufReader::new(CancellableReader { inner, cancelled });
+            graph_from_reader(reader, FileFormat::Native)
+        }
+        GraphSource::File(path) => {
+            let inner = File::open(&path)?;
+            let reader = BufReader::new(CancellableReader { inner, cancelled });
+            graph_from_file_reader(reader, FileFormat::from_path(&path))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::mpsc;
+
+    use super::*;
+    use crate::graph_errors::GraphInterfaceError;
+
+    // Текст графа-пути из n вершин (большой граф разбирается заметное время)
+    fn path_text(n: usize) -> String {
+        let mut text = String::from("undirected unweighted int\nvertices\n");
+        text += &(0..n).map(|k| format!("{}\n", k)).collect::<String>();
+        text += "edges\n";
+        text += &(1..n)
+            .map(|k| format!("{} {}\n", k - 1, k))
+            .collect::<String>();
+        text
+    }
+
+    // Количество вершин разобранного графа
+    fn vertex_cnt(result: &ParseResult) -> usize {
+        result.as_ref().unwrap().0.get_vertices().len()
+    }
+
+    #[test]
+    fn loader_reports_result_with_id() {
+        let (sender, receiver) = mpsc::channel();
+        let loader = GraphLoader::spawn(7, GraphSource::Text(path_text(3)), move |id, result| {
+            sender.send((id, result)).unwrap()
+        });
+        let (id, result) = receiver.recv().unwrap();
+        assert_eq!(id, loader.get_id());
+        assert_eq!(vertex_cnt(&result), 3);
+    }
+
+    #[test]
+    fn cancelled_reader_stops_parsing() {
+        let cancelled = Arc::new(AtomicBool::new(true));
+        let result = parse_graph(GraphSource::Text(path_text(3)), cancelled);
+        // Ошибка чтения первой строки
+        match result {
+            Err(GraphError::InterfaceError(GraphInterfaceError::AtLine { line: 1, source })) => {
+                assert!(matches!(*source, GraphError::IOError(_)))
+            }
+            _ => panic!("разбор не прерван"),
+        }
+    }
+
+    #[test]
+    fn cancelled_load_does_not_report() {
+        let (sender, receiver) = mpsc::channel();
+        let loader = GraphLoader::spawn(
+            1,
+            GraphSource::Text(path_text(200_000)),
+            move |id, result| sender.send((id, result)).unwrap(),
+        );
+        loader.cancel();
+        // Обработчик не вызывается и удаляется вместе с потоком, поэтому канал закрывается
+        assert!(receiver.recv().is_err());
+    }
+
+    #[test]
+    fn stale_results_are_distinguished_by_id() {
+        let (sender, receiver) = mpsc::channel();
+        let old_sender = sender.clone();
+        let old_loader = GraphLoader::spawn(
+            1,
+            GraphSource::Text(path_text(200_000)),
+            move |id, result| old_sender.send((id, result)).unwrap(),
+        );
+        // Новая загрузка запускается, как в приложении, после отмены текущей
+        old_loader.cancel();
+        let loader = GraphLoader::spawn(2, GraphSource::Text(path_text(5)), move |id, result| {
+            sender.send((id, result)).unwrap()
+        });
+        // Принимается только результат последней загрузки, даже если отменённая загрузка
+        // успела завершиться
+        let current: Vec<_> = receiver
+            .iter()
+            .filter(|(id, _)| *id == loader.get_id())
+            .collect();
+        assert_eq!(current.len(), 1);
+        assert_eq!(vertex_cnt(&current[0].1), 5);
+    }
+}
@@ -1,6 +1,18 @@
+use std::{
+    collections::BTreeSet,
+    fs::File,
+    io::{BufRead, BufReader, BufWriter, Write},
+    path::Path,
+};
+
+use rand::{thread_rng, Rng};
+
 use crate::{
-    graph::{Edge, EdgeWeight, EdgeWeights, Graph, Vertex, VertexKey},
-    graph_errors::{GraphError, GraphInterfaceError},
+    graph::{
+        parallel_numbers, Edge, EdgeWeight, EdgeWeights, Graph, Vertex, VertexColor, VertexKey,
+        VertexPositions,
+    },
+    graph_errors::{GraphError, GraphInterfaceError, GraphOperationError},
 };
 
 // Создание пустого графа
@@ -34,22 +46,111 @@ where
     Ok(())
 }
 
-// Добавление вершины в граф
+// Добавление вершины в граф: идентификатор, необязательная метка и необязательный цвет #rrggbb
+// (цвет всегда последний; второй аргумент считается цветом, только если аргументов два
+// и он имеет вид #rrggbb, иначе это метка)
 pub fn add_vertex<I, W>(args: &[&str], g: &mut Option<Graph<I, W>>) -> Result<(), GraphError>
 where
     I: VertexKey,
     W: EdgeWeight,
 {
-    if args.is_empty() || args.len() > 2 {
+    if args.is_empty() || args.len() > 3 {
         return Err(GraphInterfaceError::IncorrectArgumentCount.into());
     }
     let id: I = args[0]
         .parse()
         .map_err(|_| GraphInterfaceError::IncorrectArgument { i: 1 })?;
-    let label = args.get(1).map(|&s| String::from(s));
+    let (label, color) = match args {
+        [_, label, color] => (
+            Some(String::from(*label)),
+            Some(
+                color
+                    .parse()
+                    .map_err(|_| GraphInterfaceError::IncorrectArgument { i: 3 })?,
+            ),
+        ),
+        [_, arg] => match arg.parse::<VertexColor>() {
+            Ok(color) => (None, Some(color)),
+            Err(_) => (Some(String::from(*arg)), None),
+        },
+        _ => (None, None),
+    };
     g.as_mut()
         .ok_or(GraphInterfaceError::GraphNotExist)?
-        .add_vertex(Vertex { id, label })?;
+        .add_vertex(Vertex { id, label, color })?;
+    Ok(())
+}
+
+// Добавление в граф вершин матрицы смежности (строка с идентификаторами вершин)
+pub fn add_matrix_vertices<I, W>(
+    args: &[&str],
+    g: &mut Option<Graph<I, W>>,
+) -> Result<Vec<I>, GraphError>
+where
+    I: VertexKey,
+    W: EdgeWeight,
+{
+    let g = g.as_mut().ok_or(GraphInterfaceError::GraphNotExist)?;
+    let mut ids = Vec::new();
+    for (k, arg) in args.iter().enumerate() {
+        let id: I = arg
+            .parse()
+            .map_err(|_| GraphInterfaceError::IncorrectArgument { i: k + 1 })?;
+        g.add_vertex(Vertex {
+            id: id.clone(),
+            label: None,
+            color: None,
+        })?;
+        ids.push(id);
+    }
+    Ok(ids)
+}
+
+// Добавление в граф рёбер из строки row матрицы смежности: элемент в столбце j — вес ребра
+// в j-ю вершину (у невзвешенного графа — 1), 0 — отсутствие ребра; матрица неориентированного
+// графа должна быть симметричной, и рёбра добавляются по её верхнему треугольнику
+pub fn add_matrix_row<I>(
+    args: &[&str],
+    ids: &[I],
+    row: usize,
+    g: &mut Option<Graph<I, EdgeWeights>>,
+) -> Result<(), GraphError>
+where
+    I: VertexKey,
+{
+    let g = g.as_mut().ok_or(GraphInterfaceError::GraphNotExist)?;
+    // Первый лишний или недостающий элемент строки
+    if args.len() != ids.len() {
+        return Err(GraphInterfaceError::IncorrectArgument {
+            i: args.len().min(ids.len()) + 1,
+        }
+        .into());
+    }
+    let from = &ids[row];
+    for (col, (arg, to)) in args.iter().zip(ids).enumerate() {
+        let i = col + 1;
+        // Есть ли ребро, и его вес
+        let edge = if g.get_is_weighted() {
+            let w = parse_weight(arg, g, i)?;
+            (w.as_f32() != 0.0).then_some(Some(w))
+        } else {
+            match *arg {
+                "0" => None,
+                "1" => Some(None),
+                _ => return Err(GraphInterfaceError::IncorrectArgument { i }.into()),
+            }
+        };
+        // Элемент под диагональю неориентированного графа должен совпадать с симметричным
+        if !g.get_is_directed() && col < row {
+            if g.get_edge(from, to).ok().map(|e| &e.weight) != edge.as_ref() {
+                return Err(GraphInterfaceError::IncorrectArgument { i }.into());
+            }
+            continue;
+        }
+        if let Some(weight) = edge {
+            g.add_edge(from.clone(), Edge::new(to.clone(), weight))?;
+        }
+    }
     Ok(())
 }
 
@@ -72,6 +173,44 @@ where
 pub fn add_edge<I>(args: &[&str], g: &mut Option<Graph<I, EdgeWeights>>) -> Result<(), GraphError>
 where
     I: VertexKey,
+{
+    add_edge_with(args, g, |s, g| s.map(|s| parse_weight(s, g, 3)).transpose())
+}
+
+// Добавление ребра из интерфейса: вес может быть задан выражением rand(мин,макс),
+// а ребро без веса во взвешенном графе получает случайный вес из отрезка default_range,
+// если он задан
+pub fn add_edge_random<I>(
+    args: &[&str],
+    g: &mut Option<Graph<I, EdgeWeights>>,
+    default_range: Option<(&str, &str)>,
+) -> Result<(), GraphError>
+where
+    I: VertexKey,
+{
+    let mut rng = thread_rng();
+    add_edge_with(args, g, |s, g| match (s, default_range) {
+        (Some(s), _) => parse_weight_expr(s, g, 3, &mut rng).map(Some),
+        (None, Some((min, max))) if g.get_is_weighted() => Ok(Some(random_weight(
+            &parse_weight_range(min, max, g)?,
+            &mut rng,
+        ))),
+        (None, _) => Ok(None),
+    })
+}
+
+// Добавление ребра с разбором веса (третьего аргумента, если он есть) функцией weight
+fn add_edge_with<I, F>(
+    args: &[&str],
+    g: &mut Option<Graph<I, EdgeWeights>>,
+    weight: F,
+) -> Result<(), GraphError>
+where
+    I: VertexKey,
+    F: FnOnce(
+        Option<&str>,
+        &Graph<I, EdgeWeights>,
+    ) -> Result<Option<EdgeWeights>, GraphInterfaceError>,
 {
     if args.len() < 2 || args.len() > 3 {
         return Err(GraphInterfaceError::IncorrectArgumentCount.into());
@@ -83,21 +222,243 @@ where
     let j: I = args[1]
         .parse()
         .map_err(|_| GraphInterfaceError::IncorrectArgument { i: 2 })?;
-    let weight = args
-        .get(2)
-        .map(|&s| {
-            if g.get_is_float_weights() {
-                s.parse::<f32>()
-                    .map_err(|_| GraphInterfaceError::IncorrectArgument { i: 3 })
-                    .map(|x| x.into())
-            } else {
-                s.parse::<i32>()
-                    .map_err(|_| GraphInterfaceError::IncorrectArgument { i: 3 })
-                    .map(|x| x.into())
+    let weight = weight(args.get(2).copied(), g)?;
+    g.add_edge(i, Edge::new(j, weight))?;
+    Ok(())
+}
+
+// Замена весов всех рёбер взвешенного графа случайными весами из отрезка [min; max]
+// (в неориентированном графе вес ребра одинаков в обоих направлениях)
+pub fn set_random_weights<I>(
+    min: &str,
+    max: &str,
+    g: &mut Option<Graph<I, EdgeWeights>>,
+) -> Result<(), GraphError>
+where
+    I: VertexKey,
+{
+    let g = g.as_mut().ok_or(GraphInterfaceError::GraphNotExist)?;
+    if !g.get_is_weighted() {
+        return Err(GraphOperationError::WeightedEdgeInUnweightedGraph.into());
+    }
+    let range = parse_weight_range(min, max, g)?;
+    let mut rng = thread_rng();
+    let mut edges = Vec::new();
+    for i in g.get_vertices().keys() {
+        let edge_list = g.get_edge_list(i)?;
+        for (e, (index, _)) in edge_list.iter().zip(parallel_numbers(edge_list)) {
+            if g.get_is_directed() || i <= &e.to {
+                edges.push((i.clone(), e.to.clone(), index));
             }
+        }
+    }
+    for (from, to, index) in edges {
+        let weight = Some(random_weight(&range, &mut rng));
+        g.set_parallel_edge_weight(&from, &to, index, weight)?;
+    }
+    Ok(())
+}
+
+// Разбор веса ребра: числа или выражения rand(мин,макс) — случайного веса из отрезка
+fn parse_weight_expr<I, R>(
+    s: &str,
+    g: &Graph<I, EdgeWeights>,
+    i: usize,
+    rng: &mut R,
+) -> Result<EdgeWeights, GraphInterfaceError>
+where
+    I: VertexKey,
+    R: Rng,
+{
+    let range = s
+        .trim()
+        .strip_prefix("rand(")
+        .and_then(|s| s.strip_suffix(')'))
+        .and_then(|s| s.split_once(','));
+    match range {
+        Some((min, max)) => parse_weight_range(min, max, g)
+            .map(|range| random_weight(&range, rng))
+            .map_err(|_| GraphInterfaceError::IncorrectArgument { i }),
+        None => parse_weight(s, g, i),
+    }
+}
+
+// Разбор отрезка случайных весов [min; max] в соответствии с типом весов графа
+// (длина отрезка должна быть конечной, иначе из него нельзя выбрать случайный вес)
+fn parse_weight_range<I>(
+    min: &str,
+    max: &str,
+    g: &Graph<I, EdgeWeights>,
+) -> Result<(EdgeWeights, EdgeWeights), GraphInterfaceError>
+where
+    I: VertexKey,
+{
+    match (
+        parse_weight(min.trim(), g, 0),
+        parse_weight(max.trim(), g, 0),
+    ) {
+        (Ok(min), Ok(max)) if min <= max && (max.as_f32() - min.as_f32()).is_finite() => {
+            Ok((min, max))
+        }
+        _ => Err(GraphInterfaceError::IncorrectWeightRange),
+    }
+}
+
+// Случайный вес из отрезка (границы одного типа)
+fn random_weight<R: Rng>(range: &(EdgeWeights, EdgeWeights), rng: &mut R) -> EdgeWeights {
+    match range {
+        (EdgeWeights::I32(min), EdgeWeights::I32(max)) => rng.gen_range(*min..=*max).into(),
+        (min, max) => rng.gen_range(min.as_f32()..=max.as_f32()).into(),
+    }
+}
+
+// Разбор веса ребра в соответствии с типом весов графа
+fn parse_weight<I>(
+    s: &str,
+    g: &Graph<I, EdgeWeights>,
+    i: usize,
+) -> Result<EdgeWeights, GraphInterfaceError>
+where
+    I: VertexKey,
+{
+    if g.get_is_float_weights() {
+        s.parse::<f32>()
+            .map_err(|_| GraphInterfaceError::IncorrectArgument { i })
+            .map(|x| x.into())
+    } else {
+        s.parse::<i32>()
+            .map_err(|_| GraphInterfaceError::IncorrectArgument { i })
+            .map(|x| x.into())
+    }
+}
+
+// Добавление в граф рёбер, заданных по одному в строке текста
+// Возвращает ошибки добавления с номерами строк, успешно добавленные рёбра остаются в графе
+pub fn add_edges_bulk<I>(
+    text: &str,
+    g: &mut Option<Graph<I, EdgeWeights>>,
+) -> Vec<(usize, GraphError)>
+where
+    I: VertexKey,
+{
+    text.lines()
+        .enumerate()
+        .filter_map(|(n, line)| {
+            let args: Vec<_> = line.split_ascii_whitespace().collect();
+            // Пустые строки пропускаются
+            if args.is_empty() {
+                return None;
+            }
+            add_edge_random(&args, g, None).err().map(|e| (n + 1, e))
         })
-        .transpose()?;
-    g.add_edge(i, Edge::new(j, weight))?;
+        .collect()
+}
+
+// Добавление координат вершины в расположение вершин графа
+// (координаты вершин, которых нет в графе, пропускаются)
+pub fn add_position<I, W>(
+    args: &[&str],
+    g: &Option<Graph<I, W>>,
+    positions: &mut VertexPositions<I>,
+) -> Result<(), GraphError>
+where
+    I: VertexKey,
+    W: EdgeWeight,
+{
+    if args.len() != 3 {
+        return Err(GraphInterfaceError::IncorrectArgumentCount.into());
+    }
+    let i: I = args[0]
+        .parse()
+        .map_err(|_| GraphInterfaceError::IncorrectArgument { i: 1 })?;
+    let x = args[1]
+        .parse::<f32>()
+        .ok()
+        .filter(|x| x.is_finite())
+        .ok_or(GraphInterfaceError::IncorrectArgument { i: 2 })?;
+    let y = args[2]
+        .parse::<f32>()
+        .ok()
+        .filter(|y| y.is_finite())
+        .ok_or(GraphInterfaceError::IncorrectArgument { i: 3 })?;
+    if g.as_ref()
+        .ok_or(GraphInterfaceError::GraphNotExist)?
+        .get_vertices()
+        .contains_key(&i)
+    {
+        positions.insert(i, (x, y));
+    }
+    Ok(())
+}
+
+// Сохранение расположения вершин графа в файл (раздел "layout" собственного формата)
+pub fn positions_to_file<I, Writer>(
+    positions: &VertexPositions<I>,
+    writer: &mut Writer,
+) -> Result<(), GraphError>
+where
+    I: VertexKey,
+    Writer: Write,
+{
+    writeln!(writer, "layout")?;
+    for (i, (x, y)) in positions {
+        writeln!(writer, "{} {} {}", i, x, y)?;
+    }
+    Ok(())
+}
+
+// Изменение метки вершины (пустая строка удаляет метку)
+pub fn set_vertex_label<I, W>(
+    i_str: &str,
+    label_str: &str,
+    g: &mut Option<Graph<I, W>>,
+) -> Result<(), GraphError>
+where
+    I: VertexKey,
+    W: EdgeWeight,
+{
+    let i: I = i_str
+        .parse()
+        .map_err(|_| GraphInterfaceError::IncorrectArgument { i: 1 })?;
+    // Метка записывается в файл через пробел после идентификатора
+    if label_str.split_ascii_whitespace().count() > 1 {
+        return Err(GraphInterfaceError::IncorrectArgument { i: 2 }.into());
+    }
+    let label = Some(label_str.trim())
+        .filter(|s| !s.is_empty())
+        .map(String::from);
+    g.as_mut()
+        .ok_or(GraphInterfaceError::GraphNotExist)?
+        .set_vertex_label(&i, label)?;
+    Ok(())
+}
+
+// Изменение веса ребра с номером index среди параллельных ему рёбер
+// (пустая строка для невзвешенного графа)
+pub fn set_edge_weight<I>(
+    i_str: &str,
+    j_str: &str,
+    index: usize,
+    weight_str: &str,
+    g: &mut Option<Graph<I, EdgeWeights>>,
+) -> Result<(), GraphError>
+where
+    I: VertexKey,
+{
+    let g = g.as_mut().ok_or(GraphInterfaceError::GraphNotExist)?;
+    let i: I = i_str
+        .parse()
+        .map_err(|_| GraphInterfaceError::IncorrectArgument { i: 1 })?;
+    let j: I = j_str
+        .parse()
+        .map_err(|_| GraphInterfaceError::IncorrectArgument { i: 2 })?;
+    let weight_str = weight_str.trim();
+    let weight = if weight_str.is_empty() {
+        None
+    } else {
+        Some(parse_weight(weight_str, g, 3)?)
+    };
+    g.set_parallel_edge_weight(&i, &j, index, weight)?;
     Ok(())
 }
 
@@ -122,3 +483,1013 @@ where
         .remove_edge(&i, &j)?;
     Ok(())
 }
+
+// Слияние второй вершины с первой
+pub fn merge_vertices<I, W>(
+    i_str: &str,
+    j_str: &str,
+    sum_weights: bool,
+    keep_loops: bool,
+    g: &mut Option<Graph<I, W>>,
+) -> Result<(), GraphError>
+where
+    I: VertexKey,
+    W: EdgeWeight,
+{
+    let i: I = i_str
+        .parse()
+        .map_err(|_| GraphInterfaceError::IncorrectArgument { i: 1 })?;
+    let j: I = j_str
+        .parse()
+        .map_err(|_| GraphInterfaceError::IncorrectArgument { i: 2 })?;
+    g.as_mut()
+        .ok_or(GraphInterfaceError::GraphNotExist)?
+        .merge_vertices(&i, &j, sum_weights, keep_loops)?;
+    Ok(())
+}
+
+// Лексема формата DOT
+#[derive(PartialEq)]
+enum DotToken {
+    Id(String), // идентификатор, число или строка в кавычках
+    Edge(bool), // ребро: "->" (true) или "--" (false)
+    LBrace,     // "{"
+    RBrace,     // "}"
+    LBracket,   // "["
+    RBracket,   // "]"
+    Equals,     // "="
+    Separator,  // ";" или ","
+}
+
+// Разбиение текста в формате DOT на лексемы
+fn dot_tokens(text: &str) -> Result<Vec<DotToken>, GraphInterfaceError> {
+    let mut tokens = Vec::new();
+    let mut chars = text.chars().peekable();
+    let mut line_start = true;
+    while let Some(c) = chars.next() {
+        match c {
+            '\n' => {
+                line_start = true;
+                continue;
+            }
+            _ if c.is_whitespace() => continue,
+            // Строки препроцессора и однострочные комментарии
+            '#' if line_start => {
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        break;
+                    }
+                }
+                continue;
+            }
+            '/' => match chars.next() {
+                Some('/') => {
+                    for c in chars.by_ref() {
+                        if c == '\n' {
+                            break;
+                        }
+                    }
+                    line_start = true;
+                    continue;
+                }
+                Some('*') => {
+                    let mut prev = ' ';
+                    loop {
+                        match chars.next() {
+                            Some('/') if prev == '*' => break,
+                            Some(c) => prev = c,
+                            None => return Err(GraphInterfaceError::WrongDotFormat),
+                        }
+                    }
+                }
+                _ => return Err(GraphInterfaceError::WrongDotFormat),
+            },
+            '{' => tokens.push(DotToken::LBrace),
+            '}' => tokens.push(DotToken::RBrace),
+            '[' => tokens.push(DotToken::LBracket),
+            ']' => tokens.push(DotToken::RBracket),
+            '=' => tokens.push(DotToken::Equals),
+            ';' | ',' => tokens.push(DotToken::Separator),
+            '-' => match chars.peek() {
+                Some('>') => {
+                    chars.next();
+                    tokens.push(DotToken::Edge(true));
+                }
+                Some('-') => {
+                    chars.next();
+                    tokens.push(DotToken::Edge(false));
+                }
+                // Отрицательное число
+                Some(&d) if d.is_ascii_digit() || d == '.' => {
+                    let mut id = String::from(c);
+                    while let Some(&d) = chars.peek() {
+                        if !d.is_ascii_digit() && d != '.' {
+                            break;
+                        }
+                        id.push(d);
+                        chars.next();
+                    }
+                    tokens.push(DotToken::Id(id));
+                }
+                _ => return Err(GraphInterfaceError::WrongDotFormat),
+            },
+            // Строка в кавычках
+            '"' => {
+                let mut id = String::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some(c @ ('"' | '\\')) => id.push(c),
+                            Some('\n') => {}
+                            Some(c) => {
+                                id.push('\\');
+                                id.push(c);
+                            }
+                            None => return Err(GraphInterfaceError::WrongDotFormat),
+                        },
+                        Some(c) => id.push(c),
+                        None => return Err(GraphInterfaceError::WrongDotFormat),
+                    }
+                }
+                tokens.push(DotToken::Id(id));
+            }
+            // Идентификатор или число
+            _ if c.is_alphanumeric() || c == '_' || c == '.' => {
+                let mut id = String::from(c);
+                while let Some(&d) = chars.peek() {
+                    if !d.is_alphanumeric() && d != '_' && d != '.' {
+                        break;
+                    }
+                    id.push(d);
+                    chars.next();
+                }
+                tokens.push(DotToken::Id(id));
+            }
+            _ => return Err(GraphInterfaceError::WrongDotFormat),
+        }
+        line_start = false;
+    }
+    Ok(tokens)
+}
+
+// Чтение списков атрибутов вида "[a=b, c=d][e=f]"
+fn dot_attributes<'a, T>(
+    tokens: &mut std::iter::Peekable<T>,
+) -> Result<Vec<(String, String)>, GraphInterfaceError>
+where
+    T: Iterator<Item = &'a DotToken>,
+{
+    let mut attributes = Vec::new();
+    while tokens.next_if_eq(&&DotToken::LBracket).is_some() {
+        loop {
+            match tokens.next() {
+                Some(DotToken::RBracket) => break,
+                Some(DotToken::Separator) => {}
+                Some(DotToken::Id(key)) => match (tokens.next(), tokens.next()) {
+                    (Some(DotToken::Equals), Some(DotToken::Id(value))) => {
+                        attributes.push((key.clone(), value.clone()))
+                    }
+                    _ => return Err(GraphInterfaceError::WrongDotFormat),
+                },
+                _ => return Err(GraphInterfaceError::WrongDotFormat),
+            }
+        }
+    }
+    Ok(attributes)
+}
+
+// Создание графа из файла в формате DOT
+pub fn graph_from_dot<I, Reader>(mut reader: Reader) -> Result<Graph<I, EdgeWeights>, GraphError>
+where
+    I: VertexKey,
+    Reader: BufRead,
+{
+    let mut text = String::new();
+    reader.read_to_string(&mut text)?;
+    let tokens = dot_tokens(&text)?;
+    let mut tokens = tokens.iter().peekable();
+
+    // Заголовок: "[strict] (graph | digraph) [ID] {"
+    let keyword = |token: Option<&DotToken>, kw: &str| matches!(token, Some(DotToken::Id(id)) if id.eq_ignore_ascii_case(kw));
+    if keyword(tokens.peek().copied(), "strict") {
+        tokens.next();
+    }
+    let is_directed = match tokens.next() {
+        t if keyword(t, "digraph") => true,
+        t if keyword(t, "graph") => false,
+        _ => return Err(GraphInterfaceError::WrongDotFormat.into()),
+    };
+    if let Some(DotToken::Id(_)) = tokens.peek() {
+        tokens.next();
+    }
+    if tokens.next() != Some(&DotToken::LBrace) {
+        return Err(GraphInterfaceError::WrongDotFormat.into());
+    }
+
+    // Вершины с метками и рёбра с весами
+    let mut vertices: Vec<(String, Option<String>)> = Vec::new();
+    let mut edges: Vec<(String, String, Option<String>)> = Vec::new();
+    let mut add_dot_vertex = |id: &str, label: Option<String>| {
+        match vertices.iter_mut().find(|(v, _)| v == id) {
+            Some((_, l)) => *l = label.or_else(|| l.take()),
+            None => vertices.push((String::from(id), label)),
+        };
+    };
+    let find_attribute = |attributes: &[(String, String)], key: &str| {
+        attributes
+            .iter()
+            .rev()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.clone())
+    };
+
+    // Чтение операторов
+    loop {
+        match tokens.next() {
+            Some(DotToken::RBrace) => break,
+            Some(DotToken::Separator) => {}
+            // Атрибуты по умолчанию игнорируются
+            Some(DotToken::Id(id))
+                if ["graph", "node", "edge"]
+                    .iter()
+                    .any(|kw| id.eq_ignore_ascii_case(kw)) =>
+            {
+                dot_attributes(&mut tokens)?;
+            }
+            Some(DotToken::Id(id)) => {
+                // Атрибут графа игнорируется
+                if tokens.next_if_eq(&&DotToken::Equals).is_some() {
+                    match tokens.next() {
+                        Some(DotToken::Id(_)) => continue,
+                        _ => return Err(GraphInterfaceError::WrongDotFormat.into()),
+                    }
+                }
+                // Цепочка вершин, соединённых рёбрами
+                let mut chain = vec![id];
+                while let Some(DotToken::Edge(edge_directed)) = tokens.peek() {
+                    if *edge_directed != is_directed {
+                        return Err(GraphInterfaceError::WrongDotFormat.into());
+                    }
+                    tokens.next();
+                    match tokens.next() {
+                        Some(DotToken::Id(to)) => chain.push(to),
+                        _ => return Err(GraphInterfaceError::WrongDotFormat.into()),
+                    }
+                }
+                let attributes = dot_attributes(&mut tokens)?;
+                if chain.len() == 1 {
+                    add_dot_vertex(id, find_attribute(&attributes, "label"));
+                } else {
+                    let weight = find_attribute(&attributes, "weight");
+                    for pair in chain.windows(2) {
+                        add_dot_vertex(pair[0], None);
+                        add_dot_vertex(pair[1], None);
+                        edges.push((pair[0].clone(), pair[1].clone(), weight.clone()));
+                    }
+                }
+            }
+            _ => return Err(GraphInterfaceError::WrongDotFormat.into()),
+        }
+    }
+    if tokens.next().is_some() {
+        return Err(GraphInterfaceError::WrongDotFormat.into());
+    }
+
+    // Граф взвешенный, если задан вес хотя бы у одного ребра
+    let is_weighted = edges.iter().any(|(_, _, w)| w.is_some());
+    let is_float_weights = has_float_weights(&edges);
+    graph_from_parts(
+        is_directed,
+        is_weighted,
+        is_float_weights,
+        &vertices,
+        &edges,
+    )
+}
+
+// Есть ли среди записанных весов рёбер дробные числа
+fn has_float_weights(edges: &[(String, String, Option<String>)]) -> bool {
+    edges
+        .iter()
+        .filter_map(|(_, _, w)| w.as_ref())
+        .any(|w| w.parse::<i32>().is_err() && w.parse::<f32>().is_ok())
+}
+
+// Создание графа из прочитанных вершин с метками и рёбер с весами
+fn graph_from_parts<I>(
+    is_directed: bool,
+    is_weighted: bool,
+    is_float_weights: bool,
+    vertices: &[(String, Option<String>)],
+    edges: &[(String, String, Option<String>)],
+) -> Result<Graph<I, EdgeWeights>, GraphError>
+where
+    I: VertexKey,
+{
+    let mut g = Some(Graph::new(is_directed, is_weighted, is_float_weights));
+    for (id, label) in vertices {
+        let mut args = vec![&id[..]];
+        if let Some(l) = label {
+            args.push(&l[..]);
+        }
+        add_vertex(&args, &mut g)?;
+    }
+    for (from, to, weight) in edges {
+        let mut args = vec![&from[..], &to[..]];
+        if let Some(w) = weight {
+            args.push(&w[..]);
+        }
+        add_edge(&args, &mut g)?;
+    }
+    Ok(g.unwrap())
+}
+
+// Событие разбора XML
+enum XmlEvent {
+    // Открывающий (или пустой, если empty) тег: имя без префикса пространства имён и атрибуты
+    Start {
+        name: String,
+        attributes: Vec<(String, String)>,
+        empty: bool,
+    },
+    End,          // закрывающий тег
+    Text(String), // текст между тегами
+}
+
+// Замена ссылок на символы ("&lt;", "&#62;" и т. п.) самими символами
+fn xml_unescape(text: &str) -> Result<String, GraphInterfaceError> {
+    let mut result = String::new();
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        result.push_str(&rest[..start]);
+        let end = rest[start..]
+            .find(';')
+            .ok_or(GraphInterfaceError::WrongGraphMlFormat)?
+            + start;
+        let c = match &rest[start + 1..end] {
+            "lt" => '<',
+            "gt" => '>',
+            "amp" => '&',
+            "quot" => '"',
+            "apos" => '\'',
+            entity => entity
+                .strip_prefix("#x")
+                .map(|hex| u32::from_str_radix(hex, 16))
+                .or_else(|| entity.strip_prefix('#').map(|dec| dec.parse()))
+                .and_then(|code| code.ok())
+                .and_then(char::from_u32)
+                .ok_or(GraphInterfaceError::WrongGraphMlFormat)?,
+        };
+        result.push(c);
+        rest = &rest[end + 1..];
+    }
+    result.push_str(rest);
+    Ok(result)
+}
+
+// Имя тега или атрибута без префикса пространства имён
+fn xml_local_name(name: &str) -> &str {
+    name.rsplit(':').next().unwrap()
+}
+
+// Разбор открывающего тега без угловых скобок: имя и атрибуты вида a="b" или a='b'
+fn xml_start_tag(tag: &str) -> Result<(String, Vec<(String, String)>), GraphInterfaceError> {
+    let name_end = tag.find(char::is_whitespace).unwrap_or(tag.len());
+    let name = xml_local_name(&tag[..name_end]).to_owned();
+    let mut attributes = Vec::new();
+    let mut rest = tag[name_end..].trim_start();
+    while !rest.is_empty() {
+        let eq = rest
+            .find('=')
+            .ok_or(GraphInterfaceError::WrongGraphMlFormat)?;
+        let key = xml_local_name(rest[..eq].trim()).to_owned();
+        rest = rest[eq + 1..].trim_start();
+        let quote = rest
+            .chars()
+            .next()
+            .filter(|&c| c == '"' || c == '\'')
+            .ok_or(GraphInterfaceError::WrongGraphMlFormat)?;
+        let value_end = rest[1..]
+            .find(quote)
+            .ok_or(GraphInterfaceError::WrongGraphMlFormat)?
+            + 1;
+        attributes.push((key, xml_unescape(&rest[1..value_end])?));
+        rest = rest[value_end + 1..].trim_start();
+    }
+    Ok((name, attributes))
+}
+
+// Разбиение текста XML на события (объявления, комментарии и инструкции пропускаются)
+fn xml_events(text: &str) -> Result<Vec<XmlEvent>, GraphInterfaceError> {
+    let mut events = Vec::new();
+    let mut depth = 0usize;
+    let mut rest = text;
+    while let Some(start) = rest.find('<') {
+        let text = &rest[..start];
+        if !text.trim().is_empty() {
+            events.push(XmlEvent::Text(xml_unescape(text)?));
+        }
+        rest = &rest[start..];
+        // Тег заканчивается первой подходящей последовательностью символов
+        let terminator = if rest.starts_with("<!--") {
+            "-->"
+        } else if rest.starts_with("<![CDATA[") {
+            "]]>"
+        } else if rest.starts_with("<?") {
+            "?>"
+        } else {
+            ">"
+        };
+        let end = rest
+            .find(terminator)
+            .ok_or(GraphInterfaceError::WrongGraphMlFormat)?;
+        let tag = &rest[1..end];
+        rest = &rest[end + terminator.len()..];
+        if let Some(data) = tag.strip_prefix("![CDATA[") {
+            events.push(XmlEvent::Text(String::from(data)));
+        } else if tag.starts_with('!') || tag.starts_with('?') {
+            continue;
+        } else if tag.starts_with('/') {
+            depth = depth
+                .checked_sub(1)
+                .ok_or(GraphInterfaceError::WrongGraphMlFormat)?;
+            events.push(XmlEvent::End);
+        } else {
+            let empty = tag.ends_with('/');
+            let (name, attributes) = xml_start_tag(tag.trim_end_matches('/').trim())?;
+            if !empty {
+                depth += 1;
+            }
+            events.push(XmlEvent::Start {
+                name,
+                attributes,
+                empty,
+            });
+        }
+    }
+    if depth != 0 || !rest.trim().is_empty() {
+        return Err(GraphInterfaceError::WrongGraphMlFormat);
+    }
+    Ok(events)
+}
+
+// Ключ данных GraphML
+struct GraphMlKey {
+    id: String,              // идентификатор ключа
+    domain: String,          // к чему относится ключ: "node", "edge", "graph" или "all"
+    name: Option<String>,    // имя атрибута (attr.name)
+    is_float: bool,          // задан ли дробный тип атрибута (attr.type)
+    default: Option<String>, // значение по умолчанию
+}
+
+// Данные вершины или ребра GraphML: ключи и значения
+type GraphMlData = Vec<(String, String)>;
+
+// Создание графа из файла в формате GraphML: метка вершины берётся из ключа "label"
+// (или из первых данных вершины), вес ребра — из ключа "weight"; остальные данные,
+// а также неизвестные элементы и атрибуты игнорируются
+pub fn graph_from_graphml<I, Reader>(
+    mut reader: Reader,
+) -> Result<Graph<I, EdgeWeights>, GraphError>
+where
+    I: VertexKey,
+    Reader: BufRead,
+{
+    let mut text = String::new();
+    reader.read_to_string(&mut text)?;
+    let events = xml_events(&text)?;
+    let attribute = |attributes: &[(String, String)], key: &str| {
+        attributes
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.clone())
+    };
+
+    // Ключи, направленность графа, вершины с данными и рёбра с данными
+    let mut keys: Vec<GraphMlKey> = Vec::new();
+    let mut is_directed = None;
+    let mut vertices: Vec<(String, GraphMlData)> = Vec::new();
+    let mut edges: Vec<(String, String, GraphMlData)> = Vec::new();
+    // Открытые элементы: имя и, для элемента данных, его ключ и накопленный текст
+    let mut stack: Vec<(String, Option<(String, String)>)> = Vec::new();
+    for event in events {
+        match event {
+            XmlEvent::Start {
+                name,
+                attributes,
+                empty,
+            } => {
+                let parent = stack.last().map(|(name, _)| name.as_str());
+                let mut data = None;
+                match name.as_str() {
+                    "key" => keys.push(GraphMlKey {
+                        id: attribute(&attributes, "id")
+                            .ok_or(GraphInterfaceError::WrongGraphMlFormat)?,
+                        domain: attribute(&attributes, "for").unwrap_or_else(|| "all".into()),
+                        name: attribute(&attributes, "attr.name"),
+                        is_float: matches!(
+                            attribute(&attributes, "attr.type").as_deref(),
+                            Some("float" | "double")
+                        ),
+                        default: None,
+                    }),
+                    // Направленность рёбер по умолчанию задаётся у первого графа
+                    "graph" if is_directed.is_none() => {
+                        is_directed = Some(
+                            attribute(&attributes, "edgedefault").as_deref() == Some("directed"),
+                        );
+                    }
+                    "node" => vertices.push((
+                        attribute(&attributes, "id")
+                            .ok_or(GraphInterfaceError::WrongGraphMlFormat)?,
+                        Vec::new(),
+                    )),
+                    "edge" => edges.push((
+                        attribute(&attributes, "source")
+                            .ok_or(GraphInterfaceError::WrongGraphMlFormat)?,
+                        attribute(&attributes, "target")
+                            .ok_or(GraphInterfaceError::WrongGraphMlFormat)?,
+                        Vec::new(),
+                    )),
+                    "data" | "default" if matches!(parent, Some("node" | "edge" | "key")) => {
+                        let key = attribute(&attributes, "key").unwrap_or_default();
+                        data = Some((key, String::new()));
+                    }
+                    _ => {}
+                }
+                if !empty {
+                    stack.push((name, data));
+                }
+            }
+            // Текст данных собирается вместе с текстом вложенных элементов
+            // (например, метки вершин в файлах yEd)
+            XmlEvent::Text(text) => {
+                if let Some((_, text_acc)) = stack.iter_mut().rev().find_map(|(_, d)| d.as_mut()) {
+                    text_acc.push_str(&text);
+                }
+            }
+            XmlEvent::End => {
+                let (name, data) = stack.pop().ok_or(GraphInterfaceError::WrongGraphMlFormat)?;
+                let parent = stack.last().map(|(name, _)| name.as_str());
+                if let Some((key, text)) = data {
+                    let value = (key, String::from(text.trim()));
+                    match (name.as_str(), parent) {
+                        ("data", Some("node")) => vertices.last_mut().unwrap().1.push(value),
+                        ("data", Some("edge")) => edges.last_mut().unwrap().2.push(value),
+                        ("default", Some("key")) => {
+                            keys.last_mut().unwrap().default = Some(value.1)
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        }
+    }
+    let is_directed = is_directed.ok_or(GraphInterfaceError::WrongGraphMlFormat)?;
+
+    // Ключи меток вершин и весов рёбер
+    let domain_keys = |domain: &str| {
+        keys.iter()
+            .filter(move |k| k.domain == domain || k.domain == "all")
+            .collect::<Vec<_>>()
+    };
+    let (node_keys, edge_keys) = (domain_keys("node"), domain_keys("edge"));
+    let label_key = node_keys
+        .iter()
+        .find(|k| k.name.as_deref() == Some("label"));
+    let weight_key = edge_keys
+        .iter()
+        .find(|k| matches!(&k.name, Some(name) if name.eq_ignore_ascii_case("weight")));
+    // Значение данных с заданным ключом или значение ключа по умолчанию
+    let data_value = |data: &[(String, String)], key: Option<&&GraphMlKey>| {
+        key.and_then(|k| {
+            attribute(data, &k.id)
+                .or_else(|| k.default.clone())
+                .filter(|v| !v.is_empty())
+        })
+    };
+    // Без ключа "label" меткой считаются первые данные вершины
+    let vertices: Vec<(String, Option<String>)> = vertices
+        .iter()
+        .map(|(id, data)| {
+            let label = match label_key {
+                Some(_) => data_value(data, label_key),
+                None => data
+                    .first()
+                    .map(|(_, v)| v.clone())
+                    .filter(|v| !v.is_empty()),
+            };
+            (id.clone(), label)
+        })
+        .collect();
+    let edges: Vec<(String, String, Option<String>)> = edges
+        .iter()
+        .map(|(from, to, data)| (from.clone(), to.clone(), data_value(data, weight_key)))
+        .collect();
+
+    // Граф взвешенный, если объявлен ключ веса или задан вес хотя бы у одного ребра
+    let is_weighted = weight_key.is_some() || edges.iter().any(|(_, _, w)| w.is_some());
+    let is_float_weights = matches!(weight_key, Some(k) if k.is_float) || has_float_weights(&edges);
+    graph_from_parts(
+        is_directed,
+        is_weighted,
+        is_float_weights,
+        &vertices,
+        &edges,
+    )
+}
+
+// Создание графа из списка рёбер: по одному ребру "u v" или "u v w" в строке,
+// строки, начинающиеся с '#' или '%', — комментарии; вершины — концы рёбер в порядке
+// появления, граф взвешенный, если вес есть у ребра в первой строке данных
+pub fn graph_from_edge_list<I, Reader>(
+    reader: Reader,
+    is_directed: bool,
+) -> Result<Graph<I, EdgeWeights>, GraphError>
+where
+    I: VertexKey,
+    Reader: BufRead,
+{
+    // Ошибка дополняется номером строки
+    let at_line = |n: usize, e: GraphError| GraphInterfaceError::AtLine {
+        line: n + 1,
+        source: Box::new(e),
+    };
+
+    // Вершины и рёбра с весами и номерами строк
+    let mut vertices: Vec<String> = Vec::new();
+    let mut vertex_set: BTreeSet<String> = BTreeSet::new();
+    let mut edges: Vec<(String, String, Option<String>)> = Vec::new();
+    let mut edge_lines: Vec<usize> = Vec::new();
+    let mut is_weighted = None;
+    for (n, line) in reader.lines().enumerate() {
+        let line_str = line.map_err(|e| at_line(n, e.into()))?;
+        let line_str = line_str.trim();
+        // Пустые строки и комментарии пропускаются
+        if line_str.is_empty() || line_str.starts_with(['#', '%']) {
+            continue;
+        }
+        let line_split: Vec<_> = line_str.split_ascii_whitespace().collect();
+        let weight = match line_split.len() {
+            2 => None,
+            3 => Some(String::from(line_split[2])),
+            _ => return Err(at_line(n, GraphInterfaceError::IncorrectArgumentCount.into()).into()),
+        };
+        // Все строки должны быть с весом или все без веса, как первая
+        let is_weighted = *is_weighted.get_or_insert(weight.is_some());
+        if is_weighted != weight.is_some() {
+            let e = if is_weighted {
+                GraphOperationError::UnweightedEdgeInWeightedGraph
+            } else {
+                GraphOperationError::WeightedEdgeInUnweightedGraph
+            };
+            return Err(at_line(n, e.into()).into());
+        }
+        for id in &line_split[..2] {
+            if vertex_set.insert(String::from(*id)) {
+                vertices.push(String::from(*id));
+            }
+        }
+        edges.push((
+            String::from(line_split[0]),
+            String::from(line_split[1]),
+            weight,
+        ));
+        edge_lines.push(n);
+    }
+    let is_weighted = is_weighted.ok_or(GraphInterfaceError::EmptyFile)?;
+
+    let mut g = Some(Graph::new(
+        is_directed,
+        is_weighted,
+        has_float_weights(&edges),
+    ));
+    for id in &vertices {
+        add_vertex(&[id], &mut g)?;
+    }
+    for ((from, to, weight), n) in edges.iter().zip(edge_lines) {
+        let mut args = vec![&from[..], &to[..]];
+        if let Some(w) = weight {
+            args.push(&w[..]);
+        }
+        add_edge(&args, &mut g).map_err(|e| at_line(n, e))?;
+    }
+    Ok(g.unwrap())
+}
+
+// Начинается ли текст (первая непустая строка) с заголовка собственного формата
+fn has_native_header<I>(text: &[u8]) -> bool
+where
+    I: VertexKey,
+{
+    // Пустой текст разбирается как собственный формат (с ошибкой пустого файла)
+    match text
+        .lines()
+        .map_while(Result::ok)
+        .find(|line| !line.trim().is_empty())
+    {
+        Some(line) => {
+            let line_split: Vec<_> = line.split_ascii_whitespace().collect();
+            new_graph::<I, EdgeWeights>(&line_split, &mut None).is_ok()
+        }
+        None => true,
+    }
+}
+
+// Формат файла графа
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum FileFormat {
+    Native,  // собственный формат приложения
+    Dot,     // формат DOT (Graphviz)
+    GraphMl, // формат GraphML
+}
+
+impl FileFormat {
+    // Определение формата по расширению файла
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("dot" | "gv") => Self::Dot,
+            Some("graphml") => Self::GraphMl,
+            _ => Self::Native,
+        }
+    }
+}
+
+// Граф, загруженный из файла, и расположение его вершин (если оно было в файле)
+pub type GraphWithPositions<I> = (Graph<I, EdgeWeights>, Option<VertexPositions<I>>);
+
+// Загрузка графа из файла в формате, определённом по расширению
+// (расположение вершин есть только в собственном формате, а файл без его заголовка
+// загружается как список рёбер)
+pub fn graph_from_path<I, P>(path: P) -> Result<GraphWithPositions<I>, GraphError>
+where
+    I: VertexKey,
+    P: AsRef<Path>,
+{
+    let path = path.as_ref();
+    let file = File::open(path)?;
+    graph_from_file_reader(BufReader::new(file), FileFormat::from_path(path))
+}
+
+// Загрузка графа из файла в заданном формате: файл без заголовка собственного формата
+// загружается как список рёбер ориентированного графа
+pub fn graph_from_file_reader<I, Reader>(
+    mut reader: Reader,
+    format: FileFormat,
+) -> Result<GraphWithPositions<I>, GraphError>
+where
+    I: VertexKey,
+    Reader: BufRead,
+{
+    if format != FileFormat::Native {
+        return graph_from_reader(reader, format);
+    }
+    let mut bytes = Vec::new();
+    reader.read_to_end(&mut bytes)?;
+    if has_native_header::<I>(&bytes) {
+        Graph::from_file_with_positions(&bytes[..])
+    } else {
+        Ok((graph_from_edge_list(&bytes[..], true)?, None))
+    }
+}
+
+// Загрузка графа в заданном формате из потока чтения
+pub fn graph_from_reader<I, Reader>(
+    reader: Reader,
+    format: FileFormat,
+) -> Result<GraphWithPositions<I>, GraphError>
+where
+    I: VertexKey,
+    Reader: BufRead,
+{
+    match format {
+        FileFormat::Native => Graph::from_file_with_positions(reader),
+        FileFormat::Dot => Ok((graph_from_dot(reader)?, None)),
+        FileFormat::GraphMl => Ok((graph_from_graphml(reader)?, None)),
+    }
+}
+
+// Сохранение графа в файл в формате, определённом по расширению
+// (расположение вершин сохраняется только в собственном формате)
+pub fn graph_to_path<I, P>(
+    g: &Graph<I, EdgeWeights>,
+    positions: Option<&VertexPositions<I>>,
+    path: P,
+) -> Result<(), GraphError>
+where
+    I: VertexKey,
+    P: AsRef<Path>,
+{
+    let path = path.as_ref();
+    let file = File::create(path)?;
+    let mut writer = BufWriter::new(file);
+    match FileFormat::from_path(path) {
+        FileFormat::Native => {
+            g.to_file(&mut writer)?;
+            if let Some(positions) = positions {
+                positions_to_file(positions, &mut writer)?;
+            }
+        }
+        FileFormat::Dot => g.to_dot(&mut writer)?,
+        FileFormat::GraphMl => g.to_graphml(&mut writer)?,
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{io, path::PathBuf};
+
+    use super::*;
+    use crate::graph::VertexId;
+
+    // Путь к временному файлу теста (имя включает номер процесса, чтобы одновременные
+    // запуски тестов не мешали друг другу)
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("graph_visualizer_{}_{}", std::process::id(), name))
+    }
+
+    // Загрузка графа из временного файла с заданным содержимым
+    fn load(name: &str, bytes: &[u8]) -> Result<GraphWithPositions<VertexId>, GraphError> {
+        let path = temp_path(name);
+        std::fs::write(&path, bytes).unwrap();
+        let result = graph_from_path(&path);
+        std::fs::remove_file(&path).unwrap();
+        result
+    }
+
+    // Ошибка без номера строки, в которой она произошла
+    fn root_error(e: GraphError) -> GraphError {
+        match e {
+            GraphError::InterfaceError(GraphInterfaceError::AtLine { source, .. }) => *source,
+            e => e,
+        }
+    }
+
+    // Граф из списка рёбер
+    fn edge_list(text: &str, is_directed: bool) -> Graph<VertexId, EdgeWeights> {
+        graph_from_edge_list(text.as_bytes(), is_directed).unwrap()
+    }
+
+    // Ошибка разбора списка рёбер и номер строки, в которой она произошла
+    fn edge_list_error(text: &str) -> (usize, GraphError) {
+        match graph_from_edge_list::<VertexId, _>(text.as_bytes(), true) {
+            Err(GraphError::InterfaceError(GraphInterfaceError::AtLine { line, source })) => {
+                (line, *source)
+            }
+            _ => panic!("нет ошибки с номером строки"),
+        }
+    }
+
+    fn id(name: &str) -> VertexId {
+        name.parse().unwrap()
+    }
+
+    #[test]
+    fn invalid_utf8_is_invalid_encoding() {
+        let files: [(&str, &[u8]); 4] = [
+            (
+                "native.txt",
+                b"directed unweighted int\nvertices\n\xff\xfe\n",
+            ),
+            ("edges.txt", b"1 2\n\xc3\x28 3\n"),
+            ("graph.dot", b"digraph { a -> \xff }"),
+            ("graph.graphml", b"<graphml>\xff</graphml>"),
+        ];
+        for (name, bytes) in files {
+            let e = load(name, bytes).unwrap_err();
+            assert!(
+                matches!(
+                    root_error(e),
+                    GraphError::InterfaceError(GraphInterfaceError::InvalidEncoding)
+                ),
+                "{}",
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn missing_file_is_not_found() {
+        let path = temp_path("missing.txt");
+        match graph_from_path::<VertexId, _>(&path) {
+            Err(GraphError::IOError(e)) => assert_eq!(e.kind(), io::ErrorKind::NotFound),
+            _ => panic!("файл не должен существовать"),
+        }
+    }
+
+    #[test]
+    fn saved_graph_is_loaded_back() {
+        let g: Graph<VertexId, EdgeWeights> =
+            Graph::from_file(&b"directed weighted int\nvertices\n1\n2\nedges\n1 2 5\n"[..])
+                .unwrap();
+        for name in ["saved.txt", "saved.dot", "saved.graphml"] {
+            let path = temp_path(name);
+            graph_to_path(&g, None, &path).unwrap();
+            let (loaded, _) = graph_from_path::<VertexId, _>(&path).unwrap();
+            std::fs::remove_file(&path).unwrap();
+            assert_eq!(loaded, g, "{}", name);
+        }
+    }
+
+    #[test]
+    fn edge_list_without_weights_is_unweighted() {
+        let g = edge_list("# комментарий\n3 1\n\n% ещё комментарий\n1 2\n", true);
+        assert!(g.get_is_directed());
+        assert!(!g.get_is_weighted());
+        assert_eq!(g.get_vertices().len(), 3);
+        assert!(g.get_edge(&id("3"), &id("1")).is_ok());
+        assert!(g.get_edge(&id("1"), &id("3")).is_err());
+
+        let g = edge_list("1 2\n", false);
+        assert!(!g.get_is_directed());
+        assert!(g.get_edge(&id("2"), &id("1")).is_ok());
+    }
+
+    #[test]
+    fn edge_list_with_weights_is_weighted() {
+        let g = edge_list("1 2 5\n2 3 -1\n", true);
+        assert!(g.get_is_weighted());
+        assert!(!g.get_is_float_weights());
+        assert_eq!(
+            g.get_edge(&id("1"), &id("2")).unwrap().weight,
+            Some(5.into())
+        );
+        // Один дробный вес делает дробными все веса
+        let g = edge_list("1 2 5\n2 3 0.5\n", true);
+        assert!(g.get_is_float_weights());
+        assert_eq!(
+            g.get_edge(&id("1"), &id("2")).unwrap().weight,
+            Some(5.0.into())
+        );
+    }
+
+    #[test]
+    fn edge_list_with_inconsistent_columns() {
+        assert!(matches!(
+            edge_list_error("1 2 5\n# комментарий\n2 3\n"),
+            (
+                3,
+                GraphError::OperationError(GraphOperationError::UnweightedEdgeInWeightedGraph)
+            )
+        ));
+        assert!(matches!(
+            edge_list_error("1 2\n2 3 5\n"),
+            (
+                2,
+                GraphError::OperationError(GraphOperationError::WeightedEdgeInUnweightedGraph)
+            )
+        ));
+        for text in ["1\n", "1 2\n2 3 4 5\n"] {
+            assert!(matches!(
+                edge_list_error(text),
+                (
+                    _,
+                    GraphError::InterfaceError(GraphInterfaceError::IncorrectArgumentCount)
+                )
+            ));
+        }
+        // Неправильный вес
+        assert!(matches!(edge_list_error("1 2 5\n2 3 x\n"), (2, _)));
+    }
+
+    #[test]
+    fn empty_edge_list_is_error() {
+        assert!(matches!(
+            graph_from_edge_list::<VertexId, _>("# только комментарий\n\n".as_bytes(), true),
+            Err(GraphError::InterfaceError(GraphInterfaceError::EmptyFile))
+        ));
+    }
+
+    #[test]
+    fn too_wide_weight_range_is_error() {
+        let text = "directed weighted float\nvertices\na\nb\nedges\na b 1\n";
+        let mut g = Some(Graph::<VertexId, _>::from_file(text.as_bytes()).unwrap());
+        assert!(matches!(
+            add_edge_random(&["b", "a", "rand(-3e38,3e38)"], &mut g, None),
+            Err(GraphError::InterfaceError(
+                GraphInterfaceError::IncorrectArgument { i: 3 }
+            ))
+        ));
+        assert!(matches!(
+            add_edge_random(&["b", "a"], &mut g, Some(("-3e38", "3e38"))),
+            Err(GraphError::InterfaceError(
+                GraphInterfaceError::IncorrectWeightRange
+            ))
+        ));
+        assert!(matches!(
+            set_random_weights("-3e38", "3e38", &mut g),
+            Err(GraphError::InterfaceError(
+                GraphInterfaceError::IncorrectWeightRange
+            ))
+        ));
+        assert!(g.unwrap().get_edge(&id("b"), &id("a")).is_err());
+
+        // Крайние значения целых весов допустимы
+        let text = "directed weighted int\nvertices\na\nb\nedges\na b 1\n";
+        let mut g = Some(Graph::<VertexId, _>::from_file(text.as_bytes()).unwrap());
+        set_random_weights("-2147483648", "2147483647", &mut g).unwrap();
+    }
+}
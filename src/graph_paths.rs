@@ -0,0 +1,320 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::{
+    graph::{Edge, EdgeWeight, EdgeWeights, Graph, VertexKey},
+    graph_errors::{GraphAlgorithmError, GraphError, GraphInterfaceError, GraphOperationError},
+};
+
+// Состояние выполнения алгоритма поиска кратчайшего пути
+#[derive(Debug, Clone)]
+pub enum ShortestPathState<I, W>
+where
+    I: VertexKey,
+    W: EdgeWeight,
+{
+    NotStarted,
+    Step(ShortestPathData<I, W>),
+    Finished(ShortestPathData<I, W>),
+}
+
+// Данные текущего состояния алгоритма Дейкстры
+#[derive(Debug, Clone)]
+pub struct ShortestPathData<I, W>
+where
+    I: VertexKey,
+    W: EdgeWeight,
+{
+    t: I,
+    g: Graph<I, W>,
+    dist: BTreeMap<I, W>,
+    parent: BTreeMap<I, I>,
+    settled: BTreeSet<I>,
+    frontier: BTreeSet<(W, I)>,
+    last_vertex: Option<I>,
+    path_edges: BTreeSet<(I, I)>,
+}
+
+impl<I, W> ShortestPathData<I, W>
+where
+    I: VertexKey,
+    W: EdgeWeight,
+{
+    pub fn get_dist(&self, i: &I) -> Option<&W> {
+        self.dist.get(i)
+    }
+
+    pub fn get_last_vertex(&self) -> &Option<I> {
+        &self.last_vertex
+    }
+
+    pub fn get_path_edges(&self) -> &BTreeSet<(I, I)> {
+        &self.path_edges
+    }
+
+    // Расстояние до конечной вершины, если она достижима
+    pub fn get_distance(&self) -> Option<&W> {
+        if self.settled.contains(&self.t) {
+            self.dist.get(&self.t)
+        } else {
+            None
+        }
+    }
+
+    // Достигнута ли вершина, но ещё не обработана
+    pub fn is_in_frontier(&self, i: &I) -> bool {
+        self.dist.contains_key(i) && !self.settled.contains(i)
+    }
+
+    // Входит ли ребро в текущее дерево кратчайших путей (ребро от предка к вершине)
+    pub fn is_tree_edge(&self, i: &I, to: &I) -> bool {
+        self.parent.get(to) == Some(i)
+            || (!self.g.get_is_directed() && self.parent.get(i) == Some(to))
+    }
+}
+
+// Алгоритм Дейкстры
+pub fn algorithm_step<I>(
+    state: ShortestPathState<I, EdgeWeights>,
+    g: &Option<Graph<I, EdgeWeights>>,
+    s_str: &str,
+    t_str: &str,
+) -> Result<ShortestPathState<I, EdgeWeights>, GraphError>
+where
+    I: VertexKey,
+{
+    match state {
+        ShortestPathState::NotStarted => {
+            // Графа нет
+            if g.is_none() {
+                return Err(GraphInterfaceError::GraphNotExist.into());
+            }
+            let g = g.as_ref().unwrap();
+            let zero: EdgeWeights = if g.get_is_float_weights() {
+                0.0.into()
+            } else {
+                0.into()
+            };
+
+            // Граф невзвешенный или в нём есть рёбра с отрицательным весом
+            if !g.get_is_weighted() {
+                return Err(GraphAlgorithmError::GraphNotWeighted.into());
+            }
+            let has_negative_weight = g
+                .get_vertices()
+                .keys()
+                .flat_map(|i| g.get_edge_list(i).unwrap())
+                .any(|e| e.weight.as_ref().unwrap() < &zero);
+            if has_negative_weight {
+                return Err(GraphAlgorithmError::NegativeWeight.into());
+            }
+
+            let s: I = s_str
+                .parse()
+                .map_err(|_| GraphInterfaceError::IncorrectArgument { i: 1 })?;
+            let t: I = t_str
+                .parse()
+                .map_err(|_| GraphInterfaceError::IncorrectArgument { i: 2 })?;
+            if !g.get_vertices().contains_key(&s) || !g.get_vertices().contains_key(&t) {
+                return Err(GraphOperationError::SomeVerticesNotFound.into());
+            }
+
+            // Данные состояния, достигнута только начальная вершина
+            let data = ShortestPathData {
+                t,
+                g: g.clone(),
+                dist: BTreeMap::from([(s.clone(), zero.clone())]),
+                parent: BTreeMap::new(),
+                settled: BTreeSet::new(),
+                frontier: BTreeSet::from([(zero, s)]),
+                last_vertex: None,
+                path_edges: BTreeSet::new(),
+            };
+            // Алгоритм запущен
+            Ok(ShortestPathState::Step(data))
+        }
+        ShortestPathState::Step(mut data) => {
+            // Граф не должен изменяться во время выполнения алгоритма
+            if g.as_ref().map(Graph::get_revision) != Some(data.g.get_revision()) {
+                return Err(GraphAlgorithmError::GraphModifiedDuringAlgorithm.into());
+            }
+            // Достигнутая необработанная вершина с минимальным расстоянием
+            let (d, i) = match data.frontier.iter().next().cloned() {
+                Some(x) => x,
+                None => {
+                    // Конечная вершина недостижима, завершение алгоритма
+                    data.last_vertex = None;
+                    return Ok(ShortestPathState::Finished(data));
+                }
+            };
+            data.frontier.remove(&(d.clone(), i.clone()));
+            data.settled.insert(i.clone());
+            data.last_vertex = Some(i.clone());
+
+            // Достигнута конечная вершина, восстановление пути и завершение алгоритма
+            if i == data.t {
+                let is_directed = data.g.get_is_directed();
+                let mut to = data.t.clone();
+                while let Some(i) = data.parent.get(&to) {
+                    data.path_edges.insert((i.clone(), to.clone()));
+                    if !is_directed {
+                        data.path_edges.insert((to.clone(), i.clone()));
+                    }
+                    to = i.clone();
+                }
+                return Ok(ShortestPathState::Finished(data));
+            }
+
+            // Релаксация рёбер, исходящих из вершины
+            for Edge { to, weight } in data.g.get_edge_list(&i).unwrap() {
+                if data.settled.contains(to) {
+                    continue;
+                }
+                let new_d = d.clone().checked_add(weight.as_ref().unwrap().clone())?;
+                if let Some(old_d) = data.dist.get(to) {
+                    if &new_d >= old_d {
+                        continue;
+                    }
+                    data.frontier.remove(&(old_d.clone(), to.clone()));
+                }
+                data.dist.insert(to.clone(), new_d.clone());
+                data.parent.insert(to.clone(), i.clone());
+                data.frontier.insert((new_d, to.clone()));
+            }
+            Ok(ShortestPathState::Step(data))
+        }
+        ShortestPathState::Finished(_) => {
+            // Сброс состояния
+            Ok(ShortestPathState::NotStarted)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::VertexId;
+
+    type TestGraph = Option<Graph<VertexId, EdgeWeights>>;
+
+    // Идентификатор вершины
+    fn id(name: &str) -> VertexId {
+        name.parse().unwrap()
+    }
+
+    // Граф из текста в собственном формате
+    fn graph(text: &str) -> TestGraph {
+        Some(Graph::from_file(text.as_bytes()).unwrap())
+    }
+
+    // Ошибка алгоритма при запуске или выполнении
+    fn algorithm_error(g: &TestGraph, s: &str, t: &str) -> GraphAlgorithmError {
+        match run(g, s, t) {
+            Err(GraphError::AlgorithmError(e)) => e,
+            _ => panic!("нет ошибки алгоритма"),
+        }
+    }
+
+    // Выполнение алгоритма до конца
+    fn run(
+        g: &TestGraph,
+        s: &str,
+        t: &str,
+    ) -> Result<ShortestPathData<VertexId, EdgeWeights>, GraphError> {
+        let mut state = algorithm_step(ShortestPathState::NotStarted, g, s, t)?;
+        loop {
+            state = algorithm_step(state, g, s, t)?;
+            if let ShortestPathState::Finished(data) = state {
+                return Ok(data);
+            }
+        }
+    }
+
+    #[test]
+    fn overflowing_distance_is_error() {
+        let g = graph(
+            "directed weighted int\nvertices\ns\na\nt\nedges\ns a 2000000000\na t 2000000000\n",
+        );
+        assert!(matches!(
+            run(&g, "s", "t"),
+            Err(GraphError::AlgorithmError(
+                GraphAlgorithmError::WeightOverflow
+            ))
+        ));
+    }
+
+    #[test]
+    fn negative_and_missing_weights_are_rejected() {
+        let g = graph("directed weighted int\nvertices\ns\na\nt\nedges\ns a 2\na t -1\n");
+        assert!(matches!(
+            algorithm_error(&g, "s", "t"),
+            GraphAlgorithmError::NegativeWeight
+        ));
+        let g = graph("directed unweighted int\nvertices\ns\nt\nedges\ns t\n");
+        assert!(matches!(
+            algorithm_error(&g, "s", "t"),
+            GraphAlgorithmError::GraphNotWeighted
+        ));
+    }
+
+    #[test]
+    fn unreachable_target_has_no_distance() {
+        // Дуга ведёт из конечной вершины, а не в неё
+        let g = graph("directed weighted int\nvertices\ns\na\nt\nedges\ns a 1\nt s 1\n");
+        let data = run(&g, "s", "t").unwrap();
+        assert_eq!(data.get_distance(), None);
+        assert_eq!(data.get_dist(&id("a")), Some(&1.into()));
+        assert_eq!(data.get_dist(&id("t")), None);
+        assert!(data.get_path_edges().is_empty());
+    }
+
+    #[test]
+    fn undirected_path_edges_in_both_directions() {
+        let g =
+            graph("undirected weighted float\nvertices\ns\na\nt\nedges\ns a 1.5\na t 1\ns t 3\n");
+        let data = run(&g, "t", "s").unwrap();
+        assert_eq!(data.get_distance(), Some(&2.5.into()));
+        let edges = |names: &[(&str, &str)]| -> BTreeSet<_> {
+            names.iter().map(|(i, j)| (id(i), id(j))).collect()
+        };
+        assert_eq!(
+            data.get_path_edges(),
+            &edges(&[("t", "a"), ("a", "t"), ("a", "s"), ("s", "a")])
+        );
+        // Ребро дерева кратчайших путей определяется в обоих направлениях
+        assert!(data.is_tree_edge(&id("t"), &id("a")));
+        assert!(data.is_tree_edge(&id("a"), &id("t")));
+        assert!(data.is_tree_edge(&id("s"), &id("a")));
+        assert!(!data.is_tree_edge(&id("s"), &id("t")));
+    }
+
+    #[test]
+    fn directed_tree_edges_go_from_parent() {
+        let g = graph(
+            "directed weighted int\nvertices\ns\na\nb\nt\nedges\ns a 1\ns b 4\na b 1\nb t 1\n",
+        );
+        let data = run(&g, "s", "t").unwrap();
+        assert_eq!(data.get_distance(), Some(&3.into()));
+        assert_eq!(data.get_last_vertex(), &Some(id("t")));
+        assert!(data.is_tree_edge(&id("s"), &id("a")));
+        assert!(data.is_tree_edge(&id("a"), &id("b")));
+        assert!(!data.is_tree_edge(&id("b"), &id("a")));
+        assert!(!data.is_tree_edge(&id("s"), &id("b")));
+    }
+
+    #[test]
+    fn graph_modified_during_algorithm() {
+        let mut g = graph("directed weighted int\nvertices\ns\na\nt\nedges\ns a 1\na t 1\n");
+        let state = algorithm_step(ShortestPathState::NotStarted, &g, "s", "t").unwrap();
+        let state = algorithm_step(state, &g, "s", "t").unwrap();
+        g.as_mut()
+            .unwrap()
+            .set_edge_weight(&id("a"), &id("t"), Some(5.into()))
+            .unwrap();
+        assert!(matches!(
+            algorithm_step(state, &g, "s", "t"),
+            Err(GraphError::AlgorithmError(
+                GraphAlgorithmError::GraphModifiedDuringAlgorithm
+            ))
+        ));
+    }
+}
@@ -1,40 +1,203 @@
 use std::{
-    collections::BTreeMap,
-    f32::consts::{FRAC_1_SQRT_2, SQRT_2},
+    collections::{BTreeMap, BTreeSet},
+    f32::consts::{FRAC_1_SQRT_2, FRAC_PI_2, SQRT_2, TAU},
     mem::swap,
+    time::Instant,
 };
 
-use femtovg::{renderer::OpenGl, Align, Baseline, Canvas, Color, FontId, Paint, Path};
+use femtovg::{renderer::OpenGl, Align, Baseline, Canvas, Color, FontId, Paint, Path, Transform2D};
 use rand::{distributions::Uniform, prelude::ThreadRng, Rng};
 
 use crate::{
-    graph::{Edge, EdgeWeight, Graph, VertexKey},
+    graph::{parallel_numbers, Edge, EdgeWeight, Graph, VertexKey, VertexPositions},
+    graph_algorithms::GraphAlgorithmState,
     graph_errors::GraphOperationError,
-    graph_flows::AlgorithmState,
-    quad_tree,
+    graph_flows::{AlgorithmData, AlgorithmState, MatchingState},
+    graph_geometry::{
+        antiparallel_edges, arrowhead_points, curve_arrow_tip, curve_control_point, edge_bend,
+        edge_flows, edge_label_font_size, edge_label_position, edge_label_text, edge_label_weight,
+        edge_width_part, incident_edge_count, line_arrow_tip, logical_to_physical, loop_direction,
+        loop_fan_direction, loop_geometry, loop_label_offset, loop_label_shift, mix_colors,
+        point_circle_distance, point_curve_distance, point_segment_distance, vertex_text,
+        weight_range, LoopGeometry, FRONTIER_COLOR, MATCHING_COLOR, MAX_EDGE_WIDTH_COEFF,
+        MIN_CUT_COLOR, MIN_CUT_FILL_PART, REJECTED_EDGE_ALPHA, SINK_COLOR, SOURCE_COLOR,
+        TERMINAL_MARKER_COEFF, TERMINAL_RING_COEFF,
+    },
+    graph_layout::{edge_indices, ForceLayout},
+    graph_paths::{ShortestPathData, ShortestPathState},
+    graph_svg::SvgStyle,
+    graph_trees::SpanningTreeState,
+    quad_tree::MIN_AREA_SIZE,
+    strings::tr,
 };
 
+// Минимальный и максимальный масштаб
+const MIN_GRAPH_SCALE: f32 = 0.25;
+const MAX_GRAPH_SCALE: f32 = 16.0;
+
+// Пороги диаметра вершины на экране (в логических пикселях) для уровней детализации:
+// по умолчанию для скрытия текста вершин, для скрытия подписей рёбер
+// и для перехода к упрощённой отрисовке
+const DEFAULT_LOD_LABEL_DIAMETER: f32 = 12.0;
+const LOD_EDGE_LABEL_DIAMETER: f32 = 16.0;
+const LOD_SIMPLE_RENDER_DIAMETER: f32 = 4.0;
+// Минимальный размер шрифта подписи ребра на экране (в логических пикселях), при меньшем
+// размере подпись не выводится (например, маленькие подписи потока)
+const MIN_EDGE_LABEL_FONT_SIZE: f32 = 6.0;
+
+// Половина стороны квадрата, в котором располагаются вершины при готовых расположениях
+// (как при сбросе изображения)
+const LAYOUT_HALF_SIZE: f32 = 0.5;
+
+// Длительность перемещения камеры к найденной вершине и пульсации её кольца после
+// перемещения (в секундах), период пульсации (в секундах) и масштаб, до которого
+// увеличивается изображение при перемещении
+const FOCUS_MOVE_DURATION: f32 = 0.3;
+const FOCUS_PULSE_DURATION: f32 = 1.0;
+const FOCUS_PULSE_PERIOD: f32 = 0.5;
+const FOCUS_MIN_ZOOM: f32 = 2.0;
+
+// Цвет отрисовки из компонент от 0 до 1
+fn rgba_color([r, g, b, a]: [f32; 4]) -> Color {
+    Color::rgbaf(r, g, b, a)
+}
+
+// Ребро (с номером среди параллельных ему рёбер) и прямоугольник его подписи на экране
+type EdgeLabelRect<I> = ((I, I, usize), [f32; 4]);
+
+// Данные кадра, общие для отрисовки рёбер и вершин
+struct FrameData<'a, I, W>
+where
+    I: VertexKey,
+    W: EdgeWeight,
+{
+    g: &'a Graph<I, W>,                               // граф
+    g_algorithm_state: &'a GraphAlgorithmState<I, W>, // состояние выполнения алгоритма
+    flow_data: Option<&'a AlgorithmData<I, W>>,       // данные нахождения потока
+    path_data: Option<&'a ShortestPathData<I, W>>,    // данные алгоритма Дейкстры
+    matching_edges: Option<&'a BTreeSet<(I, I)>>,     // рёбра найденного паросочетания
+    loop_dirs: BTreeMap<I, (f32, f32)>,               // направления рёбер-петель
+    weight_range: Option<(f32, f32)>,                 // минимальный и максимальный веса рёбер
+    animation_time: Option<f32>,                      // время анимации потока
+    vertex_radius: f32,                               // радиус вершины
+    scale_coeff: f32,                                 // коэффициент масштаба
+    line_width: f32,                                  // толщина линий
+    full_render: bool,                                // полная ли отрисовка
+    show_edge_labels: bool,                           // выводить ли подписи рёбер
+    show_vertex_labels: bool,                         // выводить ли текст вершин
+    min_label_font_size: f32,                         // минимальный размер шрифта подписей рёбер
+}
+
+// Фокусировка камеры на вершине: плавное перемещение камеры, затем пульсация кольца вершины
+struct Focus<I> {
+    vertex: I,               // вершина
+    start: Instant,          // время начала перемещения камеры
+    start_shift: (f32, f32), // сдвиг камеры в начале перемещения
+    start_zoom: f32,         // масштаб в начале перемещения
+    moved: bool,             // завершено ли перемещение камеры
+}
+
+// Форма ребра для вычисления точек вдоль него
+#[derive(Clone, Copy)]
+enum EdgeShape {
+    Line((f32, f32), (f32, f32)),              // отрезок: начало и конец
+    Curve((f32, f32), (f32, f32), (f32, f32)), // кривая Безье: начало, контрольная точка, конец
+    Loop((f32, f32), f32, f32),                // петля: центр, радиус и угол начальной точки
+}
+
+impl EdgeShape {
+    // Точка ребра с параметром t от 0 (начало) до 1 (конец)
+    fn point(&self, t: f32) -> (f32, f32) {
+        match *self {
+            Self::Line(from, to) => (from.0 + (to.0 - from.0) * t, from.1 + (to.1 - from.1) * t),
+            Self::Curve(from, control, to) => (
+                (1.0 - t).powi(2) * from.0 + 2.0 * t * (1.0 - t) * control.0 + t.powi(2) * to.0,
+                (1.0 - t).powi(2) * from.1 + 2.0 * t * (1.0 - t) * control.1 + t.powi(2) * to.1,
+            ),
+            Self::Loop(center, radius, start_angle) => {
+                let angle = start_angle + TAU * t;
+                (
+                    center.0 + radius * angle.cos(),
+                    center.1 + radius * angle.sin(),
+                )
+            }
+        }
+    }
+
+    // Длина ребра (для кривой Безье — приближённая: среднее длин хорды и ломаной
+    // через контрольную точку)
+    fn length(&self) -> f32 {
+        let dist =
+            |a: (f32, f32), b: (f32, f32)| ((b.0 - a.0).powi(2) + (b.1 - a.1).powi(2)).sqrt();
+        match *self {
+            Self::Line(from, to) => dist(from, to),
+            Self::Curve(from, control, to) => {
+                (dist(from, to) + dist(from, control) + dist(control, to)) / 2.0
+            }
+            Self::Loop(_, radius, _) => TAU * radius,
+        }
+    }
+}
+
 // Структура для отрисовки графа
 pub struct GraphRenderer<I>
 where
     I: VertexKey,
 {
-    front_color: Color,                   // основной цвет
-    back_color: Color,                    // фоновый цвет
-    center_gravity: f32,                  // гравитация к центру
-    repulsive_force: f32,                 // сила отталкивания вершин
-    time_step: f32,                       // cкорость изменений
-    theta: f32,                           // погрешность симуляции
-    full_render: bool,                    // полная отрисовка
-    updates_stopped: bool,                // прекращены ли обновления изображения графа
-    vertices: BTreeMap<I, (f32, f32)>,    // координаты вершин
-    rng: ThreadRng,                       // генератор случайных чисел
-    mouse_press: Option<(f32, f32)>,      // текущие координаты нажатия мыши
-    mouse_press_prev: Option<(f32, f32)>, // предыдущие координаты нажатия мыши
-    mouse_dragging: bool,                 // нажата ли мышь
-    dragging_vertex: Option<I>,           // текущая перемещаемая вершина
-    zoom: f32,                            // коэффициент масштабирования
-    center_shift: (f32, f32),             // сдвиг отображаемой части изображения от центра
+    front_color: Color,                               // основной цвет
+    back_color: Color,                                // фоновый цвет
+    selection_color: Color,                           // цвет выделения
+    layout: ForceLayout,                              // вычисление сил, действующих на вершины
+    time_step: f32,                                   // cкорость изменений (за 1/60 секунды)
+    full_render: bool,                                // полная отрисовка
+    updates_stopped: bool,                            // прекращены ли обновления изображения графа
+    vertices: VertexPositions<I>,                     // координаты вершин
+    new_vertex_positions: VertexPositions<I>, // координаты вершин, которые ещё не появились в графе
+    rng: ThreadRng,                           // генератор случайных чисел
+    mouse_press: Option<(f32, f32)>,          // текущие координаты нажатия мыши
+    mouse_press_prev: Option<(f32, f32)>,     // предыдущие координаты нажатия мыши
+    mouse_dragging: bool,                     // нажата ли мышь
+    dragging_vertex: Option<I>,               // текущая перемещаемая вершина
+    pinned_vertices: BTreeSet<I>,             // закреплённые вершины
+    pin_toggle_requested: bool,               // запрошено ли закрепление вершины под курсором
+    selected_vertex: Option<I>,               // вершина, выбранная нажатием мышью
+    selected_vertices: BTreeSet<I>,           // вершины, выделенные прямоугольником
+    selection_rect: Option<((f32, f32), (f32, f32))>, // углы прямоугольника выделения в окне
+    edge_drag_from: Option<I>,                // вершина, из которой протягивается новое ребро
+    view: Option<(Transform2D, f32)>, // преобразование координат и радиус вершины при отрисовке
+    line_width: f32,                  // толщина линий полной отрисовки при последней отрисовке
+    zoom: f32,                        // коэффициент масштабирования
+    center_shift: (f32, f32),         // сдвиг отображаемой части изображения от центра
+    last_update_time: Option<Instant>, // время последнего обновления координат вершин
+    settle_epsilon: f32,              // порог смещения вершин для остановки симуляции
+    settle_frame_cnt: u32,            // количество кадров подряд со смещением ниже порога
+    settled: bool,                    // остановлена ли симуляция из-за сходимости
+    show_edge_labels: bool,           // выводить ли подписи рёбер
+    edge_label_offset: f32,           // отступ подписей от рёбер (в радиусах вершины)
+    arrow_size: f32,                  // длина стрелок дуг (в радиусах вершины)
+    edge_width_by_weight: bool,       // зависит ли толщина рёбер от их веса
+    show_grid: bool,                  // выводить ли сетку
+    show_cursor_coords: bool,         // выводить ли координаты курсора
+    animate_flow: bool,               // анимировать ли направление потока по дополняющему пути
+    flow_animated: bool,              // была ли анимация потока в последнем кадре
+    animation_start: Instant,         // время начала отсчёта анимации
+    color_by_component: bool,         // раскрашивать ли вершины по компонентам связности
+    components: BTreeMap<I, usize>,   // номера компонент связности вершин
+    size_by_degree: bool,             // зависит ли размер вершин от их степени
+    highlighted_edges: BTreeSet<(I, I)>, // выделенные рёбра (например, найденного цикла)
+    vertex_numbers: BTreeMap<I, usize>, // номера вершин (например, в топологическом порядке)
+    radius_coeffs: BTreeMap<I, f32>,  // множители радиусов вершин по степеням
+    component_colors: Vec<Color>,     // цвета компонент связности
+    coords: Vec<(f32, f32)>,          // копия координат вершин в порядке идентификаторов
+    edge_indices: Option<Vec<(usize, usize)>>, // номера концов рёбер в порядке координат вершин
+    // (None, если их нужно пересчитать)
+    antiparallel_edges: Option<BTreeSet<(I, I)>>, // дуги, у которых есть противоположные дуги
+    // (None, если их нужно пересчитать)
+    hovered_edge: Option<(I, I, usize)>, // ребро под курсором (с номером среди параллельных)
+    edge_label_rects: Vec<EdgeLabelRect<I>>, // прямоугольники подписей рёбер на экране
+    lod_label_diameter: f32,             // порог диаметра вершин на экране для скрытия текста
+    focus: Option<Focus<I>>,             // фокусировка камеры на найденной вершине
+    scale_factor: f32,                   // отношение физических пикселей окна к логическим
 }
 
 impl<I> Default for GraphRenderer<I>
@@ -55,53 +218,179 @@ where
         Self {
             front_color: Color::rgbf(1.0, 1.0, 1.0),
             back_color: Color::rgbf(0.0, 0.0, 0.0),
-            center_gravity: 1.1,
-            repulsive_force: 0.1,
+            selection_color: Color::rgbf(1.0, 0.0, 0.0),
+            layout: ForceLayout::new(),
             time_step: 0.01,
-            theta: 0.0,
             full_render: true,
             updates_stopped: false,
             vertices: BTreeMap::new(),
+            new_vertex_positions: BTreeMap::new(),
             rng: rand::thread_rng(),
             mouse_press: None,
             mouse_press_prev: None,
             mouse_dragging: false,
             dragging_vertex: None,
+            pinned_vertices: BTreeSet::new(),
+            pin_toggle_requested: false,
+            selected_vertex: None,
+            selected_vertices: BTreeSet::new(),
+            selection_rect: None,
+            edge_drag_from: None,
+            view: None,
+            line_width: 0.0,
             zoom: 1.0,
             center_shift: (0.0, 0.0),
+            scale_factor: 1.0,
+            last_update_time: None,
+            settle_epsilon: 0.0001,
+            settle_frame_cnt: 0,
+            settled: false,
+            show_edge_labels: true,
+            edge_label_offset: 0.3,
+            arrow_size: 0.6,
+            edge_width_by_weight: false,
+            show_grid: false,
+            show_cursor_coords: false,
+            animate_flow: true,
+            flow_animated: false,
+            animation_start: Instant::now(),
+            color_by_component: false,
+            components: BTreeMap::new(),
+            size_by_degree: false,
+            highlighted_edges: BTreeSet::new(),
+            vertex_numbers: BTreeMap::new(),
+            radius_coeffs: BTreeMap::new(),
+            component_colors: Vec::new(),
+            coords: Vec::new(),
+            edge_indices: None,
+            antiparallel_edges: None,
+            hovered_edge: None,
+            edge_label_rects: Vec::new(),
+            lod_label_diameter: DEFAULT_LOD_LABEL_DIAMETER,
+            focus: None,
         }
     }
 
-    // Установка цвета
-    pub fn set_color(&mut self, front_color: Color) {
+    // Установка основного и фонового цветов
+    pub fn set_color(&mut self, front_color: Color, back_color: Color) {
         self.front_color = front_color;
-        if front_color.r > 0.5 {
-            // Светлый основной цвет, тёмный фоновый цвет
-            self.back_color = Color::rgb(53, 53, 53);
+        self.back_color = back_color;
+        self.update_component_colors();
+    }
+
+    // Установка цвета выделения
+    pub fn set_selection_color(&mut self, selection_color: Color) {
+        self.selection_color = selection_color;
+    }
+
+    // Включение или выключение раскраски вершин по компонентам связности
+    pub fn set_color_by_component(&mut self, color_by_component: bool) {
+        self.color_by_component = color_by_component;
+    }
+
+    // Пересчёт компонент связности (у ориентированного графа — сильной) после изменения графа
+    pub fn update_components<W>(&mut self, g: &Option<Graph<I, W>>)
+    where
+        W: EdgeWeight,
+    {
+        self.components = match g {
+            Some(g) => g.strong_components(),
+            None => BTreeMap::new(),
+        };
+        self.update_component_colors();
+    }
+
+    // Включение или выключение зависимости размера вершин от степени
+    pub fn set_size_by_degree(&mut self, size_by_degree: bool) {
+        self.size_by_degree = size_by_degree;
+    }
+
+    // Пересчёт множителей радиусов вершин после изменения графа:
+    // степени линейно отображаются на отрезок [MIN_RADIUS_COEFF, MAX_RADIUS_COEFF]
+    pub fn update_degrees<W>(&mut self, g: &Option<Graph<I, W>>)
+    where
+        W: EdgeWeight,
+    {
+        const MIN_RADIUS_COEFF: f32 = 0.7;
+        const MAX_RADIUS_COEFF: f32 = 2.0;
+
+        let degrees = g.as_ref().map(|g| g.degrees()).unwrap_or_default();
+        let min_degree = degrees.values().copied().min().unwrap_or(0);
+        let max_degree = degrees.values().copied().max().unwrap_or(0);
+        self.radius_coeffs = degrees
+            .into_iter()
+            .map(|(i, d)| {
+                // При одинаковых степенях всех вершин размер обычный
+                let coeff = if min_degree == max_degree {
+                    1.0
+                } else {
+                    let part = (d - min_degree) as f32 / (max_degree - min_degree) as f32;
+                    MIN_RADIUS_COEFF + (MAX_RADIUS_COEFF - MIN_RADIUS_COEFF) * part
+                };
+                (i, coeff)
+            })
+            .collect();
+    }
+
+    // Радиус вершины с учётом её степени
+    fn vertex_radius_of(&self, i: &I, vertex_radius: f32) -> f32 {
+        if self.size_by_degree {
+            vertex_radius * self.radius_coeffs.get(i).copied().unwrap_or(1.0)
         } else {
-            // Наоборот
-            self.back_color = Color::rgb(246, 245, 244);
+            vertex_radius
         }
     }
 
+    // Установка выделенных рёбер
+    pub fn set_highlighted_edges(&mut self, highlighted_edges: BTreeSet<(I, I)>) {
+        self.highlighted_edges = highlighted_edges;
+    }
+
+    // Установка номеров, выводимых рядом с вершинами
+    pub fn set_vertex_numbers(&mut self, vertex_numbers: BTreeMap<I, usize>) {
+        self.vertex_numbers = vertex_numbers;
+    }
+
+    // Генерация различимых на фоне цветов компонент связности
+    fn update_component_colors(&mut self) {
+        let component_cnt = self.components.values().max().map_or(0, |x| x + 1);
+        // На тёмном фоне светлые цвета, на светлом фоне тёмные
+        let lightness = if self.back_color.r > 0.5 { 0.4 } else { 0.65 };
+        // Оттенки смещаются на золотое сечение для максимального различия соседних компонент
+        const HUE_STEP: f32 = 0.618_034;
+        self.component_colors = (0..component_cnt)
+            .map(|c| Color::hsl((c as f32 * HUE_STEP).fract(), 0.75, lightness))
+            .collect();
+    }
+
     // Установка гравитации к центру
     pub fn set_center_gravity(&mut self, center_gravity: f32) {
-        self.center_gravity = center_gravity;
+        self.layout.set_center_gravity(center_gravity);
+        self.resume_simulation();
     }
 
     // Установка силы отталкивания вершин
     pub fn set_repulsive_force(&mut self, repulsive_force: f32) {
-        self.repulsive_force = repulsive_force;
+        self.layout.set_repulsive_force(repulsive_force);
+        self.resume_simulation();
     }
 
     // Установка cкорости изменений
     pub fn set_time_step(&mut self, time_step: f32) {
         self.time_step = time_step;
+        self.resume_simulation();
     }
 
     // Установка погрешности симуляции
     pub fn set_theta(&mut self, theta: f32) {
-        self.theta = theta;
+        self.layout.set_theta(theta);
+        self.resume_simulation();
+    }
+
+    // Установка порога смещения вершин для остановки симуляции
+    pub fn set_settle_epsilon(&mut self, settle_epsilon: f32) {
+        self.settle_epsilon = settle_epsilon;
+        self.resume_simulation();
     }
 
     // Включение или выключение полной отрисовки
@@ -109,34 +398,720 @@ where
         self.full_render = full_render;
     }
 
+    // Включение или выключение вывода подписей рёбер
+    pub fn set_show_edge_labels(&mut self, show_edge_labels: bool) {
+        self.show_edge_labels = show_edge_labels;
+    }
+
+    // Установка диаметра вершины на экране (в логических пикселях),
+    // ниже которого текст вершин не выводится
+    pub fn set_lod_label_diameter(&mut self, lod_label_diameter: f32) {
+        self.lod_label_diameter = lod_label_diameter;
+    }
+
+    // Установка отступа подписей от рёбер
+    pub fn set_edge_label_offset(&mut self, edge_label_offset: f32) {
+        self.edge_label_offset = edge_label_offset;
+    }
+
+    // Установка длины стрелок дуг
+    pub fn set_arrow_size(&mut self, arrow_size: f32) {
+        self.arrow_size = arrow_size;
+    }
+
+    // Включение или выключение зависимости толщины рёбер от их веса
+    pub fn set_edge_width_by_weight(&mut self, edge_width_by_weight: bool) {
+        self.edge_width_by_weight = edge_width_by_weight;
+    }
+
+    // Включение или выключение сетки
+    pub fn set_show_grid(&mut self, show_grid: bool) {
+        self.show_grid = show_grid;
+    }
+
+    // Включение или выключение вывода координат курсора
+    pub fn set_show_cursor_coords(&mut self, show_cursor_coords: bool) {
+        self.show_cursor_coords = show_cursor_coords;
+    }
+
+    // Установка флага анимации направления потока
+    pub fn set_animate_flow(&mut self, animate_flow: bool) {
+        self.animate_flow = animate_flow;
+    }
+
+    // Была ли анимация потока в последнем кадре или идёт фокусировка на вершине
+    // (тогда нужно продолжать отрисовку кадров)
+    pub fn is_animated(&self) -> bool {
+        self.flow_animated || self.focus.is_some()
+    }
+
     // Включение или отключение обновлений изображения графа
     pub fn set_updates_stopped(&mut self, stopped: bool) {
         self.updates_stopped = stopped;
+        self.resume_simulation();
+    }
+
+    // Прекращены ли обновления изображения графа
+    pub fn get_updates_stopped(&self) -> bool {
+        self.updates_stopped
+    }
+
+    // Возобновление симуляции после её остановки из-за сходимости
+    pub fn resume_simulation(&mut self) {
+        self.settled = false;
+        self.settle_frame_cnt = 0;
+    }
+
+    // Открепление всех вершин
+    pub fn unpin_all(&mut self) {
+        self.pinned_vertices.clear();
+        self.resume_simulation();
+    }
+
+    // Закрепление или открепление вершины под курсором при следующей отрисовке
+    pub fn toggle_pin_at_cursor(&mut self) {
+        self.pin_toggle_requested = true;
+        self.resume_simulation();
+    }
+
+    // Получение координат вершин
+    pub fn get_positions(&self) -> &VertexPositions<I> {
+        &self.vertices
+    }
+
+    // Параметры изображения графа в SVG по настройкам и масштабу последней отрисовки
+    // (None, если граф ещё не отрисован)
+    pub fn svg_style(&self) -> Option<SvgStyle<I>> {
+        let (transform, vertex_radius) = self.view?;
+        let rgba = |c: &Color| [c.r, c.g, c.b, c.a];
+        let vertex_radii = if self.size_by_degree {
+            self.vertices
+                .keys()
+                .map(|i| (i.clone(), self.vertex_radius_of(i, vertex_radius)))
+                .collect()
+        } else {
+            BTreeMap::new()
+        };
+        let vertex_colors = if self.color_by_component {
+            self.components
+                .iter()
+                .map(|(i, &c)| (i.clone(), rgba(&self.component_colors[c])))
+                .collect()
+        } else {
+            BTreeMap::new()
+        };
+        Some(SvgStyle {
+            front_color: rgba(&self.front_color),
+            back_color: rgba(&self.back_color),
+            selection_color: rgba(&self.selection_color),
+            scale: transform.average_scale(),
+            vertex_radius,
+            line_width: self.line_width,
+            arrow_size: self.arrow_size,
+            edge_label_offset: self.edge_label_offset,
+            show_edge_labels: self.show_edge_labels,
+            edge_width_by_weight: self.edge_width_by_weight,
+            vertex_radii,
+            vertex_colors,
+            highlighted_edges: self.highlighted_edges.clone(),
+        })
+    }
+
+    // Установка координат вершин (например, загруженных из файла)
+    pub fn set_positions(&mut self, positions: VertexPositions<I>) {
+        self.vertices.extend(positions);
+        self.resume_simulation();
+    }
+
+    // Установка координат вершины, которая появится в графе при следующем изменении
+    // (вместо случайных координат)
+    pub fn place_new_vertex(&mut self, i: I, position: (f32, f32)) {
+        self.new_vertex_positions.insert(i, position);
+    }
+
+    // Сброс данных о рёбрах после изменения графа: номеров концов рёбер (пересчитываются
+    // при обновлении), дуг с противоположными дугами (при отрисовке) и ребра под курсором
+    pub fn reset_edges(&mut self) {
+        self.edge_indices = None;
+        self.antiparallel_edges = None;
+        self.hovered_edge = None;
     }
 
     // Сброс изображения
     pub fn reset_image(&mut self) {
+        self.reset_layout();
+        self.reset_camera();
+    }
+
+    // Сброс расположения вершин
+    pub fn reset_layout(&mut self) {
         // Назначение случайных координат вершин
         let coord_distribution = Uniform::new(-0.5f32, 0.5);
         for (x, y) in self.vertices.values_mut() {
             *x = self.rng.sample(coord_distribution);
             *y = self.rng.sample(coord_distribution);
         }
-        // Сброс камеры
+        // Открепление вершин
+        self.pinned_vertices.clear();
+        self.resume_simulation();
+    }
+
+    // Расположение вершин по окружности в порядке идентификаторов (по часовой стрелке сверху)
+    pub fn layout_circular(&mut self) {
+        let n = self.vertices.len();
+        for (k, (x, y)) in self.vertices.values_mut().enumerate() {
+            // Одна вершина располагается в центре
+            if n == 1 {
+                *x = 0.0;
+                *y = 0.0;
+                break;
+            }
+            let angle = TAU * k as f32 / n as f32 - FRAC_PI_2;
+            *x = LAYOUT_HALF_SIZE * angle.cos();
+            *y = LAYOUT_HALF_SIZE * angle.sin();
+        }
+        self.finish_layout();
+    }
+
+    // Расположение вершин по строкам почти квадратной сетки в порядке идентификаторов
+    pub fn layout_grid(&mut self) {
+        let n = self.vertices.len();
+        let cols = usize::max(1, (n as f32).sqrt().ceil() as usize);
+        let rows = n.div_ceil(cols);
+        let step = Self::layout_step(usize::max(cols, rows));
+        let (shift_x, shift_y) = (
+            Self::layout_shift(cols, step),
+            Self::layout_shift(rows, step),
+        );
+        for (k, (x, y)) in self.vertices.values_mut().enumerate() {
+            *x = shift_x + step * (k % cols) as f32;
+            *y = shift_y + step * (k / cols) as f32;
+        }
+        self.finish_layout();
+    }
+
+    // Расположение вершин ациклического ориентированного графа по слоям сверху вниз:
+    // слой вершины — длина самого длинного пути до неё из истоков, вершины слоя
+    // располагаются равномерно по горизонтали; если в графе есть цикл (или граф
+    // неориентированный), то вершины располагаются по окружности
+    pub fn layout_layered<W>(&mut self, g: &Option<Graph<I, W>>)
+    where
+        W: EdgeWeight,
+    {
+        let (g, order) = match g.as_ref().map(|g| (g, g.topological_sort())) {
+            Some((g, Ok(order))) => (g, order),
+            _ => return self.layout_circular(),
+        };
+
+        // Слои вершин в порядке топологической сортировки
+        let mut layer: BTreeMap<&I, usize> = order.iter().map(|i| (i, 0)).collect();
+        for i in &order {
+            let next = layer[i] + 1;
+            for e in g.get_edge_list(i).unwrap() {
+                let to_layer = layer.get_mut(&e.to).unwrap();
+                *to_layer = usize::max(*to_layer, next);
+            }
+        }
+        // Вершины каждого слоя в порядке идентификаторов
+        let mut layers: Vec<Vec<&I>> = Vec::new();
+        for (&i, &k) in &layer {
+            if layers.len() <= k {
+                layers.resize_with(k + 1, Vec::new);
+            }
+            layers[k].push(i);
+        }
+
+        let step_y = Self::layout_step(layers.len());
+        let shift_y = Self::layout_shift(layers.len(), step_y);
+        for (k, vertices) in layers.iter().enumerate() {
+            let step_x = Self::layout_step(vertices.len());
+            let shift_x = Self::layout_shift(vertices.len(), step_x);
+            for (l, &i) in vertices.iter().enumerate() {
+                if let Some(pos) = self.vertices.get_mut(i) {
+                    *pos = (shift_x + step_x * l as f32, shift_y + step_y * k as f32);
+                }
+            }
+        }
+        self.finish_layout();
+    }
+
+    // Расстояние между соседними вершинами ряда из заданного количества вершин,
+    // при котором ряд занимает всю сторону квадрата расположения
+    fn layout_step(cnt: usize) -> f32 {
+        if cnt > 1 {
+            2.0 * LAYOUT_HALF_SIZE / (cnt - 1) as f32
+        } else {
+            0.0
+        }
+    }
+
+    // Координата первой вершины ряда, центрированного относительно начала координат
+    fn layout_shift(cnt: usize, step: f32) -> f32 {
+        -step * (cnt.max(1) - 1) as f32 / 2.0
+    }
+
+    // Завершение готового расположения: вершины открепляются, а обновления изображения
+    // прекращаются, чтобы симуляция не изменила расположение
+    fn finish_layout(&mut self) {
+        self.pinned_vertices.clear();
+        self.set_updates_stopped(true);
+    }
+
+    // Сброс камеры
+    pub fn reset_camera(&mut self) {
         self.zoom = 1.0;
         self.center_shift = (0.0, 0.0);
     }
 
+    // Масштабирование камеры так, чтобы весь граф с отступом помещался в окне
+    // (координаты вершин не изменяются)
+    pub fn fit_view(&mut self, width: f32, height: f32) {
+        // Отступ от краёв окна (доля половины стороны)
+        const FIT_MARGIN: f32 = 0.05;
+
+        self.center_shift = (0.0, 0.0);
+        let (min_x, max_x, min_y, max_y) = match self.bounding_box() {
+            Some(bbox) => bbox,
+            None => {
+                self.zoom = 1.0;
+                return;
+            }
+        };
+        let (diff_x, diff_y) = (max_x - min_x, max_y - min_y);
+        // Одна вершина (или все в одной точке) показывается в обычном масштабе
+        if diff_x == 0.0 && diff_y == 0.0 {
+            self.zoom = 1.0;
+            return;
+        }
+
+        let min_sz = f32::min(width, height);
+        let vertex_diameter = Self::vertex_diameter(min_sz);
+        let vertex_radius = vertex_diameter / 2.0;
+        let max_diff = f32::max(1.0, f32::max(diff_x, diff_y));
+        // Масштаб, при котором половина графа вместе с радиусом вершины занимает
+        // половину стороны окна без отступа (как в draw)
+        let axis_zoom = |size: f32, diff: f32| {
+            (1.0 - FIT_MARGIN) * (size / 2.0) * max_diff
+                / ((diff / 2.0 + vertex_radius) * min_sz * (1.0 - vertex_diameter))
+        };
+        let zoom = f32::min(axis_zoom(width, diff_x), axis_zoom(height, diff_y));
+        self.zoom = if zoom.is_finite() && zoom > 0.0 {
+            zoom.clamp(MIN_GRAPH_SCALE, MAX_GRAPH_SCALE)
+        } else {
+            1.0
+        };
+    }
+
+    // Начало фокусировки камеры на вершине: камера плавно перемещается так, чтобы вершина
+    // оказалась в центре окна, после чего вершина выделяется пульсирующим кольцом
+    pub fn focus_vertex(&mut self, i: I) -> Result<(), GraphOperationError> {
+        if !self.vertices.contains_key(&i) {
+            return Err(GraphOperationError::VertexNotFound);
+        }
+        self.focus = Some(Focus {
+            vertex: i,
+            start: Instant::now(),
+            start_shift: self.center_shift,
+            start_zoom: self.zoom,
+            moved: false,
+        });
+        Ok(())
+    }
+
+    // Шаг фокусировки камеры на вершине при отрисовке: масштаб и сдвиг камеры плавно
+    // переходят к значениям, при которых вершина находится в центре окна (base_scale —
+    // коэффициент масштаба поля отрисовки при единичном масштабе, center — центр графа)
+    fn update_focus(&mut self, base_scale: f32, center: (f32, f32)) {
+        let focus = match &mut self.focus {
+            Some(focus) => focus,
+            None => return,
+        };
+        let elapsed = focus.start.elapsed().as_secs_f32();
+        let position = self.vertices.get(&focus.vertex).copied();
+        if elapsed >= FOCUS_MOVE_DURATION + FOCUS_PULSE_DURATION || position.is_none() {
+            self.focus = None;
+            return;
+        }
+        if focus.moved {
+            return;
+        }
+        let (x, y) = position.unwrap();
+
+        // Плавное начало и окончание перемещения
+        let t = f32::min(elapsed / FOCUS_MOVE_DURATION, 1.0);
+        let part = t * t * (3.0 - 2.0 * t);
+        let target_zoom = f32::max(focus.start_zoom, FOCUS_MIN_ZOOM);
+        self.zoom = focus.start_zoom + (target_zoom - focus.start_zoom) * part;
+        // Сдвиг, при котором вершина находится в центре окна при текущем масштабе
+        let scale_coeff = self.zoom * base_scale;
+        let target_shift = (-(x - center.0) * scale_coeff, -(y - center.1) * scale_coeff);
+        self.center_shift = (
+            focus.start_shift.0 + (target_shift.0 - focus.start_shift.0) * part,
+            focus.start_shift.1 + (target_shift.1 - focus.start_shift.1) * part,
+        );
+        focus.moved = t >= 1.0;
+    }
+
+    // Установка отношения физических пикселей окна к логическим
+    pub fn set_scale_factor(&mut self, scale_factor: f32) {
+        self.scale_factor = scale_factor;
+    }
+
+    // Координаты на поле для рисования (в физических пикселях), соответствующие
+    // точке окна в логических пикселях
+    fn to_canvas_coords(&self, coords: (f32, f32)) -> (f32, f32) {
+        logical_to_physical(coords, self.scale_factor)
+    }
+
+    // Сдвиг камеры на заданное количество логических пикселей
+    pub fn pan(&mut self, shift: (f32, f32)) {
+        let (x, y) = self.to_canvas_coords(shift);
+        self.center_shift.0 += x;
+        self.center_shift.1 += y;
+    }
+
+    // Выбор вершины под точкой окна (по преобразованию координат последней отрисовки),
+    // при нажатии на свободную область выбор и выделение прямоугольником снимаются
+    // (подписи рёбер перекрывают вершины)
+    pub fn pick_vertex(&mut self, coords: (f32, f32)) -> Option<I> {
+        self.selected_vertex = match self.edge_label_at(coords) {
+            Some(_) => None,
+            None => self.vertex_at(coords),
+        };
+        if self.selected_vertex.is_none() {
+            self.selected_vertices.clear();
+        }
+        self.selected_vertex.clone()
+    }
+
+    // Вершина под точкой окна (по преобразованию координат последней отрисовки)
+    fn vertex_at(&self, coords: (f32, f32)) -> Option<I> {
+        let coords = self.to_canvas_coords(coords);
+        self.view.and_then(|(transform, vertex_radius)| {
+            let (x, y) = transform.inversed().transform_point(coords.0, coords.1);
+            self.find_vertex(x, y, vertex_radius)
+        })
+    }
+
+    // Ребро, подпись которого находится под точкой окна (по последней отрисовке);
+    // при перекрытии подписей выбирается нарисованная позже
+    fn edge_label_at(&self, coords: (f32, f32)) -> Option<(I, I, usize)> {
+        let (x, y) = self.to_canvas_coords(coords);
+        self.edge_label_rects
+            .iter()
+            .rev()
+            .find(|(_, [min_x, min_y, max_x, max_y])| {
+                (*min_x..=*max_x).contains(&x) && (*min_y..=*max_y).contains(&y)
+            })
+            .map(|(edge, _)| edge.clone())
+    }
+
+    // Ребро под точкой окна и его номер среди параллельных рёбер: сначала ищется
+    // подпись ребра, затем само ребро под курсором
+    pub fn edge_at(&self, coords: (f32, f32)) -> Option<(I, I, usize)> {
+        self.edge_label_at(coords)
+            .or_else(|| self.hovered_edge.clone())
+    }
+
+    // Координаты вершин, соответствующие точке окна (по преобразованию координат
+    // последней отрисовки)
+    pub fn to_graph_coords(&self, coords: (f32, f32)) -> Option<(f32, f32)> {
+        let coords = self.to_canvas_coords(coords);
+        self.view
+            .map(|(transform, _)| transform.inversed().transform_point(coords.0, coords.1))
+    }
+
+    // Начало протягивания нового ребра из вершины под точкой окна;
+    // возвращает, есть ли там вершина
+    pub fn start_edge_drag(&mut self, coords: (f32, f32)) -> bool {
+        self.edge_drag_from = self.vertex_at(coords);
+        self.edge_drag_from.is_some()
+    }
+
+    // Протягивается ли новое ребро
+    pub fn is_edge_dragging(&self) -> bool {
+        self.edge_drag_from.is_some()
+    }
+
+    // Отмена протягивания ребра
+    pub fn cancel_edge_drag(&mut self) {
+        self.edge_drag_from = None;
+    }
+
+    // Завершение протягивания ребра в точке окна: начало и конец нового ребра,
+    // если в этой точке есть вершина
+    pub fn finish_edge_drag(&mut self, coords: (f32, f32)) -> Option<(I, I)> {
+        let from = self.edge_drag_from.take()?;
+        self.vertex_at(coords).map(|to| (from, to))
+    }
+
+    // Начало выделения вершин прямоугольником из точки окна
+    pub fn start_rect_selection(&mut self, coords: (f32, f32)) {
+        let coords = self.to_canvas_coords(coords);
+        self.selection_rect = Some((coords, coords));
+    }
+
+    // Выделяются ли вершины прямоугольником
+    pub fn is_rect_selecting(&self) -> bool {
+        self.selection_rect.is_some()
+    }
+
+    // Завершение выделения прямоугольником: выделяются вершины, центры которых
+    // находятся в прямоугольнике (по преобразованию координат последней отрисовки)
+    pub fn finish_rect_selection(&mut self) {
+        let ((start, end), (transform, _)) = match (self.selection_rect.take(), self.view) {
+            (Some(rect), Some(view)) => (rect, view),
+            _ => return,
+        };
+        let inversed = transform.inversed();
+        let (x_start, y_start) = inversed.transform_point(start.0, start.1);
+        let (x_end, y_end) = inversed.transform_point(end.0, end.1);
+        let (min_x, max_x) = (f32::min(x_start, x_end), f32::max(x_start, x_end));
+        let (min_y, max_y) = (f32::min(y_start, y_end), f32::max(y_start, y_end));
+        self.selected_vertices = self
+            .vertices
+            .iter()
+            .filter(|(_, &(x, y))| (min_x..=max_x).contains(&x) && (min_y..=max_y).contains(&y))
+            .map(|(i, _)| i.clone())
+            .collect();
+    }
+
+    // Снятие выделения вершин прямоугольником
+    pub fn clear_selection(&mut self) {
+        self.selected_vertices.clear();
+    }
+
+    // Поиск вершины, содержащей точку
+    fn find_vertex(&self, x: f32, y: f32, vertex_radius: f32) -> Option<I> {
+        self.vertices
+            .iter()
+            .find(|(i, (v_x, v_y))| {
+                (x - v_x).powi(2) + (y - v_y).powi(2)
+                    <= self.vertex_radius_of(i, vertex_radius).powi(2)
+            })
+            .map(|(i, _)| i.clone())
+    }
+
+    // Диаметр вершины (доля минимальной стороны окна)
+    fn vertex_diameter(min_sz: f32) -> f32 {
+        // Константы для количества вершин на единицу длины, минимального размера вершин
+        const VERTEX_CNT: i32 = 10;
+        const MIN_VERTEX_DIAMETER: f32 = 16.0;
+
+        f32::max(min_sz / (VERTEX_CNT as f32), MIN_VERTEX_DIAMETER) / min_sz
+    }
+
+    // Минимальные и максимальные координаты вершин
+    fn bounding_box(&self) -> Option<(f32, f32, f32, f32)> {
+        if self.vertices.is_empty() {
+            return None;
+        }
+        Some(self.vertices.values().fold(
+            (f32::MAX, f32::MIN, f32::MAX, f32::MIN),
+            |(acc_min_x, acc_max_x, acc_min_y, acc_max_y), &(x, y)| {
+                (
+                    f32::min(acc_min_x, x),
+                    f32::max(acc_max_x, x),
+                    f32::min(acc_min_y, y),
+                    f32::max(acc_max_y, y),
+                )
+            },
+        ))
+    }
+
+    // Шаг сетки вида 1, 2 или 5, умноженного на степень 10, при котором на видимом участке
+    // заданного размера помещается не больше заданного количества линий
+    fn grid_step(extent: f32, line_cnt: f32) -> f32 {
+        let raw_step = extent / line_cnt;
+        let pow10 = 10.0_f32.powf(raw_step.log10().floor());
+        [1.0, 2.0, 5.0]
+            .iter()
+            .map(|k| k * pow10)
+            .find(|&step| step >= raw_step)
+            .unwrap_or(10.0 * pow10)
+    }
+
+    // Отрисовка сетки в координатах вершин под рёбрами и вершинами
+    fn draw_grid(&self, canvas: &mut Canvas<OpenGl>, width: f32, height: f32, line_width: f32) {
+        // Наибольшее количество линий вдоль большей стороны и прозрачность линий
+        const GRID_LINE_CNT: f32 = 20.0;
+        const GRID_ALPHA: f32 = 0.15;
+
+        // Видимый участок в координатах вершин
+        let inversed = canvas.transform().inversed();
+        let (x0, y0) = inversed.transform_point(0.0, 0.0);
+        let (x1, y1) = inversed.transform_point(width, height);
+        let extent = f32::max(x1 - x0, y1 - y0);
+        if !extent.is_finite() || extent <= 0.0 {
+            return;
+        }
+        let step = Self::grid_step(extent, GRID_LINE_CNT);
+
+        let mut path = Path::new();
+        let mut x = (x0 / step).ceil() * step;
+        while x <= x1 {
+            path.move_to(x, y0);
+            path.line_to(x, y1);
+            x += step;
+        }
+        let mut y = (y0 / step).ceil() * step;
+        while y <= y1 {
+            path.move_to(x0, y);
+            path.line_to(x1, y);
+            y += step;
+        }
+        let mut color = self.front_color;
+        color.set_alphaf(GRID_ALPHA);
+        let mut paint = Paint::color(color);
+        paint.set_line_width(line_width / 2.0);
+        canvas.stroke_path(&mut path, paint);
+    }
+
+    // Добавление к пути пунктирной линии из штрихов заданной длины с промежутками
+    fn add_dashed_line(path: &mut Path, from: (f32, f32), to: (f32, f32), dash: f32, gap: f32) {
+        let dir = (to.0 - from.0, to.1 - from.1);
+        let len = (dir.0 * dir.0 + dir.1 * dir.1).sqrt();
+        if len == 0.0 {
+            return;
+        }
+        let dir = (dir.0 / len, dir.1 / len);
+        let mut t = 0.0;
+        while t < len {
+            let t_end = f32::min(t + dash, len);
+            path.move_to(from.0 + dir.0 * t, from.1 + dir.1 * t);
+            path.line_to(from.0 + dir.0 * t_end, from.1 + dir.1 * t_end);
+            t += dash + gap;
+        }
+    }
+
+    // Поиск ребра, ближайшего к точке, на расстоянии не больше заданного
+    // (ребро неориентированного графа возвращается один раз, от меньшей вершины к большей)
+    fn pick_edge<W>(
+        &self,
+        g: &Graph<I, W>,
+        p: (f32, f32),
+        vertex_radius: f32,
+        max_distance: f32,
+        loop_dirs: &BTreeMap<I, (f32, f32)>,
+    ) -> Option<(I, I, usize)>
+    where
+        W: EdgeWeight,
+    {
+        let mut closest: Option<((I, I, usize), f32)> = None;
+        for i in g.get_vertices().keys() {
+            let from = *self.vertices.get(i)?;
+            let edge_list = g.get_edge_list(i).unwrap();
+            for (Edge { to, .. }, (index, count)) in
+                edge_list.iter().zip(parallel_numbers(edge_list))
+            {
+                if !g.get_is_directed() && i > to {
+                    continue;
+                }
+                let to_pos = *self.vertices.get(to)?;
+                // Форма ребра такая же, как при отрисовке
+                let bend = edge_bend(
+                    i,
+                    to,
+                    index,
+                    count,
+                    self.has_antiparallel(&(i.clone(), to.clone())),
+                );
+                let distance = if let Some(&dir) = loop_dirs.get(i).filter(|_| i == to) {
+                    let dir = loop_fan_direction(dir, index, count);
+                    let lg = loop_geometry(from, dir, self.vertex_radius_of(i, vertex_radius));
+                    point_circle_distance(p, lg.center, lg.radius)
+                } else if bend != 0.0 {
+                    let control = curve_control_point(from, to_pos, vertex_radius, bend);
+                    point_curve_distance(p, from, control, to_pos)
+                } else {
+                    point_segment_distance(p, from, to_pos)
+                };
+                let is_closer = match &closest {
+                    Some((_, closest_distance)) => distance < *closest_distance,
+                    None => true,
+                };
+                if distance <= max_distance && is_closer {
+                    closest = Some(((i.clone(), to.clone(), index), distance));
+                }
+            }
+        }
+        closest.map(|(edge, _)| edge)
+    }
+
+    // Отрисовка стрелки дуги в виде закрашенного треугольника
+    // с острием в точке tip и основанием в направлении back_dir
+    fn draw_arrowhead(
+        canvas: &mut Canvas<OpenGl>,
+        paint: Paint,
+        tip: (f32, f32),
+        back_dir: (f32, f32),
+        size: f32,
+    ) {
+        let [tip, left, right] = match arrowhead_points(tip, back_dir, size) {
+            Some(points) => points,
+            None => return,
+        };
+        let mut path = Path::new();
+        path.move_to(tip.0, tip.1);
+        path.line_to(left.0, left.1);
+        path.line_to(right.0, right.1);
+        path.close();
+        canvas.fill_path(&mut path, paint);
+    }
+
+    // Отрисовка точек, движущихся вдоль ребра от начала к концу (или обратно при reverse)
+    // со временем time (в секундах)
+    fn draw_flow_dots(
+        canvas: &mut Canvas<OpenGl>,
+        paint: Paint,
+        shape: EdgeShape,
+        reverse: bool,
+        time: f32,
+        vertex_radius: f32,
+    ) {
+        // Расстояние между точками, скорость их движения (в радиусах вершины и радиусах
+        // вершины в секунду) и радиус точек (в радиусах вершины)
+        const FLOW_DOT_SPACING: f32 = 1.5;
+        const FLOW_DOT_SPEED: f32 = 2.0;
+        const FLOW_DOT_RADIUS: f32 = 0.15;
+
+        let cnt = (shape.length() / (FLOW_DOT_SPACING * vertex_radius))
+            .floor()
+            .max(1.0);
+        let phase = (time * FLOW_DOT_SPEED / FLOW_DOT_SPACING).fract();
+        let mut path = Path::new();
+        for k in 0..cnt as usize {
+            let t = (k as f32 + phase) / cnt;
+            let (x, y) = shape.point(if reverse { 1.0 - t } else { t });
+            path.circle(x, y, FLOW_DOT_RADIUS * vertex_radius);
+        }
+        canvas.fill_path(&mut path, paint);
+    }
+
     // Начало/конец нажатия мышью
     pub fn set_mouse_dragging(&mut self, dragging: bool) {
         self.mouse_dragging = dragging;
+        self.resume_simulation();
         self.mouse_press = None;
         self.mouse_press_prev = None;
         self.dragging_vertex = None;
     }
 
-    // Перемещение мыши
+    // Уход курсора из окна
+    pub fn set_mouse_left(&mut self) {
+        if !self.mouse_dragging {
+            self.mouse_press = None;
+        }
+        self.hovered_edge = None;
+    }
+
+    // Перемещение мыши в точку окна (в логических пикселях)
     pub fn set_mouse_move(&mut self, coords: (f32, f32)) {
+        let coords = self.to_canvas_coords(coords);
+        // Второй угол прямоугольника выделения следует за курсором
+        if let Some((_, end)) = &mut self.selection_rect {
+            *end = coords;
+        }
         swap(&mut self.mouse_press, &mut self.mouse_press_prev);
         self.mouse_press = Some(coords);
         if !self.mouse_dragging {
@@ -156,130 +1131,731 @@ where
         }
     }
 
-    // Масштабирование прокруткой колеса мыши
-    pub fn update_zoom(&mut self, scroll: f32) {
-        // Минимальный и максимальный масштаб
-        const MIN_GRAPH_SCALE: f32 = 1.0;
-        const MAX_GRAPH_SCALE: f32 = 16.0;
-
+    // Масштабирование прокруткой колеса мыши относительно курсора
+    // (координаты курсора задаются в логических пикселях относительно центра окна)
+    pub fn update_zoom(&mut self, scroll: f32, cursor: (f32, f32)) {
+        let cursor = self.to_canvas_coords(cursor);
         let zoom_prev = self.zoom;
         self.zoom = f32::clamp(
             self.zoom * SQRT_2.powf(scroll),
             MIN_GRAPH_SCALE,
             MAX_GRAPH_SCALE,
         );
-        let coeff = self.zoom / zoom_prev;
-        self.center_shift.0 *= coeff;
-        self.center_shift.1 *= coeff;
+        // При минимальном масштабе граф показывается целиком
+        if self.zoom == MIN_GRAPH_SCALE {
+            self.center_shift = (0.0, 0.0);
+            return;
+        }
+        // Точка под курсором остаётся на месте
+        let coeff = self.zoom / zoom_prev;
+        self.center_shift.0 = cursor.0 - (cursor.0 - self.center_shift.0) * coeff;
+        self.center_shift.1 = cursor.1 - (cursor.1 - self.center_shift.1) * coeff;
+    }
+
+    // Обновление координат вершин, возвращает, изменились ли они (если нет, то без внешних
+    // событий изображение не изменяется)
+    pub fn update<W>(&mut self, g: &Option<Graph<I, W>>) -> bool
+    where
+        W: EdgeWeight,
+    {
+        // Промежуток времени, для которого задана скорость изменений (в секундах)
+        const REFERENCE_TIME_DELTA: f32 = 1.0 / 60.0;
+        // Максимальный учитываемый промежуток времени между обновлениями, чтобы после задержки
+        // вершины не разлетались
+        const MAX_TIME_DELTA: f32 = 0.05;
+        // Количество подряд идущих кадров со смещением ниже порога для остановки симуляции
+        const SETTLE_FRAME_CNT: u32 = 30;
+
+        if g.is_none() {
+            let changed = !self.vertices.is_empty();
+            self.vertices.clear();
+            self.edge_indices = None;
+            return changed;
+        }
+        let g = g.as_ref().unwrap();
+        let g_vertices = g.get_vertices();
+
+        // Удаление координат несуществующих вершин
+        let vertex_cnt = self.vertices.len();
+        self.vertices.retain(|i, _| g_vertices.contains_key(i));
+        let mut changed = self.vertices.len() != vertex_cnt;
+        if changed {
+            self.edge_indices = None;
+        }
+        self.pinned_vertices.retain(|i| g_vertices.contains_key(i));
+        self.selected_vertices
+            .retain(|i| g_vertices.contains_key(i));
+        if let Some(i) = &self.selected_vertex {
+            if !g_vertices.contains_key(i) {
+                self.selected_vertex = None;
+            }
+        }
+
+        // Инициализация координат новых вершин заданными координатами или
+        // случайными числами из отрезка [-0.5; 0.5]
+        let coord_distribution = Uniform::new(-0.5f32, 0.5);
+        for i in g_vertices.keys() {
+            if self.vertices.contains_key(i) {
+                continue;
+            }
+            let position = self.new_vertex_positions.remove(i).unwrap_or_else(|| {
+                (
+                    self.rng.sample(coord_distribution),
+                    self.rng.sample(coord_distribution),
+                )
+            });
+            self.vertices.insert(i.clone(), position);
+            self.edge_indices = None;
+            self.resume_simulation();
+            changed = true;
+        }
+
+        // Если обновления графа отключены, симуляция сошлась или вершин нет
+        if self.updates_stopped || self.settled || self.vertices.is_empty() {
+            self.last_update_time = None;
+            return changed;
+        }
+
+        // Время, прошедшее с последнего обновления
+        let now = Instant::now();
+        let time_delta = match self.last_update_time {
+            Some(t) => f32::min(now.duration_since(t).as_secs_f32(), MAX_TIME_DELTA),
+            None => REFERENCE_TIME_DELTA,
+        };
+        self.last_update_time = Some(now);
+        // Смещение вершин пропорционально прошедшему времени
+        let step = self.time_step * time_delta / REFERENCE_TIME_DELTA;
+
+        // Номера концов рёбер пересчитываются только после изменения графа
+        // (координаты вершин хранятся в порядке идентификаторов, как и вершины графа)
+        if self.edge_indices.is_none() {
+            self.edge_indices = Some(edge_indices(g));
+        }
+
+        // Буфер координат переиспользуется между кадрами
+        self.coords.clear();
+        self.coords.extend(self.vertices.values().copied());
+        self.layout
+            .compute_forces(&self.coords, self.edge_indices.as_ref().unwrap());
+        // Совпадающие вершины почти не отталкиваются, поэтому они разводятся случайно
+        // и разойдутся со следующего кадра
+        if self.layout.has_coincident_points() {
+            self.separate_coincident_vertices();
+        }
+
+        // Минимальные и максимальные координаты вершин
+        let (min_x, max_x, min_y, max_y) = self.bounding_box().unwrap();
+        // Размер графа для вычисления относительного смещения вершин
+        let max_diff = f32::max(1.0, f32::max(max_x - min_x, max_y - min_y));
+        // Максимальное смещение вершины за кадр
+        let mut max_displacement: f32 = 0.0;
+
+        // Перемещаются ли выделенные вершины вместе с перемещаемой вершиной
+        let dragging_selection = matches!(
+            &self.dragging_vertex,
+            Some(i) if self.selected_vertices.contains(i)
+        );
+        // Применение сил ко всем вершинам, кроме перемещаемых и закреплённых
+        for ((i, pos), &(f_x, f_y)) in self.vertices.iter_mut().zip(self.layout.get_forces()) {
+            if self.dragging_vertex.as_ref() == Some(i)
+                || (dragging_selection && self.selected_vertices.contains(i))
+                || self.pinned_vertices.contains(i)
+            {
+                continue;
+            }
+            // Координаты остаются конечными, даже если силы оказались неправильными
+            let new_pos = (pos.0 + f_x * step, pos.1 + f_y * step);
+            if !new_pos.0.is_finite() || !new_pos.1.is_finite() {
+                continue;
+            }
+            *pos = new_pos;
+            max_displacement = f32::max(max_displacement, (f_x * f_x + f_y * f_y).sqrt() * step);
+        }
+
+        // Остановка симуляции, если смещение вершин относительно размера графа
+        // долго остаётся ниже порога
+        if max_displacement / max_diff < self.settle_epsilon {
+            self.settle_frame_cnt += 1;
+            if self.settle_frame_cnt >= SETTLE_FRAME_CNT {
+                self.settled = true;
+                self.last_update_time = None;
+            }
+        } else {
+            self.settle_frame_cnt = 0;
+        }
+        true
+    }
+
+    // Смещение вершин, совпадающих по координатам (с точностью до минимального размера
+    // области дерева квадрантов) с предыдущими, на небольшое случайное расстояние
+    fn separate_coincident_vertices(&mut self) {
+        // Максимальное смещение по каждой координате
+        const JITTER: f32 = 1e-3;
+
+        let jitter = Uniform::new_inclusive(-JITTER, JITTER);
+        let mut seen = BTreeSet::new();
+        for pos in self.vertices.values_mut() {
+            let cell = (
+                (pos.0 / MIN_AREA_SIZE).round() as i64,
+                (pos.1 / MIN_AREA_SIZE).round() as i64,
+            );
+            if !seen.insert(cell) {
+                *pos = (
+                    pos.0 + self.rng.sample(jitter),
+                    pos.1 + self.rng.sample(jitter),
+                );
+            }
+        }
+    }
+
+    // Есть ли у дуги противоположная дуга (множество таких дуг вычисляется при отрисовке)
+    fn has_antiparallel(&self, edge: &(I, I)) -> bool {
+        matches!(&self.antiparallel_edges, Some(edges) if edges.contains(edge))
+    }
+
+    // Отрисовка рёбер со стрелками и подписями (прямоугольники подписей на экране
+    // добавляются в label_rects); возвращает, была ли анимация потока
+    fn draw_edges<W>(
+        &self,
+        canvas: &mut Canvas<OpenGl>,
+        paint: &mut Paint,
+        frame: &FrameData<I, W>,
+        label_rects: &mut Vec<EdgeLabelRect<I>>,
+    ) -> Result<bool, GraphOperationError>
+    where
+        W: EdgeWeight,
+    {
+        // Коэффициент толщины ребра под курсором
+        const HOVER_WIDTH_COEFF: f32 = 2.0;
+
+        let g = frame.g;
+        let vertex_radius = frame.vertex_radius;
+        let is_hovered = |i: &I, to: &I, index: usize| match &self.hovered_edge {
+            Some((from_h, to_h, index_h)) => {
+                *index_h == index
+                    && ((from_h == i && to_h == to)
+                        || (!g.get_is_directed() && from_h == to && to_h == i))
+            }
+            None => false,
+        };
+
+        let mut flow_animated = false;
+        for i in g.get_vertices().keys() {
+            let (x_i, y_i) = *self
+                .vertices
+                .get(i)
+                .ok_or(GraphOperationError::VertexNotFound)?;
+            let edge_list = g.get_edge_list(i).unwrap();
+            for (Edge { to, weight }, (index, count)) in
+                edge_list.iter().zip(parallel_numbers(edge_list))
+            {
+                let (x_to, y_to) = *self
+                    .vertices
+                    .get(to)
+                    .ok_or(GraphOperationError::VertexNotFound)?;
+                // Ребро для поиска во множествах рёбер
+                let edge = (i.clone(), to.clone());
+
+                // Суммарный поток через ребро, если выполняется нахождение потока,
+                // и поток в последнем дополняющем пути через него
+                let (edge_total_flow, edge_flow) = edge_flows(frame.flow_data, i, to);
+
+                // Входит ли ребро в минимальный разрез после завершения алгоритма
+                let in_min_cut = match frame.g_algorithm_state {
+                    GraphAlgorithmState::MaxFlow(AlgorithmState::Finished(data)) => {
+                        data.get_min_cut_edges().contains(&edge)
+                    }
+                    _ => false,
+                };
+                // Входит ли ребро в кратчайший путь после завершения алгоритма Дейкстры
+                let in_shortest_path = match frame.g_algorithm_state {
+                    GraphAlgorithmState::ShortestPath(ShortestPathState::Finished(data)) => {
+                        data.get_path_edges().contains(&edge)
+                    }
+                    _ => false,
+                };
+                // Входит ли ребро в текущее дерево кратчайших путей во время алгоритма Дейкстры
+                let in_path_tree = match frame.g_algorithm_state {
+                    GraphAlgorithmState::ShortestPath(ShortestPathState::Step(data)) => {
+                        data.is_tree_edge(i, to)
+                    }
+                    _ => false,
+                };
+                // Добавлено ли ребро в минимальный остов или отброшено алгоритмом Краскала
+                let (in_spanning_tree, is_rejected) = match frame.g_algorithm_state {
+                    GraphAlgorithmState::SpanningTree(SpanningTreeState::Step(data))
+                    | GraphAlgorithmState::SpanningTree(SpanningTreeState::Finished(data)) => {
+                        (data.is_accepted(i, to), data.is_rejected(i, to))
+                    }
+                    _ => (false, false),
+                };
+
+                // Входит ли ребро в найденное паросочетание (ребро неориентированного графа
+                // хранится в паросочетании в одном направлении)
+                let in_matching = match frame.matching_edges {
+                    Some(edges) => {
+                        edges.contains(&edge)
+                            || (!g.get_is_directed() && edges.contains(&(to.clone(), i.clone())))
+                    }
+                    None => false,
+                };
+
+                // Выделено ли ребро вне алгоритма
+                let is_highlighted = self.highlighted_edges.contains(&edge);
+
+                // Если есть поток, ребро входит в кратчайший путь или остов или выделено,
+                // то ребро выделено, рёбра минимального разреза, дерева кратчайших путей
+                // и паросочетания выделены другими цветами, отброшенные алгоритмом Краскала
+                // рёбра полупрозрачные, иначе используется основной цвет
+                paint.set_color(match edge_flow {
+                    Some(_) => self.selection_color,
+                    None if in_shortest_path || in_spanning_tree || is_highlighted => {
+                        self.selection_color
+                    }
+                    None if in_min_cut => rgba_color(MIN_CUT_COLOR),
+                    None if in_path_tree => rgba_color(FRONTIER_COLOR),
+                    None if in_matching => rgba_color(MATCHING_COLOR),
+                    None if is_rejected => Color {
+                        a: self.front_color.a * REJECTED_EDGE_ALPHA,
+                        ..self.front_color
+                    },
+                    None => self.front_color,
+                });
+
+                // Толщина ребра: при нахождении потока зависит от заполненности ребра,
+                // иначе от веса относительно минимального и максимального весов
+                let width_part =
+                    edge_width_part(frame.weight_range, weight.as_ref(), edge_total_flow);
+                // Ребро под курсором рисуется толще
+                let hover_coeff = if is_hovered(i, to, index) {
+                    HOVER_WIDTH_COEFF
+                } else {
+                    1.0
+                };
+                paint.set_line_width(
+                    frame.line_width
+                        * hover_coeff
+                        * (1.0 + (MAX_EDGE_WIDTH_COEFF - 1.0) * width_part),
+                );
+
+                // Геометрия ребра-петли (параллельные петли расходятся веером)
+                let loop_geometry = frame.loop_dirs.get(i).filter(|_| i == to).map(|&dir| {
+                    let dir = loop_fan_direction(dir, index, count);
+                    loop_geometry((x_i, y_i), dir, self.vertex_radius_of(i, vertex_radius))
+                });
+                // Есть ли у дуги противоположная дуга
+                let is_antiparallel = self.has_antiparallel(&edge);
+                // Контрольная точка кривой Безье в центре ребра с отступом
+                // для противоположных и параллельных рёбер
+                let bend = edge_bend(i, to, index, count, is_antiparallel);
+                let control = (loop_geometry.is_none() && bend != 0.0)
+                    .then(|| curve_control_point((x_i, y_i), (x_to, y_to), vertex_radius, bend));
+
+                // Форма ребра: окружность петли (начинается у вершины), кривая Безье
+                // для противоположных и параллельных рёбер или отрезок
+                let shape = match (&loop_geometry, control) {
+                    (Some(lg), _) => {
+                        EdgeShape::Loop(lg.center, lg.radius, f32::atan2(-lg.dir.1, -lg.dir.0))
+                    }
+                    (None, Some(control)) => EdgeShape::Curve((x_i, y_i), control, (x_to, y_to)),
+                    (None, None) => EdgeShape::Line((x_i, y_i), (x_to, y_to)),
+                };
+
+                let mut path = Path::new();
+                match shape {
+                    EdgeShape::Loop(center, radius, _) => path.circle(center.0, center.1, radius),
+                    EdgeShape::Curve(from, control, to) => {
+                        path.move_to(from.0, from.1);
+                        path.quad_to(control.0, control.1, to.0, to.1);
+                    }
+                    EdgeShape::Line(from, to) => {
+                        path.move_to(from.0, from.1);
+                        path.line_to(to.0, to.1);
+                    }
+                }
+                canvas.stroke_path(&mut path, *paint);
+
+                // Анимация направления потока по дополняющему пути: точки движутся от начала
+                // дуги к концу; отрицательный поток по дуге означает поток по обратной дуге
+                // остаточной сети, и если её нет в графе, точки движутся в обратную сторону
+                // (иначе они выводятся на самой обратной дуге; обратное ребро есть у рёбер
+                // неориентированного графа и у петель)
+                if let (Some(f), Some(time)) = (edge_flow, frame.animation_time) {
+                    let reverse = f.as_f32() < 0.0;
+                    let has_reverse = !g.get_is_directed() || i == to || is_antiparallel;
+                    if f.as_f32() != 0.0 && (!reverse || !has_reverse) {
+                        let dot_paint = Paint::color(self.front_color);
+                        Self::draw_flow_dots(
+                            canvas,
+                            dot_paint,
+                            shape,
+                            reverse,
+                            time,
+                            vertex_radius,
+                        );
+                        flow_animated = true;
+                    }
+                }
+
+                if !frame.full_render {
+                    continue;
+                }
+
+                // Стрелка дуги
+                if g.get_is_directed() {
+                    // Радиус конечной вершины
+                    let to_radius = self.vertex_radius_of(to, vertex_radius);
+                    // Точка пересечения окружности вершины и дуги (острие стрелки)
+                    // и вектор от острия вдоль дуги
+                    let (vertex_edge, back_dir) = match (&loop_geometry, control) {
+                        // Точка пересечения окружностей вершины и ребра-петли
+                        // и касательная к петле в ней
+                        (Some(lg), _) => (lg.arrow_tip, lg.back_dir),
+                        (None, Some(control)) => {
+                            curve_arrow_tip((x_i, y_i), control, (x_to, y_to), to_radius)
+                        }
+                        (None, None) => line_arrow_tip((x_i, y_i), (x_to, y_to), to_radius),
+                    };
+
+                    // Отрисовка стрелки дуги
+                    Self::draw_arrowhead(
+                        canvas,
+                        *paint,
+                        vertex_edge,
+                        back_dir,
+                        vertex_radius * self.arrow_size,
+                    );
+                }
+
+                // Подписи рёбер скрыты, подпись ребра неориентированного графа выводится один раз
+                if !frame.show_edge_labels || (!g.get_is_directed() && i > to) {
+                    continue;
+                }
+
+                if let Some(w) = edge_label_weight(frame.flow_data, i, to, weight.as_ref()) {
+                    // Если выполняется нахождение потока, то выводить поток и пропускную способность ребра
+                    // маленьким шрифтом, иначе только вес ребра обычным шрифтом
+                    // (у петель и рёбер, отсутствующих в графе потоков, выводится только вес)
+                    let font_size = match edge_label_font_size(
+                        vertex_radius * frame.scale_coeff,
+                        edge_total_flow.is_some(),
+                        frame.min_label_font_size,
+                    ) {
+                        Some(font_size) => font_size,
+                        None => continue,
+                    };
+                    paint.set_font_size(font_size);
+                    let text = edge_label_text(w, edge_total_flow, edge_flow);
+
+                    // Отступ подписи от ребра (подпись петли отодвигается дальше
+                    // от вершины со многими рёбрами); подписи противоположных дуг
+                    // находятся по разные стороны от пары дуг, так как отступ
+                    // откладывается влево от направления дуги
+                    let label_offset = vertex_radius * self.edge_label_offset;
+                    let label_offset = if loop_geometry.is_some() {
+                        loop_label_offset(label_offset, vertex_radius, incident_edge_count(g, i))
+                    } else {
+                        label_offset
+                    };
+                    // Координаты текста
+                    let position = edge_label_position(
+                        (x_i, y_i),
+                        (x_to, y_to),
+                        control,
+                        loop_geometry.as_ref(),
+                        label_offset,
+                    );
+                    let rect = self.draw_edge_label(
+                        canvas,
+                        paint,
+                        frame,
+                        &text,
+                        position,
+                        loop_geometry.as_ref(),
+                    );
+                    label_rects.push(((i.clone(), to.clone(), index), rect));
+                }
+            }
+        }
+        Ok(flow_animated)
+    }
+
+    // Отрисовка подписи ребра текущим размером шрифта на фоне в виде скруглённого
+    // прямоугольника (подпись петли сдвигается наружу, чтобы не закрывать петлю);
+    // возвращает прямоугольник фона на экране (минимальные и максимальные координаты)
+    fn draw_edge_label<W>(
+        &self,
+        canvas: &mut Canvas<OpenGl>,
+        paint: &mut Paint,
+        frame: &FrameData<I, W>,
+        text: &str,
+        position: (f32, f32),
+        loop_geometry: Option<&LoopGeometry>,
+    ) -> [f32; 4]
+    where
+        W: EdgeWeight,
+    {
+        let scale_coeff = frame.scale_coeff;
+
+        // Вывод текста
+        canvas.save();
+        canvas.scale(1.0 / scale_coeff, 1.0 / scale_coeff);
+
+        let (mut x_text, mut y_text) = (position.0 * scale_coeff, position.1 * scale_coeff);
+
+        // Фон подписи в виде скруглённого прямоугольника
+        let metrics = canvas.measure_text(x_text, y_text, text, *paint).unwrap();
+        let padding = paint.font_size() / 4.0;
+        let (w_pill, h_pill) = (metrics.width() + 2.0 * padding, metrics.height() + padding);
+        if let Some(lg) = loop_geometry {
+            let (shift_x, shift_y) = loop_label_shift(lg, w_pill, h_pill);
+            x_text += shift_x;
+            y_text += shift_y;
+        }
+        let mut path = Path::new();
+        path.rounded_rect(
+            x_text - w_pill / 2.0,
+            y_text - h_pill / 2.0,
+            w_pill,
+            h_pill,
+            h_pill / 2.0,
+        );
+        paint.set_color(self.back_color);
+        canvas.fill_path(&mut path, *paint);
+        // Закраска текста
+        paint.set_color(self.front_color);
+        canvas.fill_text(x_text, y_text, text, *paint).unwrap();
+
+        // Углы фона на экране
+        let transform = canvas.transform();
+        let (x0, y0) = transform.transform_point(x_text - w_pill / 2.0, y_text - h_pill / 2.0);
+        let (x1, y1) = transform.transform_point(x_text + w_pill / 2.0, y_text + h_pill / 2.0);
+
+        canvas.restore();
+        [x0.min(x1), y0.min(y1), x0.max(x1), y0.max(y1)]
     }
 
-    // Обновление координат вершин
-    pub fn update<W>(&mut self, g: &Option<Graph<I, W>>)
+    // Отрисовка вершин с текстом, затем колец и меток истока и стока
+    fn draw_vertices<W>(
+        &self,
+        canvas: &mut Canvas<OpenGl>,
+        paint: &mut Paint,
+        frame: &FrameData<I, W>,
+    ) -> Result<(), GraphOperationError>
     where
         W: EdgeWeight,
     {
-        if g.is_none() {
-            self.vertices.clear();
-            return;
-        }
-        let g = g.as_ref().unwrap();
-        let g_vertices = g.get_vertices();
+        // Радиус кольца вокруг выделенной вершины (в радиусах вершины)
+        const SELECTION_RING_COEFF: f32 = 1.3;
 
-        // Удаление координат несуществующих вершин
-        let tmp_vertices = self
-            .vertices
-            .clone()
-            .into_iter()
-            .filter(|(i, _)| g_vertices.contains_key(i))
-            .collect();
-        self.vertices = tmp_vertices;
+        let (vertex_radius, scale_coeff) = (frame.vertex_radius, frame.scale_coeff);
+        let path_data = frame.path_data;
 
-        // Инициализация координат новых вершин случайными числами из отрезка [-0.5; 0.5]
-        let coord_distribution = Uniform::new(-0.5f32, 0.5);
-        for i in g_vertices.keys() {
-            if self.vertices.contains_key(i) {
+        for (i, (x, y)) in &self.vertices {
+            let is_pinned = self.pinned_vertices.contains(i);
+            let radius = self.vertex_radius_of(i, vertex_radius);
+            // Выбранная вершина и вершина, обработанная на последнем шаге алгоритма Дейкстры, выделены,
+            // достигнутые необработанные вершины и вершины слоистой сети выделены другим цветом
+            let in_level_graph = matches!(
+                frame.flow_data.and_then(|data| data.get_levels().as_ref()),
+                Some(levels) if levels.contains_key(i)
+            );
+            let marked_color = match path_data {
+                _ if self.selected_vertex.as_ref() == Some(i) => Some(self.selection_color),
+                Some(data) if data.get_last_vertex().as_ref() == Some(i) => {
+                    Some(self.selection_color)
+                }
+                Some(data) if data.is_in_frontier(i) => Some(rgba_color(FRONTIER_COLOR)),
+                _ if in_level_graph => Some(rgba_color(FRONTIER_COLOR)),
+                _ => None,
+            };
+            let vertex_color = match marked_color {
+                Some(color) => color,
+                None if self.color_by_component => self
+                    .components
+                    .get(i)
+                    .map_or(self.front_color, |&c| self.component_colors[c]),
+                None => self.front_color,
+            };
+            // Цвет заливки, заданный у вершины в графе
+            let fill_color = frame
+                .g
+                .get_vertices()
+                .get(i)
+                .and_then(|v| v.color)
+                .map(|c| rgba_color(c.to_rgba()));
+            // Кольцо вокруг вершины, выделенной прямоугольником
+            if self.selected_vertices.contains(i) {
+                let mut path = Path::new();
+                path.circle(*x, *y, radius * SELECTION_RING_COEFF);
+                paint.set_color(self.selection_color);
+                canvas.stroke_path(&mut path, *paint);
+            }
+            if frame.full_render {
+                // Заполнение круга цветом вершины или фоновым цветом (у вершин со стороны истока
+                // минимального разреза — с примесью цвета разреза), затем контур основным цветом
+                let back_color = match frame.g_algorithm_state {
+                    GraphAlgorithmState::MaxFlow(AlgorithmState::Finished(data))
+                        if data.get_min_cut_vertices().contains(i) =>
+                    {
+                        let back = [
+                            self.back_color.r,
+                            self.back_color.g,
+                            self.back_color.b,
+                            self.back_color.a,
+                        ];
+                        rgba_color(mix_colors(back, MIN_CUT_COLOR, MIN_CUT_FILL_PART))
+                    }
+                    _ => self.back_color,
+                };
+                let mut path = Path::new();
+                path.circle(*x, *y, radius);
+                paint.set_color(fill_color.unwrap_or(back_color));
+                canvas.fill_path(&mut path, *paint);
+                paint.set_color(vertex_color);
+                canvas.stroke_path(&mut path, *paint);
+                // Второй контур у закреплённой вершины
+                if is_pinned {
+                    let mut path = Path::new();
+                    path.circle(*x, *y, radius * 0.85);
+                    canvas.stroke_path(&mut path, *paint);
+                }
+            } else {
+                // Заполнение круга цветом вершины или основным цветом (выделение важнее цвета вершины)
+                let mut path = Path::new();
+                path.circle(*x, *y, radius);
+                paint.set_color(marked_color.or(fill_color).unwrap_or(vertex_color));
+                canvas.fill_path(&mut path, *paint);
+                // Контур фоновым цветом у закреплённой вершины
+                if is_pinned {
+                    let mut path = Path::new();
+                    path.circle(*x, *y, radius * 0.6);
+                    paint.set_color(self.back_color);
+                    canvas.stroke_path(&mut path, *paint);
+                }
                 continue;
             }
-            self.vertices.insert(
-                i.clone(),
-                (
-                    self.rng.sample(coord_distribution),
-                    self.rng.sample(coord_distribution),
-                ),
-            );
-        }
-
-        // Если обновления графа отключены
-        if self.updates_stopped {
-            return;
-        }
-
-        // Гравитация к центру
-        let mut forces: BTreeMap<_, _> = self
-            .vertices
-            .iter()
-            .map(|(i, (x, y))| {
-                (
-                    i.clone(),
-                    (-x * self.center_gravity, -y * self.center_gravity),
-                )
-            })
-            .collect();
-
-        // Минимальные и максимальные координаты вершин
-        let (min_x, max_x, min_y, max_y) = self.vertices.iter().map(|(_, coords)| *coords).fold(
-            (f32::MAX, f32::MIN, f32::MAX, f32::MIN),
-            |(acc_min_x, acc_max_x, acc_min_y, acc_max_y), (x, y)| {
-                (
-                    f32::min(acc_min_x, x),
-                    f32::max(acc_max_x, x),
-                    f32::min(acc_min_y, y),
-                    f32::max(acc_max_y, y),
-                )
-            },
-        );
 
-        // Построение дерева квадрантов для всех вершин
-        let mut tree = quad_tree::Node::Empty;
-        for v in self.vertices.values() {
-            tree = tree.insert(*v, min_x, max_x, min_y, max_y);
-        }
-        tree.finish_inserts();
+            // Текст вершин скрыт из-за мелкого масштаба
+            if !frame.show_vertex_labels {
+                continue;
+            }
 
-        // Силы отталкивания между вершинами
-        for (i, v) in &self.vertices {
-            let force = tree.get_force(*v, self.theta, min_x, max_x, min_y, max_y);
-            let force_i = forces.get_mut(i).unwrap();
-            *force_i = (
-                force_i.0 + self.repulsive_force * force.0,
-                force_i.1 + self.repulsive_force * force.1,
-            );
+            // Текст идентификатора и метки вершины
+            let text = vertex_text(frame.g, i, frame.flow_data, path_data)?;
+            // При раскраске по компонентам текст выводится основным цветом
+            if self.color_by_component {
+                paint.set_color(self.front_color);
+            }
+            // Размер шрифта по радиусу вершины
+            if self.size_by_degree {
+                paint.set_font_size(radius * scale_coeff);
+            }
+            canvas.save();
+            canvas.scale(1.0 / scale_coeff, 1.0 / scale_coeff);
+            canvas
+                .fill_text(*x * scale_coeff, *y * scale_coeff, text, *paint)
+                .unwrap();
+            canvas.restore();
         }
 
-        // Притяжение/отталкивание вершин, связанных рёбрами
-        for i in g.get_vertices().keys() {
-            let pos_i = self.vertices[i];
-            for Edge { to, .. } in g.get_edge_list(i).unwrap() {
-                let pos_to = self.vertices[to];
-                let force = (pos_i.0 - pos_to.0, pos_i.1 - pos_to.1);
-
-                let force_i = forces.get_mut(i).unwrap();
-                *force_i = (force_i.0 - force.0, force_i.1 - force.1);
-                let force_to = forces.get_mut(to).unwrap();
-                *force_to = (force_to.0 + force.0, force_to.1 + force.1);
+        // Исток и сток обводятся кольцами своих цветов и отмечаются буквами S и T
+        // справа сверху (метки масштабируются вместе с вершинами)
+        if let Some(data) = frame.flow_data {
+            for (i, marker, color) in [
+                (data.get_s(), "S", rgba_color(SOURCE_COLOR)),
+                (data.get_t(), "T", rgba_color(SINK_COLOR)),
+            ] {
+                let (x, y) = match self.vertices.get(i) {
+                    Some(&pos) => pos,
+                    None => continue,
+                };
+                let radius = self.vertex_radius_of(i, vertex_radius);
+                let mut path = Path::new();
+                path.circle(x, y, radius * TERMINAL_RING_COEFF);
+                paint.set_color(color);
+                canvas.stroke_path(&mut path, *paint);
+
+                paint.set_font_size(radius * TERMINAL_MARKER_COEFF * scale_coeff);
+                paint.set_text_align(Align::Left);
+                paint.set_text_baseline(Baseline::Bottom);
+                canvas.save();
+                canvas.scale(1.0 / scale_coeff, 1.0 / scale_coeff);
+                canvas
+                    .fill_text(
+                        (x + radius * FRAC_1_SQRT_2) * scale_coeff,
+                        (y - radius * FRAC_1_SQRT_2) * scale_coeff,
+                        marker,
+                        *paint,
+                    )
+                    .unwrap();
+                canvas.restore();
+                paint.set_text_align(Align::Center);
+                paint.set_text_baseline(Baseline::Middle);
             }
         }
 
-        // Применение сил ко всем вершинам
-        for (i, (f_x, f_y)) in forces {
-            if let Some(dragging_i) = &self.dragging_vertex {
-                if &i == dragging_i {
-                    continue;
-                }
+        // Номера вершин выводятся основным цветом справа снизу
+        if frame.show_vertex_labels {
+            paint.set_color(self.front_color);
+            paint.set_text_align(Align::Left);
+            paint.set_text_baseline(Baseline::Top);
+            for (i, number) in &self.vertex_numbers {
+                let (x, y) = match self.vertices.get(i) {
+                    Some(&pos) => pos,
+                    None => continue,
+                };
+                let radius = self.vertex_radius_of(i, vertex_radius);
+                paint.set_font_size(radius * TERMINAL_MARKER_COEFF * scale_coeff);
+                canvas.save();
+                canvas.scale(1.0 / scale_coeff, 1.0 / scale_coeff);
+                canvas
+                    .fill_text(
+                        (x + radius * FRAC_1_SQRT_2) * scale_coeff,
+                        (y + radius * FRAC_1_SQRT_2) * scale_coeff,
+                        number.to_string(),
+                        *paint,
+                    )
+                    .unwrap();
+                canvas.restore();
             }
-            let pos = self.vertices.get_mut(&i).unwrap();
-            *pos = (pos.0 + f_x * self.time_step, pos.1 + f_y * self.time_step);
+            paint.set_text_align(Align::Center);
+            paint.set_text_baseline(Baseline::Middle);
         }
+        Ok(())
+    }
+
+    // Отрисовка графа в scale раз крупнее поля width на height (для сохранения изображения
+    // в большем разрешении): размеры в пикселях и сдвиг камеры умножаются на scale,
+    // поэтому изображение совпадает с изображением в окне
+    #[allow(clippy::too_many_arguments)]
+    pub fn draw_scaled<W>(
+        &mut self,
+        canvas: &mut Canvas<OpenGl>,
+        font: FontId,
+        width: f32,
+        height: f32,
+        scale: f32,
+        g: &Option<Graph<I, W>>,
+        g_algorithm_state: &GraphAlgorithmState<I, W>,
+    ) -> Result<(), GraphOperationError>
+    where
+        W: EdgeWeight,
+    {
+        let (scale_factor, center_shift) = (self.scale_factor, self.center_shift);
+        self.scale_factor *= scale;
+        self.center_shift = (center_shift.0 * scale, center_shift.1 * scale);
+        let res = self.draw(
+            canvas,
+            font,
+            width * scale,
+            height * scale,
+            g,
+            g_algorithm_state,
+        );
+        self.scale_factor = scale_factor;
+        self.center_shift = center_shift;
+        res
     }
 
     // Отрисовка графа
@@ -289,29 +1865,21 @@ where
         font: FontId,
         width: f32,
         height: f32,
-        dpi_factor: f32,
         g: &Option<Graph<I, W>>,
-        g_algorithm_state: &AlgorithmState<I, W>,
+        g_algorithm_state: &GraphAlgorithmState<I, W>,
     ) -> Result<(), GraphOperationError>
     where
         W: EdgeWeight,
     {
-        // Константы для количества вершин на единицу длины, минимального размера вершин,
-        // скорости расширения поля
-        const VERTEX_CNT: i32 = 10;
-        const MIN_VERTEX_DIAMETER: f32 = 16.0;
+        // Скорость расширения поля
         const MOVE_TO_BORDER_SPEED: f32 = 0.005;
 
-        // Цвет выделения
-        const SELECTION_COLOR: Color = Color {
-            r: 1.0,
-            g: 0.0,
-            b: 0.0,
-            a: 1.0,
-        };
+        let dpi_factor = self.scale_factor;
 
         // Закраска поля фоновым цветом
         canvas.reset();
+        self.flow_animated = false;
+        self.edge_label_rects.clear();
         canvas.set_size(width as u32, height as u32, dpi_factor);
         canvas.clear_rect(0, 0, width as u32, height as u32, self.back_color);
 
@@ -322,27 +1890,22 @@ where
 
         // Минимальная сторона, диаметр и радиус вершины
         let min_sz = f32::min(width, height);
-        let vertex_diameter = f32::max(min_sz / (VERTEX_CNT as f32), MIN_VERTEX_DIAMETER) / min_sz;
+        let vertex_diameter = Self::vertex_diameter(min_sz);
         let vertex_radius = vertex_diameter / 2.0;
 
         // Минимальные и максимальные координаты вершин
-        let (min_x, max_x, min_y, max_y) = self.vertices.iter().map(|(_, coords)| *coords).fold(
-            (f32::MAX, f32::MIN, f32::MAX, f32::MIN),
-            |(acc_min_x, acc_max_x, acc_min_y, acc_max_y), (x, y)| {
-                (
-                    f32::min(acc_min_x, x),
-                    f32::max(acc_max_x, x),
-                    f32::min(acc_min_y, y),
-                    f32::max(acc_max_y, y),
-                )
-            },
-        );
+        let (min_x, max_x, min_y, max_y) = self.bounding_box().unwrap();
         // Размер графа по x и y
         let (diff_x, diff_y) = (max_x - min_x, max_y - min_y);
         // Центр графа
         let (center_x, center_y) = ((min_x + max_x) / 2.0, (min_y + max_y) / 2.0);
         // Коэффициент масштаба для графа
         let max_diff = f32::max(1.0, f32::max(diff_x, diff_y));
+        // Фокусировка камеры на вершине изменяет масштаб и сдвиг камеры
+        self.update_focus(
+            (min_sz - min_sz * vertex_diameter) / max_diff,
+            (center_x, center_y),
+        );
         // Коэффициент масштаба для поля отрисовки
         let scale_coeff = self.zoom * (min_sz - min_sz * vertex_diameter) / max_diff;
 
@@ -351,6 +1914,17 @@ where
         canvas.translate(width / 2.0, height / 2.0);
         canvas.scale(scale_coeff, scale_coeff);
         canvas.translate(-center_x, -center_y);
+        // Толщина линий при полной и упрощённой отрисовке
+        let (full_line_width, simple_line_width) = (2.0 / min_sz, 5.0 / min_sz);
+        self.view = Some((canvas.transform(), vertex_radius));
+        self.line_width = full_line_width;
+
+        // Уровень детализации по диаметру вершины на экране: при уменьшении вершин
+        // скрываются подписи рёбер, затем текст вершин, затем отрисовка упрощается
+        let screen_diameter = vertex_diameter * scale_coeff / dpi_factor;
+        let full_render = self.full_render && screen_diameter >= LOD_SIMPLE_RENDER_DIAMETER;
+        let show_edge_labels = self.show_edge_labels && screen_diameter >= LOD_EDGE_LABEL_DIAMETER;
+        let show_vertex_labels = full_render && screen_diameter >= self.lod_label_diameter;
 
         // Перемещение вершины, если нажата мышь
         if self.mouse_dragging {
@@ -372,376 +1946,409 @@ where
                 // Если ещё не выбрана вершина, то попытаться найти её
                 // Если мышь уже перемещается, то происходит сдвиг камеры, а не вершины
                 if self.mouse_press_prev.is_none() && self.dragging_vertex.is_none() {
-                    for (i, (v_x, v_y)) in &self.vertices {
-                        if (x - v_x).powi(2) + (y - v_y).powi(2) <= vertex_radius.powi(2) {
-                            self.dragging_vertex = Some(i.clone());
-                            break;
-                        }
-                    }
+                    self.dragging_vertex = self.find_vertex(x, y, vertex_radius);
                 }
                 // Если вершина выбрана, то обновить её координаты
                 if let Some(i) = &self.dragging_vertex {
-                    *(self.vertices.get_mut(i).unwrap()) = (x, y);
+                    let pos = self.vertices.get_mut(i).unwrap();
+                    let shift = (x - pos.0, y - pos.1);
+                    *pos = (x, y);
+                    // Выделенная вершина перемещает все выделенные вершины на то же расстояние
+                    if self.selected_vertices.contains(i) {
+                        for j in self.selected_vertices.iter().filter(|j| j != &i) {
+                            if let Some(pos) = self.vertices.get_mut(j) {
+                                *pos = (pos.0 + shift.0, pos.1 + shift.1);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        // Закрепление или открепление вершины под курсором
+        if self.pin_toggle_requested {
+            self.pin_toggle_requested = false;
+            if let Some((x, y)) = &self.mouse_press {
+                // Переход к системе координат вершин
+                let (x, y) = canvas.transform().inversed().transform_point(*x, *y);
+                if let Some(i) = self.find_vertex(x, y, vertex_radius) {
+                    if !self.pinned_vertices.remove(&i) {
+                        self.pinned_vertices.insert(i);
+                    }
                 }
             }
         }
 
         // Толщина линий, шрифт
         let mut paint = Paint::color(self.front_color);
-        if self.full_render {
-            paint.set_line_width(2.0 / min_sz);
+        let line_width = if full_render {
+            full_line_width
         } else {
-            paint.set_line_width(5.0 / min_sz);
-        }
+            simple_line_width
+        };
+        paint.set_line_width(line_width);
         paint.set_font(&[font]);
         paint.set_text_align(Align::Center);
         paint.set_text_baseline(Baseline::Middle);
 
-        // Отрисовка рёбер
-        for i in g.get_vertices().keys() {
-            let (x_i, y_i) = *self
-                .vertices
-                .get(i)
-                .ok_or(GraphOperationError::VertexNotFound)?;
-            for Edge { to, weight } in g.get_edge_list(i).unwrap() {
-                let (x_to, y_to) = *self
-                    .vertices
-                    .get(to)
-                    .ok_or(GraphOperationError::VertexNotFound)?;
-
-                // Поток в последнем дополняющем пути через текущее ребро
-                let mut edge_flow = None;
-                if let AlgorithmState::Step(data) | AlgorithmState::Finished(data) =
-                    g_algorithm_state
-                {
-                    if let Some(path) = &data.get_curr_path() {
-                        edge_flow = path.get(&(i.clone(), to.clone()));
-                    }
-                };
-
-                // Если есть поток, то ребро выделено, иначе используется основной цвет
-                paint.set_color(match edge_flow {
-                    Some(_) => SELECTION_COLOR,
-                    None => self.front_color,
-                });
-
-                let mut path = Path::new();
-                if i == to {
-                    // Окружность ребра-петли
-                    path.circle(
-                        x_i - vertex_radius * FRAC_1_SQRT_2,
-                        y_i - vertex_radius * FRAC_1_SQRT_2,
-                        vertex_radius * 2.0 / 3.0,
-                    );
-                } else if g.get_is_directed() && g.get_edge(to, i).is_ok() {
-                    // Вектор от начальной к конечной вершине
-                    let dir = (x_to - x_i, y_to - y_i);
-                    let len = (dir.0 * dir.0 + dir.1 * dir.1).sqrt();
-                    // Перпендикуляр к вектору
-                    let dir_normal = (-dir.1, dir.0);
-                    // Точка в центре ребра с отступом
-                    let (edge_center_x, edge_center_y) = (
-                        x_i + dir.0 / 2.0 + dir_normal.0 / (20.0 * len),
-                        y_i + dir.1 / 2.0 + dir_normal.1 / (20.0 * len),
-                    );
-
-                    // Кривая Безье ребра
-                    path.move_to(x_i, y_i);
-                    path.quad_to(edge_center_x, edge_center_y, x_to, y_to);
-                } else {
-                    // Линия ребра
-                    path.move_to(x_i, y_i);
-                    path.line_to(x_to, y_to);
-                }
-                canvas.stroke_path(&mut path, paint);
-
-                if !self.full_render {
-                    continue;
-                }
-
-                // Стрелка дуги
-                if g.get_is_directed() {
-                    // Координаты крайних точек стрелки дуги
-                    let coord_1: (f32, f32);
-                    let coord_2: (f32, f32);
-                    // Точка пересечения окружности вершины и дуги
-                    let vertex_edge: (f32, f32);
-
-                    if i == to {
-                        // Центр окружности ребра-петли
-                        let (x_loop, y_loop) = (
-                            x_i - vertex_radius * FRAC_1_SQRT_2,
-                            y_i - vertex_radius * FRAC_1_SQRT_2,
-                        );
-                        // Точка пересечения окружности вершины и ребра-петли
-                        vertex_edge = (
-                            (-7.0 * SQRT_2 + 8.0) * vertex_radius / 18.0 + x_i,
-                            (-7.0 * SQRT_2 - 8.0) * vertex_radius / 18.0 + y_i,
-                        );
+        // Сетка (в упрощённом режиме не выводится)
+        if self.show_grid && full_render {
+            self.draw_grid(canvas, width, height, line_width);
+        }
 
-                        // Вектор из центра вершины в центр окружности ребра-петли
-                        let dir = (x_loop - x_i, y_loop - y_i);
-                        // Длина вектора
-                        let len = (dir.0 * dir.0 + dir.1 * dir.1).sqrt();
+        // Данные алгоритма нахождения максимального потока, если он запущен
+        let flow_data = match g_algorithm_state {
+            GraphAlgorithmState::MaxFlow(AlgorithmState::Step(data))
+            | GraphAlgorithmState::MaxFlow(AlgorithmState::Finished(data)) => Some(data),
+            _ => None,
+        };
+        // Данные алгоритма Дейкстры, если он запущен
+        let path_data = match g_algorithm_state {
+            GraphAlgorithmState::ShortestPath(ShortestPathState::Step(data))
+            | GraphAlgorithmState::ShortestPath(ShortestPathState::Finished(data)) => Some(data),
+            _ => None,
+        };
+        // Рёбра найденного паросочетания
+        let matching_edges = match g_algorithm_state {
+            GraphAlgorithmState::Matching(MatchingState::Finished(data)) => Some(data.get_edges()),
+            _ => None,
+        };
 
-                        // Поворот вектора на 45 градусов против часовой стрелки
-                        let dir_1 = (
-                            dir.0 * FRAC_1_SQRT_2 - dir.1 * FRAC_1_SQRT_2,
-                            dir.0 * FRAC_1_SQRT_2 + dir.1 * FRAC_1_SQRT_2,
-                        );
-                        // Вектор с длиной в 1/2 радиуса вершины
-                        coord_1 = (
-                            vertex_edge.0 + dir_1.0 * vertex_radius * 0.5 / len,
-                            vertex_edge.1 + dir_1.1 * vertex_radius * 0.5 / len,
-                        );
+        // Расстояние от курсора до ребра в пикселях, на котором ребро считается наведённым
+        const HOVER_DISTANCE: f32 = 6.0;
+        // Минимальный и максимальный веса рёбер, если толщина рёбер зависит от веса
+        let weight_range = if self.edge_width_by_weight && g.get_is_weighted() {
+            weight_range(g)
+        } else {
+            None
+        };
 
-                        // Поворот вектора на 45 градусов по часовой стрелке
-                        let dir_2 = (
-                            dir.0 * FRAC_1_SQRT_2 + dir.1 * FRAC_1_SQRT_2,
-                            -dir.0 * FRAC_1_SQRT_2 + dir.1 * FRAC_1_SQRT_2,
-                        );
-                        // Вектор с длиной в 1/2 радиуса вершины
-                        coord_2 = (
-                            vertex_edge.0 + dir_2.0 * vertex_radius * 0.5 / len,
-                            vertex_edge.1 + dir_2.1 * vertex_radius * 0.5 / len,
-                        );
-                    } else if g.get_is_directed() && g.get_edge(to, i).is_ok() {
-                        // Вектор от начальной к конечной вершине
-                        let dir = (x_to - x_i, y_to - y_i);
-                        let len = (dir.0 * dir.0 + dir.1 * dir.1).sqrt();
-                        // Перпендикуляр к вектору
-                        let dir_normal = (-dir.1, dir.0);
-                        // Точка в центре ребра с отступом
-                        let (edge_center_x, edge_center_y) = (
-                            x_i + dir.0 / 2.0 + dir_normal.0 / (20.0 * len),
-                            y_i + dir.1 / 2.0 + dir_normal.1 / (20.0 * len),
-                        );
-                        // Вектор от конечной вершины к центральной точке
-                        let center_dir = (edge_center_x - x_to, edge_center_y - y_to);
-                        // Длина вектора
-                        let center_len_sqr =
-                            center_dir.0 * center_dir.0 + center_dir.1 * center_dir.1;
-                        let center_len = center_len_sqr.sqrt();
-
-                        // Функция отклонения точки кривой Безье от пересечения с окружностью конечной вершины
-                        let f_bezier = |t: f32| {
-                            let x = (1.0 - t).powi(2) * x_to
-                                + 2.0 * t * (1.0 - t) * edge_center_x
-                                + t.powi(2) * x_i
-                                - x_to;
-                            let y = (1.0 - t).powi(2) * y_to
-                                + 2.0 * t * (1.0 - t) * edge_center_y
-                                + t.powi(2) * y_i
-                                - y_to;
-                            x.powi(2) + y.powi(2) - vertex_radius.powi(2)
-                        };
-                        // Производная этой функции
-                        let df_bezier = |t: f32| {
-                            let x = 2.0
-                                * (2.0 * t * (x_i - edge_center_x)
-                                    + 2.0 * (1.0 - t) * (edge_center_x - x_to))
-                                * (x_i * t.powi(2)
-                                    + 2.0 * edge_center_x * t * (1.0 - t)
-                                    + x_to * (1.0 - t).powi(2)
-                                    - x_to);
-                            let y = 2.0
-                                * (2.0 * t * (y_i - edge_center_y)
-                                    + 2.0 * (1.0 - t) * (edge_center_y - y_to))
-                                * (y_i * t.powi(2)
-                                    + 2.0 * edge_center_y * t * (1.0 - t)
-                                    + y_to * (1.0 - t).powi(2)
-                                    - y_to);
-                            x + y
-                        };
-
-                        // Вычисление параметра кривой Безье алгоритмом Ньютона
-                        let mut t = 0.5;
-                        for _ in 0..5 {
-                            t -= f_bezier(t) / df_bezier(t);
-                        }
+        // Дуги с противоположными дугами пересчитываются только после изменения графа
+        if self.antiparallel_edges.is_none() {
+            self.antiparallel_edges = Some(antiparallel_edges(g));
+        }
 
-                        // Точка пересечения окружности конечной вершины и кривой Безье
-                        vertex_edge = (
-                            (1.0 - t).powi(2) * x_to
-                                + 2.0 * t * (1.0 - t) * edge_center_x
-                                + t.powi(2) * x_i,
-                            (1.0 - t).powi(2) * y_to
-                                + 2.0 * t * (1.0 - t) * edge_center_y
-                                + t.powi(2) * y_i,
-                        );
+        // Направления рёбер-петель (в сторону от соседних вершин)
+        let loop_dirs: BTreeMap<I, (f32, f32)> = g
+            .get_vertices()
+            .keys()
+            .filter(|i| g.get_edge(i, i).is_ok())
+            .filter_map(|i| {
+                let neighbors: Vec<_> = g
+                    .get_vertices()
+                    .keys()
+                    .filter(|j| j != &i && (g.get_edge(i, j).is_ok() || g.get_edge(j, i).is_ok()))
+                    .filter_map(|j| self.vertices.get(j).copied())
+                    .collect();
+                let vertex = *self.vertices.get(i)?;
+                Some((i.clone(), loop_direction(vertex, &neighbors)))
+            })
+            .collect();
 
-                        // Поворот вектора на 45 градусов против часовой стрелки
-                        let dir_1 = (
-                            center_dir.0 * FRAC_1_SQRT_2 - center_dir.1 * FRAC_1_SQRT_2,
-                            center_dir.0 * FRAC_1_SQRT_2 + center_dir.1 * FRAC_1_SQRT_2,
-                        );
-                        // Вектор с длиной в 1/2 радиуса вершины
-                        coord_1 = (
-                            vertex_edge.0 + dir_1.0 * vertex_radius * 0.5 / center_len,
-                            vertex_edge.1 + dir_1.1 * vertex_radius * 0.5 / center_len,
-                        );
+        // Вершина под курсором
+        let hovered_vertex = self.mouse_press.and_then(|(x, y)| {
+            let (x, y) = canvas.transform().inversed().transform_point(x, y);
+            self.find_vertex(x, y, vertex_radius)
+        });
+        // Ребро под курсором (кроме случаев, когда курсор над вершиной, вершина перемещается,
+        // выделяется прямоугольник или протягивается новое ребро)
+        self.hovered_edge = match self.mouse_press {
+            Some((x, y))
+                if self.dragging_vertex.is_none()
+                    && hovered_vertex.is_none()
+                    && self.selection_rect.is_none()
+                    && self.edge_drag_from.is_none() =>
+            {
+                let (x, y) = canvas.transform().inversed().transform_point(x, y);
+                let max_distance = HOVER_DISTANCE * dpi_factor / scale_coeff;
+                self.pick_edge(g, (x, y), vertex_radius, max_distance, &loop_dirs)
+            }
+            _ => None,
+        };
+        // Время анимации потока (если она включена и нужна полная отрисовка)
+        let animation_time = (self.animate_flow && full_render)
+            .then(|| self.animation_start.elapsed().as_secs_f32());
+
+        // Данные кадра для отрисовки рёбер и вершин
+        let frame = FrameData {
+            g,
+            g_algorithm_state,
+            flow_data,
+            path_data,
+            matching_edges,
+            loop_dirs,
+            weight_range,
+            animation_time,
+            vertex_radius,
+            scale_coeff,
+            line_width,
+            full_render,
+            show_edge_labels,
+            show_vertex_labels,
+            min_label_font_size: MIN_EDGE_LABEL_FONT_SIZE * dpi_factor,
+        };
 
-                        // Поворот вектора на 45 градусов по часовой стрелке
-                        let dir_2 = (
-                            center_dir.0 * FRAC_1_SQRT_2 + center_dir.1 * FRAC_1_SQRT_2,
-                            -center_dir.0 * FRAC_1_SQRT_2 + center_dir.1 * FRAC_1_SQRT_2,
-                        );
-                        // Вектор с длиной в 1/2 радиуса вершины
-                        coord_2 = (
-                            vertex_edge.0 + dir_2.0 * vertex_radius * 0.5 / center_len,
-                            vertex_edge.1 + dir_2.1 * vertex_radius * 0.5 / center_len,
-                        );
-                    } else {
-                        // Вектор от конечной к начальной вершине
-                        let rev_dir = (x_i - x_to, y_i - y_to);
-                        let len = (rev_dir.0 * rev_dir.0 + rev_dir.1 * rev_dir.1).sqrt();
-                        // Точка пересечения контура конечной вершины и дуги
-                        vertex_edge = (
-                            x_to + rev_dir.0 * vertex_radius / len,
-                            y_to + rev_dir.1 * vertex_radius / len,
-                        );
+        // Отрисовка рёбер с запоминанием прямоугольников подписей
+        let mut label_rects = std::mem::take(&mut self.edge_label_rects);
+        self.flow_animated = self.draw_edges(canvas, &mut paint, &frame, &mut label_rects)?;
+        self.edge_label_rects = label_rects;
 
-                        // Поворот вектора на 45 градусов против часовой стрелки
-                        let dir_1 = (
-                            rev_dir.0 * FRAC_1_SQRT_2 - rev_dir.1 * FRAC_1_SQRT_2,
-                            rev_dir.0 * FRAC_1_SQRT_2 + rev_dir.1 * FRAC_1_SQRT_2,
-                        );
-                        // Вектор с длиной в 1/2 радиуса вершины
-                        coord_1 = (
-                            vertex_edge.0 + dir_1.0 * vertex_radius * 0.5 / len,
-                            vertex_edge.1 + dir_1.1 * vertex_radius * 0.5 / len,
-                        );
+        // Обычный размер шрифта и толщина линий
+        paint.set_font_size(vertex_radius * scale_coeff);
+        paint.set_line_width(line_width);
 
-                        // Поворот вектора на 45 градусов по часовой стрелке
-                        let dir_2 = (
-                            rev_dir.0 * FRAC_1_SQRT_2 + rev_dir.1 * FRAC_1_SQRT_2,
-                            -rev_dir.0 * FRAC_1_SQRT_2 + rev_dir.1 * FRAC_1_SQRT_2,
-                        );
-                        // Вектор с длиной в 1/2 радиуса вершины
-                        coord_2 = (
-                            vertex_edge.0 + dir_2.0 * vertex_radius * 0.5 / len,
-                            vertex_edge.1 + dir_2.1 * vertex_radius * 0.5 / len,
-                        );
-                    }
+        // Отрисовка вершин
+        self.draw_vertices(canvas, &mut paint, &frame)?;
+
+        // Пульсирующее кольцо вокруг вершины, на которой фокусируется камера
+        // (радиус кольца изменяется от минимального до максимального в радиусах вершины)
+        if let Some(focus) = &self.focus {
+            const FOCUS_RING_MIN_COEFF: f32 = 1.3;
+            const FOCUS_RING_MAX_COEFF: f32 = 2.0;
+
+            if let Some(&(x, y)) = self.vertices.get(&focus.vertex) {
+                let phase = focus.start.elapsed().as_secs_f32() / FOCUS_PULSE_PERIOD;
+                let pulse = 0.5 - 0.5 * (TAU * phase).cos();
+                let radius = self.vertex_radius_of(&focus.vertex, vertex_radius)
+                    * (FOCUS_RING_MIN_COEFF
+                        + (FOCUS_RING_MAX_COEFF - FOCUS_RING_MIN_COEFF) * pulse);
+                let mut path = Path::new();
+                path.circle(x, y, radius);
+                paint.set_color(self.selection_color);
+                paint.set_line_width(2.0 * line_width);
+                canvas.stroke_path(&mut path, paint);
+                paint.set_line_width(line_width);
+            }
+        }
 
-                    // Отрисовка стрелки дуги
-                    let mut path = Path::new();
-                    path.move_to(coord_1.0, coord_1.1);
-                    path.line_to(vertex_edge.0, vertex_edge.1);
-                    path.line_to(coord_2.0, coord_2.1);
-                    canvas.stroke_path(&mut path, paint);
-                }
+        // Текст перемещаемой вершины или вершины под курсором выводится, даже если текст
+        // вершин скрыт из-за мелкого масштаба (но не при выключенной полной отрисовке)
+        let label_vertex = if self.full_render && !show_vertex_labels {
+            self.dragging_vertex.as_ref().or(hovered_vertex.as_ref())
+        } else {
+            None
+        };
+        if let Some(i) = label_vertex {
+            // Размер шрифта и отступ внутри подписи в пикселях
+            const LABEL_FONT_SIZE: f32 = 14.0;
+            const LABEL_PADDING: f32 = 4.0;
 
-                if let Some(w) = weight {
-                    // Если выполняется нахождение потока, то выводить поток и пропускную способность ребра,
-                    // иначе только вес ребра
-                    let text = match g_algorithm_state {
-                        AlgorithmState::NotStarted => {
-                            // Обычный размер шрифта
-                            paint.set_font_size(vertex_radius * scale_coeff);
-                            format!("{}", w)
-                        }
-                        AlgorithmState::Step(data) | AlgorithmState::Finished(data) => {
-                            // Маленький размер шрифта
-                            paint.set_font_size(vertex_radius * scale_coeff / 2.0);
-                            // Поток через ребро
-                            let f = data
-                                .get_gf()
-                                .get_edge(i, to)
-                                .unwrap()
-                                .weight
-                                .as_ref()
-                                .unwrap();
-                            // Вывод потока в последнем дополняющем пути, если он есть
-                            match edge_flow {
-                                Some(curr_f) => format!("{} ({:+}) / {}", f, curr_f, w),
-                                None => format!("{} / {}", f, w),
-                            }
-                        }
-                    };
+            let text = vertex_text(g, i, flow_data, path_data)?;
+            let (x, y) = *self
+                .vertices
+                .get(i)
+                .ok_or(GraphOperationError::VertexNotFound)?;
+            // Подпись выводится над вершиной
+            let radius = self.vertex_radius_of(i, vertex_radius);
+            let (x, y) = canvas.transform().transform_point(x, y - radius);
 
-                    // Вывод текста
-                    canvas.save();
-                    canvas.scale(1.0 / scale_coeff, 1.0 / scale_coeff);
-                    paint.set_line_width(3.0 * scale_coeff / min_sz);
+            canvas.save();
+            canvas.reset_transform();
+            paint.set_font_size(LABEL_FONT_SIZE * dpi_factor);
+            paint.set_text_baseline(Baseline::Bottom);
+            paint.set_line_width(dpi_factor);
+            let padding = LABEL_PADDING * dpi_factor;
+            let metrics = canvas.measure_text(x, y, &text, paint).unwrap();
+            let (w_box, h_box) = (
+                metrics.width() + 2.0 * padding,
+                metrics.height() + 2.0 * padding,
+            );
+            let mut path = Path::new();
+            path.rounded_rect(x - w_box / 2.0, y - h_box - padding, w_box, h_box, padding);
+            paint.set_color(self.back_color);
+            canvas.fill_path(&mut path, paint);
+            paint.set_color(self.front_color);
+            canvas.stroke_path(&mut path, paint);
+            canvas.fill_text(x, y - 2.0 * padding, text, paint).unwrap();
+            canvas.restore();
+            paint.set_text_baseline(Baseline::Middle);
+        }
 
-                    // Координаты текста
-                    let (x_text, y_text) = if i == to {
-                        (
-                            x_i - vertex_radius * FRAC_1_SQRT_2 * 7.0 / 4.0,
-                            y_i - vertex_radius * FRAC_1_SQRT_2 * 7.0 / 4.0,
-                        )
-                    } else if g.get_is_directed() && g.get_edge(to, i).is_ok() {
-                        // Вектор от начальной к конечной вершине
-                        let dir = (x_to - x_i, y_to - y_i);
-                        let len = (dir.0 * dir.0 + dir.1 * dir.1).sqrt();
-                        // Перпендикуляр к вектору
-                        let dir_normal = (-dir.1, dir.0);
-                        // Точка в центре ребра с отступом
-                        (
-                            x_i + dir.0 / 2.0 + dir_normal.0 / (40.0 * len),
-                            y_i + dir.1 / 2.0 + dir_normal.1 / (40.0 * len),
-                        )
-                    } else {
-                        ((x_i + x_to) / 2.0, (y_i + y_to) / 2.0)
-                    };
-                    // Обводка текста
-                    paint.set_color(self.back_color);
-                    canvas
-                        .stroke_text(x_text * scale_coeff, y_text * scale_coeff, &text, paint)
-                        .unwrap();
-                    // Закраска текста
-                    paint.set_color(self.front_color);
-                    canvas
-                        .fill_text(x_text * scale_coeff, y_text * scale_coeff, text, paint)
-                        .unwrap();
-
-                    paint.set_line_width(2.0 / min_sz);
-                    canvas.restore();
-                }
+        // Прямоугольник выделения пунктиром
+        if let Some((start, end)) = self.selection_rect {
+            // Длина штриха и промежутка между штрихами в пикселях
+            const DASH_LENGTH: f32 = 6.0;
+            const DASH_GAP: f32 = 4.0;
+
+            let corners = [start, (end.0, start.1), end, (start.0, end.1)];
+            let mut path = Path::new();
+            for k in 0..corners.len() {
+                Self::add_dashed_line(
+                    &mut path,
+                    corners[k],
+                    corners[(k + 1) % corners.len()],
+                    DASH_LENGTH * dpi_factor,
+                    DASH_GAP * dpi_factor,
+                );
             }
+            canvas.save();
+            canvas.reset_transform();
+            paint.set_color(self.selection_color);
+            paint.set_line_width(dpi_factor);
+            canvas.stroke_path(&mut path, paint);
+            canvas.restore();
         }
 
-        // Обычный размер шрифта
-        paint.set_font_size(vertex_radius * scale_coeff);
-
-        // Отрисовка вершин
-        for (i, (x, y)) in &self.vertices {
-            if self.full_render {
-                // Заполнение круга фоновым цветом, затем контур основным цветом
-                let mut path = Path::new();
-                path.circle(*x, *y, vertex_radius);
-                paint.set_color(self.back_color);
-                canvas.fill_path(&mut path, paint);
-                paint.set_color(self.front_color);
-                canvas.stroke_path(&mut path, paint);
-            } else {
-                // Заполнение круга основным цветом
-                let mut path = Path::new();
-                path.circle(*x, *y, vertex_radius);
-                paint.set_color(self.front_color);
-                canvas.fill_path(&mut path, paint);
-                continue;
-            }
+        // Протягиваемое новое ребро пунктиром от вершины до курсора
+        let drag_line = self
+            .edge_drag_from
+            .as_ref()
+            .and_then(|i| self.vertices.get(i))
+            .zip(self.mouse_press);
+        if let Some((&(x, y), cursor)) = drag_line {
+            // Длина штриха и промежутка между штрихами в пикселях
+            const DASH_LENGTH: f32 = 6.0;
+            const DASH_GAP: f32 = 4.0;
+
+            let from = canvas.transform().transform_point(x, y);
+            let mut path = Path::new();
+            Self::add_dashed_line(
+                &mut path,
+                from,
+                cursor,
+                DASH_LENGTH * dpi_factor,
+                DASH_GAP * dpi_factor,
+            );
+            canvas.save();
+            canvas.reset_transform();
+            paint.set_color(self.selection_color);
+            paint.set_line_width(2.0 * dpi_factor);
+            canvas.stroke_path(&mut path, paint);
+            canvas.restore();
+        }
 
-            // Текст идентификатора и метки вершины
-            let text = match &g
-                .get_vertices()
-                .get(i)
-                .ok_or(GraphOperationError::VertexNotFound)?
-                .label
-            {
-                Some(s) => format!("{} ({})", i, s),
-                None => format!("{}", i),
+        // Подсказка у курсора с концами и весом (или потоком) ребра под курсором
+        if let (Some((from, to, index)), Some((x, y))) = (&self.hovered_edge, self.mouse_press) {
+            // Размер шрифта, отступы от курсора и внутри подсказки в пикселях
+            const TOOLTIP_FONT_SIZE: f32 = 14.0;
+            const TOOLTIP_OFFSET: f32 = 16.0;
+            const TOOLTIP_PADDING: f32 = 4.0;
+
+            let arrow = if g.get_is_directed() { "→" } else { "—" };
+            let edge = g
+                .get_edges(from, to)?
+                .get(*index)
+                .ok_or(GraphOperationError::EdgeNotFound)?;
+            let (edge_total_flow, edge_flow) = edge_flows(flow_data, from, to);
+            let capacity = edge_label_weight(flow_data, from, to, edge.weight.as_ref());
+            let text = match (capacity, edge_total_flow) {
+                (Some(w), Some(_)) => format!(
+                    "{} {} {}, {} {}",
+                    from,
+                    arrow,
+                    to,
+                    tr("поток", "flow"),
+                    edge_label_text(w, edge_total_flow, edge_flow)
+                ),
+                // У параллельных рёбер выводятся веса всех рёбер
+                (Some(_), None) => format!(
+                    "{} {} {}, {} {}",
+                    from,
+                    arrow,
+                    to,
+                    tr("вес", "weight"),
+                    g.get_edges(from, to)?
+                        .iter()
+                        .filter_map(|e| e.weight.as_ref().map(|w| w.to_string()))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+                (None, _) => format!("{} {} {}", from, arrow, to),
             };
+
             canvas.save();
-            canvas.scale(1.0 / scale_coeff, 1.0 / scale_coeff);
+            canvas.reset_transform();
+            paint.set_font_size(TOOLTIP_FONT_SIZE * dpi_factor);
+            paint.set_text_align(Align::Left);
+            paint.set_text_baseline(Baseline::Top);
+            paint.set_line_width(dpi_factor);
+            let metrics = canvas.measure_text(0.0, 0.0, &text, paint).unwrap();
+            let padding = TOOLTIP_PADDING * dpi_factor;
+            let (w_box, h_box) = (
+                metrics.width() + 2.0 * padding,
+                metrics.height() + 2.0 * padding,
+            );
+            // Подсказка не выходит за правый и нижний края окна
+            let offset = TOOLTIP_OFFSET * dpi_factor;
+            let x_box = f32::max(0.0, f32::min(x + offset, width - w_box));
+            let y_box = f32::max(0.0, f32::min(y + offset, height - h_box));
+            let mut path = Path::new();
+            path.rounded_rect(x_box, y_box, w_box, h_box, padding);
+            paint.set_color(self.back_color);
+            canvas.fill_path(&mut path, paint);
+            paint.set_color(self.front_color);
+            canvas.stroke_path(&mut path, paint);
             canvas
-                .fill_text(*x * scale_coeff, *y * scale_coeff, text, paint)
+                .fill_text(x_box + padding, y_box + padding, text, paint)
                 .unwrap();
             canvas.restore();
+            paint.set_text_align(Align::Center);
+            paint.set_text_baseline(Baseline::Middle);
+        }
+
+        // Координаты курсора в системе координат вершин в левом верхнем углу окна
+        if self.show_cursor_coords {
+            if let Some((x, y)) = &self.mouse_press {
+                // Размер шрифта и отступ от края окна в пикселях
+                const COORDS_FONT_SIZE: f32 = 14.0;
+                const COORDS_PADDING: f32 = 5.0;
+
+                let (x, y) = canvas.transform().inversed().transform_point(*x, *y);
+                canvas.save();
+                canvas.reset_transform();
+                paint.set_color(self.front_color);
+                paint.set_font_size(COORDS_FONT_SIZE * dpi_factor);
+                paint.set_text_align(Align::Left);
+                paint.set_text_baseline(Baseline::Top);
+                canvas
+                    .fill_text(
+                        COORDS_PADDING * dpi_factor,
+                        COORDS_PADDING * dpi_factor,
+                        format!("x: {:.3}, y: {:.3}", x, y),
+                        paint,
+                    )
+                    .unwrap();
+                canvas.restore();
+            }
         }
 
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::VertexId;
+
+    fn id(name: &str) -> VertexId {
+        name.parse().unwrap()
+    }
+
+    #[test]
+    fn edge_labels_are_hit_in_physical_pixels() {
+        let mut renderer: GraphRenderer<VertexId> = GraphRenderer::new();
+        let edge = (id("1"), id("2"), 0);
+        // Прямоугольник подписи на поле для рисования (в физических пикселях)
+        renderer
+            .edge_label_rects
+            .push((edge.clone(), [100.0, 100.0, 120.0, 110.0]));
+        // При единичном масштабе логические пиксели совпадают с физическими
+        assert_eq!(renderer.edge_at((110.0, 105.0)), Some(edge.clone()));
+        assert_eq!(renderer.edge_at((55.0, 52.5)), None);
+        // На экране с двойной плотностью пикселей подпись в два раза ближе к углу окна
+        renderer.set_scale_factor(2.0);
+        assert_eq!(renderer.edge_at((55.0, 52.5)), Some(edge));
+        assert_eq!(renderer.edge_at((110.0, 105.0)), None);
+    }
+
+    #[test]
+    fn pan_is_in_logical_pixels() {
+        let mut renderer: GraphRenderer<VertexId> = GraphRenderer::new();
+        renderer.pan((10.0, -5.0));
+        assert_eq!(renderer.center_shift, (10.0, -5.0));
+        renderer.set_scale_factor(2.0);
+        renderer.pan((10.0, -5.0));
+        assert_eq!(renderer.center_shift, (30.0, -15.0));
+    }
+}
@@ -0,0 +1,304 @@
+use std::fmt::Write;
+
+use crate::{
+    graph::{EdgeWeights, Graph, VertexKey},
+    graph_flows::{residual_capacity, AlgorithmData, AlgorithmState},
+    strings::Lang,
+};
+
+// Шаг алгоритма нахождения максимального потока в отчёте
+#[derive(Debug, Clone)]
+struct FlowReportStep<I> {
+    path: Vec<I>,            // вершины дополняющего пути от истока к стоку
+    flow: EdgeWeights,       // поток через путь (минимальная остаточная пропускная способность)
+    total_flow: EdgeWeights, // суммарный поток после шага
+}
+
+// Отчёт о выполнении алгоритма нахождения максимального потока по шагам
+#[derive(Debug, Clone)]
+pub struct FlowReport<I> {
+    steps: Vec<FlowReportStep<I>>, // найденные дополняющие пути
+}
+
+impl<I> Default for FlowReport<I> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<I> FlowReport<I> {
+    // Создание пустого отчёта
+    pub fn new() -> Self {
+        Self { steps: Vec::new() }
+    }
+}
+
+impl<I> FlowReport<I>
+where
+    I: VertexKey,
+{
+    // Запись состояния после успешного шага алгоритма: при запуске и сбросе алгоритма
+    // отчёт очищается, после найденного дополняющего пути в отчёт добавляется шаг
+    // (после блокирующего потока алгоритма Диница — по шагу на каждый путь)
+    pub fn record(&mut self, state: &AlgorithmState<I, EdgeWeights>) {
+        match state {
+            AlgorithmState::NotStarted => self.steps.clear(),
+            AlgorithmState::Step(data) if data.get_dinic_phase().is_some() => {
+                let mut total_flow = data.get_total_flow().clone() - data.get_last_flow().clone();
+                for (path, flow) in data.get_blocking_paths() {
+                    total_flow = total_flow + flow.clone();
+                    self.steps.push(FlowReportStep {
+                        path: path.clone(),
+                        flow: flow.clone(),
+                        total_flow: total_flow.clone(),
+                    });
+                }
+            }
+            AlgorithmState::Step(data) if data.get_curr_path_vertices().is_empty() => {
+                self.steps.clear()
+            }
+            AlgorithmState::Step(data) => self.steps.push(FlowReportStep {
+                path: data.get_curr_path_vertices().to_vec(),
+                flow: data.get_last_flow().clone(),
+                total_flow: data.get_total_flow().clone(),
+            }),
+            AlgorithmState::Finished(_) => {}
+        }
+    }
+
+    // Текст отчёта: шаги алгоритма, потоки через дуги и величина потока
+    // (максимального, если алгоритм завершён, иначе текущего)
+    pub fn to_text(
+        &self,
+        g: &Graph<I, EdgeWeights>,
+        state: &AlgorithmState<I, EdgeWeights>,
+        lang: Lang,
+    ) -> String {
+        let mut text = String::new();
+        for (k, step) in self.steps.iter().enumerate() {
+            let path: Vec<_> = step.path.iter().map(|i| i.to_string()).collect();
+            writeln!(
+                text,
+                "{} {}: {} {}, {} {}, {} {}",
+                lang.tr("Шаг", "Step"),
+                k + 1,
+                lang.tr("путь", "path"),
+                path.join(" -> "),
+                lang.tr("поток", "flow"),
+                step.flow,
+                lang.tr("суммарный поток", "total flow"),
+                step.total_flow
+            )
+            .unwrap();
+        }
+
+        let data = match state {
+            AlgorithmState::Step(data) | AlgorithmState::Finished(data) => data,
+            AlgorithmState::NotStarted => return text,
+        };
+        writeln!(text, "{}", lang.tr("Потоки через дуги:", "Arc flows:")).unwrap();
+        for line in flow_lines(g, data) {
+            writeln!(text, "{}", line).unwrap();
+        }
+        writeln!(
+            text,
+            "{} {}",
+            match state {
+                AlgorithmState::Finished(_) => lang.tr("Максимальный поток:", "Maximum flow:"),
+                _ => lang.tr("Текущий поток:", "Current flow:"),
+            },
+            data.get_total_flow()
+        )
+        .unwrap();
+        text
+    }
+}
+
+// Строка таблицы потоков через дуги
+#[derive(Debug, Clone)]
+pub struct FlowRow<I> {
+    pub from: I,               // начало дуги
+    pub to: I,                 // конец дуги
+    pub capacity: EdgeWeights, // пропускная способность
+    pub flow: EdgeWeights,     // текущий поток
+    pub residual: EdgeWeights, // остаточная пропускная способность
+    pub saturated: bool, // насыщена ли дуга (поток равен положительной пропускной способности)
+}
+
+// Строки таблицы потоков через дуги сети в порядке начал и концов дуг; обратные дуги
+// с нулевой пропускной способностью, добавленные для остаточной сети, выводятся
+// только при show_residual_arcs
+pub fn flow_rows<I>(
+    data: &AlgorithmData<I, EdgeWeights>,
+    show_residual_arcs: bool,
+) -> Vec<FlowRow<I>>
+where
+    I: VertexKey,
+{
+    let (g, gc, gf) = (data.get_g(), data.get_gc(), data.get_gf());
+    let zero: EdgeWeights = if gc.get_is_float_weights() {
+        0.0.into()
+    } else {
+        0.into()
+    };
+    let mut rows = Vec::new();
+    for i in gc.get_vertices().keys() {
+        for e in gc.get_edge_list(i).unwrap() {
+            if !show_residual_arcs && g.get_edge(i, &e.to).is_err() {
+                continue;
+            }
+            let capacity = e.weight.clone().unwrap();
+            let flow = gf.get_edge(i, &e.to).unwrap().weight.clone().unwrap();
+            rows.push(FlowRow {
+                from: i.clone(),
+                to: e.to.clone(),
+                residual: residual_capacity(&capacity, &flow),
+                saturated: capacity > zero && flow == capacity,
+                capacity,
+                flow,
+            });
+        }
+    }
+    rows
+}
+
+// Потоки через дуги в виде "из -> в: поток / пропускная способность"; выводятся только дуги
+// с положительным потоком (для неориентированного графа — в направлении потока),
+// петли не влияют на поток
+pub fn flow_lines<I>(g: &Graph<I, EdgeWeights>, data: &AlgorithmData<I, EdgeWeights>) -> Vec<String>
+where
+    I: VertexKey,
+{
+    let (gc, gf) = (data.get_gc(), data.get_gf());
+    let zero: EdgeWeights = if gc.get_is_float_weights() {
+        0.0.into()
+    } else {
+        0.into()
+    };
+    let mut lines = Vec::new();
+    for i in g.get_vertices().keys() {
+        // Параллельные дуги выводятся одной строкой с суммарной пропускной способностью
+        let mut ends: Vec<&I> = g.get_edge_list(i).unwrap().iter().map(|e| &e.to).collect();
+        ends.dedup();
+        for to in ends {
+            let f = match gf.get_edge(i, to) {
+                Ok(edge) if i != to => edge.weight.as_ref().unwrap(),
+                _ => continue,
+            };
+            if f <= &zero {
+                continue;
+            }
+            // Пропускная способность берётся из графа пропускных способностей,
+            // так как у дуг невзвешенного графа она может быть равна 1
+            let c = gc.get_edge(i, to).unwrap().weight.as_ref().unwrap();
+            lines.push(format!("{} -> {}: {} / {}", i, to, f, c));
+        }
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        graph::VertexId,
+        graph_flows::{algorithm_step, PathStrategy},
+    };
+
+    type TestGraph = Option<Graph<VertexId, EdgeWeights>>;
+
+    // Сеть с тремя дополняющими путями в порядке поиска в ширину и тупиковой дугой без потока
+    const NETWORK: &str = "directed weighted int
+vertices
+s
+a
+b
+c
+t
+edges
+s a 3
+s b 2
+s c 1
+a b 1
+a t 2
+b t 3
+";
+
+    // Отчёт о выполнении заданного количества шагов алгоритма Эдмондса-Карпа
+    // (или всех шагов, если их меньше) и конечное состояние
+    fn run_report(
+        g: &TestGraph,
+        max_steps: usize,
+    ) -> (FlowReport<VertexId>, AlgorithmState<VertexId, EdgeWeights>) {
+        let mut report = FlowReport::new();
+        let mut state = AlgorithmState::NotStarted;
+        report.record(&state);
+        for _ in 0..max_steps {
+            state = algorithm_step(state, g, "s", "t", PathStrategy::Bfs, false).unwrap();
+            report.record(&state);
+            if matches!(state, AlgorithmState::Finished(_)) {
+                break;
+            }
+        }
+        (report, state)
+    }
+
+    #[test]
+    fn report_of_finished_run() {
+        let g: TestGraph = Some(Graph::from_file(NETWORK.as_bytes()).unwrap());
+        let (report, state) = run_report(&g, usize::MAX);
+        assert_eq!(
+            report.to_text(g.as_ref().unwrap(), &state, Lang::En),
+            "Step 1: path s -> a -> t, flow 2, total flow 2
+Step 2: path s -> b -> t, flow 2, total flow 4
+Step 3: path s -> a -> b -> t, flow 1, total flow 5
+Arc flows:
+a -> b: 1 / 1
+a -> t: 2 / 2
+b -> t: 3 / 3
+s -> a: 3 / 3
+s -> b: 2 / 2
+Maximum flow: 5
+"
+        );
+        assert_eq!(
+            report.to_text(g.as_ref().unwrap(), &state, Lang::Ru),
+            "Шаг 1: путь s -> a -> t, поток 2, суммарный поток 2
+Шаг 2: путь s -> b -> t, поток 2, суммарный поток 4
+Шаг 3: путь s -> a -> b -> t, поток 1, суммарный поток 5
+Потоки через дуги:
+a -> b: 1 / 1
+a -> t: 2 / 2
+b -> t: 3 / 3
+s -> a: 3 / 3
+s -> b: 2 / 2
+Максимальный поток: 5
+"
+        );
+    }
+
+    #[test]
+    fn report_of_unfinished_run() {
+        let g: TestGraph = Some(Graph::from_file(NETWORK.as_bytes()).unwrap());
+        // Первый шаг только запускает алгоритм, второй находит первый путь
+        let (report, state) = run_report(&g, 2);
+        assert_eq!(
+            report.to_text(g.as_ref().unwrap(), &state, Lang::En),
+            "Step 1: path s -> a -> t, flow 2, total flow 2
+Arc flows:
+a -> t: 2 / 2
+s -> a: 2 / 3
+Current flow: 2
+"
+        );
+    }
+
+    #[test]
+    fn restart_clears_report() {
+        let g: TestGraph = Some(Graph::from_file(NETWORK.as_bytes()).unwrap());
+        let (mut report, _) = run_report(&g, usize::MAX);
+        let state = AlgorithmState::NotStarted;
+        report.record(&state);
+        assert_eq!(report.to_text(g.as_ref().unwrap(), &state, Lang::En), "");
+    }
+}
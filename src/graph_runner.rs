@@ -0,0 +1,245 @@
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    graph::{EdgeWeights, VertexId},
+    graph_algorithms::GraphAlgorithmState,
+    graph_errors::GraphError,
+    graph_flows::AlgorithmState,
+    graph_report::FlowReport,
+};
+
+// Количество шагов между проверками, нужно ли сообщить о ходе выполнения,
+// и минимальный промежуток между сообщениями (чтобы не перегружать интерфейс)
+pub const PROGRESS_STEPS: usize = 1000;
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+
+// Состояние выполнения алгоритма над графом с вершинами-идентификаторами
+pub type RunState = GraphAlgorithmState<VertexId, EdgeWeights>;
+
+// Результат выполнения алгоритма до конца: состояние после последнего шага
+// (после остановки — промежуточное) и отчёт о шагах нахождения потока
+pub type RunResult = Result<(RunState, FlowReport<VertexId>), GraphError>;
+
+// Ход выполнения алгоритма: количество выполненных шагов и суммарный поток
+// (при нахождении максимального потока)
+#[derive(Debug, Clone)]
+pub struct RunProgress {
+    pub steps: usize,                    // количество выполненных шагов
+    pub total_flow: Option<EdgeWeights>, // текущий суммарный поток
+}
+
+impl RunProgress {
+    // Ход выполнения после заданного количества шагов с заданным состоянием
+    pub fn new(steps: usize, state: &RunState) -> Self {
+        let total_flow = match state {
+            GraphAlgorithmState::MaxFlow(AlgorithmState::Step(data))
+            | GraphAlgorithmState::MaxFlow(AlgorithmState::Finished(data)) => {
+                Some(data.get_total_flow().clone())
+            }
+            _ => None,
+        };
+        Self { steps, total_flow }
+    }
+}
+
+// Выполнение шагов алгоритма функцией step, пока алгоритм не завершится, не будет сброшен,
+// не произойдёт ошибка или не будет установлен флаг остановки; каждые PROGRESS_STEPS шагов
+// (но не чаще PROGRESS_INTERVAL) ход выполнения передаётся обработчику,
+// а каждый шаг записывается в отчёт
+pub fn run_to_end<S, P>(
+    mut state: RunState,
+    mut report: FlowReport<VertexId>,
+    mut step: S,
+    cancelled: &AtomicBool,
+    mut progress: P,
+) -> RunResult
+where
+    S: FnMut(RunState) -> Result<RunState, GraphError>,
+    P: FnMut(RunProgress),
+{
+    let mut steps = 0;
+    let mut last_progress = Instant::now();
+    loop {
+        state = step(state)?;
+        steps += 1;
+        if let GraphAlgorithmState::MaxFlow(state) = &state {
+            report.record(state);
+        }
+        if state.is_finished() || state.is_not_started() || cancelled.load(Ordering::Relaxed) {
+            return Ok((state, report));
+        }
+        if steps % PROGRESS_STEPS == 0 && last_progress.elapsed() >= PROGRESS_INTERVAL {
+            last_progress = Instant::now();
+            progress(RunProgress::new(steps, &state));
+        }
+    }
+}
+
+// Выполнение алгоритма до конца в фоновом потоке, чтобы долгое выполнение
+// не блокировало интерфейс
+pub struct AlgorithmRunner {
+    id: u64,                    // номер выполнения (передаётся обработчикам)
+    cancelled: Arc<AtomicBool>, // остановлено ли выполнение
+}
+
+impl AlgorithmRunner {
+    // Запуск выполнения; ход выполнения передаётся обработчику progress, а результат
+    // (в том числе после остановки) — обработчику done (оба вызываются в фоновом потоке
+    // и получают номер выполнения, по которому отличаются сообщения прежних выполнений)
+    pub fn spawn<S, P, F>(
+        id: u64,
+        state: RunState,
+        report: FlowReport<VertexId>,
+        step: S,
+        mut progress: P,
+        done: F,
+    ) -> Self
+    where
+        S: FnMut(RunState) -> Result<RunState, GraphError> + Send + 'static,
+        P: FnMut(u64, RunProgress) + Send + 'static,
+        F: FnOnce(u64, RunResult) + Send + 'static,
+    {
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let thread_cancelled = cancelled.clone();
+        thread::spawn(move || {
+            let progress = |run_progress| progress(id, run_progress);
+            done(
+                id,
+                run_to_end(state, report, step, &thread_cancelled, progress),
+            );
+        });
+        Self { id, cancelled }
+    }
+
+    // Номер выполнения
+    pub fn get_id(&self) -> u64 {
+        self.id
+    }
+
+    // Остановка выполнения после текущего шага
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::mpsc;
+
+    use super::*;
+    use crate::{
+        graph::Graph,
+        graph_flows::{algorithm_step, PathStrategy},
+    };
+
+    // Сеть из одной дуги с пропускной способностью 1
+    const NETWORK: &str = "directed weighted int\nvertices\ns\nt\nedges\ns t 1\n";
+
+    // Состояние после запуска нахождения максимального потока и функция шага алгоритма
+    fn max_flow() -> (
+        RunState,
+        impl FnMut(RunState) -> Result<RunState, GraphError>,
+    ) {
+        let g = Some(Graph::from_file(NETWORK.as_bytes()).unwrap());
+        let step = move |state| match state {
+            GraphAlgorithmState::MaxFlow(state) => {
+                algorithm_step(state, &g, "s", "t", PathStrategy::Dfs, false)
+                    .map(GraphAlgorithmState::MaxFlow)
+            }
+            _ => unreachable!(),
+        };
+        let state = step(GraphAlgorithmState::MaxFlow(AlgorithmState::NotStarted)).unwrap();
+        (state, step)
+    }
+
+    // Шаг, который не изменяет состояние (алгоритм никогда не завершается)
+    fn endless_step(state: RunState) -> Result<RunState, GraphError> {
+        Ok(state)
+    }
+
+    #[test]
+    fn run_to_end_finishes_algorithm() {
+        let (state, step) = max_flow();
+        let cancelled = AtomicBool::new(false);
+        let (state, _) = run_to_end(state, FlowReport::new(), step, &cancelled, |_| {}).unwrap();
+        assert!(state.is_finished());
+        assert_eq!(RunProgress::new(0, &state).total_flow, Some(1.into()));
+    }
+
+    #[test]
+    fn run_to_end_stops_after_cancellation() {
+        let (state, _) = max_flow();
+        let cancelled = AtomicBool::new(false);
+        let mut steps = 0;
+        let step = |state| {
+            steps += 1;
+            // Остановка во время пятого шага
+            if steps == 5 {
+                cancelled.store(true, Ordering::Relaxed);
+            }
+            endless_step(state)
+        };
+        let (state, _) = run_to_end(state, FlowReport::new(), step, &cancelled, |_| {}).unwrap();
+        assert_eq!(steps, 5);
+        // Возвращается промежуточное состояние
+        assert!(!state.is_finished() && !state.is_not_started());
+    }
+
+    #[test]
+    fn cancelled_runner_reports_result_with_id() {
+        let (state, _) = max_flow();
+        let (sender, receiver) = mpsc::channel();
+        let runner = AlgorithmRunner::spawn(
+            3,
+            state,
+            FlowReport::new(),
+            endless_step,
+            |_, _| {},
+            move |id, result| sender.send((id, result)).unwrap(),
+        );
+        runner.cancel();
+        let (id, result) = receiver.recv().unwrap();
+        assert_eq!(id, runner.get_id());
+        assert!(!result.unwrap().0.is_finished());
+    }
+
+    #[test]
+    fn stale_results_are_distinguished_by_id() {
+        let (sender, receiver) = mpsc::channel();
+        let old_sender = sender.clone();
+        let (state, _) = max_flow();
+        let old_runner = AlgorithmRunner::spawn(
+            1,
+            state,
+            FlowReport::new(),
+            endless_step,
+            |_, _| {},
+            move |id, result| old_sender.send((id, result)).unwrap(),
+        );
+        old_runner.cancel();
+        let (state, step) = max_flow();
+        let runner = AlgorithmRunner::spawn(
+            2,
+            state,
+            FlowReport::new(),
+            step,
+            |_, _| {},
+            move |id, result| sender.send((id, result)).unwrap(),
+        );
+        // Результат остановленного выполнения приходит, но отличается по номеру
+        let mut results: Vec<_> = receiver.iter().collect();
+        results.sort_by_key(|(id, _)| *id);
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].0, old_runner.get_id());
+        assert_eq!(results[1].0, runner.get_id());
+        assert!(!results[0].1.as_ref().unwrap().0.is_finished());
+        assert!(results[1].1.as_ref().unwrap().0.is_finished());
+    }
+}
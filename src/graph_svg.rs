@@ -0,0 +1,555 @@
+// Сохранение изображения графа в формате SVG без контекста OpenGL: рёбра, стрелки, подписи
+// и выделение результатов алгоритмов такие же, как при отрисовке в окне графа
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    f32::consts::FRAC_1_SQRT_2,
+    io::Write,
+};
+
+use crate::{
+    graph::{parallel_numbers, xml_escape, Edge, EdgeWeight, Graph, VertexKey, VertexPositions},
+    graph_algorithms::GraphAlgorithmState,
+    graph_errors::{GraphError, GraphOperationError},
+    graph_flows::{AlgorithmState, MatchingState},
+    graph_geometry::{
+        antiparallel_edges, arrowhead_points, curve_arrow_tip, curve_control_point, edge_bend,
+        edge_flows, edge_label_position, edge_label_text, edge_label_weight, edge_width_part,
+        incident_edge_count, line_arrow_tip, loop_direction, loop_fan_direction, loop_geometry,
+        loop_label_offset, loop_label_shift, mix_colors, vertex_text, weight_range, FRONTIER_COLOR,
+        MATCHING_COLOR, MAX_EDGE_WIDTH_COEFF, MIN_CUT_COLOR, MIN_CUT_FILL_PART,
+        REJECTED_EDGE_ALPHA, SINK_COLOR, SOURCE_COLOR, TERMINAL_MARKER_COEFF, TERMINAL_RING_COEFF,
+    },
+    graph_paths::ShortestPathState,
+    graph_trees::SpanningTreeState,
+};
+
+// Примерные ширина символа и высота строки (в размерах шрифта) для фона подписей рёбер,
+// так как без шрифта текст нельзя измерить
+const CHAR_WIDTH_COEFF: f32 = 0.55;
+const LINE_HEIGHT_COEFF: f32 = 1.2;
+// Отступ изображения от крайних вершин (в наибольших радиусах вершин),
+// в который помещаются петли и подписи рёбер
+const MARGIN_COEFF: f32 = 4.0;
+
+// Параметры изображения графа в SVG (размеры, кроме масштаба, в координатах вершин)
+pub struct SvgStyle<I> {
+    pub front_color: [f32; 4],                // основной цвет
+    pub back_color: [f32; 4],                 // фоновый цвет
+    pub selection_color: [f32; 4],            // цвет выделения
+    pub scale: f32,                           // масштаб: длина единицы координат вершин в SVG
+    pub vertex_radius: f32,                   // радиус вершины
+    pub line_width: f32,                      // толщина линий
+    pub arrow_size: f32,                      // длина стрелок дуг (в радиусах вершины)
+    pub edge_label_offset: f32,               // отступ подписей от рёбер (в радиусах вершины)
+    pub show_edge_labels: bool,               // выводить ли подписи рёбер
+    pub edge_width_by_weight: bool,           // зависит ли толщина рёбер от их веса
+    pub vertex_radii: BTreeMap<I, f32>,       // радиусы вершин, отличающиеся от обычного
+    pub vertex_colors: BTreeMap<I, [f32; 4]>, // цвета вершин (при раскраске по компонентам)
+    pub highlighted_edges: BTreeSet<(I, I)>,  // выделенные рёбра (например, найденного цикла)
+}
+
+impl<I> Default for SvgStyle<I> {
+    fn default() -> Self {
+        Self {
+            front_color: [0.0, 0.0, 0.0, 1.0],
+            back_color: [1.0, 1.0, 1.0, 1.0],
+            selection_color: [1.0, 0.0, 0.0, 1.0],
+            scale: 1000.0,
+            vertex_radius: 0.05,
+            line_width: 0.005,
+            arrow_size: 0.6,
+            edge_label_offset: 0.3,
+            show_edge_labels: true,
+            edge_width_by_weight: false,
+            vertex_radii: BTreeMap::new(),
+            vertex_colors: BTreeMap::new(),
+            highlighted_edges: BTreeSet::new(),
+        }
+    }
+}
+
+impl<I> SvgStyle<I>
+where
+    I: VertexKey,
+{
+    // Радиус вершины с учётом её размера
+    fn vertex_radius_of(&self, i: &I) -> f32 {
+        self.vertex_radii
+            .get(i)
+            .copied()
+            .unwrap_or(self.vertex_radius)
+    }
+}
+
+// Атрибут цвета SVG (заливки или линии) с прозрачностью, если цвет прозрачный
+fn color_attr(attr: &str, [r, g, b, a]: [f32; 4]) -> String {
+    let byte = |x: f32| (x.clamp(0.0, 1.0) * 255.0).round() as u8;
+    let color = format!(
+        "{}=\"#{:02x}{:02x}{:02x}\"",
+        attr,
+        byte(r),
+        byte(g),
+        byte(b)
+    );
+    if a < 1.0 {
+        format!("{} {}-opacity=\"{}\"", color, attr, a)
+    } else {
+        color
+    }
+}
+
+// Запись изображения графа с заданными координатами вершин в формате SVG
+// (отрисовываются все вершины независимо от видимой в окне части графа)
+pub fn graph_to_svg<I, W, Writer>(
+    g: &Graph<I, W>,
+    positions: &VertexPositions<I>,
+    g_algorithm_state: &GraphAlgorithmState<I, W>,
+    style: &SvgStyle<I>,
+    writer: &mut Writer,
+) -> Result<(), GraphError>
+where
+    I: VertexKey,
+    W: EdgeWeight,
+    Writer: Write,
+{
+    let position = |i: &I| {
+        positions
+            .get(i)
+            .copied()
+            .ok_or(GraphOperationError::VertexNotFound)
+    };
+
+    // Границы изображения: крайние координаты вершин с отступом
+    let max_radius = style
+        .vertex_radii
+        .values()
+        .fold(style.vertex_radius, |acc, &r| f32::max(acc, r));
+    let margin = MARGIN_COEFF * max_radius;
+    let (min_x, max_x, min_y, max_y) = g
+        .get_vertices()
+        .keys()
+        .map(position)
+        .collect::<Result<Vec<_>, _>>()?
+        .into_iter()
+        .fold(None, |acc, (x, y)| match acc {
+            None => Some((x, x, y, y)),
+            Some((min_x, max_x, min_y, max_y)) => Some((
+                f32::min(min_x, x),
+                f32::max(max_x, x),
+                f32::min(min_y, y),
+                f32::max(max_y, y),
+            )),
+        })
+        .unwrap_or((0.0, 0.0, 0.0, 0.0));
+    let (min_x, min_y) = (min_x - margin, min_y - margin);
+    let width = (max_x - min_x + margin) * style.scale;
+    let height = (max_y - min_y + margin) * style.scale;
+    // Переход от координат вершин к координатам SVG
+    let scale = style.scale;
+    let to_svg = |(x, y): (f32, f32)| ((x - min_x) * scale, (y - min_y) * scale);
+
+    writeln!(writer, "<?xml version=\"1.0\" encoding=\"UTF-8\"?>")?;
+    writeln!(
+        writer,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{:.0}\" height=\"{:.0}\" \
+         viewBox=\"0 0 {:.2} {:.2}\" font-family=\"sans-serif\">",
+        width.ceil(),
+        height.ceil(),
+        width,
+        height
+    )?;
+    writeln!(
+        writer,
+        "  <rect width=\"100%\" height=\"100%\" {}/>",
+        color_attr("fill", style.back_color)
+    )?;
+
+    // Данные алгоритма нахождения максимального потока, если он запущен
+    let flow_data = match g_algorithm_state {
+        GraphAlgorithmState::MaxFlow(AlgorithmState::Step(data))
+        | GraphAlgorithmState::MaxFlow(AlgorithmState::Finished(data)) => Some(data),
+        _ => None,
+    };
+    // Данные алгоритма Дейкстры, если он запущен
+    let path_data = match g_algorithm_state {
+        GraphAlgorithmState::ShortestPath(ShortestPathState::Step(data))
+        | GraphAlgorithmState::ShortestPath(ShortestPathState::Finished(data)) => Some(data),
+        _ => None,
+    };
+    // Рёбра найденного паросочетания
+    let matching_edges = match g_algorithm_state {
+        GraphAlgorithmState::Matching(MatchingState::Finished(data)) => Some(data.get_edges()),
+        _ => None,
+    };
+    // Минимальный и максимальный веса рёбер, если толщина рёбер зависит от веса
+    let weight_range = if style.edge_width_by_weight && g.get_is_weighted() {
+        weight_range(g)
+    } else {
+        None
+    };
+
+    // Дуги, у которых есть противоположные дуги
+    let antiparallel = antiparallel_edges(g);
+
+    // Направления рёбер-петель (в сторону от соседних вершин)
+    let mut loop_dirs = BTreeMap::new();
+    for i in g.get_vertices().keys() {
+        if g.get_edge(i, i).is_err() {
+            continue;
+        }
+        let neighbors: Vec<_> = g
+            .get_vertices()
+            .keys()
+            .filter(|j| j != &i && (g.get_edge(i, j).is_ok() || g.get_edge(j, i).is_ok()))
+            .map(position)
+            .collect::<Result<_, _>>()?;
+        loop_dirs.insert(i.clone(), loop_direction(position(i)?, &neighbors));
+    }
+
+    // Рёбра
+    for i in g.get_vertices().keys() {
+        let from = position(i)?;
+        let edge_list = g.get_edge_list(i).unwrap();
+        for (Edge { to, weight }, (index, count)) in
+            edge_list.iter().zip(parallel_numbers(edge_list))
+        {
+            let to_pos = position(to)?;
+
+            // Суммарный поток через ребро и поток в последнем дополняющем пути
+            let (edge_total_flow, edge_flow) = edge_flows(flow_data, i, to);
+
+            // Цвет ребра такой же, как при отрисовке в окне
+            let edge = (i.clone(), to.clone());
+            let in_min_cut = match g_algorithm_state {
+                GraphAlgorithmState::MaxFlow(AlgorithmState::Finished(data)) => {
+                    data.get_min_cut_edges().contains(&edge)
+                }
+                _ => false,
+            };
+            let in_shortest_path = match g_algorithm_state {
+                GraphAlgorithmState::ShortestPath(ShortestPathState::Finished(data)) => {
+                    data.get_path_edges().contains(&edge)
+                }
+                _ => false,
+            };
+            let in_path_tree = match g_algorithm_state {
+                GraphAlgorithmState::ShortestPath(ShortestPathState::Step(data)) => {
+                    data.is_tree_edge(i, to)
+                }
+                _ => false,
+            };
+            let (in_spanning_tree, is_rejected) = match g_algorithm_state {
+                GraphAlgorithmState::SpanningTree(SpanningTreeState::Step(data))
+                | GraphAlgorithmState::SpanningTree(SpanningTreeState::Finished(data)) => {
+                    (data.is_accepted(i, to), data.is_rejected(i, to))
+                }
+                _ => (false, false),
+            };
+            let in_matching = match matching_edges {
+                Some(edges) => {
+                    edges.contains(&edge)
+                        || (!g.get_is_directed() && edges.contains(&(to.clone(), i.clone())))
+                }
+                None => false,
+            };
+            let edge_color = if edge_flow.is_some()
+                || in_shortest_path
+                || in_spanning_tree
+                || style.highlighted_edges.contains(&edge)
+            {
+                style.selection_color
+            } else if in_min_cut {
+                MIN_CUT_COLOR
+            } else if in_path_tree {
+                FRONTIER_COLOR
+            } else if in_matching {
+                MATCHING_COLOR
+            } else if is_rejected {
+                let mut color = style.front_color;
+                color[3] *= REJECTED_EDGE_ALPHA;
+                color
+            } else {
+                style.front_color
+            };
+            let width_part = edge_width_part(weight_range, weight.as_ref(), edge_total_flow);
+            let stroke = format!(
+                "fill=\"none\" {} stroke-width=\"{:.2}\"",
+                color_attr("stroke", edge_color),
+                style.line_width * (1.0 + (MAX_EDGE_WIDTH_COEFF - 1.0) * width_part) * scale
+            );
+
+            // Форма ребра: окружность петли, кривая Безье для противоположных и параллельных
+            // рёбер или отрезок
+            let loop_geometry = loop_dirs.get(i).filter(|_| i == to).map(|&dir| {
+                let dir = loop_fan_direction(dir, index, count);
+                loop_geometry(from, dir, style.vertex_radius_of(i))
+            });
+            let bend = edge_bend(i, to, index, count, antiparallel.contains(&edge));
+            let control = (loop_geometry.is_none() && bend != 0.0)
+                .then(|| curve_control_point(from, to_pos, style.vertex_radius, bend));
+            let ((x_from, y_from), (x_to, y_to)) = (to_svg(from), to_svg(to_pos));
+            if let Some(lg) = &loop_geometry {
+                let (x, y) = to_svg(lg.center);
+                writeln!(
+                    writer,
+                    "  <circle cx=\"{:.2}\" cy=\"{:.2}\" r=\"{:.2}\" {}/>",
+                    x,
+                    y,
+                    lg.radius * scale,
+                    stroke
+                )?;
+            } else if let Some(control) = control {
+                let (x_c, y_c) = to_svg(control);
+                writeln!(
+                    writer,
+                    "  <path d=\"M {:.2} {:.2} Q {:.2} {:.2} {:.2} {:.2}\" {}/>",
+                    x_from, y_from, x_c, y_c, x_to, y_to, stroke
+                )?;
+            } else {
+                writeln!(
+                    writer,
+                    "  <line x1=\"{:.2}\" y1=\"{:.2}\" x2=\"{:.2}\" y2=\"{:.2}\" {}/>",
+                    x_from, y_from, x_to, y_to, stroke
+                )?;
+            }
+
+            // Стрелка дуги
+            if g.get_is_directed() {
+                let to_radius = style.vertex_radius_of(to);
+                let (tip, back_dir) = if let Some(lg) = &loop_geometry {
+                    (lg.arrow_tip, lg.back_dir)
+                } else if let Some(control) = control {
+                    curve_arrow_tip(from, control, to_pos, to_radius)
+                } else {
+                    line_arrow_tip(from, to_pos, to_radius)
+                };
+                let size = style.vertex_radius * style.arrow_size;
+                if let Some(points) = arrowhead_points(tip, back_dir, size) {
+                    let points: Vec<_> = points
+                        .iter()
+                        .map(|&p| {
+                            let (x, y) = to_svg(p);
+                            format!("{:.2},{:.2}", x, y)
+                        })
+                        .collect();
+                    writeln!(
+                        writer,
+                        "  <polygon points=\"{}\" {}/>",
+                        points.join(" "),
+                        color_attr("fill", edge_color)
+                    )?;
+                }
+            }
+
+            // Подпись ребра неориентированного графа выводится один раз
+            if !style.show_edge_labels || (!g.get_is_directed() && i > to) {
+                continue;
+            }
+            let w = match edge_label_weight(flow_data, i, to, weight.as_ref()) {
+                Some(w) => w,
+                None => continue,
+            };
+            // Поток и пропускная способность выводятся маленьким шрифтом
+            let font_size = match edge_total_flow {
+                None => style.vertex_radius * scale,
+                Some(_) => style.vertex_radius * scale / 2.0,
+            };
+            let text = edge_label_text(w, edge_total_flow, edge_flow);
+            let label_offset = style.vertex_radius * style.edge_label_offset;
+            let label_offset = if loop_geometry.is_some() {
+                loop_label_offset(label_offset, style.vertex_radius, incident_edge_count(g, i))
+            } else {
+                label_offset
+            };
+            let (mut x_text, mut y_text) = to_svg(edge_label_position(
+                from,
+                to_pos,
+                control,
+                loop_geometry.as_ref(),
+                label_offset,
+            ));
+
+            // Фон подписи в виде скруглённого прямоугольника
+            let padding = font_size / 4.0;
+            let (w_pill, h_pill) = (
+                text.chars().count() as f32 * CHAR_WIDTH_COEFF * font_size + 2.0 * padding,
+                LINE_HEIGHT_COEFF * font_size + padding,
+            );
+            // Подпись петли сдвигается наружу, чтобы не закрывать петлю
+            if let Some(lg) = &loop_geometry {
+                let (shift_x, shift_y) = loop_label_shift(lg, w_pill, h_pill);
+                x_text += shift_x;
+                y_text += shift_y;
+            }
+            writeln!(
+                writer,
+                "  <rect x=\"{:.2}\" y=\"{:.2}\" width=\"{:.2}\" height=\"{:.2}\" rx=\"{:.2}\" {}/>",
+                x_text - w_pill / 2.0,
+                y_text - h_pill / 2.0,
+                w_pill,
+                h_pill,
+                h_pill / 2.0,
+                color_attr("fill", style.back_color)
+            )?;
+            writeln!(
+                writer,
+                "  <text x=\"{:.2}\" y=\"{:.2}\" font-size=\"{:.2}\" text-anchor=\"middle\" \
+                 dominant-baseline=\"central\" {}>{}</text>",
+                x_text,
+                y_text,
+                font_size,
+                color_attr("fill", style.front_color),
+                xml_escape(&text)
+            )?;
+        }
+    }
+
+    // Вершины: круг цвета вершины (или фонового цвета) с контуром и текст
+    for i in g.get_vertices().keys() {
+        let (x, y) = to_svg(position(i)?);
+        let radius = style.vertex_radius_of(i);
+        // Вершина, обработанная на последнем шаге алгоритма Дейкстры, выделена,
+        // достигнутые необработанные вершины и вершины слоистой сети выделены другим цветом
+        let component_color = style.vertex_colors.get(i).copied();
+        let in_level_graph = matches!(
+            flow_data.and_then(|data| data.get_levels().as_ref()),
+            Some(levels) if levels.contains_key(i)
+        );
+        let vertex_color = match path_data {
+            Some(data) if data.get_last_vertex().as_ref() == Some(i) => style.selection_color,
+            Some(data) if data.is_in_frontier(i) => FRONTIER_COLOR,
+            _ if in_level_graph => FRONTIER_COLOR,
+            _ => component_color.unwrap_or(style.front_color),
+        };
+        // Вершины со стороны истока минимального разреза заливаются фоновым цветом
+        // с примесью цвета разреза
+        let back_color = match g_algorithm_state {
+            GraphAlgorithmState::MaxFlow(AlgorithmState::Finished(data))
+                if data.get_min_cut_vertices().contains(i) =>
+            {
+                mix_colors(style.back_color, MIN_CUT_COLOR, MIN_CUT_FILL_PART)
+            }
+            _ => style.back_color,
+        };
+        let fill_color = g.get_vertex(i)?.color.map_or(back_color, |c| c.to_rgba());
+        writeln!(
+            writer,
+            "  <circle cx=\"{:.2}\" cy=\"{:.2}\" r=\"{:.2}\" {} {} stroke-width=\"{:.2}\"/>",
+            x,
+            y,
+            radius * scale,
+            color_attr("fill", fill_color),
+            color_attr("stroke", vertex_color),
+            style.line_width * scale
+        )?;
+        // При раскраске по компонентам текст выводится основным цветом
+        let text_color = match component_color {
+            Some(_) => style.front_color,
+            None => vertex_color,
+        };
+        writeln!(
+            writer,
+            "  <text x=\"{:.2}\" y=\"{:.2}\" font-size=\"{:.2}\" text-anchor=\"middle\" \
+             dominant-baseline=\"central\" {}>{}</text>",
+            x,
+            y,
+            radius * scale,
+            color_attr("fill", text_color),
+            xml_escape(&vertex_text(g, i, flow_data, path_data)?)
+        )?;
+    }
+
+    // Исток и сток обводятся кольцами своих цветов и отмечаются буквами S и T справа сверху
+    if let Some(data) = flow_data {
+        for (i, marker, color) in [
+            (data.get_s(), "S", SOURCE_COLOR),
+            (data.get_t(), "T", SINK_COLOR),
+        ] {
+            let (x, y) = to_svg(position(i)?);
+            let radius = style.vertex_radius_of(i) * scale;
+            writeln!(
+                writer,
+                "  <circle cx=\"{:.2}\" cy=\"{:.2}\" r=\"{:.2}\" fill=\"none\" {} stroke-width=\"{:.2}\"/>",
+                x,
+                y,
+                radius * TERMINAL_RING_COEFF,
+                color_attr("stroke", color),
+                style.line_width * scale
+            )?;
+            writeln!(
+                writer,
+                "  <text x=\"{:.2}\" y=\"{:.2}\" font-size=\"{:.2}\" text-anchor=\"start\" \
+                 dominant-baseline=\"text-after-edge\" {}>{}</text>",
+                x + radius * FRAC_1_SQRT_2,
+                y - radius * FRAC_1_SQRT_2,
+                radius * TERMINAL_MARKER_COEFF,
+                color_attr("fill", color),
+                marker
+            )?;
+        }
+    }
+
+    writeln!(writer, "</svg>")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+    use crate::{
+        graph::{EdgeWeights, VertexId},
+        graph_flows::{algorithm_step, PathStrategy},
+    };
+
+    // Граф с противоположными дугами и петлёй и расположением вершин
+    const FIXTURE: &str = include_str!("../tests/svg/small.txt");
+
+    // Сравнение изображения графа с ожидаемым файлом в папке tests/svg; при заданной
+    // переменной окружения UPDATE_SVG ожидаемый файл перезаписывается
+    fn assert_svg(name: &str, state: &GraphAlgorithmState<VertexId, EdgeWeights>) {
+        let (g, positions) = Graph::from_file_with_positions(FIXTURE.as_bytes()).unwrap();
+        let mut svg = Vec::new();
+        graph_to_svg(
+            &g,
+            &positions.unwrap(),
+            state,
+            &SvgStyle::default(),
+            &mut svg,
+        )
+        .unwrap();
+        let svg = String::from_utf8(svg).unwrap();
+
+        // Путь относительно файла с тестом, как в include_str!
+        let path = Path::new(file!())
+            .parent()
+            .unwrap()
+            .join("../tests/svg")
+            .join(name);
+        if std::env::var_os("UPDATE_SVG").is_some() {
+            std::fs::write(&path, &svg).unwrap();
+        }
+        let expected = std::fs::read_to_string(&path).unwrap();
+        assert!(svg == expected, "{} отличается от:\n{}", name, svg);
+    }
+
+    #[test]
+    fn graph_svg_matches_expected() {
+        assert_svg(
+            "small.svg",
+            &GraphAlgorithmState::MaxFlow(AlgorithmState::NotStarted),
+        );
+    }
+
+    #[test]
+    fn max_flow_svg_matches_expected() {
+        let g = Some(Graph::from_file(FIXTURE.as_bytes()).unwrap());
+        let mut state = AlgorithmState::NotStarted;
+        while !matches!(state, AlgorithmState::Finished(_)) {
+            state = algorithm_step(state, &g, "s", "t", PathStrategy::Bfs, false).unwrap();
+        }
+        assert_svg("small_max_flow.svg", &GraphAlgorithmState::MaxFlow(state));
+    }
+}
@@ -0,0 +1,347 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::{
+    graph::{EdgeWeight, EdgeWeights, Graph, VertexKey},
+    graph_errors::{GraphAlgorithmError, GraphError, GraphInterfaceError},
+};
+
+// Состояние выполнения алгоритма нахождения минимального остова
+#[derive(Debug, Clone)]
+pub enum SpanningTreeState<I, W>
+where
+    I: VertexKey,
+    W: EdgeWeight,
+{
+    NotStarted,
+    Step(SpanningTreeData<I, W>),
+    Finished(SpanningTreeData<I, W>),
+}
+
+// Данные текущего состояния алгоритма Краскала
+#[derive(Debug, Clone)]
+pub struct SpanningTreeData<I, W>
+where
+    I: VertexKey,
+    W: EdgeWeight,
+{
+    g: Graph<I, W>,
+    edges: Vec<(W, I, I)>,
+    next_edge: usize,
+    parent: BTreeMap<I, I>,
+    accepted: BTreeSet<(I, I)>,
+    rejected: BTreeSet<(I, I)>,
+    last_edge: Option<(I, I)>,
+    total_weight: W,
+}
+
+impl<I, W> SpanningTreeData<I, W>
+where
+    I: VertexKey,
+    W: EdgeWeight,
+{
+    // Рёбра, добавленные в остов (каждое ребро хранится в одном направлении)
+    pub fn get_accepted_edges(&self) -> &BTreeSet<(I, I)> {
+        &self.accepted
+    }
+
+    // Рёбра, отброшенные из-за образования цикла
+    pub fn get_rejected_edges(&self) -> &BTreeSet<(I, I)> {
+        &self.rejected
+    }
+
+    // Ребро, рассмотренное на последнем шаге
+    pub fn get_last_edge(&self) -> &Option<(I, I)> {
+        &self.last_edge
+    }
+
+    pub fn get_total_weight(&self) -> &W {
+        &self.total_weight
+    }
+
+    // Количество компонент связности найденного остовного леса
+    pub fn get_tree_count(&self) -> usize {
+        self.g.get_vertices().len() - self.accepted.len()
+    }
+
+    // Добавлено ли ребро в остов (в любом направлении)
+    pub fn is_accepted(&self, i: &I, to: &I) -> bool {
+        self.accepted.contains(&(i.clone(), to.clone()))
+            || self.accepted.contains(&(to.clone(), i.clone()))
+    }
+
+    // Отброшено ли ребро (в любом направлении)
+    pub fn is_rejected(&self, i: &I, to: &I) -> bool {
+        self.rejected.contains(&(i.clone(), to.clone()))
+            || self.rejected.contains(&(to.clone(), i.clone()))
+    }
+}
+
+// Корень дерева системы непересекающихся множеств со сжатием путей
+fn find_root<I>(parent: &mut BTreeMap<I, I>, i: &I) -> I
+where
+    I: VertexKey,
+{
+    let mut root = i.clone();
+    while parent[&root] != root {
+        root = parent[&root].clone();
+    }
+    let mut j = i.clone();
+    while j != root {
+        j = parent.insert(j, root.clone()).unwrap();
+    }
+    root
+}
+
+// Рёбра графа без петель в порядке возрастания весов (каждое ребро в одном направлении);
+// граф должен быть неориентированным и взвешенным
+fn sorted_edges<I>(g: &Graph<I, EdgeWeights>) -> Result<Vec<(EdgeWeights, I, I)>, GraphError>
+where
+    I: VertexKey,
+{
+    // Граф ориентированный или невзвешенный
+    if g.get_is_directed() {
+        return Err(GraphAlgorithmError::GraphDirected.into());
+    }
+    if !g.get_is_weighted() {
+        return Err(GraphAlgorithmError::GraphNotWeighted.into());
+    }
+    let mut edges: Vec<_> = g
+        .get_vertices()
+        .keys()
+        .flat_map(|i| g.get_edge_list(i).unwrap().iter().map(move |e| (i, e)))
+        .filter(|(i, e)| *i < &e.to)
+        .map(|(i, e)| (e.weight.clone().unwrap(), i.clone(), e.to.clone()))
+        .collect();
+    edges.sort();
+    Ok(edges)
+}
+
+// Нулевой вес того же типа, что и веса графа (начальный вес остова)
+fn zero_weight<I>(g: &Graph<I, EdgeWeights>) -> EdgeWeights
+where
+    I: VertexKey,
+{
+    if g.get_is_float_weights() {
+        0.0.into()
+    } else {
+        0.into()
+    }
+}
+
+// Каждая вершина графа в своей компоненте системы непересекающихся множеств
+fn single_vertex_sets<I, W>(g: &Graph<I, W>) -> BTreeMap<I, I>
+where
+    I: VertexKey,
+    W: EdgeWeight,
+{
+    g.get_vertices()
+        .keys()
+        .map(|i| (i.clone(), i.clone()))
+        .collect()
+}
+
+// Минимальный остов (для несвязного графа — остовный лес) алгоритмом Краскала
+// без пошагового выполнения: рёбра остова (каждое в одном направлении) и их суммарный вес
+pub fn minimum_spanning_tree<I>(
+    g: &Graph<I, EdgeWeights>,
+) -> Result<(BTreeSet<(I, I)>, EdgeWeights), GraphError>
+where
+    I: VertexKey,
+{
+    let edges = sorted_edges(g)?;
+    let mut total_weight = zero_weight(g);
+    let mut parent = single_vertex_sets(g);
+    let mut accepted = BTreeSet::new();
+    for (w, i, to) in edges {
+        let (root_i, root_to) = (find_root(&mut parent, &i), find_root(&mut parent, &to));
+        if root_i != root_to {
+            parent.insert(root_i, root_to);
+            total_weight = total_weight.checked_add(w)?;
+            accepted.insert((i, to));
+        }
+    }
+    Ok((accepted, total_weight))
+}
+
+// Алгоритм Краскала: на каждом шаге рассматривается следующее по весу ребро,
+// которое добавляется в остов, если соединяет разные компоненты, иначе отбрасывается
+// (для несвязного графа находится минимальный остовный лес)
+pub fn algorithm_step<I>(
+    state: SpanningTreeState<I, EdgeWeights>,
+    g: &Option<Graph<I, EdgeWeights>>,
+) -> Result<SpanningTreeState<I, EdgeWeights>, GraphError>
+where
+    I: VertexKey,
+{
+    match state {
+        SpanningTreeState::NotStarted => {
+            let g = g.as_ref().ok_or(GraphInterfaceError::GraphNotExist)?;
+            // Рёбра без петель в порядке возрастания весов
+            let edges = sorted_edges(g)?;
+
+            // Данные состояния, каждая вершина в своей компоненте
+            let data = SpanningTreeData {
+                g: g.clone(),
+                edges,
+                next_edge: 0,
+                parent: single_vertex_sets(g),
+                accepted: BTreeSet::new(),
+                rejected: BTreeSet::new(),
+                last_edge: None,
+                total_weight: zero_weight(g),
+            };
+            // Алгоритм запущен
+            Ok(SpanningTreeState::Step(data))
+        }
+        SpanningTreeState::Step(mut data) => {
+            // Граф не должен изменяться во время выполнения алгоритма
+            if g.as_ref().map(Graph::get_revision) != Some(data.g.get_revision()) {
+                return Err(GraphAlgorithmError::GraphModifiedDuringAlgorithm.into());
+            }
+            // Рёбер не осталось
+            let (w, i, to) = match data.edges.get(data.next_edge).cloned() {
+                Some(x) => x,
+                None => {
+                    data.last_edge = None;
+                    return Ok(SpanningTreeState::Finished(data));
+                }
+            };
+            data.next_edge += 1;
+
+            // Ребро добавляется, если его концы в разных компонентах
+            let (root_i, root_to) = (
+                find_root(&mut data.parent, &i),
+                find_root(&mut data.parent, &to),
+            );
+            if root_i != root_to {
+                data.parent.insert(root_i, root_to);
+                data.total_weight = data.total_weight.clone().checked_add(w)?;
+                data.accepted.insert((i.clone(), to.clone()));
+            } else {
+                data.rejected.insert((i.clone(), to.clone()));
+            }
+            data.last_edge = Some((i, to));
+
+            // Остов построен (в нём на одно ребро меньше, чем вершин) или рёбер не осталось
+            if data.accepted.len() + 1 >= data.g.get_vertices().len()
+                || data.next_edge == data.edges.len()
+            {
+                Ok(SpanningTreeState::Finished(data))
+            } else {
+                Ok(SpanningTreeState::Step(data))
+            }
+        }
+        SpanningTreeState::Finished(_) => {
+            // Сброс состояния
+            Ok(SpanningTreeState::NotStarted)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::VertexId;
+
+    type TestGraph = Option<Graph<VertexId, EdgeWeights>>;
+
+    // Идентификатор вершины
+    fn id(name: &str) -> VertexId {
+        name.parse().unwrap()
+    }
+
+    // Граф из текста в собственном формате
+    fn graph(text: &str) -> TestGraph {
+        Some(Graph::from_file(text.as_bytes()).unwrap())
+    }
+
+    // Множество рёбер по парам имён вершин
+    fn edges(names: &[(&str, &str)]) -> BTreeSet<(VertexId, VertexId)> {
+        names.iter().map(|(i, j)| (id(i), id(j))).collect()
+    }
+
+    // Пошаговое выполнение алгоритма до конца
+    fn run(g: &TestGraph) -> SpanningTreeData<VertexId, EdgeWeights> {
+        let mut state = algorithm_step(SpanningTreeState::NotStarted, g).unwrap();
+        loop {
+            state = algorithm_step(state, g).unwrap();
+            if let SpanningTreeState::Finished(data) = state {
+                return data;
+            }
+        }
+    }
+
+    #[test]
+    fn total_weight_of_spanning_tree() {
+        let g = graph(
+            "undirected weighted int\nvertices\n1\n2\n3\n4\nedges\n1 2 1\n2 3 2\n1 3 2\n3 4 5\n2 4 7\n1 1 0\n",
+        );
+        let (tree, total_weight) = minimum_spanning_tree(g.as_ref().unwrap()).unwrap();
+        assert_eq!(tree.len(), 3);
+        assert_eq!(total_weight, 8.into());
+        assert!(tree.contains(&(id("1"), id("2"))));
+        assert!(tree.contains(&(id("3"), id("4"))));
+
+        // Пошаговое выполнение находит остов того же веса
+        let data = run(&g);
+        assert_eq!(data.get_total_weight(), &total_weight);
+        assert_eq!(data.get_accepted_edges().len(), 3);
+        assert_eq!(data.get_tree_count(), 1);
+    }
+
+    #[test]
+    fn spanning_forest_of_disconnected_graph() {
+        let g = graph(
+            "undirected weighted float\nvertices\na\nb\nc\nd\ne\nedges\na b 1.5\nc d 0.5\nd e 2\nc e 1\n",
+        );
+        let (tree, total_weight) = minimum_spanning_tree(g.as_ref().unwrap()).unwrap();
+        assert_eq!(tree, edges(&[("a", "b"), ("c", "d"), ("c", "e")]));
+        assert_eq!(total_weight, 3.0.into());
+
+        let data = run(&g);
+        assert_eq!(data.get_accepted_edges(), &tree);
+        assert_eq!(data.get_rejected_edges(), &edges(&[("d", "e")]));
+        assert_eq!(data.get_tree_count(), 2);
+    }
+
+    #[test]
+    fn lightest_parallel_edge_is_taken() {
+        let g = graph(
+            "undirected weighted int\nvertices\n1\n2\n3\nedges\n1 2 5\n1 2 2\n2 3 4\n2 3 4\n",
+        );
+        let (tree, total_weight) = minimum_spanning_tree(g.as_ref().unwrap()).unwrap();
+        assert_eq!(tree, edges(&[("1", "2"), ("2", "3")]));
+        assert_eq!(total_weight, 6.into());
+        assert_eq!(run(&g).get_total_weight(), &6.into());
+    }
+
+    #[test]
+    fn directed_and_unweighted_graphs_are_rejected() {
+        let g = graph("directed weighted int\nvertices\n1\n2\nedges\n1 2 1\n");
+        assert!(matches!(
+            minimum_spanning_tree(g.as_ref().unwrap()),
+            Err(GraphError::AlgorithmError(
+                GraphAlgorithmError::GraphDirected
+            ))
+        ));
+        assert!(matches!(
+            algorithm_step(SpanningTreeState::NotStarted, &g),
+            Err(GraphError::AlgorithmError(
+                GraphAlgorithmError::GraphDirected
+            ))
+        ));
+        let g = graph("undirected unweighted int\nvertices\n1\n2\nedges\n1 2\n");
+        assert!(matches!(
+            minimum_spanning_tree(g.as_ref().unwrap()),
+            Err(GraphError::AlgorithmError(
+                GraphAlgorithmError::GraphNotWeighted
+            ))
+        ));
+        assert!(matches!(
+            algorithm_step(SpanningTreeState::NotStarted, &g),
+            Err(GraphError::AlgorithmError(
+                GraphAlgorithmError::GraphNotWeighted
+            ))
+        ));
+    }
+}
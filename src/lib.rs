@@ -0,0 +1,30 @@
+// Библиотека для работы с графами без графического интерфейса: граф и его файлы,
+// алгоритмы, генераторы графов и режим командной строки
+pub mod graph;
+pub mod graph_algorithms;
+pub mod graph_cli;
+pub mod graph_errors;
+pub mod graph_examples;
+pub mod graph_flows;
+pub mod graph_generators;
+pub mod graph_geometry;
+pub mod graph_layout;
+pub mod graph_loader;
+pub mod graph_parser;
+pub mod graph_paths;
+pub mod graph_report;
+pub mod graph_runner;
+pub mod graph_svg;
+pub mod graph_trees;
+pub mod quad_tree;
+pub mod strings;
+pub mod text_diff;
+
+pub use graph::{
+    Edge, EdgeWeight, EdgeWeights, Graph, Vertex, VertexId, VertexKey, VertexPositions,
+};
+pub use graph_errors::GraphError;
+pub use graph_flows::{algorithm_step, AlgorithmState, PathStrategy};
+pub use graph_layout::ForceLayout;
+pub use graph_parser::{graph_from_path, graph_to_path, FileFormat};
+pub use quad_tree::QuadTree;
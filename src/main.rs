@@ -1,15 +1,27 @@
 #![windows_subsystem = "windows"]
 
-pub mod graph;
+// Модули без зависимостей от графического интерфейса находятся в библиотеке
+// и импортируются сюда, чтобы модули интерфейса обращались к ним через crate::
+use graph_visualizer::{
+    graph, graph_algorithms, graph_cli, graph_errors, graph_examples, graph_flows,
+    graph_generators, graph_geometry, graph_loader, graph_parser, graph_paths, graph_report,
+    graph_runner, graph_svg, graph_trees, quad_tree, strings, text_diff,
+};
+
 pub mod graph_app;
-pub mod graph_errors;
-pub mod graph_flows;
-pub mod graph_parser;
 pub mod graph_renderer;
-pub mod quad_tree;
+pub mod settings;
 
 fn main() {
     std::env::set_var("GTK_USE_PORTAL", "1");
+    // Язык интерфейса по умолчанию задаётся переменной окружения LANG
+    strings::Lang::from_env().set_current();
+
+    // С аргументами командной строки программа работает без графического интерфейса
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if !args.is_empty() {
+        std::process::exit(graph_cli::run(&args));
+    }
 
     crate::graph_app::graph_window::init_app();
 }
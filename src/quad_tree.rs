@@ -1,108 +1,230 @@
+// Минимальный размер области: точки в области меньшего размера считаются совпадающими
+// (иначе близкие точки разбивали бы область почти бесконечно)
+pub const MIN_AREA_SIZE: f32 = 1e-6;
+// Минимальный квадрат расстояния в вычислении силы, ограничивающий силу отталкивания
+// очень близких точек
+const MIN_DISTANCE_SQR: f32 = 1e-8;
+
 // Данные вершины дерева квадрантов
-pub struct NodeData {
+struct NodeData {
     mass: u32,          // масса всех точек в вершине
     center: (f32, f32), // центр масс
-    l_u: Box<Node>,     // левая верхняя область (меньшие x, меньшие y)
-    l_d: Box<Node>,     // левая нижняя область (меньшие x, большие y)
-    r_u: Box<Node>,     // правая верхняя область (большие x, меньшие y)
-    r_d: Box<Node>,     // правая нижняя область (большие x, большие y)
+    children: [usize; 4], // индексы областей: левой верхней (меньшие x, меньшие y), левой нижней
+                        // (меньшие x, большие y), правой верхней и правой нижней
 }
 
 // Вершина дерева квадрантов
-pub enum Node {
-    Empty,           // пустая
-    One((f32, f32)), // одна точка
-    Many(NodeData),  // множество точек, есть разбиение на квадранты
+enum Node {
+    Empty,                // пустая
+    One((f32, f32), u32), // одна точка (и количество совпадающих с ней точек)
+    Many(NodeData),       // множество точек, есть разбиение на квадранты
+}
+
+// Область вершины дерева: минимальные и максимальные координаты
+#[derive(Clone, Copy)]
+struct Area {
+    min_x: f32,
+    max_x: f32,
+    min_y: f32,
+    max_y: f32,
 }
 
-impl Default for NodeData {
+impl Area {
+    // Слишком ли мала область для разбиения на квадранты
+    fn is_tiny(&self) -> bool {
+        self.max_x - self.min_x < MIN_AREA_SIZE && self.max_y - self.min_y < MIN_AREA_SIZE
+    }
+
+    // Середина области
+    fn mid(&self) -> (f32, f32) {
+        (
+            (self.min_x + self.max_x) / 2.0,
+            (self.min_y + self.max_y) / 2.0,
+        )
+    }
+
+    // Квадранты области в порядке индексов областей вершины
+    fn quadrants(&self) -> [Area; 4] {
+        let (mid_x, mid_y) = self.mid();
+        [
+            Area {
+                max_x: mid_x,
+                max_y: mid_y,
+                ..*self
+            },
+            Area {
+                max_x: mid_x,
+                min_y: mid_y,
+                ..*self
+            },
+            Area {
+                min_x: mid_x,
+                max_y: mid_y,
+                ..*self
+            },
+            Area {
+                min_x: mid_x,
+                min_y: mid_y,
+                ..*self
+            },
+        ]
+    }
+}
+
+// Дерево квадрантов; вершины хранятся в одном векторе (корень — первая вершина),
+// который переиспользуется при каждом построении, чтобы не выделять память каждый кадр
+pub struct QuadTree {
+    nodes: Vec<Node>, // вершины дерева
+    area: Area,       // область корня
+    coincident: bool, // есть ли совпадающие (или почти совпадающие) точки
+}
+
+impl Default for QuadTree {
     fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl QuadTree {
+    // Создание пустого дерева
+    pub fn new() -> Self {
         Self {
-            mass: 0,
-            center: (0.0, 0.0),
-            l_u: Box::new(Node::Empty),
-            r_u: Box::new(Node::Empty),
-            l_d: Box::new(Node::Empty),
-            r_d: Box::new(Node::Empty),
+            nodes: Vec::new(),
+            area: Area {
+                min_x: 0.0,
+                max_x: 0.0,
+                min_y: 0.0,
+                max_y: 0.0,
+            },
+            coincident: false,
         }
     }
-}
 
-impl Node {
-    // Вставка в дерево
-    pub fn insert(
-        self,
-        vertex: (f32, f32),
-        min_x: f32,
-        max_x: f32,
-        min_y: f32,
-        max_y: f32,
-    ) -> Self {
-        match self {
-            Self::Empty => Self::One(vertex),
-            Self::One(other_vertex) => Self::Many(NodeData::default())
-                .insert(other_vertex, min_x, max_x, min_y, max_y)
-                .insert(vertex, min_x, max_x, min_y, max_y),
-            Self::Many(mut data) => {
-                data.mass += 1;
-                data.center.0 += vertex.0;
-                data.center.1 += vertex.1;
-
-                let mid_x = (min_x + max_x) / 2.0;
-                let mid_y = (min_y + max_y) / 2.0;
-                if vertex.0 < mid_x {
+    // Построение дерева для точек в заданной области с пересчётом центров масс
+    pub fn build<T>(&mut self, vertices: T, min_x: f32, max_x: f32, min_y: f32, max_y: f32)
+    where
+        T: IntoIterator<Item = (f32, f32)>,
+    {
+        self.nodes.clear();
+        self.nodes.push(Node::Empty);
+        self.area = Area {
+            min_x,
+            max_x,
+            min_y,
+            max_y,
+        };
+        self.coincident = false;
+        for vertex in vertices {
+            self.insert(0, vertex, 1, self.area);
+        }
+        self.finish_inserts(0);
+    }
+
+    // Были ли при последнем построении совпадающие или почти совпадающие точки
+    // (сила отталкивания между ними не определена или почти равна нулю)
+    pub fn has_coincident_points(&self) -> bool {
+        self.coincident
+    }
+
+    // Вставка в поддерево точки с заданной массой (количеством совпадающих точек)
+    fn insert(&mut self, node: usize, vertex: (f32, f32), mass: u32, area: Area) {
+        match self.nodes[node] {
+            Node::Empty => self.nodes[node] = Node::One(vertex, mass),
+            // Совпадающие точки и точки в слишком маленькой области объединяются
+            Node::One(other_vertex, other_mass) if other_vertex == vertex || area.is_tiny() => {
+                self.coincident = true;
+                self.nodes[node] = Node::One(other_vertex, other_mass + mass);
+            }
+            Node::One(other_vertex, other_mass) => {
+                let first_child = self.nodes.len();
+                self.nodes.extend((0..4).map(|_| Node::Empty));
+                self.nodes[node] = Node::Many(NodeData {
+                    mass: 0,
+                    center: (0.0, 0.0),
+                    children: [
+                        first_child,
+                        first_child + 1,
+                        first_child + 2,
+                        first_child + 3,
+                    ],
+                });
+                self.insert(node, other_vertex, other_mass, area);
+                self.insert(node, vertex, mass, area);
+            }
+            Node::Many(ref mut data) => {
+                data.mass += mass;
+                data.center.0 += vertex.0 * mass as f32;
+                data.center.1 += vertex.1 * mass as f32;
+
+                let (mid_x, mid_y) = area.mid();
+                let quadrant = if vertex.0 < mid_x {
                     if vertex.1 < mid_y {
-                        data.l_u = Box::new(data.l_u.insert(vertex, min_x, mid_x, min_y, mid_y));
+                        0
                     } else {
-                        data.l_d = Box::new(data.l_d.insert(vertex, min_x, mid_x, mid_y, max_y));
+                        1
                     }
                 } else if vertex.1 <= mid_y {
-                    data.r_u = Box::new(data.r_u.insert(vertex, mid_x, max_x, min_y, mid_y));
+                    2
                 } else {
-                    data.r_d = Box::new(data.r_d.insert(vertex, mid_x, max_x, mid_y, max_y));
-                }
-                Self::Many(data)
+                    3
+                };
+                let child = data.children[quadrant];
+                self.insert(child, vertex, mass, area.quadrants()[quadrant]);
             }
         }
     }
 
     // Пересчёт центров масс
-    pub fn finish_inserts(&mut self) {
-        if let Self::Many(data) = self {
-            data.center.0 /= data.mass as f32;
-            data.center.1 /= data.mass as f32;
-            data.l_u.finish_inserts();
-            data.l_d.finish_inserts();
-            data.r_u.finish_inserts();
-            data.r_d.finish_inserts();
+    fn finish_inserts(&mut self, node: usize) {
+        let children = match &mut self.nodes[node] {
+            Node::Many(data) => {
+                data.center.0 /= data.mass as f32;
+                data.center.1 /= data.mass as f32;
+                data.children
+            }
+            _ => return,
+        };
+        for child in children {
+            self.finish_inserts(child);
         }
     }
 
     // Вычисление силы, действующей на точку
-    pub fn get_force(
+    pub fn get_force(&self, vertex: (f32, f32), theta: f32) -> (f32, f32) {
+        if self.nodes.is_empty() {
+            return (0.0, 0.0);
+        }
+        self.get_node_force(0, vertex, theta, self.area)
+    }
+
+    // Вычисление силы, с которой на точку действуют точки поддерева
+    fn get_node_force(
         &self,
+        node: usize,
         vertex: (f32, f32),
         theta: f32,
-        min_x: f32,
-        max_x: f32,
-        min_y: f32,
-        max_y: f32,
+        area: Area,
     ) -> (f32, f32) {
-        match self {
+        match &self.nodes[node] {
             Node::Empty => (0.0, 0.0),
-            Node::One(other_vertex) => {
+            Node::One(other_vertex, mass) => {
+                // Совпадающие точки (в том числе сама точка) не отталкивают её,
+                // так как направление силы не определено
                 if vertex == *other_vertex {
                     (0.0, 0.0)
                 } else {
                     let dir = (other_vertex.0 - vertex.0, other_vertex.1 - vertex.1);
-                    let len_sqr = dir.0 * dir.0 + dir.1 * dir.1;
-                    (-dir.0 / len_sqr, -dir.1 / len_sqr)
+                    let len_sqr = f32::max(dir.0 * dir.0 + dir.1 * dir.1, MIN_DISTANCE_SQR);
+                    (
+                        -dir.0 * (*mass as f32) / len_sqr,
+                        -dir.1 * (*mass as f32) / len_sqr,
+                    )
                 }
             }
             Node::Many(data) => {
-                let width = f32::max(max_x - min_x, max_y - min_y);
+                let width = f32::max(area.max_x - area.min_x, area.max_y - area.min_y);
                 let dir = (data.center.0 - vertex.0, data.center.1 - vertex.1);
-                let len_sqr = dir.0 * dir.0 + dir.1 * dir.1;
+                let len_sqr = f32::max(dir.0 * dir.0 + dir.1 * dir.1, MIN_DISTANCE_SQR);
                 let len = len_sqr.sqrt();
                 if width / len < theta {
                     // Все точки в области вершины считаются одной (центром масс)
@@ -111,20 +233,12 @@ impl Node {
                         -dir.1 * (data.mass as f32) / len_sqr,
                     )
                 } else {
-                    let mid_x = (min_x + max_x) / 2.0;
-                    let mid_y = (min_y + max_y) / 2.0;
-                    let force_l_u = data
-                        .l_u
-                        .get_force(vertex, theta, min_x, mid_x, min_y, mid_y);
-                    let force_l_d = data
-                        .l_d
-                        .get_force(vertex, theta, min_x, mid_x, mid_y, max_y);
-                    let force_r_u = data
-                        .r_u
-                        .get_force(vertex, theta, mid_x, max_x, min_y, mid_y);
-                    let force_r_d = data
-                        .r_d
-                        .get_force(vertex, theta, mid_x, max_x, mid_y, max_y);
+                    let quadrants = area.quadrants();
+                    let force = |k: usize| {
+                        self.get_node_force(data.children[k], vertex, theta, quadrants[k])
+                    };
+                    let (force_l_u, force_l_d, force_r_u, force_r_d) =
+                        (force(0), force(1), force(2), force(3));
                     (
                         force_l_u.0 + force_l_d.0 + force_r_u.0 + force_r_d.0,
                         force_l_u.1 + force_l_d.1 + force_r_u.1 + force_r_d.1,
@@ -134,3 +248,100 @@ impl Node {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use super::*;
+
+    // Случайные точки в квадрате [-1; 1] x [-1; 1]
+    fn random_points(n: usize, seed: u64) -> Vec<(f32, f32)> {
+        let mut rng = StdRng::seed_from_u64(seed);
+        (0..n)
+            .map(|_| (rng.gen_range(-1.0..1.0), rng.gen_range(-1.0..1.0)))
+            .collect()
+    }
+
+    // Дерево для точек в квадрате [-1; 1] x [-1; 1]
+    fn tree(points: &[(f32, f32)]) -> QuadTree {
+        let mut tree = QuadTree::new();
+        tree.build(points.iter().copied(), -1.0, 1.0, -1.0, 1.0);
+        tree
+    }
+
+    // Длина вектора
+    fn len(v: (f32, f32)) -> f32 {
+        (v.0 * v.0 + v.1 * v.1).sqrt()
+    }
+
+    #[test]
+    fn exact_force_is_pairwise_sum() {
+        let points = random_points(200, 1);
+        let tree = tree(&points);
+        for &p in &points {
+            let mut expected = (0.0, 0.0);
+            for &q in points.iter().filter(|&&q| q != p) {
+                let dir = (q.0 - p.0, q.1 - p.1);
+                let len_sqr = dir.0 * dir.0 + dir.1 * dir.1;
+                expected.0 -= dir.0 / len_sqr;
+                expected.1 -= dir.1 / len_sqr;
+            }
+            let force = tree.get_force(p, 0.0);
+            let diff = (force.0 - expected.0, force.1 - expected.1);
+            assert!(len(diff) <= 1e-3 * len(expected).max(1.0));
+        }
+    }
+
+    #[test]
+    fn approximate_force_error_is_small() {
+        // Средняя относительная погрешность силы при θ = 0.7 по сравнению с точным вычислением
+        const MAX_MEAN_ERROR: f32 = 0.02;
+
+        let points = random_points(2000, 2);
+        let tree = tree(&points);
+        let mean_error = points
+            .iter()
+            .map(|&p| {
+                let exact = tree.get_force(p, 0.0);
+                let approx = tree.get_force(p, 0.7);
+                len((approx.0 - exact.0, approx.1 - exact.1)) / len(exact)
+            })
+            .sum::<f32>()
+            / points.len() as f32;
+        assert!(mean_error < MAX_MEAN_ERROR, "{}", mean_error);
+    }
+
+    #[test]
+    fn coincident_points_give_finite_forces() {
+        // Совпадающие, почти совпадающие и обычные точки, в том числе все точки в одной
+        let cases = [
+            vec![(0.5, 0.5), (0.5, 0.5)],
+            vec![(0.0, 0.0); 10],
+            vec![(0.1, 0.2), (0.1, 0.2), (0.1 + 1e-7, 0.2), (-0.3, 0.4)],
+            vec![(0.25, -0.25), (0.25, -0.25 + 1e-9), (0.9, 0.9), (0.9, 0.9)],
+        ];
+        for points in &cases {
+            let tree = tree(points);
+            assert!(tree.has_coincident_points());
+            for &p in points {
+                for theta in [0.0, 0.7] {
+                    let force = tree.get_force(p, theta);
+                    assert!(
+                        force.0.is_finite() && force.1.is_finite(),
+                        "{:?} {:?}",
+                        points,
+                        force
+                    );
+                }
+            }
+        }
+        // Сила в точке, где находятся все точки, не определена и равна нулю
+        assert_eq!(tree(&cases[1]).get_force((0.0, 0.0), 0.0), (0.0, 0.0));
+    }
+
+    #[test]
+    fn distinct_points_are_not_coincident() {
+        assert!(!tree(&random_points(100, 3)).has_coincident_points());
+    }
+}
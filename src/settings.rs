@@ -0,0 +1,363 @@
+use std::{fmt::Write, path::PathBuf, str::FromStr};
+
+// Наибольший масштаб сохраняемого изображения PNG относительно окна графа
+pub const MAX_IMAGE_SCALE: f32 = 8.0;
+
+// Настройки приложения, сохраняемые между запусками
+#[derive(Debug, Clone, PartialEq)]
+pub struct Settings {
+    pub center_gravity: f32,                       // сила гравитации к центру
+    pub repulsive_force: f32,                      // сила отталкивания вершин
+    pub time_step: f32,                            // скорость изменений
+    pub theta: f32,                                // погрешность симуляции
+    pub settle_epsilon: f32,                       // порог остановки симуляции
+    pub edge_label_offset: f32,                    // отступ подписей рёбер
+    pub arrow_size: f32,                           // длина стрелок дуг
+    pub lod_label_diameter: f32,                   // порог диаметра вершин для скрытия текста
+    pub full_render: bool,                         // отрисовывать ли детали и текст
+    pub show_edge_labels: bool,                    // выводить ли подписи рёбер
+    pub color_by_component: bool,                  // раскрашивать ли компоненты связности
+    pub edge_width_by_weight: bool,                // зависит ли толщина рёбер от веса
+    pub size_by_degree: bool,                      // зависит ли размер вершин от степени
+    pub show_grid: bool,                           // выводить ли сетку
+    pub show_cursor_coords: bool,                  // выводить ли координаты курсора
+    pub animate_flow: bool,                        // анимировать ли направление потока
+    pub algorithm_play_interval: u64, // интервал автоматического выполнения алгоритма (мс)
+    pub image_scale: f32, // во сколько раз сохраняемое изображение PNG больше окна графа
+    pub front_color: Option<[f32; 4]>, // основной цвет (если не задан, берётся из темы)
+    pub back_color: Option<[f32; 4]>, // фоновый цвет (если не задан, подбирается по теме)
+    pub selection_color: [f32; 4], // цвет выделения
+    pub main_window_size: Option<(i32, i32)>, // размер окна управления
+    pub graph_window_size: (u32, u32), // размер окна графа
+    pub graph_window_position: Option<(i32, i32)>, // положение окна графа
+    pub last_directory: Option<PathBuf>, // последняя папка открытия или сохранения файла
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            center_gravity: 1.1,
+            repulsive_force: 0.1,
+            time_step: 0.01,
+            theta: 0.7,
+            settle_epsilon: 0.0001,
+            edge_label_offset: 0.3,
+            arrow_size: 0.6,
+            lod_label_diameter: 12.0,
+            full_render: true,
+            show_edge_labels: true,
+            color_by_component: false,
+            edge_width_by_weight: false,
+            size_by_degree: false,
+            show_grid: false,
+            show_cursor_coords: false,
+            animate_flow: true,
+            algorithm_play_interval: 500,
+            image_scale: 1.0,
+            front_color: None,
+            back_color: None,
+            selection_color: [1.0, 0.0, 0.0, 1.0],
+            main_window_size: None,
+            graph_window_size: (800, 600),
+            graph_window_position: None,
+            last_directory: None,
+        }
+    }
+}
+
+// Замена значения настройки, если оно правильное
+fn set<T>(setting: &mut T, value: Option<T>) {
+    if let Some(value) = value {
+        *setting = value;
+    }
+}
+
+// Разбор неотрицательного конечного числа
+fn parse_number<T: FromStr + Into<f64> + Copy>(value: &str) -> Option<T> {
+    value
+        .parse::<T>()
+        .ok()
+        .filter(|&x| x.into().is_finite() && x.into() >= 0.0)
+}
+
+// Разбор массива чисел в квадратных скобках: [1, 2, ...]
+fn parse_array<T: FromStr>(value: &str) -> Option<Vec<T>> {
+    value
+        .strip_prefix('[')?
+        .strip_suffix(']')?
+        .split(',')
+        .map(|x| x.trim().parse().ok())
+        .collect()
+}
+
+// Разбор цвета: массива из четырёх компонент от 0 до 1
+fn parse_color(value: &str) -> Option<[f32; 4]> {
+    let color: [f32; 4] = parse_array(value)?.try_into().ok()?;
+    color
+        .iter()
+        .all(|x| (0.0..=1.0).contains(x))
+        .then_some(color)
+}
+
+// Разбор пары чисел
+fn parse_pair<T: FromStr + Copy>(value: &str) -> Option<(T, T)> {
+    match parse_array(value)?.as_slice() {
+        &[x, y] => Some((x, y)),
+        _ => None,
+    }
+}
+
+// Разбор строки в кавычках (с экранированием обратной косой чертой)
+fn parse_string(value: &str) -> Option<String> {
+    let value = value.strip_prefix('"')?.strip_suffix('"')?;
+    let mut result = String::new();
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => result.push(chars.next()?),
+            '"' => return None,
+            c => result.push(c),
+        }
+    }
+    Some(result)
+}
+
+// Запись строки в кавычках с экранированием
+fn string_to_file(value: &str) -> String {
+    let escaped: String = value
+        .chars()
+        .flat_map(|c| match c {
+            '\\' | '"' => vec!['\\', c],
+            c => vec![c],
+        })
+        .collect();
+    format!("\"{}\"", escaped)
+}
+
+// Запись массива чисел в квадратных скобках
+fn array_to_file<T: ToString>(values: &[T]) -> String {
+    let values: Vec<_> = values.iter().map(|x| x.to_string()).collect();
+    format!("[{}]", values.join(", "))
+}
+
+impl Settings {
+    // Путь к файлу настроек в папке настроек пользователя
+    fn path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("graph_visualizer").join("settings.toml"))
+    }
+
+    // Загрузка настроек; если файла нет или он повреждён, используются настройки по умолчанию
+    pub fn load() -> Self {
+        Self::path()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .map(|text| Self::from_text(&text))
+            .unwrap_or_default()
+    }
+
+    // Сохранение настроек (ошибки игнорируются, так как настройки сохраняются при закрытии)
+    pub fn save(&self) {
+        if let Some(path) = Self::path() {
+            if let Some(dir) = path.parent() {
+                let _ = std::fs::create_dir_all(dir);
+            }
+            let _ = std::fs::write(path, self.to_text());
+        }
+    }
+
+    // Разбор настроек из строк вида "ключ = значение" (подмножество TOML);
+    // неизвестные ключи и неправильные значения пропускаются
+    pub fn from_text(text: &str) -> Self {
+        let mut settings = Self::default();
+        for line in text.lines() {
+            let (key, value) = match line.split_once('=') {
+                Some((key, value)) => (key.trim(), value.trim()),
+                None => continue,
+            };
+            match key {
+                "center_gravity" => set(&mut settings.center_gravity, parse_number(value)),
+                "repulsive_force" => set(&mut settings.repulsive_force, parse_number(value)),
+                "time_step" => set(&mut settings.time_step, parse_number(value)),
+                "theta" => set(&mut settings.theta, parse_number(value)),
+                "settle_epsilon" => set(&mut settings.settle_epsilon, parse_number(value)),
+                "edge_label_offset" => set(&mut settings.edge_label_offset, parse_number(value)),
+                "arrow_size" => set(&mut settings.arrow_size, parse_number(value)),
+                "lod_label_diameter" => set(&mut settings.lod_label_diameter, parse_number(value)),
+                "full_render" => set(&mut settings.full_render, value.parse().ok()),
+                "show_edge_labels" => set(&mut settings.show_edge_labels, value.parse().ok()),
+                "color_by_component" => set(&mut settings.color_by_component, value.parse().ok()),
+                "edge_width_by_weight" => {
+                    set(&mut settings.edge_width_by_weight, value.parse().ok())
+                }
+                "size_by_degree" => set(&mut settings.size_by_degree, value.parse().ok()),
+                "show_grid" => set(&mut settings.show_grid, value.parse().ok()),
+                "show_cursor_coords" => set(&mut settings.show_cursor_coords, value.parse().ok()),
+                "animate_flow" => set(&mut settings.animate_flow, value.parse().ok()),
+                "algorithm_play_interval" => {
+                    set(&mut settings.algorithm_play_interval, value.parse().ok())
+                }
+                "image_scale" => set(
+                    &mut settings.image_scale,
+                    parse_number(value).filter(|&x: &f32| (1.0..=MAX_IMAGE_SCALE).contains(&x)),
+                ),
+                "front_color" => set(&mut settings.front_color, parse_color(value).map(Some)),
+                "back_color" => set(&mut settings.back_color, parse_color(value).map(Some)),
+                "selection_color" => set(&mut settings.selection_color, parse_color(value)),
+                "main_window_size" => set(
+                    &mut settings.main_window_size,
+                    parse_pair(value).filter(|&(w, h)| w > 0 && h > 0).map(Some),
+                ),
+                "graph_window_size" => set(
+                    &mut settings.graph_window_size,
+                    parse_pair(value).filter(|&(w, h)| w > 0 && h > 0),
+                ),
+                "graph_window_position" => set(
+                    &mut settings.graph_window_position,
+                    parse_pair(value).map(Some),
+                ),
+                "last_directory" => set(
+                    &mut settings.last_directory,
+                    parse_string(value).map(|dir| Some(PathBuf::from(dir))),
+                ),
+                _ => (),
+            }
+        }
+        settings
+    }
+
+    // Запись настроек в строки вида "ключ = значение"
+    pub fn to_text(&self) -> String {
+        let mut text = String::new();
+        let mut add = |key: &str, value: String| writeln!(text, "{} = {}", key, value).unwrap();
+        add("center_gravity", self.center_gravity.to_string());
+        add("repulsive_force", self.repulsive_force.to_string());
+        add("time_step", self.time_step.to_string());
+        add("theta", self.theta.to_string());
+        add("settle_epsilon", self.settle_epsilon.to_string());
+        add("edge_label_offset", self.edge_label_offset.to_string());
+        add("arrow_size", self.arrow_size.to_string());
+        add("lod_label_diameter", self.lod_label_diameter.to_string());
+        add("full_render", self.full_render.to_string());
+        add("show_edge_labels", self.show_edge_labels.to_string());
+        add("color_by_component", self.color_by_component.to_string());
+        add(
+            "edge_width_by_weight",
+            self.edge_width_by_weight.to_string(),
+        );
+        add("size_by_degree", self.size_by_degree.to_string());
+        add("show_grid", self.show_grid.to_string());
+        add("show_cursor_coords", self.show_cursor_coords.to_string());
+        add("animate_flow", self.animate_flow.to_string());
+        add(
+            "algorithm_play_interval",
+            self.algorithm_play_interval.to_string(),
+        );
+        add("image_scale", self.image_scale.to_string());
+        if let Some(color) = &self.front_color {
+            add("front_color", array_to_file(color));
+        }
+        if let Some(color) = &self.back_color {
+            add("back_color", array_to_file(color));
+        }
+        add("selection_color", array_to_file(&self.selection_color));
+        if let Some((w, h)) = self.main_window_size {
+            add("main_window_size", array_to_file(&[w, h]));
+        }
+        let (w, h) = self.graph_window_size;
+        add("graph_window_size", array_to_file(&[w, h]));
+        if let Some((x, y)) = self.graph_window_position {
+            add("graph_window_position", array_to_file(&[x, y]));
+        }
+        if let Some(dir) = self.last_directory.as_ref().and_then(|dir| dir.to_str()) {
+            add("last_directory", string_to_file(dir));
+        }
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Настройки, отличающиеся от настроек по умолчанию во всех полях
+    fn changed_settings() -> Settings {
+        Settings {
+            center_gravity: 2.5,
+            repulsive_force: 0.25,
+            time_step: 0.02,
+            theta: 0.5,
+            settle_epsilon: 0.001,
+            edge_label_offset: 0.5,
+            arrow_size: 1.0,
+            lod_label_diameter: 8.0,
+            full_render: false,
+            show_edge_labels: false,
+            color_by_component: true,
+            edge_width_by_weight: true,
+            size_by_degree: true,
+            show_grid: true,
+            show_cursor_coords: true,
+            animate_flow: false,
+            algorithm_play_interval: 250,
+            image_scale: 2.5,
+            front_color: Some([0.0, 0.5, 1.0, 1.0]),
+            back_color: Some([0.125, 0.25, 0.375, 0.5]),
+            selection_color: [0.0, 1.0, 0.0, 1.0],
+            main_window_size: Some((1024, 768)),
+            graph_window_size: (640, 480),
+            graph_window_position: Some((-10, 20)),
+            last_directory: Some(PathBuf::from("/home/user/\"graphs\"\\x")),
+        }
+    }
+
+    #[test]
+    fn settings_round_trip() {
+        let settings = changed_settings();
+        assert_ne!(settings, Settings::default());
+        assert_eq!(Settings::from_text(&settings.to_text()), settings);
+        assert_eq!(
+            Settings::from_text(&Settings::default().to_text()),
+            Settings::default()
+        );
+    }
+
+    #[test]
+    fn unknown_keys_and_other_lines_are_skipped() {
+        let text = "# комментарий\n\
+                    unknown_key = 5\n\
+                    theta = 0.3\n\
+                    без знака равенства\n\
+                    center_gravity=2\n\
+                    show_grid = true\n";
+        let settings = Settings::from_text(text);
+        assert_eq!(
+            settings,
+            Settings {
+                theta: 0.3,
+                center_gravity: 2.0,
+                show_grid: true,
+                ..Settings::default()
+            }
+        );
+    }
+
+    #[test]
+    fn invalid_values_keep_defaults() {
+        let text = "center_gravity = -1\n\
+                    theta = NaN\n\
+                    time_step = inf\n\
+                    full_render = yes\n\
+                    algorithm_play_interval = -5\n\
+                    image_scale = 0.5\n\
+                    front_color = [1, 2, 0, 1]\n\
+                    back_color = [0, 0, 0]\n\
+                    main_window_size = [0, 600]\n\
+                    graph_window_size = [800]\n\
+                    last_directory = \"a\"b\"\n";
+        assert_eq!(Settings::from_text(text), Settings::default());
+    }
+
+    #[test]
+    fn empty_text_gives_defaults() {
+        assert_eq!(Settings::from_text(""), Settings::default());
+    }
+}
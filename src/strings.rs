@@ -0,0 +1,49 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+// Язык интерфейса
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Lang {
+    Ru, // русский
+    En, // английский
+}
+
+// Выбран ли английский язык (текущий язык нужен и там, где нет модели приложения:
+// в тексте ошибок и в потоке окна графа)
+static IS_ENGLISH: AtomicBool = AtomicBool::new(false);
+
+impl Lang {
+    // Язык по переменной окружения LANG: английский, если задана нерусская локаль
+    pub fn from_env() -> Self {
+        match std::env::var("LANG") {
+            Ok(lang) if !lang.is_empty() && !lang.starts_with("ru") => Self::En,
+            _ => Self::Ru,
+        }
+    }
+
+    // Текущий язык
+    pub fn current() -> Self {
+        if IS_ENGLISH.load(Ordering::Relaxed) {
+            Self::En
+        } else {
+            Self::Ru
+        }
+    }
+
+    // Установка текущего языка
+    pub fn set_current(self) {
+        IS_ENGLISH.store(self == Self::En, Ordering::Relaxed);
+    }
+
+    // Строка на этом языке
+    pub fn tr(self, ru: &'static str, en: &'static str) -> &'static str {
+        match self {
+            Self::Ru => ru,
+            Self::En => en,
+        }
+    }
+}
+
+// Строка на текущем языке
+pub fn tr(ru: &'static str, en: &'static str) -> &'static str {
+    Lang::current().tr(ru, en)
+}
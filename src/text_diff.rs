@@ -0,0 +1,169 @@
+// Построчное сравнение текстов для изменения текста без его полной замены
+
+use std::cmp::max;
+
+// Максимальное количество клеток таблицы наибольшей общей подпоследовательности строк;
+// при большем размере изменённая часть текста заменяется целиком
+const MAX_LCS_CELLS: usize = 1_000_000;
+
+// Замена строк старого текста с номерами от start до end (не включая) новым текстом
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineEdit {
+    pub start: usize, // номер первой заменяемой строки
+    pub end: usize,   // номер строки после последней заменяемой
+    pub text: String, // новые строки (вместе с символами перевода строки)
+}
+
+// Замены строк, превращающие старый текст в новый, в порядке возрастания номеров строк
+// (строки наибольшей общей подпоследовательности не изменяются)
+pub fn line_edits(old: &str, new: &str) -> Vec<LineEdit> {
+    let old_lines: Vec<&str> = old.split_inclusive('\n').collect();
+    let new_lines: Vec<&str> = new.split_inclusive('\n').collect();
+
+    // Общие начальные и конечные строки не сравниваются
+    let prefix = old_lines
+        .iter()
+        .zip(&new_lines)
+        .take_while(|(a, b)| a == b)
+        .count();
+    let suffix = old_lines[prefix..]
+        .iter()
+        .rev()
+        .zip(new_lines[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let old_mid = &old_lines[prefix..old_lines.len() - suffix];
+    let new_mid = &new_lines[prefix..new_lines.len() - suffix];
+    let (n, m) = (old_mid.len(), new_mid.len());
+    if n == 0 && m == 0 {
+        return Vec::new();
+    }
+    if n.saturating_mul(m) > MAX_LCS_CELLS {
+        return vec![LineEdit {
+            start: prefix,
+            end: prefix + n,
+            text: new_mid.concat(),
+        }];
+    }
+
+    // lcs[i * (m + 1) + j] — длина наибольшей общей подпоследовательности
+    // строк old_mid[i..] и new_mid[j..]
+    let mut lcs = vec![0u32; (n + 1) * (m + 1)];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i * (m + 1) + j] = if old_mid[i] == new_mid[j] {
+                lcs[(i + 1) * (m + 1) + j + 1] + 1
+            } else {
+                max(lcs[(i + 1) * (m + 1) + j], lcs[i * (m + 1) + j + 1])
+            };
+        }
+    }
+
+    // Совпадающие строки остаются, идущие подряд удаления и вставки объединяются в замены
+    let mut edits = Vec::new();
+    let mut curr: Option<LineEdit> = None;
+    let (mut i, mut j) = (0, 0);
+    while i < n || j < m {
+        if i < n && j < m && old_mid[i] == new_mid[j] {
+            edits.extend(curr.take());
+            i += 1;
+            j += 1;
+            continue;
+        }
+        let edit = curr.get_or_insert_with(|| LineEdit {
+            start: prefix + i,
+            end: prefix + i,
+            text: String::new(),
+        });
+        if j < m && (i == n || lcs[i * (m + 1) + j + 1] >= lcs[(i + 1) * (m + 1) + j]) {
+            // Вставка строки нового текста
+            edit.text.push_str(new_mid[j]);
+            j += 1;
+        } else {
+            // Удаление строки старого текста
+            i += 1;
+            edit.end = prefix + i;
+        }
+    }
+    edits.extend(curr);
+    edits
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Применение замен к старому тексту (с конца, чтобы номера строк не сдвигались)
+    fn apply(old: &str, edits: &[LineEdit]) -> String {
+        let mut lines: Vec<String> = old.split_inclusive('\n').map(String::from).collect();
+        for edit in edits.iter().rev() {
+            lines.splice(
+                edit.start..edit.end,
+                edit.text.split_inclusive('\n').map(String::from),
+            );
+        }
+        lines.concat()
+    }
+
+    // Замены, превращающие старый текст в новый
+    fn edits(old: &str, new: &str) -> Vec<LineEdit> {
+        let edits = line_edits(old, new);
+        assert_eq!(apply(old, &edits), new);
+        edits
+    }
+
+    fn edit(start: usize, end: usize, text: &str) -> LineEdit {
+        LineEdit {
+            start,
+            end,
+            text: String::from(text),
+        }
+    }
+
+    #[test]
+    fn identical_texts_have_no_edits() {
+        assert_eq!(edits("", ""), []);
+        assert_eq!(edits("a\nb\nc\n", "a\nb\nc\n"), []);
+        assert_eq!(edits("a\nb", "a\nb"), []);
+    }
+
+    #[test]
+    fn insertion() {
+        assert_eq!(edits("a\nc\n", "a\nb\nc\n"), [edit(1, 1, "b\n")]);
+        assert_eq!(edits("b\n", "a\nb\n"), [edit(0, 0, "a\n")]);
+        assert_eq!(edits("a\n", "a\nb\nc\n"), [edit(1, 1, "b\nc\n")]);
+        assert_eq!(edits("", "a\n"), [edit(0, 0, "a\n")]);
+    }
+
+    #[test]
+    fn deletion() {
+        assert_eq!(edits("a\nb\nc\n", "a\nc\n"), [edit(1, 2, "")]);
+        assert_eq!(edits("a\nb\nc\n", "c\n"), [edit(0, 2, "")]);
+        assert_eq!(edits("a\n", ""), [edit(0, 1, "")]);
+    }
+
+    #[test]
+    fn replacement() {
+        assert_eq!(edits("a\nb\nc\n", "a\nx\nc\n"), [edit(1, 2, "x\n")]);
+        // Несколько изменённых мест разделяются совпадающими строками
+        assert_eq!(
+            edits("a\nb\nc\nd\ne\n", "x\nb\nc\ny\nz\ne\n"),
+            [edit(0, 1, "x\n"), edit(3, 4, "y\nz\n")]
+        );
+        // Изменение последней строки без перевода строки
+        assert_eq!(edits("a\nb", "a\nc"), [edit(1, 2, "c")]);
+        assert_eq!(edits("a\nb", "a\nb\n"), [edit(1, 2, "b\n")]);
+    }
+
+    #[test]
+    fn large_change_is_replaced_entirely() {
+        let old: String = (0..2000).map(|k| format!("{}\n", k)).collect();
+        let new: String = (0..2000).map(|k| format!("{}\n", k + 1)).collect();
+        // Общие первая и последняя строки остаются, а середина слишком велика для сравнения
+        let text = format!("a\n{}b\n", old);
+        assert_eq!(
+            edits(&text, &format!("a\n{}b\n", new)),
+            [edit(1, 2001, &new)]
+        );
+    }
+}